@@ -2,7 +2,9 @@
 //!
 //! Usage: cargo run --example generate_qr
 
+use qlink::keystone::multipart::{AnimatedQr, DEFAULT_MAX_FRAGMENT_LEN};
 use qlink::{QrEncoder, QrPayload};
+use std::path::Path;
 
 fn main() -> anyhow::Result<()> {
     // Initialize logging
@@ -28,5 +30,16 @@ fn main() -> anyhow::Result<()> {
     println!("✓ UR QR code generated and saved to qr_ur_example.png");
     println!("  Content: {}", ur_string);
 
+    // Generate an animated multi-part QR for a larger payload
+    let payload = vec![0xabu8; 1200];
+    let mut animation = AnimatedQr::new("bytes", &payload, DEFAULT_MAX_FRAGMENT_LEN)?;
+    let frames = animation.recommended_frame_count();
+    animation.save(Path::new("qr_animated.gif"), frames)?;
+    println!(
+        "✓ Animated QR ({} fragments, {} frames) saved to qr_animated.gif",
+        animation.fragment_count(),
+        frames
+    );
+
     Ok(())
 }
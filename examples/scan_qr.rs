@@ -40,7 +40,7 @@ async fn main() -> anyhow::Result<()> {
 
     let mut scanner = QlinkScanner::new(config).await?;
 
-    println!("✓ Scanner initialized: {}", scanner.camera.info().name);
+    println!("✓ Scanner initialized: {}", scanner.source.info().name);
     println!("\nScanning for QR codes (Ctrl+C to stop)...\n");
 
     loop {
@@ -0,0 +1,288 @@
+//! Minimal HTTP/1.1 request parsing and response writing for the local API
+//!
+//! The API serves one request per connection (`Connection: close`), mirroring
+//! the metrics endpoint, so there is no keep-alive or chunked encoding support.
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Upper bound on the request line plus headers
+pub(crate) const MAX_HEADER_BYTES: usize = 16 * 1024;
+
+/// Upper bound on a request body
+pub(crate) const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Error carrying the HTTP status that should be returned to the client
+#[derive(Debug, Clone)]
+pub(crate) struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, message)
+    }
+}
+
+/// Parsed HTTP request
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub method: String,
+    pub target: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Request path without any query string
+    pub fn path(&self) -> &str {
+        self.target
+            .split_once('?')
+            .map(|(path, _)| path)
+            .unwrap_or(&self.target)
+    }
+
+    /// Value of a query-string parameter (not percent-decoded)
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        let (_, query) = self.target.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Case-insensitive header lookup
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read a single request from the stream.
+pub(crate) async fn read_request<R: AsyncRead + Unpin>(
+    stream: &mut R,
+) -> std::result::Result<Request, ApiError> {
+    let mut buffer = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+
+    let header_end = loop {
+        if let Some(pos) = find_header_end(&buffer) {
+            break pos;
+        }
+        if buffer.len() > MAX_HEADER_BYTES {
+            return Err(ApiError::new(431, "Request headers too large"));
+        }
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| ApiError::bad_request(format!("Failed to read request: {e}")))?;
+        if read == 0 {
+            return Err(ApiError::bad_request(
+                "Connection closed before request was complete",
+            ));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buffer[..header_end])
+        .map_err(|_| ApiError::bad_request("Request headers are not valid UTF-8"))?;
+    let mut lines = head.split("\r\n");
+
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ApiError::bad_request("Malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(ApiError::new(505, "Unsupported HTTP version"));
+    }
+
+    let mut headers = Vec::new();
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| ApiError::bad_request("Malformed header line"))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut request = Request {
+        method: method.to_ascii_uppercase(),
+        target: target.to_string(),
+        headers,
+        body: Vec::new(),
+    };
+
+    if request.header("transfer-encoding").is_some() {
+        return Err(ApiError::new(501, "Transfer-Encoding is not supported"));
+    }
+
+    let content_length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| ApiError::bad_request("Invalid Content-Length"))?,
+        None => 0,
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Err(ApiError::new(413, "Request body too large"));
+    }
+
+    let mut body = buffer[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| ApiError::bad_request(format!("Failed to read request body: {e}")))?;
+        if read == 0 {
+            return Err(ApiError::bad_request(
+                "Request body shorter than Content-Length",
+            ));
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+    request.body = body;
+
+    Ok(request)
+}
+
+fn find_header_end(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// HTTP response under construction
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub status: u16,
+    headers: Vec<(&'static str, String)>,
+    content_type: Option<&'static str>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &Value) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            content_type: Some("application/json"),
+            body: serde_json::to_vec(value).unwrap_or_default(),
+        }
+    }
+
+    pub fn empty(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            content_type: None,
+            body: Vec::new(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        let mut response = Vec::with_capacity(256 + self.body.len());
+        response.extend_from_slice(
+            format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status)).as_bytes(),
+        );
+        response.extend_from_slice(b"Connection: close\r\n");
+        response.extend_from_slice(b"Cache-Control: no-store\r\n");
+        if let Some(content_type) = self.content_type {
+            response.extend_from_slice(b"Content-Type: ");
+            response.extend_from_slice(content_type.as_bytes());
+            response.extend_from_slice(b"\r\n");
+        }
+        for (name, value) in &self.headers {
+            response.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        response
+            .extend_from_slice(format!("Content-Length: {}\r\n\r\n", self.body.len()).as_bytes());
+        response.extend_from_slice(&self.body);
+        response
+    }
+}
+
+impl From<ApiError> for Response {
+    fn from(err: ApiError) -> Self {
+        Response::json(err.status, &json!({ "error": err.message }))
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        426 => "Upgrade Required",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        503 => "Service Unavailable",
+        505 => "HTTP Version Not Supported",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_request_with_body() {
+        let raw = b"POST /show-qr?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\r\nabcd";
+        let request = read_request(&mut &raw[..]).await.unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path(), "/show-qr");
+        assert_eq!(request.query_param("x"), Some("1"));
+        assert_eq!(request.header("HOST"), Some("localhost"));
+        assert_eq!(request.body, b"abcd");
+    }
+
+    #[tokio::test]
+    async fn test_read_request_rejects_oversized_body() {
+        let raw = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        let err = read_request(&mut raw.as_bytes()).await.unwrap_err();
+        assert_eq!(err.status, 413);
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        let bytes = Response::empty(204)
+            .with_header("Vary", "Origin")
+            .into_bytes();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.starts_with("HTTP/1.1 204 No Content\r\n"));
+        assert!(text.contains("Vary: Origin\r\n"));
+        assert!(text.ends_with("Content-Length: 0\r\n\r\n"));
+
+        let conflict = Response::from(ApiError::new(409, "stale")).into_bytes();
+        assert!(conflict.starts_with(b"HTTP/1.1 409 Conflict\r\n"));
+    }
+}
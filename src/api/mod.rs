@@ -0,0 +1,550 @@
+//! Local HTTP API for browser tooling and other local clients
+//!
+//! Serves `GET /health`, `POST /scan-once`, `POST /scan-keystone`,
+//! `POST /show-qr` and `GET /sessions` on top of a shared [`QlinkScanner`],
+//! plus a `GET /events` WebSocket that streams [`ScanEvent`](crate::ScanEvent)s
+//! for live progress. Sign requests shown via `/show-qr` are recorded in the
+//! scanner's [`SessionStore`](crate::session::SessionStore) so scanned
+//! signatures can be matched to them.
+//! Requests are authenticated with the optional bearer token from
+//! [`ApiOptions`], browser origins are checked against `allowed_origins`, and
+//! the listener refuses to bind to non-loopback addresses unless
+//! `allow_remote` is set.
+
+mod http;
+mod ws;
+
+use crate::config::ApiOptions;
+use crate::error::{Error, Result};
+use crate::events::EventSender;
+use crate::keystone::UrMessageRegistry;
+use crate::keystone::multipart::{
+    DEFAULT_MAX_FRAGMENT_LEN, MultiPartEncoder, RECOMMENDED_FRAME_DELAY_MS,
+};
+use crate::output::{keystone_payload_value, qr_payload_value};
+use crate::session::SharedSessionStore;
+use crate::{FrameSource, KeystonePayload, QlinkScanner, QrEncoder, QrPayload, metrics};
+use http::{ApiError, Request, Response};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Scanner shared between API requests; scans are serialized by the lock
+pub type SharedScanner<S> = Arc<Mutex<QlinkScanner<S>>>;
+
+/// Default time a scan request may wait for a QR code
+pub const DEFAULT_SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Upper bound accepted for a client-supplied `timeout_ms`
+pub const MAX_SCAN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Time a client has to send its complete request head and body
+pub const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Running API server; the accept loop stops when this is dropped
+pub struct ApiServer {
+    local_addr: SocketAddr,
+    accept_task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    /// Bind the API listener and spawn the accept loop.
+    pub async fn bind<S: FrameSource + 'static>(
+        options: &ApiOptions,
+        scanner: SharedScanner<S>,
+    ) -> Result<Self> {
+        let addr = resolve_bind_address(options)?;
+        let listener = TcpListener::bind(addr).await.map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to bind API listener {addr}: {e}"),
+            ))
+        })?;
+        let local_addr = listener.local_addr()?;
+        let (events, sessions) = {
+            let scanner = scanner.lock().await;
+            (scanner.event_sender(), scanner.session_store())
+        };
+
+        let state = Arc::new(ApiState {
+            scanner,
+            events,
+            sessions,
+            token: options.token.clone().filter(|t| !t.is_empty()),
+            allowed_origins: options.allowed_origins.clone(),
+            started: Instant::now(),
+        });
+
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(pair) => pair,
+                    Err(err) => {
+                        warn!(target: "qlink::api", error = %err, "API accept failed");
+                        tokio::time::sleep(Duration::from_millis(250)).await;
+                        continue;
+                    }
+                };
+
+                let state = Arc::clone(&state);
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, state).await {
+                        debug!(target: "qlink::api", peer = %peer, error = %err, "API connection closed");
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            accept_task,
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Resolve the configured bind address, enforcing the loopback-only default.
+fn resolve_bind_address(options: &ApiOptions) -> Result<SocketAddr> {
+    let addrs: Vec<SocketAddr> = match options.bind_address.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, options.port)],
+        Err(_) => options
+            .socket_address()
+            .to_socket_addrs()
+            .map_err(|e| {
+                Error::Config(format!(
+                    "Invalid API bind address '{}': {e}",
+                    options.bind_address
+                ))
+            })?
+            .collect(),
+    };
+
+    let addr = addrs.first().copied().ok_or_else(|| {
+        Error::Config(format!(
+            "API bind address '{}' did not resolve",
+            options.bind_address
+        ))
+    })?;
+
+    if !options.allow_remote && !addrs.iter().all(|a| a.ip().is_loopback()) {
+        return Err(Error::Config(format!(
+            "Refusing to bind API to non-loopback address '{}'; set api.allow_remote = true to override",
+            options.bind_address
+        )));
+    }
+
+    Ok(addr)
+}
+
+struct ApiState<S: FrameSource> {
+    scanner: SharedScanner<S>,
+    events: EventSender,
+    sessions: SharedSessionStore,
+    token: Option<String>,
+    allowed_origins: Vec<String>,
+    started: Instant,
+}
+
+async fn handle_connection<S: FrameSource + 'static>(
+    mut stream: TcpStream,
+    state: Arc<ApiState<S>>,
+) -> Result<()> {
+    // Idle or trickling clients would otherwise hold the task open forever
+    let request = tokio::time::timeout(REQUEST_READ_TIMEOUT, http::read_request(&mut stream))
+        .await
+        .unwrap_or_else(|_| Err(ApiError::new(408, "Timed out reading request")));
+    let response = match request {
+        Ok(request) if ws::is_upgrade(&request) => match state.accept_upgrade(&request) {
+            Ok(key) => {
+                let origin = request.header("origin");
+                return ws::serve_events(stream, &key, origin, state.events.subscribe()).await;
+            }
+            Err(err) => Response::from(err),
+        },
+        Ok(request) => state.handle(request).await,
+        Err(err) => Response::from(err),
+    };
+
+    stream.write_all(&response.into_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ScanRequest {
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ShowQrRequest {
+    text: Option<String>,
+    ur_type: Option<String>,
+    data_hex: Option<String>,
+    max_fragment_len: Option<usize>,
+}
+
+impl<S: FrameSource + 'static> ApiState<S> {
+    async fn handle(&self, request: Request) -> Response {
+        let origin = request.header("origin").map(str::to_string);
+        if let Some(ref origin) = origin {
+            if !self.origin_allowed(origin) {
+                return ApiError::new(403, format!("Origin '{origin}' is not allowed")).into();
+            }
+        }
+
+        let response = if request.method == "OPTIONS" {
+            Response::empty(204)
+                .with_header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+                .with_header(
+                    "Access-Control-Allow-Headers",
+                    "Authorization, Content-Type",
+                )
+                .with_header("Access-Control-Max-Age", "600")
+        } else {
+            match self.route(&request).await {
+                Ok(response) => response,
+                Err(err) => err.into(),
+            }
+        };
+
+        match origin {
+            Some(origin) => response
+                .with_header("Access-Control-Allow-Origin", origin)
+                .with_header("Vary", "Origin"),
+            None => response,
+        }
+    }
+
+    /// Check origin, path and token for a WebSocket upgrade request.
+    fn accept_upgrade(&self, request: &Request) -> std::result::Result<String, ApiError> {
+        if let Some(origin) = request.header("origin") {
+            if !self.origin_allowed(origin) {
+                return Err(ApiError::new(
+                    403,
+                    format!("Origin '{origin}' is not allowed"),
+                ));
+            }
+        }
+        if request.path() != "/events" {
+            return Err(ApiError::new(404, "Not found"));
+        }
+        self.authorize(request)?;
+        ws::handshake_key(request)
+    }
+
+    async fn route(&self, request: &Request) -> std::result::Result<Response, ApiError> {
+        let path = request.path();
+        if path != "/health" {
+            self.authorize(request)?;
+        }
+
+        match (request.method.as_str(), path) {
+            ("GET", "/health") => Ok(self.health()),
+            ("POST", "/scan-once") => self.scan_once(request).await,
+            ("POST", "/scan-keystone") => self.scan_keystone(request).await,
+            ("POST", "/show-qr") => show_qr(request, &self.sessions),
+            ("GET", "/sessions") => Ok(self.sessions()),
+            ("GET", "/events") => Err(ApiError::new(426, "WebSocket upgrade required")),
+            (
+                _,
+                "/health" | "/scan-once" | "/scan-keystone" | "/show-qr" | "/sessions" | "/events",
+            ) => Err(ApiError::new(405, "Method not allowed")),
+            _ => Err(ApiError::new(404, "Not found")),
+        }
+    }
+
+    fn origin_allowed(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+
+    fn authorize(&self, request: &Request) -> std::result::Result<(), ApiError> {
+        let Some(expected) = &self.token else {
+            return Ok(());
+        };
+
+        // Browsers cannot set headers on WebSocket requests, so `/events` also accepts `?token=`
+        let provided = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .or_else(|| {
+                (request.path() == "/events")
+                    .then(|| request.query_param("token"))
+                    .flatten()
+            })
+            .unwrap_or_default();
+
+        if constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            Ok(())
+        } else {
+            Err(ApiError::new(401, "Missing or invalid API token"))
+        }
+    }
+
+    fn health(&self) -> Response {
+        let source = self
+            .scanner
+            .try_lock()
+            .ok()
+            .map(|scanner| scanner.source.description());
+
+        Response::json(
+            200,
+            &json!({
+                "status": "ok",
+                "version": env!("CARGO_PKG_VERSION"),
+                "uptime_secs": self.started.elapsed().as_secs(),
+                "scanner": {
+                    "busy": source.is_none(),
+                    "source": source,
+                },
+            }),
+        )
+    }
+
+    fn sessions(&self) -> Response {
+        let sessions = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Response::json(200, &sessions.to_value())
+    }
+
+    async fn scan_once(&self, request: &Request) -> std::result::Result<Response, ApiError> {
+        let timeout = scan_timeout(request)?;
+        let started = Instant::now();
+
+        let qr = tokio::time::timeout(timeout, async {
+            let mut scanner = self.scanner.lock().await;
+            loop {
+                match scanner.scan_once().await {
+                    Err(Error::NoQrCodeFound) => {
+                        tokio::time::sleep(Duration::from_millis(90)).await;
+                    }
+                    other => return other,
+                }
+            }
+        })
+        .await
+        .map_err(|_| timeout_error(timeout))?
+        .map_err(scan_error)?;
+
+        let keystone = match KeystonePayload::try_from(qr.clone()) {
+            Ok(payload) => {
+                metrics::record(started.elapsed(), true, Some(&payload.ur_type));
+                keystone_payload_value(&payload)
+            }
+            Err(Error::InvalidKeystonePayload(_)) | Err(Error::UrParse(_)) => Value::Null,
+            Err(err) => return Err(scan_error(err)),
+        };
+
+        Ok(Response::json(
+            200,
+            &json!({ "qr": qr_payload_value(&qr), "keystone": keystone }),
+        ))
+    }
+
+    async fn scan_keystone(&self, request: &Request) -> std::result::Result<Response, ApiError> {
+        let timeout = scan_timeout(request)?;
+        let started = Instant::now();
+
+        let result = tokio::time::timeout(timeout, async {
+            let mut scanner = self.scanner.lock().await;
+            scanner.scan_keystone().await
+        })
+        .await;
+
+        match result {
+            Ok(Ok(payload)) => {
+                metrics::record(started.elapsed(), true, Some(&payload.ur_type));
+                Ok(Response::json(200, &keystone_payload_value(&payload)))
+            }
+            Ok(Err(err)) => {
+                metrics::record(started.elapsed(), false, None);
+                Err(scan_error(err))
+            }
+            Err(_) => {
+                metrics::record(started.elapsed(), false, None);
+                // A signature was scanned but skipped: report why rather than
+                // a bare timeout
+                let rejected = self
+                    .sessions
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .orphans()
+                    .last()
+                    .filter(|orphan| orphan.received >= started)
+                    .map(|orphan| orphan.reason.clone());
+                Err(match rejected {
+                    Some(reason) => ApiError::new(
+                        409,
+                        format!("Scanned signature does not answer a pending request: {reason}"),
+                    ),
+                    None => timeout_error(timeout),
+                })
+            }
+        }
+    }
+}
+
+fn show_qr(
+    request: &Request,
+    sessions: &SharedSessionStore,
+) -> std::result::Result<Response, ApiError> {
+    let body: ShowQrRequest = parse_body(request)?;
+    let mut sign_request = None;
+
+    let parts = match (body.text, body.ur_type, body.data_hex) {
+        (Some(text), None, None) => vec![text],
+        (None, Some(ur_type), Some(data_hex)) => {
+            let data = hex::decode(data_hex.trim())
+                .map_err(|e| ApiError::bad_request(format!("Invalid data_hex: {e}")))?;
+            sign_request = UrMessageRegistry::global()
+                .decode(&ur_type, &data)
+                .ok()
+                .filter(|message| message.is_sign_request());
+            let max_fragment_len = body
+                .max_fragment_len
+                .unwrap_or(DEFAULT_MAX_FRAGMENT_LEN)
+                .max(10);
+            MultiPartEncoder::new(&ur_type, &data, max_fragment_len)
+                .map_err(|e| ApiError::bad_request(e.to_string()))?
+                .all_parts()
+        }
+        _ => {
+            return Err(ApiError::bad_request(
+                "Expected either `text` or both `ur_type` and `data_hex`",
+            ));
+        }
+    };
+
+    let encoder = QrEncoder::new();
+    let mut rendered = Vec::with_capacity(parts.len());
+    for (index, content) in parts.iter().enumerate() {
+        let svg = encoder
+            .encode_svg(&QrPayload::from_string(content.clone()))
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        rendered.push(json!({
+            "index": index,
+            "content": content,
+            "svg": svg,
+        }));
+    }
+
+    // Only open a session once the QR code can actually be shown
+    let request_id = sign_request.and_then(|message| {
+        sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .record_request(message)
+            .ok()
+    });
+
+    Ok(Response::json(
+        200,
+        &json!({
+            "multipart": parts.len() > 1,
+            "part_count": parts.len(),
+            "frame_delay_ms": RECOMMENDED_FRAME_DELAY_MS,
+            "request_id": request_id.map(|id| id.to_string()),
+            "parts": rendered,
+        }),
+    ))
+}
+
+fn parse_body<T: for<'de> Deserialize<'de> + Default>(
+    request: &Request,
+) -> std::result::Result<T, ApiError> {
+    if request.body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(&request.body)
+        .map_err(|e| ApiError::bad_request(format!("Invalid JSON body: {e}")))
+}
+
+fn scan_timeout(request: &Request) -> std::result::Result<Duration, ApiError> {
+    let body: ScanRequest = parse_body(request)?;
+    Ok(body
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SCAN_TIMEOUT)
+        .min(MAX_SCAN_TIMEOUT))
+}
+
+fn timeout_error(timeout: Duration) -> ApiError {
+    ApiError::new(
+        408,
+        format!("Scan timed out after {} ms", timeout.as_millis()),
+    )
+}
+
+fn scan_error(err: Error) -> ApiError {
+    match err {
+        Error::EndOfStream | Error::CameraNotFound(_) => ApiError::new(503, err.to_string()),
+        other => ApiError::new(500, other.to_string()),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_bind_address_rejects_remote() {
+        let mut options = ApiOptions {
+            bind_address: "0.0.0.0".to_string(),
+            ..ApiOptions::default()
+        };
+        assert!(matches!(
+            resolve_bind_address(&options),
+            Err(Error::Config(_))
+        ));
+
+        options.allow_remote = true;
+        assert!(resolve_bind_address(&options).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_bind_address_accepts_ipv6_loopback() {
+        let options = ApiOptions {
+            bind_address: "::1".to_string(),
+            ..ApiOptions::default()
+        };
+        let addr = resolve_bind_address(&options).unwrap();
+        assert!(addr.ip().is_loopback());
+        assert_eq!(addr.port(), options.port);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
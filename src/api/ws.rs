@@ -0,0 +1,84 @@
+//! WebSocket streaming of scan progress events (`GET /events`)
+
+use crate::ScanEvent;
+use crate::error::{Error, Result};
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+
+use super::http::{ApiError, Request};
+
+/// Whether the request asks to upgrade to a WebSocket
+pub(crate) fn is_upgrade(request: &Request) -> bool {
+    request
+        .header("upgrade")
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+/// Validate the WebSocket handshake headers and return the client key
+pub(crate) fn handshake_key(request: &Request) -> std::result::Result<String, ApiError> {
+    if request.method != "GET" {
+        return Err(ApiError::new(405, "WebSocket upgrade requires GET"));
+    }
+    if request.header("sec-websocket-version") != Some("13") {
+        return Err(ApiError::bad_request("Unsupported WebSocket version"));
+    }
+    request
+        .header("sec-websocket-key")
+        .map(str::to_string)
+        .ok_or_else(|| ApiError::bad_request("Missing Sec-WebSocket-Key"))
+}
+
+/// Complete the handshake and stream events until the client disconnects.
+pub(crate) async fn serve_events(
+    mut stream: TcpStream,
+    key: &str,
+    origin: Option<&str>,
+    mut events: broadcast::Receiver<ScanEvent>,
+) -> Result<()> {
+    let mut handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    if let Some(origin) = origin {
+        handshake.push_str(&format!("Access-Control-Allow-Origin: {origin}\r\n"));
+    }
+    handshake.push_str("\r\n");
+    stream.write_all(handshake.as_bytes()).await?;
+
+    let mut socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let text = serde_json::to_string(&event)?;
+                    socket.send(Message::Text(text)).await.map_err(ws_error)?;
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(target: "qlink::api", skipped, "WebSocket client lagged behind scan events");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.next() => match incoming {
+                // Pings are answered by tungstenite; other client messages are ignored
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(ws_error(err)),
+            },
+        }
+    }
+
+    let _ = socket.close(None).await;
+    Ok(())
+}
+
+fn ws_error(err: tokio_tungstenite::tungstenite::Error) -> Error {
+    Error::Other(format!("WebSocket error: {err}"))
+}
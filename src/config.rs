@@ -348,10 +348,12 @@ impl FromStr for MetricsFormat {
     }
 }
 
-/// Local API binding configuration for the forthcoming daemon
+/// Local API binding configuration for the daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ApiOptions {
+    /// Serve the local HTTP API from `qlinkd`
+    pub enabled: bool,
     /// Bind address for the local API server
     pub bind_address: String,
     /// Bind port for the local API server
@@ -362,22 +364,40 @@ pub struct ApiOptions {
     pub allowed_origins: Vec<String>,
     /// Optional Unix domain socket path for streaming structured events
     pub unix_socket: Option<PathBuf>,
+    /// Permit binding the API to non-loopback interfaces
+    pub allow_remote: bool,
 }
 
 impl Default for ApiOptions {
     fn default() -> Self {
         Self {
+            enabled: false,
             bind_address: "127.0.0.1".to_string(),
             port: 9233,
             token: None,
             allowed_origins: Vec::new(),
             unix_socket: None,
+            allow_remote: false,
         }
     }
 }
 
 impl ApiOptions {
     pub(crate) fn apply_env_overrides(&mut self) {
+        if let Ok(enabled) = env::var("QLINK_API_ENABLED") {
+            match enabled.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" => self.enabled = true,
+                "0" | "false" | "off" => self.enabled = false,
+                _ => {}
+            }
+        }
+        if let Ok(allow_remote) = env::var("QLINK_API_ALLOW_REMOTE") {
+            match allow_remote.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" => self.allow_remote = true,
+                "0" | "false" | "off" => self.allow_remote = false,
+                _ => {}
+            }
+        }
         if let Ok(addr) = env::var("QLINK_BIND_ADDRESS") {
             self.bind_address = addr;
         }
@@ -28,6 +28,10 @@ pub enum Error {
     #[error("No QR code found in frame")]
     NoQrCodeFound,
 
+    /// Frame source has no more frames to deliver
+    #[error("Frame source exhausted")]
+    EndOfStream,
+
     /// QR code encoding failed
     #[error("Failed to encode QR code: {0}")]
     QrEncode(String),
@@ -44,6 +48,10 @@ pub enum Error {
     #[error("CBOR error: {0}")]
     Cbor(String),
 
+    /// CBOR decoding error with the path and offset it happened at
+    #[error("CBOR decode failed: {0}")]
+    CborDecode(crate::keystone::cbor::CborDecodeError),
+
     /// I/O error
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -0,0 +1,148 @@
+//! Structured scan-session events for live progress reporting
+//!
+//! [`QlinkScanner`](crate::QlinkScanner) publishes a [`ScanEvent`] for every
+//! step of a `scan_keystone` session. The local API streams them over
+//! WebSocket (`GET /events`) and `qlinkd` forwards them to Unix socket clients.
+
+use crate::keystone::multipart::DecodeProgress;
+use crate::session::ResponseMatch;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Number of events buffered for slow subscribers before they start lagging
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Sender half used to publish scan events
+pub type EventSender = broadcast::Sender<ScanEvent>;
+
+/// Event emitted while a Keystone scan session is in progress
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScanEvent {
+    /// A new `scan_keystone` session started waiting for frames
+    SessionStarted {
+        /// Session identifier shared by all events of this scan
+        session_id: Uuid,
+    },
+    /// A UR fragment was decoded from a frame
+    FragmentReceived {
+        /// Session identifier
+        session_id: Uuid,
+        /// UR type of the fragment, if it could be parsed
+        ur_type: Option<String>,
+        /// Whether the fragment had been seen before in this session
+        duplicate: bool,
+    },
+    /// Multi-part decoding advanced
+    Progress {
+        /// Session identifier
+        session_id: Uuid,
+        /// Number of unique fragments received so far
+        parts_received: usize,
+        /// Total number of parts, if known
+        total_parts: Option<usize>,
+        /// Estimated completion (0-100)
+        percentage: u8,
+    },
+    /// The session produced a Keystone payload
+    Completed {
+        /// Session identifier
+        session_id: Uuid,
+        /// UR type of the decoded payload
+        ur_type: String,
+        /// Structured payload, as produced by `output::keystone_payload_value`
+        payload: Value,
+    },
+    /// A scanned signature was matched against recorded sign requests
+    ResponseChecked {
+        /// Session identifier
+        session_id: Uuid,
+        /// Request identifier carried by the response, if any
+        request_id: Option<Uuid>,
+        /// `matched`, `expired`, `mismatched` or `orphaned`
+        outcome: String,
+        /// Signature verification result for matched responses
+        verification: Option<String>,
+        /// Verification detail for matched responses
+        detail: Option<String>,
+    },
+    /// A fragment or the whole session failed
+    Error {
+        /// Session identifier
+        session_id: Uuid,
+        /// Error description
+        message: String,
+        /// Whether the session ended because of this error
+        fatal: bool,
+    },
+}
+
+impl ScanEvent {
+    /// Build a progress event from a decoder snapshot
+    pub fn progress(session_id: Uuid, progress: &DecodeProgress) -> Self {
+        ScanEvent::Progress {
+            session_id,
+            parts_received: progress.parts_received,
+            total_parts: progress.total_parts,
+            percentage: progress.percentage,
+        }
+    }
+
+    /// Build a response-check event from a session store match
+    pub fn response_checked(session_id: Uuid, outcome: &ResponseMatch) -> Self {
+        let verification = outcome.verification();
+        ScanEvent::ResponseChecked {
+            session_id,
+            request_id: outcome.request_id(),
+            outcome: outcome.label().to_string(),
+            verification: verification.map(|v| v.status.label().to_string()),
+            detail: verification.map(|v| v.detail.clone()),
+        }
+    }
+
+    /// Session this event belongs to
+    pub fn session_id(&self) -> Uuid {
+        match self {
+            ScanEvent::SessionStarted { session_id }
+            | ScanEvent::FragmentReceived { session_id, .. }
+            | ScanEvent::Progress { session_id, .. }
+            | ScanEvent::Completed { session_id, .. }
+            | ScanEvent::ResponseChecked { session_id, .. }
+            | ScanEvent::Error { session_id, .. } => *session_id,
+        }
+    }
+
+    /// Serialize the event to the JSON shape sent to clients
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+/// Create a new event channel
+pub fn channel() -> EventSender {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_event_shape() {
+        let session_id = Uuid::new_v4();
+        let progress = DecodeProgress {
+            parts_received: 3,
+            total_parts: None,
+            percentage: 37,
+            complete: false,
+        };
+
+        let value = ScanEvent::progress(session_id, &progress).to_value();
+        assert_eq!(value["event"], "progress");
+        assert_eq!(value["parts_received"], 3);
+        assert_eq!(value["percentage"], 37);
+        assert_eq!(value["session_id"], session_id.to_string());
+    }
+}
@@ -1,10 +1,14 @@
 //! CBOR decoding helpers
 
 use minicbor::Decoder;
+use minicbor::data::Type;
 
 /// Helper trait for decoding optional values from CBOR maps
 pub trait DecodeOptional<'b> {
-    /// Try to decode an optional value, returning None if key not present
+    /// Decode a value that may be encoded as CBOR `null` or `undefined`
+    ///
+    /// Returns `None` (consuming the item) for `null` / `undefined`; any
+    /// other decode failure is propagated rather than treated as absent.
     fn decode_optional<T: minicbor::Decode<'b, ()>>(
         &mut self,
     ) -> Result<Option<T>, minicbor::decode::Error>;
@@ -14,10 +18,25 @@ impl<'b> DecodeOptional<'b> for Decoder<'b> {
     fn decode_optional<T: minicbor::Decode<'b, ()>>(
         &mut self,
     ) -> Result<Option<T>, minicbor::decode::Error> {
-        // Try to decode, if it fails due to unexpected type or missing data, return None
-        match self.decode() {
-            Ok(val) => Ok(Some(val)),
-            Err(_) => Ok(None),
+        match self.datatype()? {
+            Type::Null | Type::Undefined => {
+                self.skip()?;
+                Ok(None)
+            }
+            _ => self.decode().map(Some),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_optional_propagates_type_errors() {
+        let mut d = Decoder::new(&[0xf6, 0x05, 0x61, b'x']);
+        assert_eq!(d.decode_optional::<u32>().unwrap(), None);
+        assert_eq!(d.decode_optional::<u32>().unwrap(), Some(5));
+        assert!(d.decode_optional::<u32>().is_err());
+    }
+}
@@ -2,12 +2,15 @@
 
 pub mod decode;
 pub mod encode;
+pub mod validate;
 
 // Re-export traits if needed
 // pub use encode::*;
 // pub use decode::*;
+pub use validate::{CborDecodeError, DecodeMode};
 
 use crate::error::{Error, Result};
+use minicbor::Decoder;
 
 /// Helper to encode a value to CBOR bytes
 pub fn to_bytes<T: minicbor::Encode<()>>(value: &T) -> Result<Vec<u8>> {
@@ -15,8 +18,38 @@ pub fn to_bytes<T: minicbor::Encode<()>>(value: &T) -> Result<Vec<u8>> {
 }
 
 /// Helper to decode CBOR bytes to a value
+///
+/// Lenient: unknown keys and trailing bytes are ignored. Failures carry the
+/// path and byte offset of the offending item, with the root named after the
+/// top-level tag when it identifies the message.
 pub fn from_bytes<'a, T: minicbor::Decode<'a, ()>>(bytes: &'a [u8]) -> Result<T> {
-    minicbor::decode(bytes).map_err(|e| Error::Cbor(format!("CBOR decode failed: {}", e)))
+    decode_at(None, bytes)
+}
+
+/// Decode CBOR bytes of the given UR type in `mode`
+///
+/// Strict mode validates the whole payload before the typed decode runs.
+pub fn from_bytes_with_mode<'a, T: minicbor::Decode<'a, ()>>(
+    bytes: &'a [u8],
+    ur_type: &str,
+    mode: DecodeMode,
+) -> Result<T> {
+    if mode == DecodeMode::Strict {
+        validate::validate(Some(ur_type), bytes, mode).map_err(Error::CborDecode)?;
+    }
+    decode_at(Some(ur_type), bytes)
+}
+
+fn decode_at<'a, T: minicbor::Decode<'a, ()>>(root: Option<&str>, bytes: &'a [u8]) -> Result<T> {
+    let mut d = Decoder::new(bytes);
+    T::decode(&mut d, &mut ()).map_err(|e| {
+        let offset = validate::error_offset(&e, d.position());
+        Error::CborDecode(CborDecodeError {
+            path: validate::locate(root, bytes, offset),
+            offset,
+            message: e.to_string(),
+        })
+    })
 }
 
 /// CBOR tag constants for Keystone protocol
@@ -24,6 +57,9 @@ pub mod tags {
     /// UUID tag (RFC 4122)
     pub const UUID: u64 = 37;
 
+    /// Crypto-hdkey (BIP32 extended key)
+    pub const CRYPTO_HDKEY: u64 = 303;
+
     /// Crypto-keypath (BIP32 derivation path)
     pub const CRYPTO_KEYPATH: u64 = 304;
 
@@ -36,9 +72,51 @@ pub mod tags {
     /// Crypto-output
     pub const CRYPTO_OUTPUT: u64 = 308;
 
+    /// Crypto-psbt (partially signed Bitcoin transaction)
+    pub const CRYPTO_PSBT: u64 = 310;
+
     /// Crypto-account
     pub const CRYPTO_ACCOUNT: u64 = 311;
 
+    /// PSBT (UR 2.0 registry name for crypto-psbt)
+    pub const PSBT: u64 = 40310;
+
+    // Output descriptor script expressions (BCR-2020-010). These only appear
+    // inside crypto-output, so they may share numbers with top-level tags.
+
+    /// `sh(...)` script expression
+    pub const SCRIPT_SH: u64 = 400;
+
+    /// `wsh(...)` script expression
+    pub const SCRIPT_WSH: u64 = 401;
+
+    /// `pk(...)` script expression
+    pub const SCRIPT_PK: u64 = 402;
+
+    /// `pkh(...)` script expression
+    pub const SCRIPT_PKH: u64 = 403;
+
+    /// `wpkh(...)` script expression
+    pub const SCRIPT_WPKH: u64 = 404;
+
+    /// `combo(...)` script expression
+    pub const SCRIPT_COMBO: u64 = 405;
+
+    /// `multi(...)` script expression
+    pub const SCRIPT_MULTI: u64 = 406;
+
+    /// `sortedmulti(...)` script expression
+    pub const SCRIPT_SORTED_MULTI: u64 = 407;
+
+    /// `raw(...)` script expression
+    pub const SCRIPT_RAW: u64 = 408;
+
+    /// `tr(...)` script expression
+    pub const SCRIPT_TAPROOT: u64 = 409;
+
+    /// `cosigner(...)` script expression
+    pub const SCRIPT_COSIGNER: u64 = 410;
+
     /// ETH sign request
     pub const ETH_SIGN_REQUEST: u64 = 401;
 
@@ -51,6 +129,48 @@ pub mod tags {
     /// SOL signature
     pub const SOL_SIGNATURE: u64 = 1102;
 
+    /// Crypto-multi-accounts (Keystone multi-chain pairing)
+    pub const CRYPTO_MULTI_ACCOUNTS: u64 = 1103;
+
+    /// NEAR sign request
+    pub const NEAR_SIGN_REQUEST: u64 = 2101;
+
+    /// NEAR signature
+    pub const NEAR_SIGNATURE: u64 = 2102;
+
+    /// Cardano UTXO (inside cardano-sign-request)
+    pub const CARDANO_UTXO: u64 = 2201;
+
+    /// Cardano sign request
+    pub const CARDANO_SIGN_REQUEST: u64 = 2202;
+
+    /// Cardano signature
+    pub const CARDANO_SIGNATURE: u64 = 2203;
+
+    /// Cardano certificate key (inside cardano-sign-request)
+    pub const CARDANO_CERT_KEY: u64 = 2204;
+
+    /// Aptos sign request
+    pub const APTOS_SIGN_REQUEST: u64 = 3101;
+
+    /// Aptos signature
+    pub const APTOS_SIGNATURE: u64 = 3102;
+
+    /// Cosmos sign request
+    pub const COSMOS_SIGN_REQUEST: u64 = 4101;
+
+    /// Cosmos signature
+    pub const COSMOS_SIGNATURE: u64 = 4102;
+
+    /// Keystone sign request (gzip-compressed base protobuf)
+    pub const KEYSTONE_SIGN_REQUEST: u64 = 6101;
+
+    /// Sui sign request
+    pub const SUI_SIGN_REQUEST: u64 = 7101;
+
+    /// Sui signature
+    pub const SUI_SIGNATURE: u64 = 7102;
+
     /// Stellar sign request
     pub const STELLAR_SIGN_REQUEST: u64 = 8201;
 
@@ -0,0 +1,850 @@
+//! Schema-aware CBOR validation and error paths
+//!
+//! The typed decoders stop at the first problem and only say what went
+//! wrong, not where. This module walks the raw item tree next to a table of
+//! the known map layouts, so a failure can be reported as
+//! `eth-sign-request.derivation_path.components[3]` plus a byte offset.
+//!
+//! [`DecodeMode::Strict`] also rejects what the typed decoders tolerate for
+//! forward compatibility: unknown map keys, trailing bytes, indefinite-length
+//! items and non-canonical (non-shortest or unsorted) encodings. A map whose
+//! layout is not in the table (for example the body of an application
+//! registered UR type) cannot be checked for unknown keys, so strict mode
+//! rejects it too.
+
+use super::tags;
+use std::fmt;
+
+/// How much the decoder tolerates beyond the known message layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Reject unknown map keys, maps of unknown layout, trailing bytes,
+    /// indefinite lengths and non-canonical encodings
+    Strict,
+    /// Skip unknown keys and ignore encoding details (newer firmware may add
+    /// fields)
+    #[default]
+    Lenient,
+}
+
+/// CBOR decode failure with the location it happened at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CborDecodeError {
+    /// Dotted path to the failing item, rooted at the UR type
+    pub path: String,
+    /// Byte offset into the CBOR payload
+    pub offset: usize,
+    /// What went wrong
+    pub message: String,
+}
+
+impl fmt::Display for CborDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {} (byte {})",
+            self.message, self.path, self.offset
+        )
+    }
+}
+
+impl std::error::Error for CborDecodeError {}
+
+/// Map layout of one message or nested structure
+struct Schema {
+    /// UR type (or descriptive name for nested structures)
+    name: &'static str,
+    /// CBOR tag the structure is wrapped in, if any
+    tag: Option<u64>,
+    /// Whether the tag identifies the structure inside other messages too
+    ///
+    /// Tags 401/402 double as crypto-output script expressions, so the
+    /// message tags are only trusted at the top level.
+    nested: bool,
+    /// Integer map keys and their field names
+    fields: &'static [(u64, &'static str)],
+}
+
+const SCHEMAS: &[Schema] = &[
+    Schema {
+        name: "crypto-keypath",
+        tag: Some(tags::CRYPTO_KEYPATH),
+        nested: true,
+        fields: &[(1, "components"), (2, "source_fingerprint"), (3, "depth")],
+    },
+    Schema {
+        name: "crypto-hdkey",
+        tag: Some(tags::CRYPTO_HDKEY),
+        nested: true,
+        fields: &[
+            (1, "is_master"),
+            (2, "is_private"),
+            (3, "key_data"),
+            (4, "chain_code"),
+            (5, "use_info"),
+            (6, "origin"),
+            (7, "children"),
+            (8, "parent_fingerprint"),
+            (9, "name"),
+            (10, "note"),
+        ],
+    },
+    Schema {
+        name: "crypto-coin-info",
+        tag: Some(tags::CRYPTO_COIN_INFO),
+        nested: true,
+        fields: &[(1, "type"), (2, "network")],
+    },
+    Schema {
+        name: "crypto-eckey",
+        tag: Some(tags::CRYPTO_ECKEY),
+        nested: true,
+        fields: &[(1, "curve"), (2, "is_private"), (3, "data")],
+    },
+    Schema {
+        name: "multi-key",
+        tag: Some(tags::SCRIPT_MULTI),
+        nested: true,
+        fields: &[(1, "threshold"), (2, "keys")],
+    },
+    Schema {
+        name: "sorted-multi-key",
+        tag: Some(tags::SCRIPT_SORTED_MULTI),
+        nested: true,
+        fields: &[(1, "threshold"), (2, "keys")],
+    },
+    Schema {
+        name: "crypto-account",
+        tag: None,
+        nested: false,
+        fields: &[
+            (1, "master_fingerprint"),
+            (2, "public_key"),
+            (3, "key_path"),
+            (4, "chain_code"),
+        ],
+    },
+    Schema {
+        name: "crypto-multi-accounts",
+        tag: Some(tags::CRYPTO_MULTI_ACCOUNTS),
+        nested: false,
+        fields: &[
+            (1, "master_fingerprint"),
+            (2, "keys"),
+            (3, "device_type"),
+            (4, "device_id"),
+            (5, "firmware_version"),
+        ],
+    },
+    Schema {
+        name: "eth-sign-request",
+        tag: Some(tags::ETH_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "data_type"),
+            (4, "chain_id"),
+            (5, "derivation_path"),
+            (6, "address"),
+            (7, "origin"),
+        ],
+    },
+    Schema {
+        name: "eth-signature",
+        tag: Some(tags::ETH_SIGNATURE),
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signature"), (3, "origin")],
+    },
+    Schema {
+        name: "sol-sign-request",
+        tag: Some(tags::SOL_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "derivation_path"),
+            (4, "origin"),
+        ],
+    },
+    Schema {
+        name: "sol-signature",
+        tag: Some(tags::SOL_SIGNATURE),
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signature"), (3, "public_key")],
+    },
+    Schema {
+        name: "near-sign-request",
+        tag: Some(tags::NEAR_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "derivation_path"),
+            (4, "account"),
+            (5, "origin"),
+        ],
+    },
+    Schema {
+        name: "near-signature",
+        tag: Some(tags::NEAR_SIGNATURE),
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signatures")],
+    },
+    Schema {
+        name: "cardano-utxo",
+        tag: Some(tags::CARDANO_UTXO),
+        nested: true,
+        fields: &[
+            (1, "transaction_hash"),
+            (2, "index"),
+            (3, "amount"),
+            (4, "key_path"),
+            (5, "address"),
+        ],
+    },
+    Schema {
+        name: "cardano-sign-request",
+        tag: Some(tags::CARDANO_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "utxos"),
+            (4, "cert_keys"),
+            (5, "origin"),
+        ],
+    },
+    Schema {
+        name: "cardano-signature",
+        tag: Some(tags::CARDANO_SIGNATURE),
+        nested: false,
+        fields: &[(1, "request_id"), (2, "witness_set")],
+    },
+    Schema {
+        name: "cardano-cert-key",
+        tag: Some(tags::CARDANO_CERT_KEY),
+        nested: true,
+        fields: &[(1, "key_hash"), (2, "key_path")],
+    },
+    Schema {
+        name: "aptos-sign-request",
+        tag: Some(tags::APTOS_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "derivation_paths"),
+            (4, "accounts"),
+            (5, "origin"),
+            (6, "sign_type"),
+        ],
+    },
+    Schema {
+        name: "aptos-signature",
+        tag: Some(tags::APTOS_SIGNATURE),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "signature"),
+            (3, "authentication_key"),
+        ],
+    },
+    Schema {
+        name: "cosmos-sign-request",
+        tag: Some(tags::COSMOS_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "data_type"),
+            (4, "derivation_paths"),
+            (5, "addresses"),
+            (6, "origin"),
+        ],
+    },
+    Schema {
+        name: "cosmos-signature",
+        tag: Some(tags::COSMOS_SIGNATURE),
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signature"), (3, "public_key")],
+    },
+    Schema {
+        name: "keystone-sign-request",
+        tag: Some(tags::KEYSTONE_SIGN_REQUEST),
+        nested: false,
+        fields: &[(1, "sign_data")],
+    },
+    Schema {
+        name: "sui-sign-request",
+        tag: Some(tags::SUI_SIGN_REQUEST),
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "intent_message"),
+            (3, "derivation_paths"),
+            (4, "addresses"),
+            (5, "origin"),
+        ],
+    },
+    Schema {
+        name: "sui-signature",
+        tag: Some(tags::SUI_SIGNATURE),
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signature"), (3, "public_key")],
+    },
+    Schema {
+        name: "hbar-sign-request",
+        tag: None,
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "transaction"),
+            (3, "derivation_path"),
+            (4, "account_id"),
+            (5, "origin"),
+        ],
+    },
+    Schema {
+        name: "hbar-signature",
+        tag: None,
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signature"), (3, "public_key")],
+    },
+    Schema {
+        name: "stellar-sign-request",
+        tag: None,
+        nested: false,
+        fields: &[
+            (1, "request_id"),
+            (2, "sign_data"),
+            (3, "derivation_path"),
+            (4, "address"),
+            (5, "origin"),
+            (6, "sign_type"),
+        ],
+    },
+    Schema {
+        name: "stellar-signature",
+        tag: None,
+        nested: false,
+        fields: &[(1, "request_id"), (2, "signature")],
+    },
+];
+
+/// Name used for the root of a path when the message type is unknown
+const UNKNOWN_ROOT: &str = "$";
+
+/// Deepest nesting of arrays, maps and tags walked
+///
+/// Real messages nest a handful of levels; the bound keeps crafted payloads
+/// from exhausting the stack.
+pub const MAX_NESTING: usize = 32;
+
+/// Check `bytes` is a single well-formed CBOR item
+///
+/// `root` is the UR type, used to name the top-level fields. In lenient mode
+/// only well-formedness is checked; strict mode applies the extra rules
+/// listed in the module docs.
+pub fn validate(
+    root: Option<&str>,
+    bytes: &[u8],
+    mode: DecodeMode,
+) -> std::result::Result<(), CborDecodeError> {
+    let mut walker = Walker::new(root, bytes, mode, None);
+    walker.walk_root()?;
+    if mode == DecodeMode::Strict && walker.pos < bytes.len() {
+        return Err(walker.error(walker.pos, "trailing bytes after top-level item"));
+    }
+    Ok(())
+}
+
+/// Path of the innermost item containing byte `offset`
+///
+/// Falls back to the root name when the offset lies past every item (for
+/// example a missing field reported once a map has been read).
+pub fn locate(root: Option<&str>, bytes: &[u8], offset: usize) -> String {
+    let mut walker = Walker::new(root, bytes, DecodeMode::Lenient, Some(offset));
+    let walked = walker.walk_root();
+    match (walker.located.take(), walked) {
+        (Some(path), _) => path,
+        (None, Err(e)) => e.path,
+        (None, Ok(())) => walker.render_path(),
+    }
+}
+
+/// Offset of a typed-decode failure, from the failing decoder's position
+///
+/// `minicbor` reports a type mismatch after consuming the offending item's
+/// initial byte, so the item starts one byte earlier. Other failures (missing
+/// fields, bad tags, truncation) are reported where decoding stopped.
+pub fn error_offset(e: &minicbor::decode::Error, position: usize) -> usize {
+    if e.is_type_mismatch() {
+        position.saturating_sub(1)
+    } else {
+        position
+    }
+}
+
+fn root_schema(root: Option<&str>, bytes: &[u8]) -> Option<&'static Schema> {
+    match root {
+        Some(name) => SCHEMAS.iter().find(|schema| schema.name == name),
+        None => {
+            let tag = leading_tag(bytes)?;
+            SCHEMAS.iter().find(|schema| schema.tag == Some(tag))
+        }
+    }
+}
+
+fn nested_schema(tag: u64) -> Option<&'static Schema> {
+    SCHEMAS
+        .iter()
+        .find(|schema| schema.nested && schema.tag == Some(tag))
+}
+
+fn leading_tag(bytes: &[u8]) -> Option<u64> {
+    let (initial, rest) = bytes.split_first()?;
+    if initial >> 5 != 6 {
+        return None;
+    }
+    match initial & 0x1f {
+        info @ 0..=23 => Some(u64::from(info)),
+        info @ 24..=27 => {
+            let width = 1usize << (info - 24);
+            let arg = rest.get(..width)?;
+            Some(
+                arg.iter()
+                    .fold(0, |acc, byte| (acc << 8) | u64::from(*byte)),
+            )
+        }
+        _ => None,
+    }
+}
+
+enum Segment {
+    Field(&'static str),
+    Key(u64),
+    Index(u64),
+    NonIntegerKey,
+}
+
+struct Header {
+    major: u8,
+    info: u8,
+    /// Argument value; `None` for indefinite lengths
+    arg: Option<u64>,
+}
+
+struct Walker<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    mode: DecodeMode,
+    root: String,
+    schema: Option<&'static Schema>,
+    path: Vec<Segment>,
+    depth: usize,
+    target: Option<usize>,
+    located: Option<String>,
+}
+
+impl<'a> Walker<'a> {
+    fn new(root: Option<&str>, bytes: &'a [u8], mode: DecodeMode, target: Option<usize>) -> Self {
+        let schema = root_schema(root, bytes);
+        let root = root
+            .or(schema.map(|schema| schema.name))
+            .unwrap_or(UNKNOWN_ROOT)
+            .to_string();
+        Self {
+            bytes,
+            pos: 0,
+            mode,
+            root,
+            schema,
+            path: Vec::new(),
+            depth: 0,
+            target,
+            located: None,
+        }
+    }
+
+    fn walk_root(&mut self) -> std::result::Result<(), CborDecodeError> {
+        if self.bytes.is_empty() {
+            return Err(self.error(0, "empty payload"));
+        }
+        self.walk(self.schema)
+    }
+
+    fn strict(&self) -> bool {
+        self.mode == DecodeMode::Strict
+    }
+
+    fn render_path(&self) -> String {
+        let mut path = self.root.clone();
+        for segment in &self.path {
+            match segment {
+                Segment::Field(name) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+                Segment::Key(key) => path.push_str(&format!(".{key}")),
+                Segment::Index(index) => path.push_str(&format!("[{index}]")),
+                Segment::NonIntegerKey => path.push_str(".?"),
+            }
+        }
+        path
+    }
+
+    fn error(&self, offset: usize, message: &str) -> CborDecodeError {
+        CborDecodeError {
+            path: self.render_path(),
+            offset,
+            message: message.to_string(),
+        }
+    }
+
+    fn take(&mut self, len: u64) -> std::result::Result<&'a [u8], CborDecodeError> {
+        let start = self.pos;
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| start.checked_add(len))
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| self.error(start, "unexpected end of input"))?;
+        self.pos = end;
+        Ok(&self.bytes[start..end])
+    }
+
+    fn header(&mut self) -> std::result::Result<Header, CborDecodeError> {
+        let start = self.pos;
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        let arg = match info {
+            0..=23 => Some(u64::from(info)),
+            24..=27 => {
+                let width = 1u64 << (info - 24);
+                let arg = self
+                    .take(width)?
+                    .iter()
+                    .fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte));
+                // Floats are exempt: their width is the precision, not a length
+                if self.strict() && major != 7 && !is_shortest(info, arg) {
+                    return Err(self.error(start, "non-canonical integer or length encoding"));
+                }
+                Some(arg)
+            }
+            28..=30 => return Err(self.error(start, "reserved additional information")),
+            _ => None,
+        };
+        if arg.is_none() && !matches!(major, 2..=5 | 7) {
+            return Err(self.error(start, "indefinite length on a type without one"));
+        }
+        Ok(Header { major, info, arg })
+    }
+
+    fn walk(
+        &mut self,
+        schema: Option<&'static Schema>,
+    ) -> std::result::Result<(), CborDecodeError> {
+        let start = self.pos;
+        if self.depth >= MAX_NESTING {
+            return Err(self.error(start, &format!("nested deeper than {MAX_NESTING}")));
+        }
+        self.depth += 1;
+        self.walk_item(start, schema)?;
+        self.depth -= 1;
+        if let Some(target) = self.target {
+            if self.located.is_none() && start <= target && target < self.pos {
+                self.located = Some(self.render_path());
+            }
+        }
+        Ok(())
+    }
+
+    fn walk_item(
+        &mut self,
+        start: usize,
+        schema: Option<&'static Schema>,
+    ) -> std::result::Result<(), CborDecodeError> {
+        let header = self.header()?;
+        if header.arg.is_none() && header.major != 7 && self.strict() {
+            return Err(self.error(start, "indefinite-length item"));
+        }
+        match (header.major, header.arg) {
+            (0 | 1, _) => {}
+            (2 | 3, Some(len)) => {
+                let content = self.take(len)?;
+                if header.major == 3 && std::str::from_utf8(content).is_err() {
+                    return Err(self.error(start, "invalid UTF-8 in text string"));
+                }
+            }
+            (2 | 3, None) => self.walk_chunks(header.major)?,
+            (4, len) => self.walk_array(len)?,
+            (5, len) => self.walk_map(start, len, schema)?,
+            (6, Some(tag)) => {
+                // A tag names the structure it wraps; the root schema comes
+                // from the UR type when the message itself is untagged
+                let inner = if start == 0 {
+                    schema.or_else(|| nested_schema(tag))
+                } else {
+                    nested_schema(tag)
+                };
+                self.walk(inner)?;
+            }
+            (7, None) => return Err(self.error(start, "unexpected break")),
+            (7, Some(value)) => {
+                if header.info == 24 && value < 32 {
+                    return Err(self.error(start, "invalid simple value"));
+                }
+            }
+            _ => unreachable!("major type is three bits"),
+        }
+        Ok(())
+    }
+
+    fn walk_chunks(&mut self, major: u8) -> std::result::Result<(), CborDecodeError> {
+        loop {
+            let start = self.pos;
+            if self.bytes.get(start) == Some(&0xff) {
+                self.pos += 1;
+                return Ok(());
+            }
+            let chunk = self.header()?;
+            let Some(len) = chunk.arg.filter(|_| chunk.major == major) else {
+                return Err(self.error(start, "invalid chunk in indefinite-length string"));
+            };
+            self.take(len)?;
+        }
+    }
+
+    fn at_break(&mut self) -> bool {
+        if self.bytes.get(self.pos) == Some(&0xff) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn walk_array(&mut self, len: Option<u64>) -> std::result::Result<(), CborDecodeError> {
+        let mut index = 0;
+        while len.map_or_else(|| !self.at_break(), |len| index < len) {
+            self.path.push(Segment::Index(index));
+            self.walk(None)?;
+            self.path.pop();
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn walk_map(
+        &mut self,
+        start: usize,
+        len: Option<u64>,
+        schema: Option<&'static Schema>,
+    ) -> std::result::Result<(), CborDecodeError> {
+        if schema.is_none() && self.strict() {
+            return Err(self.error(start, "map with no known layout"));
+        }
+        let mut previous: Option<&'a [u8]> = None;
+        let mut index = 0;
+        while len.map_or_else(|| !self.at_break(), |len| index < len) {
+            let key_start = self.pos;
+            let key = self.map_key()?;
+            let bytes = self.bytes;
+            let key_bytes = &bytes[key_start..self.pos];
+            if self.strict() {
+                // Canonical order: shorter encodings first, then bytewise
+                if let Some(previous) = previous {
+                    let ordered = (previous.len(), previous) < (key_bytes.len(), key_bytes);
+                    if !ordered {
+                        let message = if previous == key_bytes {
+                            "duplicate map key"
+                        } else {
+                            "map keys not in canonical order"
+                        };
+                        return Err(self.error(key_start, message));
+                    }
+                }
+            }
+            previous = Some(key_bytes);
+
+            let segment = match (key, schema) {
+                (Some(key), Some(schema)) => {
+                    match schema.fields.iter().find(|(field, _)| *field == key) {
+                        Some((_, name)) => Segment::Field(name),
+                        None if self.strict() => {
+                            return Err(self.error(
+                                key_start,
+                                &format!("unknown map key {key} in {}", schema.name),
+                            ));
+                        }
+                        None => Segment::Key(key),
+                    }
+                }
+                (Some(key), None) => Segment::Key(key),
+                (None, Some(schema)) if self.strict() => {
+                    return Err(self.error(
+                        key_start,
+                        &format!("non-integer map key in {}", schema.name),
+                    ));
+                }
+                (None, _) => Segment::NonIntegerKey,
+            };
+            self.path.push(segment);
+            self.walk(None)?;
+            self.path.pop();
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// Walk a map key, returning it when it is an unsigned integer
+    fn map_key(&mut self) -> std::result::Result<Option<u64>, CborDecodeError> {
+        let start = self.pos;
+        if self.bytes.get(start).is_some_and(|byte| byte >> 5 == 0) {
+            return Ok(self.header()?.arg);
+        }
+        self.walk(None)?;
+        Ok(None)
+    }
+}
+
+fn is_shortest(info: u8, arg: u64) -> bool {
+    match info {
+        24 => arg >= 24,
+        25 => arg > 0xff,
+        26 => arg > 0xffff,
+        27 => arg > 0xffff_ffff,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // eth-sign-request: {1: 37(h'00..'), 2: h'01', 3: 1,
+    //   5: 304({1: [44, true, 60, true, 0, true]})}
+    fn eth_request(components: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0xd9, 0x01, 0x91, 0xa4, 0x01, 0xd8, 0x25, 0x50];
+        bytes.extend_from_slice(&[0u8; 16]);
+        bytes.extend_from_slice(&[0x02, 0x41, 0x01, 0x03, 0x01, 0x05]);
+        bytes.extend_from_slice(&[0xd9, 0x01, 0x30, 0xa1, 0x01]);
+        bytes.extend_from_slice(components);
+        bytes
+    }
+
+    const COMPONENTS: &[u8] = &[0x86, 0x18, 0x2c, 0xf5, 0x18, 0x3c, 0xf5, 0x00, 0xf5];
+
+    #[test]
+    fn test_canonical_payload_passes_both_modes() {
+        let bytes = eth_request(COMPONENTS);
+        assert!(validate(Some("eth-sign-request"), &bytes, DecodeMode::Strict).is_ok());
+        assert!(validate(None, &bytes, DecodeMode::Lenient).is_ok());
+    }
+
+    #[test]
+    fn test_strict_rejects_what_lenient_accepts() {
+        // 60 encoded in two bytes instead of one
+        let wide = eth_request(&[0x86, 0x18, 0x2c, 0xf5, 0x19, 0x00, 0x3c, 0xf5, 0x00, 0xf5]);
+        let err = validate(Some("eth-sign-request"), &wide, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.path, "eth-sign-request.derivation_path.components[2]");
+        assert_eq!(err.offset, 39);
+        assert!(validate(Some("eth-sign-request"), &wide, DecodeMode::Lenient).is_ok());
+
+        let mut trailing = eth_request(COMPONENTS);
+        trailing.push(0x00);
+        let err = validate(None, &trailing, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.message, "trailing bytes after top-level item");
+        assert_eq!(err.offset, trailing.len() - 1);
+
+        let indefinite = eth_request(&[0x9f, 0x18, 0x2c, 0xf5, 0xff]);
+        let err = validate(None, &indefinite, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.message, "indefinite-length item");
+        assert_eq!(err.path, "eth-sign-request.derivation_path.components");
+        assert!(validate(None, &indefinite, DecodeMode::Lenient).is_ok());
+    }
+
+    #[test]
+    fn test_strict_rejects_unknown_and_unordered_keys() {
+        // hbar-signature {1: h'', 9: h''}: untagged, named from the UR type
+        let unknown = [0xa2, 0x01, 0x40, 0x09, 0x40];
+        let err = validate(Some("hbar-signature"), &unknown, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.message, "unknown map key 9 in hbar-signature");
+        assert_eq!(err.path, "hbar-signature");
+        assert_eq!(err.offset, 3);
+        assert!(validate(Some("hbar-signature"), &unknown, DecodeMode::Lenient).is_ok());
+
+        let unordered = [0xa2, 0x02, 0x40, 0x01, 0x40];
+        let err = validate(Some("hbar-signature"), &unordered, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.message, "map keys not in canonical order");
+    }
+
+    #[test]
+    fn test_strict_rejects_maps_of_unknown_layout() {
+        // Custom UR type whose body is a map: its keys cannot be checked
+        let custom = [0xa1, 0x01, 0x40];
+        let err = validate(Some("x-custody-approval"), &custom, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.message, "map with no known layout");
+        assert_eq!(err.path, "x-custody-approval");
+        assert!(validate(Some("x-custody-approval"), &custom, DecodeMode::Lenient).is_ok());
+
+        // crypto-output wsh(sortedmulti(1, 303({3: h'')})) with an extra key
+        let mut output = vec![
+            0xd9, 0x01, 0x91, 0xd9, 0x01, 0x97, 0xa2, 0x01, 0x01, 0x02, 0x81,
+        ];
+        output.extend_from_slice(&[0xd9, 0x01, 0x2f, 0xa1, 0x03, 0x40]);
+        assert!(validate(Some("crypto-output"), &output, DecodeMode::Strict).is_ok());
+        output[6] = 0xa3;
+        output.extend_from_slice(&[0x03, 0x00]);
+        let err = validate(Some("crypto-output"), &output, DecodeMode::Strict).unwrap_err();
+        assert_eq!(err.message, "unknown map key 3 in sorted-multi-key");
+
+        // crypto-psbt is a bare byte string
+        assert!(validate(Some("crypto-psbt"), &[0x42, 0x70, 0x73], DecodeMode::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_deep_nesting_is_an_error() {
+        let mut bytes = vec![0x81; 100_000];
+        bytes.push(0x00);
+        let err = validate(None, &bytes, DecodeMode::Lenient).unwrap_err();
+        assert_eq!(err.message, "nested deeper than 32");
+        assert_eq!(err.offset, MAX_NESTING);
+        assert_eq!(
+            locate(None, &bytes, 99_999),
+            format!("${}", "[0]".repeat(32))
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_offset_is_item_start() {
+        // {1: "x"} decoded as a map of u8 to bytes
+        let bytes = [0xa1, 0x01, 0x61, b'x'];
+        let mut d = minicbor::Decoder::new(&bytes);
+        d.map().unwrap();
+        d.u8().unwrap();
+        let err = d.bytes().unwrap_err();
+        assert_eq!(error_offset(&err, d.position()), 2);
+    }
+
+    #[test]
+    fn test_locate_names_innermost_item() {
+        let bytes = eth_request(COMPONENTS);
+        assert_eq!(
+            locate(None, &bytes, 41),
+            "eth-sign-request.derivation_path.components[3]"
+        );
+        assert_eq!(locate(None, &bytes, 28), "eth-sign-request.data_type");
+        assert_eq!(locate(None, &bytes, bytes.len()), "eth-sign-request");
+        assert_eq!(locate(None, &[0xa1, 0x01, 0x40], 2), "$.1");
+    }
+
+    #[test]
+    fn test_truncated_input_reports_offset() {
+        let bytes = eth_request(COMPONENTS);
+        let err = validate(None, &bytes[..bytes.len() - 2], DecodeMode::Lenient).unwrap_err();
+        assert_eq!(err.message, "unexpected end of input");
+        assert_eq!(err.path, "eth-sign-request.derivation_path.components[4]");
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::error::{Error, Result};
 use crate::keystone::cbor;
+use minicbor::data::Type;
 use minicbor::{Decoder, Encoder};
 use serde::{Deserialize, Serialize};
 
@@ -14,27 +15,37 @@ pub struct PathComponent {
     pub index: u32,
     /// Whether this is a hardened derivation
     pub hardened: bool,
+    /// Whether this component is a wildcard (`*`), as used in descriptor child paths
+    #[serde(default)]
+    pub wildcard: bool,
 }
 
 impl PathComponent {
     /// Create a new path component
     pub fn new(index: u32, hardened: bool) -> Self {
-        Self { index, hardened }
+        Self {
+            index,
+            hardened,
+            wildcard: false,
+        }
     }
 
     /// Create a hardened component
     pub fn hardened(index: u32) -> Self {
-        Self {
-            index,
-            hardened: true,
-        }
+        Self::new(index, true)
     }
 
     /// Create a normal (non-hardened) component
     pub fn normal(index: u32) -> Self {
+        Self::new(index, false)
+    }
+
+    /// Create a wildcard component (`*`)
+    pub fn wildcard(hardened: bool) -> Self {
         Self {
-            index,
-            hardened: false,
+            index: 0,
+            hardened,
+            wildcard: true,
         }
     }
 
@@ -102,6 +113,10 @@ impl CryptoKeyPath {
                     component
                 };
 
+                if index_str == "*" {
+                    return Ok(PathComponent::wildcard(hardened));
+                }
+
                 index_str
                     .parse::<u32>()
                     .map(|index| PathComponent::new(index, hardened))
@@ -124,10 +139,15 @@ impl CryptoKeyPath {
             .components
             .iter()
             .map(|c| {
-                if c.hardened {
-                    format!("{}'", c.index)
+                let index = if c.wildcard {
+                    "*".to_string()
                 } else {
                     c.index.to_string()
+                };
+                if c.hardened {
+                    format!("{}'", index)
+                } else {
+                    index
                 }
             })
             .collect();
@@ -157,11 +177,24 @@ impl minicbor::Encode<()> for CryptoKeyPath {
 
         e.map(map_size)?;
 
-        // Key 1: components (array of u32 with hardened bit)
+        // Key 1: components (array of u32 with hardened bit). Wildcards can only be
+        // expressed in the BCR-2020-007 form of `index, is-hardened` pairs.
         e.u64(1)?;
-        e.array(self.components.len() as u64)?;
-        for component in &self.components {
-            e.u32(component.to_bip32_index())?;
+        if self.components.iter().any(|c| c.wildcard) {
+            e.array(self.components.len() as u64 * 2)?;
+            for component in &self.components {
+                if component.wildcard {
+                    e.array(0)?;
+                } else {
+                    e.u32(component.index)?;
+                }
+                e.bool(component.hardened)?;
+            }
+        } else {
+            e.array(self.components.len() as u64)?;
+            for component in &self.components {
+                e.u32(component.to_bip32_index())?;
+            }
         }
 
         // Key 2: source fingerprint (optional)
@@ -209,21 +242,39 @@ impl<'b> minicbor::Decode<'b, ()> for CryptoKeyPath {
                         minicbor::decode::Error::message("Expected definite-length array")
                     })?;
                     let mut comps = Vec::new();
-                    for _ in 0..arr_len {
-                        let bip32_index = d.u32()?;
-                        let hardened = (bip32_index & 0x80000000) != 0;
-                        let index = bip32_index & 0x7FFFFFFF;
-                        comps.push(PathComponent::new(index, hardened));
+                    let mut remaining = arr_len;
+                    while remaining > 0 {
+                        remaining -= 1;
+                        let mut component = if d.datatype()? == Type::Array {
+                            // Wildcard `[]`; ranges `[low, high]` are treated as wildcards
+                            d.skip()?;
+                            PathComponent::wildcard(false)
+                        } else {
+                            let bip32_index = d.u32()?;
+                            let hardened = (bip32_index & 0x80000000) != 0;
+                            PathComponent::new(bip32_index & 0x7FFFFFFF, hardened)
+                        };
+
+                        // Device output pairs every index with an is-hardened flag
+                        if remaining > 0 && d.datatype()? == Type::Bool {
+                            remaining -= 1;
+                            component.hardened = d.bool()?;
+                        }
+                        comps.push(component);
                     }
                     components = Some(comps);
                 }
                 2 => {
-                    // Source fingerprint
-                    let bytes = d.bytes()?;
-                    if bytes.len() == 4 {
-                        let mut fp = [0u8; 4];
-                        fp.copy_from_slice(bytes);
-                        source_fingerprint = Some(fp);
+                    // Source fingerprint: bytes here, uint32 in device output
+                    if d.datatype()? == Type::Bytes {
+                        let bytes = d.bytes()?;
+                        if bytes.len() == 4 {
+                            let mut fp = [0u8; 4];
+                            fp.copy_from_slice(bytes);
+                            source_fingerprint = Some(fp);
+                        }
+                    } else {
+                        source_fingerprint = Some(d.u32()?.to_be_bytes());
                     }
                 }
                 3 => {
@@ -287,4 +338,38 @@ mod tests {
 
         assert_eq!(path, decoded);
     }
+
+    #[test]
+    fn test_decode_index_hardened_pairs() {
+        // {1: [84, true, 0, true, 0, true], 2: 0x73c5da0a} as produced by devices
+        let mut bytes = Vec::new();
+        let mut e = Encoder::new(&mut bytes);
+        e.tag(minicbor::data::Tag::Unassigned(cbor::tags::CRYPTO_KEYPATH))
+            .unwrap()
+            .map(2)
+            .unwrap()
+            .u8(1)
+            .unwrap()
+            .array(6)
+            .unwrap();
+        for index in [84u32, 0, 0] {
+            e.u32(index).unwrap().bool(true).unwrap();
+        }
+        e.u8(2).unwrap().u32(0x73c5da0a).unwrap();
+
+        let decoded: CryptoKeyPath = cbor::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_string(), "m/84'/0'/0'");
+        assert_eq!(decoded.source_fingerprint, Some([0x73, 0xc5, 0xda, 0x0a]));
+    }
+
+    #[test]
+    fn test_wildcard_roundtrip() {
+        let path = CryptoKeyPath::from_str("0/*").unwrap();
+        assert_eq!(path.components[1], PathComponent::wildcard(false));
+        assert_eq!(path.to_string(), "m/0/*");
+
+        let bytes = cbor::to_bytes(&path).unwrap();
+        let decoded: CryptoKeyPath = cbor::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, path);
+    }
 }
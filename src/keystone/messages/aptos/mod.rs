@@ -0,0 +1,419 @@
+//! Aptos (APT) signature request and response types
+//!
+//! Reference: https://github.com/KeystoneHQ/keystone-sdk-rust
+
+pub mod transaction;
+
+pub use transaction::{AptosEntryFunction, AptosPayload, AptosTransaction};
+
+use crate::error::{Error, Result};
+use crate::keystone::cbor;
+use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::{Decoder, Encoder};
+use uuid::Uuid;
+
+/// What the Aptos sign data contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AptosSignType {
+    /// Transaction signed by a single account
+    Single = 1,
+    /// Transaction signed by several accounts (multi-agent)
+    Multi = 2,
+    /// Off-chain message
+    Message = 3,
+}
+
+impl AptosSignType {
+    /// Short lowercase label for output
+    pub fn label(&self) -> &'static str {
+        match self {
+            AptosSignType::Single => "single",
+            AptosSignType::Multi => "multi",
+            AptosSignType::Message => "message",
+        }
+    }
+}
+
+impl TryFrom<u8> for AptosSignType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            1 => Ok(AptosSignType::Single),
+            2 => Ok(AptosSignType::Multi),
+            3 => Ok(AptosSignType::Message),
+            _ => Err(Error::InvalidKeystonePayload(format!(
+                "Invalid Aptos sign type: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Aptos signature request
+#[derive(Debug, Clone)]
+pub struct AptosSignRequest {
+    /// Optional request identifier
+    pub request_id: Option<Uuid>,
+    /// Signing message (prefixed BCS transaction or off-chain message)
+    pub sign_data: Vec<u8>,
+    /// Authentication key derivation paths, one per signer
+    pub derivation_paths: Vec<CryptoKeyPath>,
+    /// Optional 32-byte account addresses, one per signer
+    pub accounts: Vec<Vec<u8>>,
+    /// Optional application origin string
+    pub origin: Option<String>,
+    /// What `sign_data` contains
+    pub sign_type: AptosSignType,
+}
+
+impl AptosSignRequest {
+    /// Construct a new Aptos sign request
+    pub fn new(
+        sign_data: Vec<u8>,
+        derivation_paths: Vec<CryptoKeyPath>,
+        sign_type: AptosSignType,
+        request_id: Option<Uuid>,
+    ) -> Self {
+        Self {
+            request_id,
+            sign_data,
+            derivation_paths,
+            accounts: Vec::new(),
+            origin: None,
+            sign_type,
+        }
+    }
+
+    /// Attach the signing account addresses
+    pub fn with_accounts(mut self, accounts: Vec<Vec<u8>>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    /// Attach an origin descriptor (e.g. dApp name)
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Decode `sign_data` as a raw transaction
+    pub fn decode_transaction(&self) -> Result<AptosTransaction> {
+        match self.sign_type {
+            AptosSignType::Single | AptosSignType::Multi => {
+                AptosTransaction::decode(&self.sign_data)
+            }
+            AptosSignType::Message => Err(Error::InvalidKeystonePayload(
+                "Aptos sign request carries a message, not a transaction".to_string(),
+            )),
+        }
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 3; // sign_data + derivation_paths + sign_type
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        if !self.accounts.is_empty() {
+            len += 1;
+        }
+        if self.origin.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for AptosSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::APTOS_SIGN_REQUEST,
+        ))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.bytes(&self.sign_data)?;
+
+        e.u8(3)?;
+        e.array(self.derivation_paths.len() as u64)?;
+        for path in &self.derivation_paths {
+            path.encode(e, ctx)?;
+        }
+
+        if !self.accounts.is_empty() {
+            e.u8(4)?;
+            e.array(self.accounts.len() as u64)?;
+            for account in &self.accounts {
+                e.bytes(account)?;
+            }
+        }
+
+        if let Some(ref origin) = self.origin {
+            e.u8(5)?;
+            e.str(origin)?;
+        }
+
+        e.u8(6)?;
+        e.u8(self.sign_type as u8)?;
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for AptosSignRequest {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::APTOS_SIGN_REQUEST) {
+            return Err(minicbor::decode::Error::message(
+                "expected aptos-sign-request tag",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut sign_data = None;
+        let mut derivation_paths = None;
+        let mut accounts = Vec::new();
+        let mut origin = None;
+        let mut sign_type = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => sign_data = Some(d.bytes()?.to_vec()),
+                3 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length array")
+                    })?;
+                    let mut paths = Vec::with_capacity(len as usize);
+                    for _ in 0..len {
+                        paths.push(CryptoKeyPath::decode(d, ctx)?);
+                    }
+                    derivation_paths = Some(paths);
+                }
+                4 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length array")
+                    })?;
+                    for _ in 0..len {
+                        accounts.push(d.bytes()?.to_vec());
+                    }
+                }
+                5 => origin = Some(d.str()?.to_string()),
+                6 => {
+                    sign_type = Some(
+                        AptosSignType::try_from(d.u8()?)
+                            .map_err(|_| minicbor::decode::Error::message("invalid sign type"))?,
+                    )
+                }
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            sign_data: sign_data
+                .ok_or_else(|| minicbor::decode::Error::message("missing sign_data"))?,
+            derivation_paths: derivation_paths
+                .ok_or_else(|| minicbor::decode::Error::message("missing derivation_paths"))?,
+            accounts,
+            origin,
+            sign_type: sign_type
+                .ok_or_else(|| minicbor::decode::Error::message("missing sign_type"))?,
+        })
+    }
+}
+
+/// Aptos signature response
+#[derive(Debug, Clone)]
+pub struct AptosSignature {
+    /// Request identifier echo
+    pub request_id: Option<Uuid>,
+    /// Signature bytes (64 bytes Ed25519 signature)
+    pub signature: Vec<u8>,
+    /// Ed25519 public key of the signer
+    pub authentication_key: Vec<u8>,
+}
+
+impl AptosSignature {
+    /// Create a new signature container
+    pub fn new(signature: Vec<u8>, authentication_key: Vec<u8>, request_id: Option<Uuid>) -> Self {
+        Self {
+            request_id,
+            signature,
+            authentication_key,
+        }
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 2; // signature + authentication_key
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for AptosSignature {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(cbor::tags::APTOS_SIGNATURE))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.bytes(&self.signature)?;
+
+        e.u8(3)?;
+        e.bytes(&self.authentication_key)?;
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for AptosSignature {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::APTOS_SIGNATURE) {
+            return Err(minicbor::decode::Error::message(
+                "expected aptos-signature tag",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut signature = None;
+        let mut authentication_key = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => signature = Some(d.bytes()?.to_vec()),
+                3 => authentication_key = Some(d.bytes()?.to_vec()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            signature: signature
+                .ok_or_else(|| minicbor::decode::Error::message("missing signature"))?,
+            authentication_key: authentication_key
+                .ok_or_else(|| minicbor::decode::Error::message("missing authentication_key"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aptos_sign_request_roundtrip() {
+        let paths = vec![
+            CryptoKeyPath::from_str("m/44'/637'/0'/0'/0'").unwrap(),
+            CryptoKeyPath::from_str("m/44'/637'/1'/0'/0'").unwrap(),
+        ];
+        let request_id = Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap());
+        let request = AptosSignRequest::new(
+            vec![0xde, 0xad],
+            paths.clone(),
+            AptosSignType::Multi,
+            request_id,
+        )
+        .with_accounts(vec![vec![0xaa; 32], vec![0xbb; 32]])
+        .with_origin("petra".to_string());
+
+        let bytes = request.to_cbor().unwrap();
+        let decoded = AptosSignRequest::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.request_id, request_id);
+        assert_eq!(decoded.sign_data, request.sign_data);
+        assert_eq!(decoded.derivation_paths, paths);
+        assert_eq!(decoded.accounts, request.accounts);
+        assert_eq!(decoded.origin.as_deref(), Some("petra"));
+        assert_eq!(decoded.sign_type, AptosSignType::Multi);
+    }
+
+    #[test]
+    fn test_aptos_signature_roundtrip() {
+        let sig = AptosSignature::new(vec![0u8; 64], vec![1u8; 32], None);
+
+        let bytes = sig.to_cbor().unwrap();
+        let decoded = AptosSignature::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.signature, sig.signature);
+        assert_eq!(decoded.authentication_key, sig.authentication_key);
+    }
+}
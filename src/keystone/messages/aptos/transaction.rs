@@ -0,0 +1,451 @@
+//! Aptos BCS `RawTransaction` decoding for review before signing
+//!
+//! The wallet signs `sha3_256("APTOS::RawTransaction") || bcs(RawTransaction)`.
+//! The prefix is stripped when present; entry function, script, module bundle
+//! and multisig payloads are decoded far enough to reach the gas fields.
+//!
+//! Reference: https://github.com/aptos-labs/aptos-core/tree/main/types/src/transaction
+
+use crate::error::{Error, Result};
+
+/// `sha3_256(b"APTOS::RawTransaction")`
+const RAW_TRANSACTION_PREFIX: [u8; 32] = [
+    0xb5, 0xe9, 0x7d, 0xb0, 0x7f, 0xa0, 0xbd, 0x0e, 0x55, 0x98, 0xaa, 0x36, 0x43, 0xa9, 0xbc, 0x6f,
+    0x66, 0x93, 0xbd, 0xdc, 0x1a, 0x9f, 0xec, 0x9e, 0x67, 0x4a, 0x46, 0x1e, 0xaa, 0x00, 0xb1, 0x93,
+];
+
+/// `sha3_256(b"APTOS::RawTransactionWithData")` (multi-agent and fee payer)
+const RAW_TRANSACTION_WITH_DATA_PREFIX: [u8; 32] = [
+    0x5e, 0xfa, 0x3c, 0x4f, 0x02, 0xf8, 0x3a, 0x0f, 0x4b, 0x2d, 0x69, 0xfc, 0x95, 0xc6, 0x07, 0xcc,
+    0x02, 0x82, 0x5c, 0xc4, 0xe7, 0xbe, 0x53, 0x6e, 0xf0, 0x99, 0x2d, 0xf0, 0x50, 0xd9, 0xe6, 0x7c,
+];
+
+const OCTAS_PER_APT: u64 = 100_000_000;
+
+const APTOS_COIN: &str = "0x1::aptos_coin::AptosCoin";
+
+/// Move entry function call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AptosEntryFunction {
+    /// Address the module is published under
+    pub module_address: [u8; 32],
+    /// Module name
+    pub module: String,
+    /// Function name
+    pub function: String,
+    /// Type arguments in Move syntax (e.g. `0x1::aptos_coin::AptosCoin`)
+    pub type_args: Vec<String>,
+    /// BCS-encoded arguments
+    pub args: Vec<Vec<u8>>,
+}
+
+impl AptosEntryFunction {
+    /// Fully qualified function name (e.g. `0x1::aptos_account::transfer`)
+    pub fn id(&self) -> String {
+        format!(
+            "{}::{}::{}",
+            format_short_address(&self.module_address),
+            self.module,
+            self.function
+        )
+    }
+
+    /// Recipient and amount in octas for APT transfers
+    ///
+    /// Covers `aptos_account::transfer`, and `coin::transfer` /
+    /// `aptos_account::transfer_coins` instantiated with `AptosCoin`.
+    pub fn apt_transfer(&self) -> Option<([u8; 32], u64)> {
+        let is_apt = |args: &[String]| args.len() == 1 && args[0] == APTOS_COIN;
+        let matches = match self.id().as_str() {
+            "0x1::aptos_account::transfer" => self.type_args.is_empty(),
+            "0x1::coin::transfer" | "0x1::aptos_account::transfer_coins" => is_apt(&self.type_args),
+            _ => false,
+        };
+        if !matches || self.args.len() != 2 {
+            return None;
+        }
+        let recipient = self.args[0].as_slice().try_into().ok()?;
+        let amount = u64::from_le_bytes(self.args[1].as_slice().try_into().ok()?);
+        Some((recipient, amount))
+    }
+}
+
+/// Transaction payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AptosPayload {
+    /// Move script (bytecode is not reviewed)
+    Script {
+        /// Length of the script bytecode
+        code_len: usize,
+    },
+    /// Module publication (deprecated payload)
+    ModuleBundle {
+        /// Number of modules published
+        modules: usize,
+    },
+    /// Entry function call
+    EntryFunction(AptosEntryFunction),
+    /// Multisig account transaction
+    Multisig {
+        /// Multisig account address
+        multisig_address: [u8; 32],
+        /// Entry function to execute, if given inline
+        function: Option<AptosEntryFunction>,
+    },
+}
+
+impl AptosPayload {
+    /// Short lowercase label for output
+    pub fn label(&self) -> &'static str {
+        match self {
+            AptosPayload::Script { .. } => "script",
+            AptosPayload::ModuleBundle { .. } => "module_bundle",
+            AptosPayload::EntryFunction(_) => "entry_function",
+            AptosPayload::Multisig { .. } => "multisig",
+        }
+    }
+}
+
+/// Decoded Aptos `RawTransaction`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AptosTransaction {
+    /// Sending account
+    pub sender: [u8; 32],
+    /// Sender sequence number
+    pub sequence_number: u64,
+    /// Transaction payload
+    pub payload: AptosPayload,
+    /// Maximum gas units
+    pub max_gas_amount: u64,
+    /// Price per gas unit in octas
+    pub gas_unit_price: u64,
+    /// Expiration as a Unix timestamp in seconds
+    pub expiration_timestamp_secs: u64,
+    /// Chain ID (1 = mainnet, 2 = testnet)
+    pub chain_id: u8,
+}
+
+impl AptosTransaction {
+    /// Decode a signing message or bare BCS `RawTransaction`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(&RAW_TRANSACTION_WITH_DATA_PREFIX) {
+            return Err(bcs_error(
+                "multi-agent and fee payer transactions are not decoded",
+            ));
+        }
+        let body = bytes
+            .strip_prefix(RAW_TRANSACTION_PREFIX.as_slice())
+            .unwrap_or(bytes);
+
+        let mut reader = BcsReader::new(body);
+        let transaction = Self {
+            sender: reader.array32()?,
+            sequence_number: reader.u64()?,
+            payload: reader.payload()?,
+            max_gas_amount: reader.u64()?,
+            gas_unit_price: reader.u64()?,
+            expiration_timestamp_secs: reader.u64()?,
+            chain_id: reader.u8()?,
+        };
+        reader.finish()?;
+        Ok(transaction)
+    }
+
+    /// Maximum fee the sender can be charged, in octas
+    pub fn max_fee(&self) -> Option<u64> {
+        self.max_gas_amount.checked_mul(self.gas_unit_price)
+    }
+
+    /// Network name for well-known chain IDs
+    pub fn network(&self) -> Option<&'static str> {
+        match self.chain_id {
+            1 => Some("mainnet"),
+            2 => Some("testnet"),
+            _ => None,
+        }
+    }
+}
+
+/// Format an address as `0x` followed by 64 hex digits
+pub fn format_address(address: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(address))
+}
+
+/// Format an address with leading zeros trimmed (`0x1`)
+fn format_short_address(address: &[u8; 32]) -> String {
+    let hex = hex::encode(address);
+    let trimmed = hex.trim_start_matches('0');
+    format!("0x{}", if trimmed.is_empty() { "0" } else { trimmed })
+}
+
+/// Format octas as APT without floating point (e.g. `0.5 APT`)
+pub fn format_octas_as_apt(octas: u64) -> String {
+    let whole = octas / OCTAS_PER_APT;
+    let fraction = octas % OCTAS_PER_APT;
+    if fraction == 0 {
+        return format!("{whole} APT");
+    }
+    let fraction = format!("{fraction:08}");
+    format!("{whole}.{} APT", fraction.trim_end_matches('0'))
+}
+
+struct BcsReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BcsReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| bcs_error("unexpected end of data"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn finish(&self) -> Result<()> {
+        if self.pos == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(bcs_error(&format!(
+                "{} trailing bytes",
+                self.bytes.len() - self.pos
+            )))
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn array32(&mut self) -> Result<[u8; 32]> {
+        Ok(self.take(32)?.try_into().unwrap())
+    }
+
+    /// ULEB128 length prefix, capped at `u32::MAX` as BCS requires
+    fn uleb_len(&mut self) -> Result<usize> {
+        let mut value = 0u64;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return usize::try_from(value)
+                    .ok()
+                    .filter(|_| value <= u64::from(u32::MAX))
+                    .ok_or_else(|| bcs_error("length out of range"));
+            }
+        }
+        Err(bcs_error("invalid ULEB128 length"))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.uleb_len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| bcs_error("invalid UTF-8 string"))
+    }
+
+    fn payload(&mut self) -> Result<AptosPayload> {
+        match self.uleb_len()? {
+            0 => {
+                let code_len = self.bytes()?.len();
+                self.type_tags()?;
+                for _ in 0..self.uleb_len()? {
+                    self.transaction_argument()?;
+                }
+                Ok(AptosPayload::Script { code_len })
+            }
+            1 => {
+                let modules = self.uleb_len()?;
+                for _ in 0..modules {
+                    self.bytes()?;
+                }
+                Ok(AptosPayload::ModuleBundle { modules })
+            }
+            2 => Ok(AptosPayload::EntryFunction(self.entry_function()?)),
+            3 => {
+                let multisig_address = self.array32()?;
+                let function = match self.u8()? {
+                    0 => None,
+                    1 => match self.uleb_len()? {
+                        0 => Some(self.entry_function()?),
+                        other => {
+                            return Err(bcs_error(&format!(
+                                "unknown multisig payload variant {other}"
+                            )));
+                        }
+                    },
+                    other => return Err(bcs_error(&format!("invalid option tag {other}"))),
+                };
+                Ok(AptosPayload::Multisig {
+                    multisig_address,
+                    function,
+                })
+            }
+            other => Err(bcs_error(&format!("unknown payload variant {other}"))),
+        }
+    }
+
+    fn entry_function(&mut self) -> Result<AptosEntryFunction> {
+        let module_address = self.array32()?;
+        let module = self.string()?;
+        let function = self.string()?;
+        let type_args = self.type_tags()?;
+        let args = (0..self.uleb_len()?)
+            .map(|_| self.bytes().map(<[u8]>::to_vec))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AptosEntryFunction {
+            module_address,
+            module,
+            function,
+            type_args,
+            args,
+        })
+    }
+
+    fn type_tags(&mut self) -> Result<Vec<String>> {
+        (0..self.uleb_len()?).map(|_| self.type_tag()).collect()
+    }
+
+    fn type_tag(&mut self) -> Result<String> {
+        Ok(match self.uleb_len()? {
+            0 => "bool".to_string(),
+            1 => "u8".to_string(),
+            2 => "u64".to_string(),
+            3 => "u128".to_string(),
+            4 => "address".to_string(),
+            5 => "signer".to_string(),
+            6 => format!("vector<{}>", self.type_tag()?),
+            7 => {
+                let address = self.array32()?;
+                let module = self.string()?;
+                let name = self.string()?;
+                let type_args = self.type_tags()?;
+                let base = format!("{}::{}::{}", format_short_address(&address), module, name);
+                if type_args.is_empty() {
+                    base
+                } else {
+                    format!("{}<{}>", base, type_args.join(", "))
+                }
+            }
+            8 => "u16".to_string(),
+            9 => "u32".to_string(),
+            10 => "u256".to_string(),
+            other => return Err(bcs_error(&format!("unknown type tag {other}"))),
+        })
+    }
+
+    fn transaction_argument(&mut self) -> Result<()> {
+        match self.uleb_len()? {
+            0 | 5 => {
+                self.take(1)?;
+            }
+            1 => {
+                self.take(8)?;
+            }
+            2 => {
+                self.take(16)?;
+            }
+            3 => {
+                self.take(32)?;
+            }
+            4 | 9 => {
+                self.bytes()?;
+            }
+            6 => {
+                self.take(2)?;
+            }
+            7 => {
+                self.take(4)?;
+            }
+            8 => {
+                self.take(32)?;
+            }
+            other => return Err(bcs_error(&format!("unknown script argument {other}"))),
+        }
+        Ok(())
+    }
+}
+
+fn bcs_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Aptos transaction: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signing message for `0x1::aptos_account::transfer(0x…bb, 150_000_000)`
+    fn transfer_signing_message() -> Vec<u8> {
+        let mut bytes = RAW_TRANSACTION_PREFIX.to_vec();
+        bytes.extend_from_slice(&[0xaa; 32]); // sender
+        bytes.extend_from_slice(&7u64.to_le_bytes()); // sequence_number
+        bytes.push(2); // EntryFunction
+        let mut module_address = [0u8; 32];
+        module_address[31] = 1;
+        bytes.extend_from_slice(&module_address);
+        bytes.push(13);
+        bytes.extend_from_slice(b"aptos_account");
+        bytes.push(8);
+        bytes.extend_from_slice(b"transfer");
+        bytes.push(0); // type args
+        bytes.push(2); // args
+        bytes.push(32);
+        bytes.extend_from_slice(&[0xbb; 32]);
+        bytes.push(8);
+        bytes.extend_from_slice(&150_000_000u64.to_le_bytes());
+        bytes.extend_from_slice(&2_000u64.to_le_bytes()); // max_gas_amount
+        bytes.extend_from_slice(&100u64.to_le_bytes()); // gas_unit_price
+        bytes.extend_from_slice(&1_700_000_000u64.to_le_bytes()); // expiration
+        bytes.push(1); // chain_id
+        bytes
+    }
+
+    #[test]
+    fn test_decode_apt_transfer() {
+        let transaction = AptosTransaction::decode(&transfer_signing_message()).unwrap();
+
+        assert_eq!(transaction.sender, [0xaa; 32]);
+        assert_eq!(transaction.sequence_number, 7);
+        assert_eq!(transaction.network(), Some("mainnet"));
+        assert_eq!(transaction.max_fee(), Some(200_000));
+        match &transaction.payload {
+            AptosPayload::EntryFunction(function) => {
+                assert_eq!(function.id(), "0x1::aptos_account::transfer");
+                assert_eq!(function.apt_transfer(), Some(([0xbb; 32], 150_000_000)));
+            }
+            other => panic!("unexpected payload {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_bare_raw_transaction_and_trailing_bytes() {
+        let message = transfer_signing_message();
+        assert!(AptosTransaction::decode(&message[32..]).is_ok());
+
+        let mut trailing = message.clone();
+        trailing.push(0);
+        let err = AptosTransaction::decode(&trailing).unwrap_err();
+        assert!(err.to_string().contains("1 trailing bytes"));
+    }
+
+    #[test]
+    fn test_format_octas_as_apt() {
+        assert_eq!(format_octas_as_apt(150_000_000), "1.5 APT");
+        assert_eq!(format_octas_as_apt(200_000_000), "2 APT");
+        assert_eq!(format_octas_as_apt(1), "0.00000001 APT");
+    }
+}
@@ -0,0 +1,777 @@
+//! Bitcoin PSBT (`crypto-psbt` / `psbt`) messages and a minimal BIP-174 parser
+//!
+//! The parser only extracts what is needed to review a transaction before
+//! signing: inputs, outputs, amounts and per-input signing state. It supports
+//! PSBT v0 (BIP-174) and v2 (BIP-370).
+//!
+//! Reference: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-006-urtypes.md
+
+use crate::error::{Error, Result};
+use crate::keystone::cbor;
+use minicbor::{Decoder, Encoder};
+use sha2::{Digest, Sha256};
+
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+// Global key types
+const GLOBAL_UNSIGNED_TX: u8 = 0x00;
+const GLOBAL_TX_VERSION: u8 = 0x02;
+const GLOBAL_FALLBACK_LOCKTIME: u8 = 0x03;
+const GLOBAL_INPUT_COUNT: u8 = 0x04;
+const GLOBAL_OUTPUT_COUNT: u8 = 0x05;
+const GLOBAL_VERSION: u8 = 0xfb;
+
+// Input key types
+const IN_NON_WITNESS_UTXO: u8 = 0x00;
+const IN_WITNESS_UTXO: u8 = 0x01;
+const IN_PARTIAL_SIG: u8 = 0x02;
+const IN_FINAL_SCRIPTSIG: u8 = 0x07;
+const IN_FINAL_SCRIPTWITNESS: u8 = 0x08;
+const IN_PREVIOUS_TXID: u8 = 0x0e;
+const IN_OUTPUT_INDEX: u8 = 0x0f;
+const IN_SEQUENCE: u8 = 0x10;
+const IN_TAP_KEY_SIG: u8 = 0x13;
+const IN_TAP_SCRIPT_SIG: u8 = 0x14;
+
+// Output key types
+const OUT_BIP32_DERIVATION: u8 = 0x02;
+const OUT_AMOUNT: u8 = 0x03;
+const OUT_SCRIPT: u8 = 0x04;
+const OUT_TAP_BIP32_DERIVATION: u8 = 0x07;
+
+/// Bitcoin PSBT carried by the `crypto-psbt` (or newer `psbt`) UR type
+///
+/// Both UR types decode to this struct; encoding always produces the
+/// `crypto-psbt` form (an untagged CBOR byte string).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoPsbt {
+    /// Serialized PSBT bytes (starting with `psbt\xff`)
+    pub psbt: Vec<u8>,
+}
+
+impl CryptoPsbt {
+    /// Wrap serialized PSBT bytes
+    pub fn new(psbt: Vec<u8>) -> Self {
+        Self { psbt }
+    }
+
+    /// Parse the wrapped PSBT
+    pub fn parse(&self) -> Result<Psbt> {
+        Psbt::parse(&self.psbt)
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for CryptoPsbt {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.bytes(&self.psbt)?;
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CryptoPsbt {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Top-level UR bodies are normally untagged, but accept the tagged form too
+        if d.datatype()? == minicbor::data::Type::Tag {
+            let tag = d.tag()?;
+            if tag != minicbor::data::Tag::Unassigned(cbor::tags::CRYPTO_PSBT)
+                && tag != minicbor::data::Tag::Unassigned(cbor::tags::PSBT)
+            {
+                return Err(minicbor::decode::Error::message("expected crypto-psbt tag"));
+            }
+        }
+
+        Ok(Self {
+            psbt: d.bytes()?.to_vec(),
+        })
+    }
+}
+
+/// Summary of a parsed PSBT
+#[derive(Debug, Clone)]
+pub struct Psbt {
+    /// PSBT format version (0 or 2)
+    pub version: u32,
+    /// Version of the unsigned transaction
+    pub tx_version: u32,
+    /// Transaction lock time
+    pub lock_time: u32,
+    /// Transaction inputs
+    pub inputs: Vec<PsbtInput>,
+    /// Transaction outputs
+    pub outputs: Vec<PsbtOutput>,
+}
+
+/// PSBT input with its spent amount and signing state
+#[derive(Debug, Clone)]
+pub struct PsbtInput {
+    /// Previous transaction ID (display byte order)
+    pub previous_txid: String,
+    /// Output index in the previous transaction
+    pub previous_vout: u32,
+    /// Input sequence number
+    pub sequence: u32,
+    /// Amount being spent in satoshis, if the PSBT includes the UTXO
+    pub amount: Option<u64>,
+    /// Script of the spent output, if known
+    pub script_pubkey: Option<Vec<u8>>,
+    /// Signing state of this input
+    pub status: InputSignStatus,
+}
+
+/// Signing state of a PSBT input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSignStatus {
+    /// No signatures present
+    Unsigned,
+    /// Partial or Taproot signatures present but not finalized
+    Signed {
+        /// Number of signatures attached to the input
+        signatures: usize,
+    },
+    /// Final scriptSig / witness present
+    Finalized,
+}
+
+impl InputSignStatus {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputSignStatus::Unsigned => "unsigned",
+            InputSignStatus::Signed { .. } => "signed",
+            InputSignStatus::Finalized => "finalized",
+        }
+    }
+
+    /// Whether the input carries at least one signature
+    pub fn is_signed(&self) -> bool {
+        !matches!(self, InputSignStatus::Unsigned)
+    }
+}
+
+/// PSBT output
+#[derive(Debug, Clone)]
+pub struct PsbtOutput {
+    /// Amount in satoshis
+    pub amount: u64,
+    /// Output script
+    pub script_pubkey: Vec<u8>,
+    /// Detected script template
+    pub script_type: ScriptType,
+    /// True when the PSBT carries wallet derivation info (typically change)
+    pub is_change: bool,
+}
+
+/// Standard output script templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptType {
+    /// Pay to public key hash
+    P2pkh,
+    /// Pay to script hash
+    P2sh,
+    /// Pay to witness public key hash
+    P2wpkh,
+    /// Pay to witness script hash
+    P2wsh,
+    /// Pay to Taproot
+    P2tr,
+    /// Data carrier output
+    OpReturn,
+    /// Any other script
+    Unknown,
+}
+
+impl ScriptType {
+    /// Classify an output script
+    pub fn classify(script: &[u8]) -> Self {
+        match script {
+            [0x76, 0xa9, 0x14, .., 0x88, 0xac] if script.len() == 25 => ScriptType::P2pkh,
+            [0xa9, 0x14, .., 0x87] if script.len() == 23 => ScriptType::P2sh,
+            [0x00, 0x14, ..] if script.len() == 22 => ScriptType::P2wpkh,
+            [0x00, 0x20, ..] if script.len() == 34 => ScriptType::P2wsh,
+            [0x51, 0x20, ..] if script.len() == 34 => ScriptType::P2tr,
+            [0x6a, ..] => ScriptType::OpReturn,
+            _ => ScriptType::Unknown,
+        }
+    }
+
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScriptType::P2pkh => "p2pkh",
+            ScriptType::P2sh => "p2sh",
+            ScriptType::P2wpkh => "p2wpkh",
+            ScriptType::P2wsh => "p2wsh",
+            ScriptType::P2tr => "p2tr",
+            ScriptType::OpReturn => "op_return",
+            ScriptType::Unknown => "unknown",
+        }
+    }
+}
+
+impl Psbt {
+    /// Parse serialized PSBT bytes
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(PSBT_MAGIC.len())? != PSBT_MAGIC {
+            return Err(psbt_error("missing psbt magic bytes"));
+        }
+
+        let globals = read_map(&mut reader)?;
+        let version = match find(&globals, GLOBAL_VERSION) {
+            Some(value) => Reader::new(value).u32()?,
+            None => 0,
+        };
+
+        match version {
+            0 => Self::parse_v0(&globals, &mut reader),
+            2 => Self::parse_v2(&globals, &mut reader),
+            other => Err(psbt_error(&format!("unsupported version {other}"))),
+        }
+    }
+
+    fn parse_v0(globals: &[(Vec<u8>, Vec<u8>)], reader: &mut Reader<'_>) -> Result<Self> {
+        let unsigned = find(globals, GLOBAL_UNSIGNED_TX)
+            .ok_or_else(|| psbt_error("missing unsigned transaction"))?;
+        let tx = RawTx::parse(unsigned)?;
+
+        let mut inputs = Vec::with_capacity(tx.inputs.len());
+        for txin in &tx.inputs {
+            let map = read_map(reader)?;
+            inputs.push(PsbtInput::from_map(
+                &map,
+                &txin.txid,
+                txin.vout,
+                txin.sequence,
+            )?);
+        }
+
+        let mut outputs = Vec::with_capacity(tx.outputs.len());
+        for txout in tx.outputs {
+            let map = read_map(reader)?;
+            outputs.push(PsbtOutput::new(txout.value, txout.script, &map));
+        }
+
+        Ok(Self {
+            version: 0,
+            tx_version: tx.version,
+            lock_time: tx.lock_time,
+            inputs,
+            outputs,
+        })
+    }
+
+    fn parse_v2(globals: &[(Vec<u8>, Vec<u8>)], reader: &mut Reader<'_>) -> Result<Self> {
+        let tx_version = find(globals, GLOBAL_TX_VERSION)
+            .map(|value| Reader::new(value).u32())
+            .transpose()?
+            .ok_or_else(|| psbt_error("missing transaction version"))?;
+        let lock_time = find(globals, GLOBAL_FALLBACK_LOCKTIME)
+            .map(|value| Reader::new(value).u32())
+            .transpose()?
+            .unwrap_or(0);
+        let input_count = find(globals, GLOBAL_INPUT_COUNT)
+            .map(|value| Reader::new(value).compact_size())
+            .transpose()?
+            .ok_or_else(|| psbt_error("missing input count"))?;
+        let output_count = find(globals, GLOBAL_OUTPUT_COUNT)
+            .map(|value| Reader::new(value).compact_size())
+            .transpose()?
+            .ok_or_else(|| psbt_error("missing output count"))?;
+
+        let mut inputs = Vec::new();
+        for _ in 0..input_count {
+            let map = read_map(reader)?;
+            let txid = find(&map, IN_PREVIOUS_TXID)
+                .ok_or_else(|| psbt_error("input missing previous txid"))?;
+            let vout = find(&map, IN_OUTPUT_INDEX)
+                .map(|value| Reader::new(value).u32())
+                .transpose()?
+                .ok_or_else(|| psbt_error("input missing output index"))?;
+            let sequence = find(&map, IN_SEQUENCE)
+                .map(|value| Reader::new(value).u32())
+                .transpose()?
+                .unwrap_or(0xffff_ffff);
+            inputs.push(PsbtInput::from_map(&map, txid, vout, sequence)?);
+        }
+
+        let mut outputs = Vec::new();
+        for _ in 0..output_count {
+            let map = read_map(reader)?;
+            let amount = find(&map, OUT_AMOUNT)
+                .map(|value| Reader::new(value).u64())
+                .transpose()?
+                .ok_or_else(|| psbt_error("output missing amount"))?;
+            let script = find(&map, OUT_SCRIPT)
+                .ok_or_else(|| psbt_error("output missing script"))?
+                .to_vec();
+            outputs.push(PsbtOutput::new(amount, script, &map));
+        }
+
+        Ok(Self {
+            version: 2,
+            tx_version,
+            lock_time,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Sum of input amounts, if every input carries its UTXO
+    pub fn total_input(&self) -> Option<u64> {
+        self.inputs
+            .iter()
+            .map(|input| input.amount)
+            .try_fold(0u64, |acc, amount| acc.checked_add(amount?))
+    }
+
+    /// Sum of output amounts
+    pub fn total_output(&self) -> u64 {
+        self.outputs
+            .iter()
+            .fold(0u64, |acc, output| acc.saturating_add(output.amount))
+    }
+
+    /// Transaction fee, if every input amount is known
+    pub fn fee(&self) -> Option<u64> {
+        self.total_input()?.checked_sub(self.total_output())
+    }
+
+    /// Number of inputs carrying at least one signature
+    pub fn signed_inputs(&self) -> usize {
+        self.inputs
+            .iter()
+            .filter(|input| input.status.is_signed())
+            .count()
+    }
+}
+
+impl PsbtInput {
+    /// `previous_txid` is in internal (hash) byte order
+    fn from_map(
+        map: &[(Vec<u8>, Vec<u8>)],
+        previous_txid: &[u8],
+        previous_vout: u32,
+        sequence: u32,
+    ) -> Result<Self> {
+        // The full previous transaction only proves the spent amount if it
+        // hashes to the txid the input actually spends
+        let non_witness_utxo = find(map, IN_NON_WITNESS_UTXO)
+            .map(RawTx::parse)
+            .transpose()?;
+        if let Some(previous) = &non_witness_utxo {
+            if previous.txid[..] != *previous_txid {
+                return Err(psbt_error(
+                    "non-witness UTXO does not match the input's previous txid",
+                ));
+            }
+        }
+
+        let (amount, script_pubkey) = if let Some(value) = find(map, IN_WITNESS_UTXO) {
+            let mut reader = Reader::new(value);
+            let amount = reader.u64()?;
+            let script = reader.var_bytes()?.to_vec();
+            (Some(amount), Some(script))
+        } else if let Some(previous) = non_witness_utxo {
+            match previous.outputs.into_iter().nth(previous_vout as usize) {
+                Some(output) => (Some(output.value), Some(output.script)),
+                None => return Err(psbt_error("non-witness UTXO is missing spent output")),
+            }
+        } else {
+            (None, None)
+        };
+
+        let finalized =
+            find(map, IN_FINAL_SCRIPTSIG).is_some() || find(map, IN_FINAL_SCRIPTWITNESS).is_some();
+        let signatures = map
+            .iter()
+            .filter(|(key, _)| {
+                matches!(
+                    key.first(),
+                    Some(&IN_PARTIAL_SIG | &IN_TAP_KEY_SIG | &IN_TAP_SCRIPT_SIG)
+                )
+            })
+            .count();
+
+        let status = if finalized {
+            InputSignStatus::Finalized
+        } else if signatures > 0 {
+            InputSignStatus::Signed { signatures }
+        } else {
+            InputSignStatus::Unsigned
+        };
+
+        Ok(Self {
+            previous_txid: display_txid(previous_txid),
+            previous_vout,
+            sequence,
+            amount,
+            script_pubkey,
+            status,
+        })
+    }
+}
+
+impl PsbtOutput {
+    fn new(amount: u64, script_pubkey: Vec<u8>, map: &[(Vec<u8>, Vec<u8>)]) -> Self {
+        let is_change = map.iter().any(|(key, _)| {
+            matches!(
+                key.first(),
+                Some(&OUT_BIP32_DERIVATION | &OUT_TAP_BIP32_DERIVATION)
+            )
+        });
+        Self {
+            amount,
+            script_type: ScriptType::classify(&script_pubkey),
+            script_pubkey,
+            is_change,
+        }
+    }
+}
+
+struct TxIn {
+    txid: [u8; 32],
+    vout: u32,
+    sequence: u32,
+}
+
+struct TxOut {
+    value: u64,
+    script: Vec<u8>,
+}
+
+struct RawTx {
+    /// Double SHA-256 of the non-witness serialization (internal byte order)
+    txid: [u8; 32],
+    version: u32,
+    inputs: Vec<TxIn>,
+    outputs: Vec<TxOut>,
+    lock_time: u32,
+}
+
+impl RawTx {
+    fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let version = reader.u32()?;
+
+        // Segwit serialization: marker 0x00 followed by flag 0x01
+        let segwit = reader.peek(2) == Some(&[0x00, 0x01][..]);
+        if segwit {
+            reader.take(2)?;
+        }
+
+        let body_start = reader.pos;
+        let input_count = reader.compact_size()?;
+        let mut inputs = Vec::new();
+        for _ in 0..input_count {
+            let mut txid = [0u8; 32];
+            txid.copy_from_slice(reader.take(32)?);
+            let vout = reader.u32()?;
+            reader.var_bytes()?; // scriptSig
+            let sequence = reader.u32()?;
+            inputs.push(TxIn {
+                txid,
+                vout,
+                sequence,
+            });
+        }
+
+        let output_count = reader.compact_size()?;
+        let mut outputs = Vec::new();
+        for _ in 0..output_count {
+            let value = reader.u64()?;
+            let script = reader.var_bytes()?.to_vec();
+            outputs.push(TxOut { value, script });
+        }
+        let body_end = reader.pos;
+
+        if segwit {
+            for _ in 0..inputs.len() {
+                let items = reader.compact_size()?;
+                for _ in 0..items {
+                    reader.var_bytes()?;
+                }
+            }
+        }
+
+        let lock_time = reader.u32()?;
+
+        // The txid commits to version, inputs, outputs and lock time only
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes[..4]);
+        hasher.update(&bytes[body_start..body_end]);
+        hasher.update(lock_time.to_le_bytes());
+        let txid = Sha256::digest(hasher.finalize()).into();
+
+        Ok(Self {
+            txid,
+            version,
+            inputs,
+            outputs,
+            lock_time,
+        })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn peek(&self, len: usize) -> Option<&'a [u8]> {
+        self.bytes.get(self.pos..self.pos + len)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| psbt_error("unexpected end of data"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn compact_size(&mut self) -> Result<u64> {
+        match self.u8()? {
+            0xfd => {
+                let bytes = self.take(2)?;
+                Ok(u16::from_le_bytes([bytes[0], bytes[1]]) as u64)
+            }
+            0xfe => Ok(self.u32()? as u64),
+            0xff => self.u64(),
+            value => Ok(value as u64),
+        }
+    }
+
+    fn var_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.compact_size()?;
+        let len = usize::try_from(len).map_err(|_| psbt_error("length overflow"))?;
+        self.take(len)
+    }
+}
+
+/// Read one `<key><value>` map terminated by a zero-length key
+fn read_map(reader: &mut Reader<'_>) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut entries = Vec::new();
+    loop {
+        let key = reader.var_bytes()?;
+        if key.is_empty() {
+            return Ok(entries);
+        }
+        let value = reader.var_bytes()?;
+        entries.push((key.to_vec(), value.to_vec()));
+    }
+}
+
+fn find(map: &[(Vec<u8>, Vec<u8>)], key_type: u8) -> Option<&[u8]> {
+    map.iter()
+        .find(|(key, _)| key.first() == Some(&key_type))
+        .map(|(_, value)| value.as_slice())
+}
+
+fn display_txid(txid: &[u8]) -> String {
+    let mut reversed = txid.to_vec();
+    reversed.reverse();
+    hex::encode(reversed)
+}
+
+fn psbt_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid PSBT: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_var(buf: &mut Vec<u8>, bytes: &[u8]) {
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+
+    fn push_entry(buf: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+        push_var(buf, key);
+        push_var(buf, value);
+    }
+
+    /// One P2WPKH input (signed, 100_000 sats) paying 60_000 out and 39_000 to change
+    fn sample_psbt() -> Vec<u8> {
+        let mut tx = Vec::new();
+        tx.extend_from_slice(&2u32.to_le_bytes());
+        tx.push(1);
+        tx.extend_from_slice(&[0x11; 32]);
+        tx.extend_from_slice(&1u32.to_le_bytes());
+        tx.push(0);
+        tx.extend_from_slice(&0xffff_fffdu32.to_le_bytes());
+        tx.push(2);
+        for (value, script) in [(60_000u64, vec![0x00, 0x14]), (39_000u64, vec![0x51, 0x20])] {
+            let mut script = script;
+            script.extend(std::iter::repeat_n(
+                0xab,
+                if script[0] == 0 { 20 } else { 32 },
+            ));
+            tx.extend_from_slice(&value.to_le_bytes());
+            push_var(&mut tx, &script);
+        }
+        tx.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut psbt = PSBT_MAGIC.to_vec();
+        push_entry(&mut psbt, &[GLOBAL_UNSIGNED_TX], &tx);
+        psbt.push(0);
+
+        let mut utxo = 100_000u64.to_le_bytes().to_vec();
+        let mut script = vec![0x00, 0x14];
+        script.extend([0xcd; 20]);
+        push_var(&mut utxo, &script);
+        push_entry(&mut psbt, &[IN_WITNESS_UTXO], &utxo);
+        let mut sig_key = vec![IN_PARTIAL_SIG];
+        sig_key.extend([0x02; 33]);
+        push_entry(&mut psbt, &sig_key, &[0x30; 71]);
+        psbt.push(0);
+
+        psbt.push(0);
+        let mut derivation_key = vec![OUT_TAP_BIP32_DERIVATION];
+        derivation_key.extend([0x03; 32]);
+        push_entry(&mut psbt, &derivation_key, &[0x00; 9]);
+        psbt.push(0);
+
+        psbt
+    }
+
+    #[test]
+    fn test_parse_v0_psbt() {
+        let psbt = Psbt::parse(&sample_psbt()).unwrap();
+
+        assert_eq!(psbt.version, 0);
+        assert_eq!(psbt.tx_version, 2);
+        assert_eq!(psbt.inputs.len(), 1);
+        assert_eq!(psbt.inputs[0].previous_vout, 1);
+        assert_eq!(psbt.inputs[0].amount, Some(100_000));
+        assert_eq!(
+            psbt.inputs[0].status,
+            InputSignStatus::Signed { signatures: 1 }
+        );
+
+        assert_eq!(psbt.outputs.len(), 2);
+        assert_eq!(psbt.outputs[0].script_type, ScriptType::P2wpkh);
+        assert!(!psbt.outputs[0].is_change);
+        assert_eq!(psbt.outputs[1].script_type, ScriptType::P2tr);
+        assert!(psbt.outputs[1].is_change);
+        assert_eq!(psbt.fee(), Some(1_000));
+    }
+
+    /// Legacy transaction paying `value` to a P2PKH script at output 0
+    fn previous_tx(value: u64) -> Vec<u8> {
+        let mut tx = 1u32.to_le_bytes().to_vec();
+        tx.push(1);
+        tx.extend_from_slice(&[0x22; 32]);
+        tx.extend_from_slice(&0u32.to_le_bytes());
+        tx.push(0);
+        tx.extend_from_slice(&0xffff_ffffu32.to_le_bytes());
+        tx.push(1);
+        tx.extend_from_slice(&value.to_le_bytes());
+        let mut script = vec![0x76, 0xa9, 0x14];
+        script.extend([0xee; 20]);
+        script.extend([0x88, 0xac]);
+        push_var(&mut tx, &script);
+        tx.extend_from_slice(&0u32.to_le_bytes());
+        tx
+    }
+
+    /// PSBT spending output 0 of `spent_txid` with `utxo` as its non-witness UTXO
+    fn non_witness_psbt(spent_txid: [u8; 32], utxo: &[u8]) -> Vec<u8> {
+        let mut tx = 2u32.to_le_bytes().to_vec();
+        tx.push(1);
+        tx.extend_from_slice(&spent_txid);
+        tx.extend_from_slice(&0u32.to_le_bytes());
+        tx.push(0);
+        tx.extend_from_slice(&0xffff_ffffu32.to_le_bytes());
+        tx.push(1);
+        tx.extend_from_slice(&40_000u64.to_le_bytes());
+        push_var(&mut tx, &[0x6a]);
+        tx.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut psbt = PSBT_MAGIC.to_vec();
+        push_entry(&mut psbt, &[GLOBAL_UNSIGNED_TX], &tx);
+        psbt.push(0);
+        push_entry(&mut psbt, &[IN_NON_WITNESS_UTXO], utxo);
+        psbt.push(0);
+        psbt.push(0);
+        psbt
+    }
+
+    #[test]
+    fn test_non_witness_utxo_must_hash_to_spent_txid() {
+        let utxo = previous_tx(50_000);
+        let txid: [u8; 32] = Sha256::digest(Sha256::digest(&utxo)).into();
+
+        let psbt = Psbt::parse(&non_witness_psbt(txid, &utxo)).unwrap();
+        assert_eq!(psbt.inputs[0].amount, Some(50_000));
+        assert_eq!(psbt.inputs[0].previous_txid, display_txid(&txid));
+        assert_eq!(psbt.fee(), Some(10_000));
+
+        // Same txid, but a UTXO inflated to hide a larger fee
+        let forged = previous_tx(5_000_000);
+        let err = Psbt::parse(&non_witness_psbt(txid, &forged)).unwrap_err();
+        assert!(err.to_string().contains("does not match"));
+    }
+
+    #[test]
+    fn test_rejects_bad_magic() {
+        assert!(Psbt::parse(b"nope\xff\x00").is_err());
+    }
+
+    #[test]
+    fn test_crypto_psbt_roundtrip() {
+        let message = CryptoPsbt::new(sample_psbt());
+        let bytes = message.to_cbor().unwrap();
+        assert_eq!(CryptoPsbt::from_cbor(&bytes).unwrap(), message);
+
+        // Tagged form is accepted as well
+        let mut tagged = Vec::new();
+        let mut encoder = Encoder::new(&mut tagged);
+        encoder
+            .tag(minicbor::data::Tag::Unassigned(cbor::tags::CRYPTO_PSBT))
+            .unwrap()
+            .bytes(&message.psbt)
+            .unwrap();
+        assert_eq!(CryptoPsbt::from_cbor(&tagged).unwrap(), message);
+    }
+}
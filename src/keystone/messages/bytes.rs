@@ -0,0 +1,241 @@
+//! Generic `bytes` UR payload
+//!
+//! BCR-2020-006 defines `ur:bytes` as a single CBOR byte string with no
+//! further structure. Tools use it for signed message exports, plain text
+//! notes, JSON blobs and even other UR strings, so the contents are sniffed
+//! rather than assumed. Some encoders skip the CBOR wrapper (or use a text
+//! string); those payloads are accepted as-is.
+//!
+//! Reference: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-006-urtypes.md
+
+use crate::error::Result;
+use crate::keystone::cbor;
+use minicbor::data::Type;
+use minicbor::{Decoder, Encoder};
+use serde_json::Value;
+
+/// How the payload of a `bytes` UR was framed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BytesFraming {
+    /// CBOR byte string (the standard form)
+    ByteString,
+    /// CBOR text string
+    TextString,
+    /// No CBOR wrapper; the UR body is the content
+    Raw,
+}
+
+impl BytesFraming {
+    /// Short lowercase label for output
+    pub fn label(&self) -> &'static str {
+        match self {
+            BytesFraming::ByteString => "byte_string",
+            BytesFraming::TextString => "text_string",
+            BytesFraming::Raw => "raw",
+        }
+    }
+}
+
+/// What the unwrapped bytes appear to contain
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytesContent {
+    /// A nested UR string, lower-cased (e.g. `ur:crypto-psbt/...`)
+    Ur(String),
+    /// JSON object or array
+    Json(Value),
+    /// Printable UTF-8 text
+    Text(String),
+    /// Anything else
+    Binary,
+}
+
+impl BytesContent {
+    /// Short lowercase label for output
+    pub fn label(&self) -> &'static str {
+        match self {
+            BytesContent::Ur(_) => "ur",
+            BytesContent::Json(_) => "json",
+            BytesContent::Text(_) => "text",
+            BytesContent::Binary => "binary",
+        }
+    }
+}
+
+/// Contents of a `bytes` UR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bytes {
+    /// Unwrapped content bytes
+    pub data: Vec<u8>,
+    /// Framing the content arrived in
+    pub framing: BytesFraming,
+}
+
+impl Bytes {
+    /// Wrap content bytes as a standard CBOR byte string payload
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            framing: BytesFraming::ByteString,
+        }
+    }
+
+    /// Unwrap a `bytes` UR body, accepting unframed content
+    pub fn from_ur_data(data: &[u8]) -> Self {
+        let mut d = Decoder::new(data);
+        let unwrapped = match d.datatype() {
+            Ok(Type::Bytes) => d
+                .bytes()
+                .ok()
+                .map(|bytes| (bytes.to_vec(), BytesFraming::ByteString)),
+            Ok(Type::String) => d
+                .str()
+                .ok()
+                .map(|text| (text.as_bytes().to_vec(), BytesFraming::TextString)),
+            _ => None,
+        };
+        match unwrapped {
+            Some((content, framing)) if d.position() == data.len() => Self {
+                data: content,
+                framing,
+            },
+            _ => Self {
+                data: data.to_vec(),
+                framing: BytesFraming::Raw,
+            },
+        }
+    }
+
+    /// Sniff the content type
+    ///
+    /// Checked in order: nested UR, JSON object/array, printable text.
+    pub fn content(&self) -> BytesContent {
+        let Ok(text) = std::str::from_utf8(&self.data) else {
+            return BytesContent::Binary;
+        };
+        let trimmed = text.trim();
+        let is_ur = trimmed.len() > 3
+            && trimmed
+                .get(..3)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("ur:"));
+        if is_ur {
+            // QR alphanumeric mode carries URs upper-cased
+            return BytesContent::Ur(trimmed.to_ascii_lowercase());
+        }
+        if trimmed.starts_with(['{', '[']) {
+            if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
+                return BytesContent::Json(value);
+            }
+        }
+        let printable = text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'));
+        if printable && !text.is_empty() {
+            BytesContent::Text(text.to_string())
+        } else {
+            BytesContent::Binary
+        }
+    }
+
+    /// Encode to CBOR bytes
+    ///
+    /// Raw payloads are written back unframed so they round-trip unchanged.
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for Bytes {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        match self.framing {
+            BytesFraming::ByteString => {
+                e.bytes(&self.data)?;
+            }
+            BytesFraming::TextString => match std::str::from_utf8(&self.data) {
+                Ok(text) => {
+                    e.str(text)?;
+                }
+                Err(_) => {
+                    e.bytes(&self.data)?;
+                }
+            },
+            BytesFraming::Raw => e
+                .writer_mut()
+                .write_all(&self.data)
+                .map_err(minicbor::encode::Error::write)?,
+        }
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for Bytes {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        match d.datatype()? {
+            Type::String => Ok(Self {
+                data: d.str()?.as_bytes().to_vec(),
+                framing: BytesFraming::TextString,
+            }),
+            _ => Ok(Self::new(d.bytes()?.to_vec())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_roundtrip_and_text_detection() {
+        let bytes = Bytes::new(b"Signed by alice\n".to_vec());
+
+        let encoded = bytes.to_cbor().unwrap();
+        assert_eq!(encoded[0], 0x50); // byte string, length 16
+        let decoded = Bytes::from_ur_data(&encoded);
+
+        assert_eq!(decoded, bytes);
+        assert_eq!(
+            decoded.content(),
+            BytesContent::Text("Signed by alice\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detects_json_and_nested_ur() {
+        let json = Bytes::new(br#"{"address":"0xabc","signature":"0x01"}"#.to_vec());
+        match json.content() {
+            BytesContent::Json(value) => assert_eq!(value["address"], "0xabc"),
+            other => panic!("unexpected content {other:?}"),
+        }
+
+        let nested = Bytes::new(b"UR:CRYPTO-PSBT/HDCXAAAA".to_vec());
+        assert_eq!(
+            nested.content(),
+            BytesContent::Ur("ur:crypto-psbt/hdcxaaaa".to_string())
+        );
+
+        assert_eq!(Bytes::new(vec![0x00, 0xff]).content(), BytesContent::Binary);
+    }
+
+    #[test]
+    fn test_unframed_payload_is_kept_raw() {
+        let decoded = Bytes::from_ur_data(b"hello world");
+        assert_eq!(decoded.framing, BytesFraming::Raw);
+        assert_eq!(decoded.data, b"hello world");
+        assert_eq!(decoded.to_cbor().unwrap(), b"hello world");
+
+        let text = Bytes::from_ur_data(&[0x62, b'h', b'i']);
+        assert_eq!(text.framing, BytesFraming::TextString);
+        assert_eq!(text.to_cbor().unwrap(), vec![0x62, b'h', b'i']);
+    }
+}
@@ -0,0 +1,574 @@
+//! Cardano (ADA) signature request and response types
+//!
+//! The sign data is a CBOR transaction body. Because Cardano inputs only
+//! reference earlier outputs, the request also lists each spent UTXO with
+//! its amount, address and key path so the wallet can show what is being
+//! spent, plus the certificate keys needed for staking certificates.
+//!
+//! Reference: https://github.com/KeystoneHQ/keystone-sdk-rust
+
+use crate::error::Result;
+use crate::keystone::cbor;
+use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::data::Type;
+use minicbor::{Decoder, Encoder};
+use uuid::Uuid;
+
+const LOVELACE_PER_ADA: u64 = 1_000_000;
+
+/// Unspent output consumed by the transaction (`cardano-utxo`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardanoUtxo {
+    /// Hash of the transaction that created the output
+    pub transaction_hash: Vec<u8>,
+    /// Output index within that transaction
+    pub index: u32,
+    /// Amount in lovelace, as a decimal string
+    pub amount: String,
+    /// Derivation path of the key that controls the output
+    pub key_path: CryptoKeyPath,
+    /// Bech32 address of the output
+    pub address: String,
+}
+
+impl CardanoUtxo {
+    /// Amount in lovelace, if it parses
+    pub fn lovelace(&self) -> Option<u64> {
+        self.amount.parse().ok()
+    }
+}
+
+impl minicbor::Encode<()> for CardanoUtxo {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(cbor::tags::CARDANO_UTXO))?;
+        e.map(5)?;
+        e.u8(1)?.bytes(&self.transaction_hash)?;
+        e.u8(2)?.u32(self.index)?;
+        e.u8(3)?.str(&self.amount)?;
+        e.u8(4)?;
+        self.key_path.encode(e, ctx)?;
+        e.u8(5)?.str(&self.address)?;
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CardanoUtxo {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::CARDANO_UTXO) {
+            return Err(minicbor::decode::Error::message(
+                "expected cardano-utxo tag",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut transaction_hash = None;
+        let mut index = None;
+        let mut amount = None;
+        let mut key_path = None;
+        let mut address = None;
+
+        for _ in 0..map_len {
+            match d.u8()? {
+                1 => transaction_hash = Some(d.bytes()?.to_vec()),
+                2 => index = Some(d.u32()?),
+                3 => amount = Some(d.str()?.to_string()),
+                4 => key_path = Some(CryptoKeyPath::decode(d, ctx)?),
+                5 => address = Some(d.str()?.to_string()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            transaction_hash: transaction_hash
+                .ok_or_else(|| minicbor::decode::Error::message("missing transaction_hash"))?,
+            index: index.ok_or_else(|| minicbor::decode::Error::message("missing index"))?,
+            amount: amount.ok_or_else(|| minicbor::decode::Error::message("missing amount"))?,
+            key_path: key_path
+                .ok_or_else(|| minicbor::decode::Error::message("missing key_path"))?,
+            address: address.ok_or_else(|| minicbor::decode::Error::message("missing address"))?,
+        })
+    }
+}
+
+/// Key required by a certificate in the transaction (`cardano-cert-key`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardanoCertKey {
+    /// Blake2b-224 hash of the key
+    pub key_hash: Vec<u8>,
+    /// Derivation path of the key
+    pub key_path: CryptoKeyPath,
+}
+
+impl minicbor::Encode<()> for CardanoCertKey {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::CARDANO_CERT_KEY,
+        ))?;
+        e.map(2)?;
+        e.u8(1)?.bytes(&self.key_hash)?;
+        e.u8(2)?;
+        self.key_path.encode(e, ctx)?;
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CardanoCertKey {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::CARDANO_CERT_KEY) {
+            return Err(minicbor::decode::Error::message(
+                "expected cardano-cert-key tag",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut key_hash = None;
+        let mut key_path = None;
+
+        for _ in 0..map_len {
+            match d.u8()? {
+                1 => key_hash = Some(d.bytes()?.to_vec()),
+                2 => key_path = Some(CryptoKeyPath::decode(d, ctx)?),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            key_hash: key_hash
+                .ok_or_else(|| minicbor::decode::Error::message("missing key_hash"))?,
+            key_path: key_path
+                .ok_or_else(|| minicbor::decode::Error::message("missing key_path"))?,
+        })
+    }
+}
+
+/// Cardano signature request
+#[derive(Debug, Clone)]
+pub struct CardanoSignRequest {
+    /// Optional request identifier
+    pub request_id: Option<Uuid>,
+    /// CBOR-encoded transaction body
+    pub sign_data: Vec<u8>,
+    /// Outputs spent by the transaction
+    pub utxos: Vec<CardanoUtxo>,
+    /// Keys required by certificates (stake registration, delegation, ...)
+    pub cert_keys: Vec<CardanoCertKey>,
+    /// Optional application origin string
+    pub origin: Option<String>,
+}
+
+impl CardanoSignRequest {
+    /// Construct a new Cardano sign request
+    pub fn new(sign_data: Vec<u8>, utxos: Vec<CardanoUtxo>, request_id: Option<Uuid>) -> Self {
+        Self {
+            request_id,
+            sign_data,
+            utxos,
+            cert_keys: Vec::new(),
+            origin: None,
+        }
+    }
+
+    /// Attach certificate keys
+    pub fn with_cert_keys(mut self, cert_keys: Vec<CardanoCertKey>) -> Self {
+        self.cert_keys = cert_keys;
+        self
+    }
+
+    /// Attach an origin descriptor (e.g. dApp name)
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Total lovelace across the listed UTXOs, if every amount parses
+    pub fn total_input_lovelace(&self) -> Option<u64> {
+        self.utxos
+            .iter()
+            .try_fold(0u64, |total, utxo| total.checked_add(utxo.lovelace()?))
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 3; // sign_data + utxos + cert_keys
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        if self.origin.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for CardanoSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::CARDANO_SIGN_REQUEST,
+        ))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.bytes(&self.sign_data)?;
+
+        e.u8(3)?;
+        e.array(self.utxos.len() as u64)?;
+        for utxo in &self.utxos {
+            utxo.encode(e, ctx)?;
+        }
+
+        e.u8(4)?;
+        e.array(self.cert_keys.len() as u64)?;
+        for cert_key in &self.cert_keys {
+            cert_key.encode(e, ctx)?;
+        }
+
+        if let Some(ref origin) = self.origin {
+            e.u8(5)?;
+            e.str(origin)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CardanoSignRequest {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Device output leaves the top-level map untagged
+        if d.datatype()? == Type::Tag {
+            let tag = d.tag()?;
+            if tag != minicbor::data::Tag::Unassigned(cbor::tags::CARDANO_SIGN_REQUEST) {
+                return Err(minicbor::decode::Error::message(
+                    "expected cardano-sign-request tag",
+                ));
+            }
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut sign_data = None;
+        let mut utxos = Vec::new();
+        let mut cert_keys = Vec::new();
+        let mut origin = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => sign_data = Some(d.bytes()?.to_vec()),
+                3 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length array")
+                    })?;
+                    for _ in 0..len {
+                        utxos.push(CardanoUtxo::decode(d, ctx)?);
+                    }
+                }
+                4 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length array")
+                    })?;
+                    for _ in 0..len {
+                        cert_keys.push(CardanoCertKey::decode(d, ctx)?);
+                    }
+                }
+                5 => origin = Some(d.str()?.to_string()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            sign_data: sign_data
+                .ok_or_else(|| minicbor::decode::Error::message("missing sign_data"))?,
+            utxos,
+            cert_keys,
+            origin,
+        })
+    }
+}
+
+/// Cardano signature response
+#[derive(Debug, Clone)]
+pub struct CardanoSignature {
+    /// Request identifier echo
+    pub request_id: Option<Uuid>,
+    /// CBOR-encoded transaction witness set (vkey witnesses)
+    pub witness_set: Vec<u8>,
+}
+
+impl CardanoSignature {
+    /// Create a new signature container
+    pub fn new(witness_set: Vec<u8>, request_id: Option<Uuid>) -> Self {
+        Self {
+            request_id,
+            witness_set,
+        }
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 1; // witness_set
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for CardanoSignature {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::CARDANO_SIGNATURE,
+        ))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.bytes(&self.witness_set)?;
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CardanoSignature {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Device output leaves the top-level map untagged
+        if d.datatype()? == Type::Tag {
+            let tag = d.tag()?;
+            if tag != minicbor::data::Tag::Unassigned(cbor::tags::CARDANO_SIGNATURE) {
+                return Err(minicbor::decode::Error::message(
+                    "expected cardano-signature tag",
+                ));
+            }
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut witness_set = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => witness_set = Some(d.bytes()?.to_vec()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            witness_set: witness_set
+                .ok_or_else(|| minicbor::decode::Error::message("missing witness_set"))?,
+        })
+    }
+}
+
+/// Format lovelace as ADA without floating point (e.g. `1.5 ADA`)
+pub fn format_lovelace_as_ada(lovelace: u64) -> String {
+    let whole = lovelace / LOVELACE_PER_ADA;
+    let fraction = lovelace % LOVELACE_PER_ADA;
+    if fraction == 0 {
+        return format!("{whole} ADA");
+    }
+    let fraction = format!("{fraction:06}");
+    format!("{whole}.{} ADA", fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utxo(index: u32, amount: &str) -> CardanoUtxo {
+        CardanoUtxo {
+            transaction_hash: vec![0x11; 32],
+            index,
+            amount: amount.to_string(),
+            key_path: CryptoKeyPath::from_str("m/1852'/1815'/0'/0/0").unwrap(),
+            address: "addr1qexample".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cardano_sign_request_roundtrip() {
+        let request_id = Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap());
+        let cert_key = CardanoCertKey {
+            key_hash: vec![0x22; 28],
+            key_path: CryptoKeyPath::from_str("m/1852'/1815'/0'/2/0").unwrap(),
+        };
+        let request = CardanoSignRequest::new(
+            vec![0xa4, 0x00],
+            vec![utxo(0, "1500000"), utxo(3, "2000000")],
+            request_id,
+        )
+        .with_cert_keys(vec![cert_key.clone()])
+        .with_origin("eternl".to_string());
+
+        let bytes = request.to_cbor().unwrap();
+        let decoded = CardanoSignRequest::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.request_id, request_id);
+        assert_eq!(decoded.sign_data, request.sign_data);
+        assert_eq!(decoded.utxos, request.utxos);
+        assert_eq!(decoded.cert_keys, vec![cert_key]);
+        assert_eq!(decoded.origin.as_deref(), Some("eternl"));
+        assert_eq!(decoded.total_input_lovelace(), Some(3_500_000));
+    }
+
+    /// Device wire layout: untagged top-level map, tagged UTXO and cert-key
+    /// entries, key paths as index/hardened pairs with a uint32 fingerprint
+    #[test]
+    fn test_decode_device_layout() {
+        let bytes = hex::decode(concat!(
+            "a501d825509b1deb4d3b7d4bad9bdd2b0d7b3dcb6d025854a300818258204e3a",
+            "6e7fdcb0d0efa17bf79c13aed2b4cb9baf37fb1aa2e39553d5bd720c5c990001",
+            "8182581d01333333333333333333333333333333333333333333333333333333",
+            "331a001e8480021a000298100381d90899a50158204e3a6e7fdcb0d0efa17bf7",
+            "9c13aed2b4cb9baf37fb1aa2e39553d5bd720c5c990200036732323030303030",
+            "04d90130a2018a19073cf5190717f500f500f400f4021a73c5da0a05783a6164",
+            "6472317178636b38766d63767364746e3078646a797937786e33687574677533",
+            "786b357a706b686a72776b68617a6670786e66336b366d380481d9089ca20158",
+            "1c4444444444444444444444444444444444444444444444444444444402d901",
+            "30a2018a19073cf5190717f500f502f400f4021a73c5da0a0566657465726e6c",
+        ))
+        .unwrap();
+
+        let request = CardanoSignRequest::from_cbor(&bytes).unwrap();
+        assert_eq!(
+            request.request_id,
+            Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap())
+        );
+        assert_eq!(request.sign_data.len(), 84);
+        assert_eq!(request.utxos.len(), 1);
+        let utxo = &request.utxos[0];
+        assert_eq!(utxo.index, 0);
+        assert_eq!(utxo.lovelace(), Some(2_200_000));
+        assert_eq!(utxo.key_path.to_string(), "m/1852'/1815'/0'/0/0");
+        assert!(utxo.address.starts_with("addr1q"));
+        assert_eq!(request.cert_keys.len(), 1);
+        assert_eq!(request.cert_keys[0].key_hash, vec![0x44; 28]);
+        assert_eq!(
+            request.cert_keys[0].key_path.to_string(),
+            "m/1852'/1815'/0'/2/0"
+        );
+        assert_eq!(request.origin.as_deref(), Some("eternl"));
+
+        let signature = CardanoSignature::from_cbor(
+            &hex::decode(concat!(
+                "a201d825509b1deb4d3b7d4bad9bdd2b0d7b3dcb6d025868a100818258205555",
+                "5555555555555555555555555555555555555555555555555555555555555840",
+                "6666666666666666666666666666666666666666666666666666666666666666",
+                "6666666666666666666666666666666666666666666666666666666666666666",
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(signature.request_id, request.request_id);
+        assert_eq!(signature.witness_set.len(), 104);
+    }
+
+    #[test]
+    fn test_cardano_signature_roundtrip() {
+        let sig = CardanoSignature::new(vec![0xa1, 0x00, 0x80], None);
+
+        let bytes = sig.to_cbor().unwrap();
+        let decoded = CardanoSignature::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.witness_set, sig.witness_set);
+    }
+
+    #[test]
+    fn test_format_lovelace_as_ada() {
+        assert_eq!(format_lovelace_as_ada(3_500_000), "3.5 ADA");
+        assert_eq!(format_lovelace_as_ada(2_000_000), "2 ADA");
+        assert_eq!(format_lovelace_as_ada(1), "0.000001 ADA");
+    }
+}
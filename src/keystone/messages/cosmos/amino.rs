@@ -0,0 +1,239 @@
+//! Amino JSON sign documents (`StdSignDoc`)
+//!
+//! Legacy Cosmos SDK signing serialises the transaction as sorted JSON:
+//! `{account_number, chain_id, fee: {amount, gas}, memo, msgs, sequence}`.
+//! Numbers are strings on the wire, but some wallets emit bare integers,
+//! so both are accepted.
+
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// A denomination and amount (`{"denom": "uatom", "amount": "1000"}`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CosmosCoin {
+    /// Denomination (e.g. `uatom`, `uosmo`, `ibc/...`)
+    pub denom: String,
+    /// Integer amount in the smallest unit
+    pub amount: String,
+}
+
+impl CosmosCoin {
+    fn from_value(value: &Value) -> Result<Self> {
+        Ok(Self {
+            denom: string_field(value, "denom")?,
+            amount: string_field(value, "amount")?,
+        })
+    }
+
+    /// `amount denom` for display
+    pub fn display(&self) -> String {
+        format!("{} {}", self.amount, self.denom)
+    }
+}
+
+/// One message inside an amino sign document
+#[derive(Debug, Clone, PartialEq)]
+pub struct AminoMsg {
+    /// Amino type name (e.g. `cosmos-sdk/MsgSend`)
+    pub msg_type: String,
+    /// Message fields exactly as signed
+    pub value: Value,
+}
+
+impl AminoMsg {
+    /// Type name without the module prefix (`cosmos-sdk/MsgSend` -> `MsgSend`)
+    pub fn label(&self) -> &str {
+        self.msg_type.rsplit('/').next().unwrap_or(&self.msg_type)
+    }
+
+    /// Reviewable fields for well-known messages as `(name, value)` pairs
+    ///
+    /// Unknown message types return an empty list; callers fall back to the
+    /// raw `value`.
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let text = |key: &str| self.value.get(key).and_then(json_text);
+        let coin = |key: &str| {
+            self.value
+                .get(key)
+                .and_then(|v| CosmosCoin::from_value(v).ok())
+                .map(|c| c.display())
+        };
+        let coins = |key: &str| {
+            self.value.get(key).and_then(Value::as_array).map(|list| {
+                list.iter()
+                    .filter_map(|v| CosmosCoin::from_value(v).ok())
+                    .map(|c| c.display())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        };
+
+        let fields: Vec<(&'static str, Option<String>)> = match self.msg_type.as_str() {
+            "cosmos-sdk/MsgSend" => vec![
+                ("from", text("from_address")),
+                ("to", text("to_address")),
+                ("amount", coins("amount")),
+            ],
+            "cosmos-sdk/MsgDelegate" | "cosmos-sdk/MsgUndelegate" => vec![
+                ("delegator", text("delegator_address")),
+                ("validator", text("validator_address")),
+                ("amount", coin("amount")),
+            ],
+            "cosmos-sdk/MsgBeginRedelegate" => vec![
+                ("delegator", text("delegator_address")),
+                ("from validator", text("validator_src_address")),
+                ("to validator", text("validator_dst_address")),
+                ("amount", coin("amount")),
+            ],
+            "cosmos-sdk/MsgWithdrawDelegationReward" => vec![
+                ("delegator", text("delegator_address")),
+                ("validator", text("validator_address")),
+            ],
+            "cosmos-sdk/MsgVote" => vec![
+                ("voter", text("voter")),
+                ("proposal", text("proposal_id")),
+                ("option", text("option")),
+            ],
+            "cosmos-sdk/MsgTransfer" => vec![
+                ("sender", text("sender")),
+                ("receiver", text("receiver")),
+                ("channel", text("source_channel")),
+                ("token", coin("token")),
+            ],
+            _ => Vec::new(),
+        };
+
+        fields
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .collect()
+    }
+}
+
+/// Parsed amino JSON sign document
+#[derive(Debug, Clone, PartialEq)]
+pub struct AminoSignDoc {
+    /// Chain the transaction is valid on (e.g. `cosmoshub-4`)
+    pub chain_id: String,
+    /// Signer's account number
+    pub account_number: String,
+    /// Signer's sequence
+    pub sequence: String,
+    /// Fee coins
+    pub fee: Vec<CosmosCoin>,
+    /// Gas limit
+    pub gas: String,
+    /// Free-form memo
+    pub memo: String,
+    /// Messages in signing order
+    pub msgs: Vec<AminoMsg>,
+}
+
+impl AminoSignDoc {
+    /// Parse an amino JSON sign document
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let value: Value =
+            serde_json::from_slice(bytes).map_err(|e| invalid(format!("not valid JSON: {e}")))?;
+        Self::from_value(&value)
+    }
+
+    fn from_value(value: &Value) -> Result<Self> {
+        let fee = value.get("fee").ok_or_else(|| invalid("missing fee"))?;
+        let fee_amount = fee
+            .get("amount")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("missing fee.amount"))?
+            .iter()
+            .map(CosmosCoin::from_value)
+            .collect::<Result<Vec<_>>>()?;
+        let msgs = value
+            .get("msgs")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("missing msgs"))?
+            .iter()
+            .map(|msg| {
+                Ok(AminoMsg {
+                    msg_type: string_field(msg, "type")?,
+                    value: msg.get("value").cloned().unwrap_or(Value::Null),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            chain_id: string_field(value, "chain_id")?,
+            account_number: string_field(value, "account_number")?,
+            sequence: string_field(value, "sequence")?,
+            fee: fee_amount,
+            gas: string_field(fee, "gas")?,
+            memo: value.get("memo").and_then(json_text).unwrap_or_default(),
+            msgs,
+        })
+    }
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Result<String> {
+    value
+        .get(key)
+        .and_then(json_text)
+        .ok_or_else(|| invalid(format!("missing {key}")))
+}
+
+fn invalid(msg: impl std::fmt::Display) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid amino sign doc: {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELEGATE: &str = r#"{"account_number":"12","chain_id":"cosmoshub-4","fee":{"amount":[{"amount":"2500","denom":"uatom"}],"gas":"250000"},"memo":"stake","msgs":[{"type":"cosmos-sdk/MsgDelegate","value":{"amount":{"amount":"1000000","denom":"uatom"},"delegator_address":"cosmos1delegator","validator_address":"cosmosvaloper1validator"}}],"sequence":"3"}"#;
+
+    #[test]
+    fn test_parse_delegate_sign_doc() {
+        let doc = AminoSignDoc::parse(DELEGATE.as_bytes()).unwrap();
+
+        assert_eq!(doc.chain_id, "cosmoshub-4");
+        assert_eq!(doc.account_number, "12");
+        assert_eq!(doc.sequence, "3");
+        assert_eq!(doc.fee[0].display(), "2500 uatom");
+        assert_eq!(doc.gas, "250000");
+        assert_eq!(doc.memo, "stake");
+        assert_eq!(doc.msgs[0].label(), "MsgDelegate");
+        assert_eq!(
+            doc.msgs[0].details(),
+            vec![
+                ("delegator", "cosmos1delegator".to_string()),
+                ("validator", "cosmosvaloper1validator".to_string()),
+                ("amount", "1000000 uatom".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_numeric_fields_and_unknown_msg() {
+        let json = r#"{"account_number":5,"chain_id":"osmosis-1","fee":{"amount":[],"gas":100000},"msgs":[{"type":"osmosis/gamm/swap-exact-amount-in","value":{}}],"sequence":0}"#;
+        let doc = AminoSignDoc::parse(json.as_bytes()).unwrap();
+
+        assert_eq!(doc.account_number, "5");
+        assert_eq!(doc.gas, "100000");
+        assert_eq!(doc.memo, "");
+        assert_eq!(doc.msgs[0].label(), "swap-exact-amount-in");
+        assert!(doc.msgs[0].details().is_empty());
+    }
+
+    #[test]
+    fn test_missing_chain_id_is_rejected() {
+        let json =
+            r#"{"account_number":"1","fee":{"amount":[],"gas":"1"},"msgs":[],"sequence":"0"}"#;
+        let err = AminoSignDoc::parse(json.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("missing chain_id"));
+    }
+}
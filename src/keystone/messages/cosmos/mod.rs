@@ -0,0 +1,561 @@
+//! Cosmos SDK signature request and response types
+//!
+//! Reference: archive/keystone-sdk-rust/libs/ur-registry/src/cosmos/
+//!
+//! One request type covers every Cosmos SDK chain (ATOM, OSMO, ...). The
+//! wire format has no chain ID field; the chain is named inside the sign
+//! document itself, which [`CosmosSignRequest::chain_id`] reads back out.
+
+pub mod amino;
+
+pub use amino::{AminoMsg, AminoSignDoc, CosmosCoin};
+
+use crate::error::{Error, Result};
+use crate::keystone::cbor;
+use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::{Decoder, Encoder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Encoding of the Cosmos sign document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum CosmosDataType {
+    /// Legacy amino JSON `StdSignDoc`
+    Amino = 1,
+    /// Protobuf `SignDoc` (SIGN_MODE_DIRECT)
+    Direct = 2,
+    /// CBOR screens (SIGN_MODE_TEXTUAL)
+    Textual = 3,
+    /// Arbitrary message (ADR-036)
+    Message = 4,
+}
+
+impl CosmosDataType {
+    /// Short lowercase label for output
+    pub fn label(&self) -> &'static str {
+        match self {
+            CosmosDataType::Amino => "amino",
+            CosmosDataType::Direct => "direct",
+            CosmosDataType::Textual => "textual",
+            CosmosDataType::Message => "message",
+        }
+    }
+}
+
+impl From<CosmosDataType> for u8 {
+    fn from(t: CosmosDataType) -> u8 {
+        t as u8
+    }
+}
+
+impl TryFrom<u8> for CosmosDataType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            1 => Ok(CosmosDataType::Amino),
+            2 => Ok(CosmosDataType::Direct),
+            3 => Ok(CosmosDataType::Textual),
+            4 => Ok(CosmosDataType::Message),
+            _ => Err(Error::InvalidKeystonePayload(format!(
+                "Invalid Cosmos data type: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Signing account named by a Cosmos request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosmosAccount {
+    /// BIP32 derivation path (source fingerprint identifies the wallet)
+    pub derivation_path: CryptoKeyPath,
+    /// Optional bech32 address (e.g. `cosmos1...`, `osmo1...`)
+    pub address: Option<String>,
+}
+
+impl CosmosAccount {
+    /// Create an account entry without an address
+    pub fn new(derivation_path: CryptoKeyPath) -> Self {
+        Self {
+            derivation_path,
+            address: None,
+        }
+    }
+
+    /// Set the bech32 address
+    pub fn with_address(mut self, address: String) -> Self {
+        self.address = Some(address);
+        self
+    }
+}
+
+/// Cosmos signature request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosmosSignRequest {
+    /// Optional request ID for matching request/response
+    pub request_id: Option<Uuid>,
+    /// Sign document bytes in the encoding given by `data_type`
+    pub sign_data: Vec<u8>,
+    /// Encoding of `sign_data`
+    pub data_type: CosmosDataType,
+    /// Accounts expected to sign (multi-signer requests list several)
+    pub accounts: Vec<CosmosAccount>,
+    /// Optional origin (dApp identifier)
+    pub origin: Option<String>,
+}
+
+impl CosmosSignRequest {
+    /// Create a new sign request with a fresh request ID
+    pub fn new(
+        sign_data: Vec<u8>,
+        data_type: CosmosDataType,
+        accounts: Vec<CosmosAccount>,
+    ) -> Self {
+        Self {
+            request_id: Some(Uuid::new_v4()),
+            sign_data,
+            data_type,
+            accounts,
+            origin: None,
+        }
+    }
+
+    /// Set the origin (dApp URL)
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Parse `sign_data` as an amino JSON sign document
+    pub fn decode_amino(&self) -> Result<AminoSignDoc> {
+        match self.data_type {
+            CosmosDataType::Amino => AminoSignDoc::parse(&self.sign_data),
+            other => Err(Error::InvalidKeystonePayload(format!(
+                "Cosmos sign request carries {} data, not amino JSON",
+                other.label()
+            ))),
+        }
+    }
+
+    /// Chain ID named by the sign document, if it can be read
+    ///
+    /// Amino documents carry `chain_id` as a JSON field and direct documents
+    /// carry it as `SignDoc` field 3. Textual and message requests return
+    /// `None`.
+    pub fn chain_id(&self) -> Option<String> {
+        match self.data_type {
+            CosmosDataType::Amino => AminoSignDoc::parse(&self.sign_data)
+                .ok()
+                .map(|doc| doc.chain_id),
+            CosmosDataType::Direct => direct_chain_id(&self.sign_data),
+            CosmosDataType::Textual | CosmosDataType::Message => None,
+        }
+    }
+
+    /// Serialize the request as CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Parse a request from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+/// Read `chain_id` (field 3) from a protobuf `SignDoc`
+fn direct_chain_id(bytes: &[u8]) -> Option<String> {
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let key = read_varint(bytes, &mut pos)?;
+        match key & 0x07 {
+            0 => {
+                read_varint(bytes, &mut pos)?;
+            }
+            1 => pos += 8,
+            2 => {
+                let len = usize::try_from(read_varint(bytes, &mut pos)?).ok()?;
+                let end = pos.checked_add(len).filter(|end| *end <= bytes.len())?;
+                if key >> 3 == 3 {
+                    return String::from_utf8(bytes[pos..end].to_vec()).ok();
+                }
+                pos = end;
+            }
+            5 => pos += 4,
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+// CBOR encoding for CosmosSignRequest (tag 4101)
+impl minicbor::Encode<()> for CosmosSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::COSMOS_SIGN_REQUEST,
+        ))?;
+
+        // Addresses are all-or-nothing on the wire: one per derivation path
+        let addresses: Option<Vec<&str>> = self
+            .accounts
+            .iter()
+            .map(|account| account.address.as_deref())
+            .collect();
+        let addresses = addresses.filter(|list| !list.is_empty());
+
+        let mut size = 3; // sign_data, data_type, derivation_paths
+        if self.request_id.is_some() {
+            size += 1;
+        }
+        if addresses.is_some() {
+            size += 1;
+        }
+        if self.origin.is_some() {
+            size += 1;
+        }
+
+        e.map(size)?;
+
+        // Key 1: request_id (optional UUID with tag 37)
+        if let Some(ref uuid) = self.request_id {
+            e.u64(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        // Key 2: sign_data
+        e.u64(2)?.bytes(&self.sign_data)?;
+
+        // Key 3: data_type
+        e.u64(3)?.u8(self.data_type.into())?;
+
+        // Key 4: derivation_paths
+        e.u64(4)?.array(self.accounts.len() as u64)?;
+        for account in &self.accounts {
+            e.encode(&account.derivation_path)?;
+        }
+
+        // Key 5: addresses (optional)
+        if let Some(addresses) = addresses {
+            e.u64(5)?.array(addresses.len() as u64)?;
+            for address in addresses {
+                e.str(address)?;
+            }
+        }
+
+        // Key 6: origin (optional)
+        if let Some(ref origin) = self.origin {
+            e.u64(6)?.str(origin)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CosmosSignRequest {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::COSMOS_SIGN_REQUEST) {
+            return Err(minicbor::decode::Error::message(
+                "Expected cosmos-sign-request tag 4101",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("Expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut sign_data = None;
+        let mut data_type = None;
+        let mut derivation_paths: Option<Vec<CryptoKeyPath>> = None;
+        let mut addresses: Option<Vec<String>> = None;
+        let mut origin = None;
+
+        for _ in 0..map_len {
+            let key = d.u64()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag == minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        let bytes = d.bytes()?;
+                        request_id = Uuid::from_slice(bytes).ok();
+                    }
+                }
+                2 => sign_data = Some(d.bytes()?.to_vec()),
+                3 => {
+                    data_type = Some(
+                        CosmosDataType::try_from(d.u8()?)
+                            .map_err(|_| minicbor::decode::Error::message("Invalid data type"))?,
+                    )
+                }
+                4 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("Expected definite-length array")
+                    })?;
+                    let mut paths = Vec::with_capacity(len as usize);
+                    for _ in 0..len {
+                        paths.push(d.decode()?);
+                    }
+                    derivation_paths = Some(paths);
+                }
+                5 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("Expected definite-length array")
+                    })?;
+                    let mut list = Vec::with_capacity(len as usize);
+                    for _ in 0..len {
+                        list.push(d.str()?.to_string());
+                    }
+                    addresses = Some(list);
+                }
+                6 => origin = Some(d.str()?.to_string()),
+                _ => {
+                    d.skip()?;
+                }
+            }
+        }
+
+        let derivation_paths = derivation_paths
+            .ok_or_else(|| minicbor::decode::Error::message("Missing derivation_paths"))?;
+        let accounts = match addresses {
+            Some(addresses) if addresses.len() != derivation_paths.len() => {
+                return Err(minicbor::decode::Error::message(
+                    "Address count does not match derivation path count",
+                ));
+            }
+            Some(addresses) => derivation_paths
+                .into_iter()
+                .zip(addresses)
+                .map(|(path, address)| CosmosAccount::new(path).with_address(address))
+                .collect(),
+            None => derivation_paths
+                .into_iter()
+                .map(CosmosAccount::new)
+                .collect(),
+        };
+
+        Ok(CosmosSignRequest {
+            request_id,
+            sign_data: sign_data
+                .ok_or_else(|| minicbor::decode::Error::message("Missing sign_data"))?,
+            data_type: data_type
+                .ok_or_else(|| minicbor::decode::Error::message("Missing data_type"))?,
+            accounts,
+            origin,
+        })
+    }
+}
+
+/// Cosmos signature (response from Keystone)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosmosSignature {
+    /// Request ID (matches the request)
+    pub request_id: Option<Uuid>,
+    /// secp256k1 signature (64 bytes: r + s)
+    pub signature: Vec<u8>,
+    /// Compressed public key of the signing account
+    pub public_key: Option<Vec<u8>>,
+}
+
+impl CosmosSignature {
+    /// Create a new signature
+    pub fn new(signature: Vec<u8>, request_id: Option<Uuid>) -> Self {
+        Self {
+            request_id,
+            signature,
+            public_key: None,
+        }
+    }
+
+    /// Attach the signer's public key
+    pub fn with_public_key(mut self, public_key: Vec<u8>) -> Self {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    /// Serialize the signature as CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Parse a signature from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+// CBOR encoding for CosmosSignature (tag 4102)
+impl minicbor::Encode<()> for CosmosSignature {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::COSMOS_SIGNATURE,
+        ))?;
+
+        let mut size = 1; // signature is required
+        if self.request_id.is_some() {
+            size += 1;
+        }
+        if self.public_key.is_some() {
+            size += 1;
+        }
+
+        e.map(size)?;
+
+        // Key 1: request_id (optional)
+        if let Some(ref uuid) = self.request_id {
+            e.u64(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        // Key 2: signature
+        e.u64(2)?.bytes(&self.signature)?;
+
+        // Key 3: public_key (optional)
+        if let Some(ref public_key) = self.public_key {
+            e.u64(3)?.bytes(public_key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CosmosSignature {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::COSMOS_SIGNATURE) {
+            return Err(minicbor::decode::Error::message(
+                "Expected cosmos-signature tag 4102",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("Expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut signature = None;
+        let mut public_key = None;
+
+        for _ in 0..map_len {
+            let key = d.u64()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag == minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        let bytes = d.bytes()?;
+                        request_id = Uuid::from_slice(bytes).ok();
+                    }
+                }
+                2 => signature = Some(d.bytes()?.to_vec()),
+                3 => public_key = Some(d.bytes()?.to_vec()),
+                _ => {
+                    d.skip()?;
+                }
+            }
+        }
+
+        Ok(CosmosSignature {
+            request_id,
+            signature: signature
+                .ok_or_else(|| minicbor::decode::Error::message("Missing signature"))?,
+            public_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosmos_sign_request_cbor() {
+        let accounts = vec![
+            CosmosAccount::new(CryptoKeyPath::from_str("m/44'/118'/0'/0/0").unwrap())
+                .with_address("cosmos1abc".to_string()),
+            CosmosAccount::new(CryptoKeyPath::from_str("m/44'/118'/0'/0/1").unwrap())
+                .with_address("cosmos1def".to_string()),
+        ];
+        let request = CosmosSignRequest::new(b"{}".to_vec(), CosmosDataType::Amino, accounts)
+            .with_origin("keplr".to_string());
+
+        let bytes = request.to_cbor().unwrap();
+        let decoded = CosmosSignRequest::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.request_id, request.request_id);
+        assert_eq!(decoded.data_type, CosmosDataType::Amino);
+        assert_eq!(decoded.accounts.len(), 2);
+        assert_eq!(
+            decoded.accounts[1].derivation_path.to_string(),
+            "m/44'/118'/0'/0/1"
+        );
+        assert_eq!(decoded.accounts[1].address.as_deref(), Some("cosmos1def"));
+        assert_eq!(decoded.origin.as_deref(), Some("keplr"));
+    }
+
+    #[test]
+    fn test_cosmos_sign_request_without_addresses() {
+        let accounts = vec![CosmosAccount::new(
+            CryptoKeyPath::from_str("m/44'/118'/0'/0/0").unwrap(),
+        )];
+        let request = CosmosSignRequest::new(vec![0x0a, 0x00], CosmosDataType::Direct, accounts);
+
+        let decoded = CosmosSignRequest::from_cbor(&request.to_cbor().unwrap()).unwrap();
+
+        assert_eq!(decoded.data_type, CosmosDataType::Direct);
+        assert!(decoded.accounts[0].address.is_none());
+    }
+
+    #[test]
+    fn test_cosmos_signature_cbor() {
+        let sig = CosmosSignature::new(vec![7u8; 64], Some(Uuid::new_v4()))
+            .with_public_key(vec![2u8; 33]);
+
+        let decoded = CosmosSignature::from_cbor(&sig.to_cbor().unwrap()).unwrap();
+
+        assert_eq!(decoded.request_id, sig.request_id);
+        assert_eq!(decoded.signature, sig.signature);
+        assert_eq!(decoded.public_key, sig.public_key);
+    }
+
+    #[test]
+    fn test_chain_id_from_direct_sign_doc() {
+        // SignDoc { body_bytes: [0x01], auth_info_bytes: [], chain_id: "osmosis-1", account_number: 7 }
+        let mut sign_doc = vec![0x0a, 0x01, 0x01, 0x12, 0x00, 0x1a, 0x09];
+        sign_doc.extend_from_slice(b"osmosis-1");
+        sign_doc.extend_from_slice(&[0x20, 0x07]);
+        let request = CosmosSignRequest::new(sign_doc, CosmosDataType::Direct, Vec::new());
+
+        assert_eq!(request.chain_id().as_deref(), Some("osmosis-1"));
+    }
+}
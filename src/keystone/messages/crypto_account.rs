@@ -1,14 +1,23 @@
 //! Crypto-account (wallet pairing) message types
 //!
-//! Simplified implementation for wallet connection/pairing
-//! Full implementation with CryptoOutput descriptors can be added later
+//! Two wire forms are supported: the BCR-2020-015 form used by devices
+//! (`{1: master-fingerprint, 2: [crypto-output]}`) and a compact single-key
+//! form (`{1: fingerprint, 2: public key, 3: keypath, 4: chain code}`).
+//!
+//! Reference: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-015-account.md
 
 use crate::error::Result;
 use crate::keystone::cbor;
 use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::data::Type;
 use minicbor::{Decoder, Encoder};
 
-/// Simplified crypto-account for wallet pairing
+use super::crypto_output::{CryptoOutput, OutputKey};
+
+/// Crypto-account for wallet pairing
+///
+/// When decoded from device output, `public_key`, `key_path` and
+/// `chain_code` mirror the first descriptor in `outputs`.
 #[derive(Debug, Clone)]
 pub struct CryptoAccount {
     /// Master fingerprint (4 bytes)
@@ -19,6 +28,8 @@ pub struct CryptoAccount {
     pub key_path: CryptoKeyPath,
     /// Optional chain code
     pub chain_code: Option<Vec<u8>>,
+    /// Output descriptors (device form); empty for the compact form
+    pub outputs: Vec<CryptoOutput>,
 }
 
 impl CryptoAccount {
@@ -29,6 +40,39 @@ impl CryptoAccount {
             public_key,
             key_path,
             chain_code: None,
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Create an account from output descriptors (device form)
+    pub fn from_outputs(master_fingerprint: [u8; 4], outputs: Vec<CryptoOutput>) -> Self {
+        let mut account = Self::new(
+            master_fingerprint,
+            Vec::new(),
+            CryptoKeyPath::new(Vec::new()),
+        );
+        account.outputs = outputs;
+        account.fill_from_outputs();
+        account
+    }
+
+    /// Output descriptor strings for every output
+    pub fn descriptors(&self) -> Result<Vec<String>> {
+        self.outputs.iter().map(CryptoOutput::descriptor).collect()
+    }
+
+    /// Mirror the first single-key output into the compact fields
+    fn fill_from_outputs(&mut self) {
+        match self.outputs.first().map(|output| &output.key) {
+            Some(OutputKey::HdKey(key)) => {
+                self.public_key = key.key_data.clone();
+                self.chain_code = key.chain_code.clone();
+                if let Some(origin) = &key.origin {
+                    self.key_path = origin.clone();
+                }
+            }
+            Some(OutputKey::EcKey(key)) => self.public_key = key.data.clone(),
+            _ => {}
         }
     }
 
@@ -60,6 +104,17 @@ impl minicbor::Encode<()> for CryptoAccount {
         e: &mut Encoder<W>,
         ctx: &mut (),
     ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        if !self.outputs.is_empty() {
+            e.map(2)?;
+            e.u8(1)?.u32(self.fingerprint_u32())?;
+            e.u8(2)?.array(self.outputs.len() as u64)?;
+            for output in &self.outputs {
+                e.tag(minicbor::data::Tag::Unassigned(cbor::tags::CRYPTO_OUTPUT))?;
+                output.encode(e, ctx)?;
+            }
+            return Ok(());
+        }
+
         let map_size = if self.chain_code.is_some() { 4 } else { 3 };
         e.map(map_size)?;
 
@@ -95,6 +150,7 @@ impl<'b> minicbor::Decode<'b, ()> for CryptoAccount {
         let mut public_key = None;
         let mut key_path = None;
         let mut chain_code = None;
+        let mut outputs = Vec::new();
 
         let map_len = d.map()?.ok_or_else(|| {
             minicbor::decode::Error::message("expected definite-length map for CryptoAccount")
@@ -108,6 +164,15 @@ impl<'b> minicbor::Decode<'b, ()> for CryptoAccount {
                     let fp_u32 = d.u32()?;
                     master_fingerprint = Some(fp_u32.to_be_bytes());
                 }
+                2 if d.datatype()? == Type::Array => {
+                    // output descriptors (device form)
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length output array")
+                    })?;
+                    for _ in 0..len {
+                        outputs.push(CryptoOutput::decode(d, ctx)?);
+                    }
+                }
                 2 => {
                     // public_key
                     public_key = Some(d.bytes()?.to_vec());
@@ -133,14 +198,20 @@ impl<'b> minicbor::Decode<'b, ()> for CryptoAccount {
             }
         }
 
+        let master_fingerprint = master_fingerprint
+            .ok_or_else(|| minicbor::decode::Error::message("missing master_fingerprint"))?;
+        if !outputs.is_empty() {
+            return Ok(Self::from_outputs(master_fingerprint, outputs));
+        }
+
         Ok(Self {
-            master_fingerprint: master_fingerprint
-                .ok_or_else(|| minicbor::decode::Error::message("missing master_fingerprint"))?,
+            master_fingerprint,
             public_key: public_key
                 .ok_or_else(|| minicbor::decode::Error::message("missing public_key"))?,
             key_path: key_path
                 .ok_or_else(|| minicbor::decode::Error::message("missing key_path"))?,
             chain_code,
+            outputs,
         })
     }
 }
@@ -197,4 +268,29 @@ mod tests {
         let account = CryptoAccount::new(fp, public_key, path);
         assert_eq!(account.fingerprint_u32(), 0x12345678);
     }
+
+    #[test]
+    fn test_crypto_account_with_outputs() {
+        use crate::keystone::messages::{CryptoHdKey, ScriptExpression};
+
+        let origin = CryptoKeyPath::from_str("m/84'/0'/0'")
+            .unwrap()
+            .with_source_fingerprint([0x73, 0xc5, 0xda, 0x0a]);
+        let key = CryptoHdKey::new(vec![0x03; 33], vec![0x11; 32])
+            .with_origin(origin.clone())
+            .with_parent_fingerprint([0xde, 0xad, 0xbe, 0xef]);
+        let output = CryptoOutput::new(
+            vec![ScriptExpression::WitnessPublicKeyHash],
+            OutputKey::HdKey(key),
+        );
+        let account = CryptoAccount::from_outputs([0x73, 0xc5, 0xda, 0x0a], vec![output]);
+
+        let decoded = CryptoAccount::from_cbor(&account.to_cbor().unwrap()).unwrap();
+        assert_eq!(decoded.outputs, account.outputs);
+        assert_eq!(decoded.public_key, vec![0x03; 33]);
+        assert_eq!(decoded.key_path, origin);
+
+        let descriptors = decoded.descriptors().unwrap();
+        assert!(descriptors[0].starts_with("wpkh([73c5da0a/84'/0'/0']xpub"));
+    }
 }
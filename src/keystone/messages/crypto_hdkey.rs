@@ -0,0 +1,445 @@
+//! Crypto-hdkey (BIP32 extended key) message types
+//!
+//! Reference: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-007-hdkey.md
+
+use crate::error::{Error, Result};
+use crate::keystone::cbor;
+use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::data::{Tag, Type};
+use minicbor::{Decoder, Encoder};
+
+/// BIP32 version bytes for mainnet extended public keys (`xpub`)
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+/// BIP32 version bytes for testnet extended public keys (`tpub`)
+const TPUB_VERSION: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+/// Coin and network the key is intended for (crypto-coin-info, tag 305)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CryptoCoinInfo {
+    /// SLIP-44 coin type (0 = Bitcoin, 60 = Ethereum)
+    pub coin_type: u32,
+    /// Network (0 = mainnet, 1 = testnet)
+    pub network: u32,
+}
+
+impl CryptoCoinInfo {
+    /// Create coin info for a coin type and network
+    pub fn new(coin_type: u32, network: u32) -> Self {
+        Self { coin_type, network }
+    }
+
+    /// Whether the key belongs to a test network
+    pub fn is_testnet(&self) -> bool {
+        self.network == 1
+    }
+}
+
+impl minicbor::Encode<()> for CryptoCoinInfo {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(Tag::Unassigned(cbor::tags::CRYPTO_COIN_INFO))?;
+        e.map(2)?;
+        e.u8(1)?.u32(self.coin_type)?;
+        e.u8(2)?.u32(self.network)?;
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CryptoCoinInfo {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        expect_tag(d, cbor::tags::CRYPTO_COIN_INFO, "crypto-coin-info")?;
+
+        let map_len = d.map()?.ok_or_else(|| {
+            minicbor::decode::Error::message("expected definite-length map for CryptoCoinInfo")
+        })?;
+
+        let mut info = Self::default();
+        for _ in 0..map_len {
+            match d.u8()? {
+                1 => info.coin_type = d.u32()?,
+                2 => info.network = d.u32()?,
+                _ => d.skip()?,
+            }
+        }
+        Ok(info)
+    }
+}
+
+/// BIP32 extended key as exported by Keystone for watch-only pairing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoHdKey {
+    /// Whether this is a master key (always private)
+    pub is_master: bool,
+    /// Whether `key_data` is a private key
+    pub is_private: bool,
+    /// Key material (33-byte compressed public key, or 0x00-prefixed private key)
+    pub key_data: Vec<u8>,
+    /// BIP32 chain code (32 bytes)
+    pub chain_code: Option<Vec<u8>>,
+    /// Coin and network the key is intended for
+    pub use_info: Option<CryptoCoinInfo>,
+    /// Derivation path from the master key to this key
+    pub origin: Option<CryptoKeyPath>,
+    /// Child derivation path template (e.g. `0/*`)
+    pub children: Option<CryptoKeyPath>,
+    /// Fingerprint of the parent key
+    pub parent_fingerprint: Option<[u8; 4]>,
+    /// Short name for the key
+    pub name: Option<String>,
+    /// Free-form note
+    pub note: Option<String>,
+}
+
+impl CryptoHdKey {
+    /// Create a derived public key with its chain code
+    pub fn new(key_data: Vec<u8>, chain_code: Vec<u8>) -> Self {
+        Self {
+            is_master: false,
+            is_private: false,
+            key_data,
+            chain_code: Some(chain_code),
+            use_info: None,
+            origin: None,
+            children: None,
+            parent_fingerprint: None,
+            name: None,
+            note: None,
+        }
+    }
+
+    /// Set the coin/network info
+    pub fn with_use_info(mut self, use_info: CryptoCoinInfo) -> Self {
+        self.use_info = Some(use_info);
+        self
+    }
+
+    /// Set the origin derivation path
+    pub fn with_origin(mut self, origin: CryptoKeyPath) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Set the child derivation path template
+    pub fn with_children(mut self, children: CryptoKeyPath) -> Self {
+        self.children = Some(children);
+        self
+    }
+
+    /// Set the parent key fingerprint
+    pub fn with_parent_fingerprint(mut self, fingerprint: [u8; 4]) -> Self {
+        self.parent_fingerprint = Some(fingerprint);
+        self
+    }
+
+    /// Set the key name
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Set the key note
+    pub fn with_note(mut self, note: String) -> Self {
+        self.note = Some(note);
+        self
+    }
+
+    /// Master fingerprint recorded in the origin path, if any
+    pub fn source_fingerprint(&self) -> Option<[u8; 4]> {
+        self.origin
+            .as_ref()
+            .and_then(|path| path.source_fingerprint)
+    }
+
+    /// SLIP-44 coin type from the use info, or the `44'/<coin>'` origin path
+    pub fn coin_type(&self) -> Option<u32> {
+        if let Some(info) = self.use_info {
+            return Some(info.coin_type);
+        }
+        self.origin
+            .as_ref()
+            .and_then(|origin| origin.components.get(1))
+            .filter(|component| component.hardened && !component.wildcard)
+            .map(|component| component.index)
+    }
+
+    /// Serialize as a base58check extended public key (`xpub` / `tpub`)
+    pub fn xpub(&self) -> Result<String> {
+        if self.is_master || self.is_private {
+            return Err(Error::InvalidKeystonePayload(
+                "Cannot export a private hdkey as xpub".to_string(),
+            ));
+        }
+        if self.key_data.len() != 33 {
+            return Err(Error::InvalidKeystonePayload(format!(
+                "Invalid hdkey public key length: {}",
+                self.key_data.len()
+            )));
+        }
+        let chain_code = self
+            .chain_code
+            .as_deref()
+            .filter(|code| code.len() == 32)
+            .ok_or_else(|| {
+                Error::InvalidKeystonePayload("hdkey is missing a 32-byte chain code".to_string())
+            })?;
+
+        let version = if self.use_info.is_some_and(|info| info.is_testnet()) {
+            TPUB_VERSION
+        } else {
+            XPUB_VERSION
+        };
+        let (depth, child_number) = match &self.origin {
+            Some(origin) => {
+                let depth = origin
+                    .depth
+                    .unwrap_or(origin.components.len().min(u8::MAX as usize) as u8);
+                let child = origin
+                    .components
+                    .last()
+                    .map(|c| c.to_bip32_index())
+                    .unwrap_or(0);
+                (depth, child)
+            }
+            None => (0, 0),
+        };
+
+        let mut raw = Vec::with_capacity(78);
+        raw.extend_from_slice(&version);
+        raw.push(depth);
+        raw.extend_from_slice(&self.parent_fingerprint.unwrap_or([0; 4]));
+        raw.extend_from_slice(&child_number.to_be_bytes());
+        raw.extend_from_slice(chain_code);
+        raw.extend_from_slice(&self.key_data);
+        Ok(bs58::encode(raw).with_check().into_string())
+    }
+
+    /// Descriptor key expression, e.g. `[73c5da0a/84'/0'/0']xpub.../0/*`
+    pub fn descriptor_key(&self) -> Result<String> {
+        let mut key = String::new();
+        if let Some(origin) = &self.origin {
+            if let Some(fingerprint) = origin.source_fingerprint {
+                key.push('[');
+                key.push_str(&hex::encode(fingerprint));
+                for component in origin.to_string().split('/').skip(1) {
+                    key.push('/');
+                    key.push_str(component);
+                }
+                key.push(']');
+            }
+        }
+        key.push_str(&self.xpub()?);
+        if let Some(children) = &self.children {
+            for component in children.to_string().split('/').skip(1) {
+                key.push('/');
+                key.push_str(component);
+            }
+        }
+        Ok(key)
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 1; // key_data
+        if self.is_master {
+            len += 1;
+        }
+        if self.is_private && !self.is_master {
+            len += 1;
+        }
+        len += self.chain_code.is_some() as u64;
+        len += self.use_info.is_some() as u64;
+        len += self.origin.is_some() as u64;
+        len += self.children.is_some() as u64;
+        len += self.parent_fingerprint.is_some() as u64;
+        len += self.name.is_some() as u64;
+        len += self.note.is_some() as u64;
+        len
+    }
+}
+
+impl minicbor::Encode<()> for CryptoHdKey {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(self.map_len())?;
+
+        if self.is_master {
+            e.u8(1)?.bool(true)?;
+        }
+        if self.is_private && !self.is_master {
+            e.u8(2)?.bool(true)?;
+        }
+        e.u8(3)?.bytes(&self.key_data)?;
+        if let Some(chain_code) = &self.chain_code {
+            e.u8(4)?.bytes(chain_code)?;
+        }
+        if let Some(use_info) = &self.use_info {
+            e.u8(5)?;
+            use_info.encode(e, ctx)?;
+        }
+        if let Some(origin) = &self.origin {
+            e.u8(6)?;
+            origin.encode(e, ctx)?;
+        }
+        if let Some(children) = &self.children {
+            e.u8(7)?;
+            children.encode(e, ctx)?;
+        }
+        if let Some(fingerprint) = self.parent_fingerprint {
+            e.u8(8)?.u32(u32::from_be_bytes(fingerprint))?;
+        }
+        if let Some(name) = &self.name {
+            e.u8(9)?.str(name)?;
+        }
+        if let Some(note) = &self.note {
+            e.u8(10)?.str(note)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CryptoHdKey {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Top-level crypto-hdkey bodies are untagged; embedded ones carry tag 303
+        if d.datatype()? == Type::Tag {
+            expect_tag(d, cbor::tags::CRYPTO_HDKEY, "crypto-hdkey")?;
+        }
+
+        let map_len = d.map()?.ok_or_else(|| {
+            minicbor::decode::Error::message("expected definite-length map for CryptoHdKey")
+        })?;
+
+        let mut is_master = false;
+        let mut is_private = false;
+        let mut key_data = None;
+        let mut chain_code = None;
+        let mut use_info = None;
+        let mut origin = None;
+        let mut children = None;
+        let mut parent_fingerprint = None;
+        let mut name = None;
+        let mut note = None;
+
+        for _ in 0..map_len {
+            match d.u8()? {
+                1 => is_master = d.bool()?,
+                2 => is_private = d.bool()?,
+                3 => key_data = Some(d.bytes()?.to_vec()),
+                4 => chain_code = Some(d.bytes()?.to_vec()),
+                5 => use_info = Some(CryptoCoinInfo::decode(d, ctx)?),
+                6 => origin = Some(CryptoKeyPath::decode(d, ctx)?),
+                7 => children = Some(CryptoKeyPath::decode(d, ctx)?),
+                8 => parent_fingerprint = Some(d.u32()?.to_be_bytes()),
+                9 => name = Some(d.str()?.to_string()),
+                10 => note = Some(d.str()?.to_string()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            is_master,
+            // Master keys are always private
+            is_private: is_private || is_master,
+            key_data: key_data
+                .ok_or_else(|| minicbor::decode::Error::message("missing key_data"))?,
+            chain_code,
+            use_info,
+            origin,
+            children,
+            parent_fingerprint,
+            name,
+            note,
+        })
+    }
+}
+
+/// Consume a tag and check it matches `expected`
+pub(crate) fn expect_tag(
+    d: &mut Decoder<'_>,
+    expected: u64,
+    name: &'static str,
+) -> std::result::Result<(), minicbor::decode::Error> {
+    if d.tag()? != Tag::Unassigned(expected) {
+        return Err(minicbor::decode::Error::message(format!(
+            "expected {name} tag {expected}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP32 test vector 1, chain m/0'
+    const PUBLIC_KEY: &str = "035a784662a4a20a65bf6aab9ae98a6c068a81c52e4b032c0fb5400c706cfccc56";
+    const CHAIN_CODE: &str = "47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141";
+    const XPUB: &str = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+
+    fn sample_key() -> CryptoHdKey {
+        CryptoHdKey::new(
+            hex::decode(PUBLIC_KEY).unwrap(),
+            hex::decode(CHAIN_CODE).unwrap(),
+        )
+        .with_origin(
+            CryptoKeyPath::from_str("m/0'")
+                .unwrap()
+                .with_source_fingerprint([0x34, 0x42, 0x19, 0x3e]),
+        )
+        .with_parent_fingerprint([0x34, 0x42, 0x19, 0x3e])
+    }
+
+    #[test]
+    fn test_xpub_serialization() {
+        assert_eq!(sample_key().xpub().unwrap(), XPUB);
+    }
+
+    #[test]
+    fn test_descriptor_key() {
+        let key = sample_key().with_children(CryptoKeyPath::from_str("0/*").unwrap());
+        assert_eq!(
+            key.descriptor_key().unwrap(),
+            format!("[3442193e/0']{XPUB}/0/*")
+        );
+    }
+
+    #[test]
+    fn test_hdkey_cbor_roundtrip() {
+        let key = sample_key()
+            .with_use_info(CryptoCoinInfo::new(0, 1))
+            .with_name("Keystone".to_string());
+
+        let bytes = key.to_cbor().unwrap();
+        let decoded = CryptoHdKey::from_cbor(&bytes).unwrap();
+        assert_eq!(decoded, key);
+        assert!(decoded.xpub().unwrap().starts_with("tpub"));
+    }
+
+    #[test]
+    fn test_private_key_has_no_xpub() {
+        let mut key = sample_key();
+        key.is_private = true;
+        assert!(key.xpub().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Crypto-multi-accounts (Keystone multi-chain pairing) message types
+//!
+//! Emitted by the "connect software wallet" flow: one master fingerprint,
+//! device details and an extended or plain key per supported chain.
+
+use crate::error::Result;
+use crate::keystone::cbor;
+use minicbor::data::{Tag, Type};
+use minicbor::{Decoder, Encoder};
+
+use super::crypto_hdkey::{CryptoHdKey, expect_tag};
+
+/// Multi-chain account export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoMultiAccounts {
+    /// Master fingerprint (4 bytes)
+    pub master_fingerprint: [u8; 4],
+    /// Keys for every exported chain
+    pub keys: Vec<CryptoHdKey>,
+    /// Device type (e.g. "Keystone 3 Pro")
+    pub device_type: Option<String>,
+    /// Unique device identifier
+    pub device_id: Option<String>,
+    /// Firmware version
+    pub firmware_version: Option<String>,
+}
+
+impl CryptoMultiAccounts {
+    /// Create a multi-account export
+    pub fn new(master_fingerprint: [u8; 4], keys: Vec<CryptoHdKey>) -> Self {
+        Self {
+            master_fingerprint,
+            keys,
+            device_type: None,
+            device_id: None,
+            firmware_version: None,
+        }
+    }
+
+    /// Set the device type
+    pub fn with_device_type(mut self, device_type: String) -> Self {
+        self.device_type = Some(device_type);
+        self
+    }
+
+    /// Set the device identifier
+    pub fn with_device_id(mut self, device_id: String) -> Self {
+        self.device_id = Some(device_id);
+        self
+    }
+
+    /// Set the firmware version
+    pub fn with_firmware_version(mut self, firmware_version: String) -> Self {
+        self.firmware_version = Some(firmware_version);
+        self
+    }
+
+    /// Get the master fingerprint as u32
+    pub fn fingerprint_u32(&self) -> u32 {
+        u32::from_be_bytes(self.master_fingerprint)
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+
+    fn map_len(&self) -> u64 {
+        2 + self.device_type.is_some() as u64
+            + self.device_id.is_some() as u64
+            + self.firmware_version.is_some() as u64
+    }
+}
+
+impl minicbor::Encode<()> for CryptoMultiAccounts {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(self.map_len())?;
+
+        e.u8(1)?.u32(self.fingerprint_u32())?;
+
+        e.u8(2)?.array(self.keys.len() as u64)?;
+        for key in &self.keys {
+            e.tag(Tag::Unassigned(cbor::tags::CRYPTO_HDKEY))?;
+            key.encode(e, ctx)?;
+        }
+
+        if let Some(device_type) = &self.device_type {
+            e.u8(3)?.str(device_type)?;
+        }
+        if let Some(device_id) = &self.device_id {
+            e.u8(4)?.str(device_id)?;
+        }
+        if let Some(firmware_version) = &self.firmware_version {
+            e.u8(5)?.str(firmware_version)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CryptoMultiAccounts {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        if d.datatype()? == Type::Tag {
+            expect_tag(
+                d,
+                cbor::tags::CRYPTO_MULTI_ACCOUNTS,
+                "crypto-multi-accounts",
+            )?;
+        }
+
+        let map_len = d.map()?.ok_or_else(|| {
+            minicbor::decode::Error::message("expected definite-length map for CryptoMultiAccounts")
+        })?;
+
+        let mut master_fingerprint = None;
+        let mut keys = Vec::new();
+        let mut device_type = None;
+        let mut device_id = None;
+        let mut firmware_version = None;
+
+        for _ in 0..map_len {
+            match d.u8()? {
+                1 => master_fingerprint = Some(d.u32()?.to_be_bytes()),
+                2 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length key array")
+                    })?;
+                    for _ in 0..len {
+                        keys.push(CryptoHdKey::decode(d, ctx)?);
+                    }
+                }
+                3 => device_type = Some(d.str()?.to_string()),
+                4 => device_id = Some(d.str()?.to_string()),
+                5 => firmware_version = Some(d.str()?.to_string()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            master_fingerprint: master_fingerprint
+                .ok_or_else(|| minicbor::decode::Error::message("missing master_fingerprint"))?,
+            keys,
+            device_type,
+            device_id,
+            firmware_version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+
+    #[test]
+    fn test_multi_accounts_roundtrip() {
+        let eth = CryptoHdKey::new(vec![0x02; 33], vec![0xaa; 32])
+            .with_origin(CryptoKeyPath::from_str("m/44'/60'/0'").unwrap());
+        let mut sol = CryptoHdKey::new(vec![0x11; 32], Vec::new())
+            .with_origin(CryptoKeyPath::from_str("m/44'/501'/0'").unwrap());
+        sol.chain_code = None;
+
+        let accounts = CryptoMultiAccounts::new([0xf2, 0x3f, 0x9f, 0xd2], vec![eth, sol])
+            .with_device_type("Keystone 3 Pro".to_string())
+            .with_device_id("28475c8d80f6c06bafbe46a7d1750f3fcf2565f7".to_string())
+            .with_firmware_version("1.1.0".to_string());
+
+        let decoded = CryptoMultiAccounts::from_cbor(&accounts.to_cbor().unwrap()).unwrap();
+        assert_eq!(decoded, accounts);
+        assert_eq!(decoded.keys[0].coin_type(), Some(60));
+        assert_eq!(decoded.keys[1].coin_type(), Some(501));
+    }
+}
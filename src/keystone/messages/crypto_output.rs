@@ -0,0 +1,516 @@
+//! Crypto-output (output descriptor) message types
+//!
+//! A crypto-output is a chain of script-expression tags wrapping a key:
+//! `#6.308(#6.400(#6.404(#6.303({...}))))` is `sh(wpkh(KEY))`.
+//!
+//! Reference: https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-010-output-desc.md
+
+use crate::error::{Error, Result};
+use crate::keystone::cbor;
+use minicbor::data::Tag;
+use minicbor::{Decoder, Encoder};
+
+use super::crypto_hdkey::CryptoHdKey;
+
+const CHECKSUM_INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Script expression wrapping the key of an output descriptor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptExpression {
+    /// `sh(...)`
+    ScriptHash,
+    /// `wsh(...)`
+    WitnessScriptHash,
+    /// `pk(...)`
+    PublicKey,
+    /// `pkh(...)`
+    PublicKeyHash,
+    /// `wpkh(...)`
+    WitnessPublicKeyHash,
+    /// `combo(...)`
+    Combo,
+    /// `tr(...)`
+    Taproot,
+    /// `cosigner(...)`
+    Cosigner,
+}
+
+impl ScriptExpression {
+    /// CBOR tag for this expression
+    pub fn tag(&self) -> u64 {
+        match self {
+            ScriptExpression::ScriptHash => cbor::tags::SCRIPT_SH,
+            ScriptExpression::WitnessScriptHash => cbor::tags::SCRIPT_WSH,
+            ScriptExpression::PublicKey => cbor::tags::SCRIPT_PK,
+            ScriptExpression::PublicKeyHash => cbor::tags::SCRIPT_PKH,
+            ScriptExpression::WitnessPublicKeyHash => cbor::tags::SCRIPT_WPKH,
+            ScriptExpression::Combo => cbor::tags::SCRIPT_COMBO,
+            ScriptExpression::Taproot => cbor::tags::SCRIPT_TAPROOT,
+            ScriptExpression::Cosigner => cbor::tags::SCRIPT_COSIGNER,
+        }
+    }
+
+    /// Look up the expression for a CBOR tag
+    pub fn from_tag(tag: u64) -> Option<Self> {
+        match tag {
+            cbor::tags::SCRIPT_SH => Some(ScriptExpression::ScriptHash),
+            cbor::tags::SCRIPT_WSH => Some(ScriptExpression::WitnessScriptHash),
+            cbor::tags::SCRIPT_PK => Some(ScriptExpression::PublicKey),
+            cbor::tags::SCRIPT_PKH => Some(ScriptExpression::PublicKeyHash),
+            cbor::tags::SCRIPT_WPKH => Some(ScriptExpression::WitnessPublicKeyHash),
+            cbor::tags::SCRIPT_COMBO => Some(ScriptExpression::Combo),
+            cbor::tags::SCRIPT_TAPROOT => Some(ScriptExpression::Taproot),
+            cbor::tags::SCRIPT_COSIGNER => Some(ScriptExpression::Cosigner),
+            _ => None,
+        }
+    }
+
+    /// Descriptor function name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScriptExpression::ScriptHash => "sh",
+            ScriptExpression::WitnessScriptHash => "wsh",
+            ScriptExpression::PublicKey => "pk",
+            ScriptExpression::PublicKeyHash => "pkh",
+            ScriptExpression::WitnessPublicKeyHash => "wpkh",
+            ScriptExpression::Combo => "combo",
+            ScriptExpression::Taproot => "tr",
+            ScriptExpression::Cosigner => "cosigner",
+        }
+    }
+}
+
+/// Plain elliptic curve key (crypto-eckey, tag 306)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoEcKey {
+    /// Curve identifier (0 = secp256k1)
+    pub curve: u32,
+    /// Whether `data` is a private key
+    pub is_private: bool,
+    /// Key bytes
+    pub data: Vec<u8>,
+}
+
+impl CryptoEcKey {
+    /// Create a secp256k1 public key
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            curve: 0,
+            is_private: false,
+            data,
+        }
+    }
+}
+
+impl minicbor::Encode<()> for CryptoEcKey {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        let map_len = 1 + (self.curve != 0) as u64 + self.is_private as u64;
+        e.map(map_len)?;
+        if self.curve != 0 {
+            e.u8(1)?.u32(self.curve)?;
+        }
+        if self.is_private {
+            e.u8(2)?.bool(true)?;
+        }
+        e.u8(3)?.bytes(&self.data)?;
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CryptoEcKey {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let map_len = d.map()?.ok_or_else(|| {
+            minicbor::decode::Error::message("expected definite-length map for CryptoEcKey")
+        })?;
+
+        let mut curve = 0;
+        let mut is_private = false;
+        let mut data = None;
+        for _ in 0..map_len {
+            match d.u8()? {
+                1 => curve = d.u32()?,
+                2 => is_private = d.bool()?,
+                3 => data = Some(d.bytes()?.to_vec()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            curve,
+            is_private,
+            data: data.ok_or_else(|| minicbor::decode::Error::message("missing key data"))?,
+        })
+    }
+}
+
+/// Key at the core of an output descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputKey {
+    /// Extended key (`xpub`)
+    HdKey(CryptoHdKey),
+    /// Single public key
+    EcKey(CryptoEcKey),
+    /// `multi(...)` / `sortedmulti(...)` threshold key set
+    Multi(MultiKey),
+}
+
+impl OutputKey {
+    /// Descriptor key expression
+    pub fn descriptor_key(&self) -> Result<String> {
+        match self {
+            OutputKey::HdKey(key) => key.descriptor_key(),
+            OutputKey::EcKey(key) => Ok(hex::encode(&key.data)),
+            OutputKey::Multi(multi) => {
+                let keys = multi
+                    .keys
+                    .iter()
+                    .map(OutputKey::descriptor_key)
+                    .collect::<Result<Vec<_>>>()?;
+                let name = if multi.sorted { "sortedmulti" } else { "multi" };
+                Ok(format!("{name}({},{})", multi.threshold, keys.join(",")))
+            }
+        }
+    }
+
+    fn encode_tagged<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        match self {
+            OutputKey::HdKey(key) => {
+                e.tag(Tag::Unassigned(cbor::tags::CRYPTO_HDKEY))?;
+                minicbor::Encode::encode(key, e, ctx)
+            }
+            OutputKey::EcKey(key) => {
+                e.tag(Tag::Unassigned(cbor::tags::CRYPTO_ECKEY))?;
+                minicbor::Encode::encode(key, e, ctx)
+            }
+            OutputKey::Multi(multi) => {
+                let tag = if multi.sorted {
+                    cbor::tags::SCRIPT_SORTED_MULTI
+                } else {
+                    cbor::tags::SCRIPT_MULTI
+                };
+                e.tag(Tag::Unassigned(tag))?;
+                e.map(2)?;
+                e.u8(1)?.u32(multi.threshold)?;
+                e.u8(2)?.array(multi.keys.len() as u64)?;
+                for key in &multi.keys {
+                    key.encode_tagged(e, ctx)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Decode a key whose tag has already been consumed
+    fn decode_with_tag(
+        tag: u64,
+        d: &mut Decoder<'_>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        match tag {
+            cbor::tags::CRYPTO_HDKEY => Ok(OutputKey::HdKey(minicbor::Decode::decode(d, ctx)?)),
+            cbor::tags::CRYPTO_ECKEY => Ok(OutputKey::EcKey(minicbor::Decode::decode(d, ctx)?)),
+            cbor::tags::SCRIPT_MULTI | cbor::tags::SCRIPT_SORTED_MULTI => {
+                let map_len = d.map()?.ok_or_else(|| {
+                    minicbor::decode::Error::message("expected definite-length map for multikey")
+                })?;
+                let mut threshold = None;
+                let mut keys = Vec::new();
+                for _ in 0..map_len {
+                    match d.u8()? {
+                        1 => threshold = Some(d.u32()?),
+                        2 => {
+                            let len = d.array()?.ok_or_else(|| {
+                                minicbor::decode::Error::message(
+                                    "expected definite-length array for multikey keys",
+                                )
+                            })?;
+                            for _ in 0..len {
+                                let key_tag = read_tag(d)?;
+                                // multi() only takes plain keys; refusing to
+                                // nest also bounds the recursion depth
+                                if matches!(
+                                    key_tag,
+                                    cbor::tags::SCRIPT_MULTI | cbor::tags::SCRIPT_SORTED_MULTI
+                                ) {
+                                    return Err(minicbor::decode::Error::message(
+                                        "multikey keys cannot be multikeys",
+                                    ));
+                                }
+                                keys.push(Self::decode_with_tag(key_tag, d, ctx)?);
+                            }
+                        }
+                        _ => d.skip()?,
+                    }
+                }
+                Ok(OutputKey::Multi(MultiKey {
+                    threshold: threshold
+                        .ok_or_else(|| minicbor::decode::Error::message("missing threshold"))?,
+                    keys,
+                    sorted: tag == cbor::tags::SCRIPT_SORTED_MULTI,
+                }))
+            }
+            other => Err(minicbor::decode::Error::message(format!(
+                "unsupported crypto-output key tag {other}"
+            ))),
+        }
+    }
+}
+
+/// Threshold key set for `multi` / `sortedmulti` descriptors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiKey {
+    /// Number of signatures required
+    pub threshold: u32,
+    /// Participating keys
+    pub keys: Vec<OutputKey>,
+    /// True for `sortedmulti`
+    pub sorted: bool,
+}
+
+/// Output descriptor (crypto-output, tag 308)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoOutput {
+    /// Script expressions from outermost to innermost
+    pub script_expressions: Vec<ScriptExpression>,
+    /// Key wrapped by the expressions
+    pub key: OutputKey,
+}
+
+impl CryptoOutput {
+    /// Create an output descriptor
+    pub fn new(script_expressions: Vec<ScriptExpression>, key: OutputKey) -> Self {
+        Self {
+            script_expressions,
+            key,
+        }
+    }
+
+    /// Extended key, if the descriptor wraps a single hdkey
+    pub fn hd_key(&self) -> Option<&CryptoHdKey> {
+        match &self.key {
+            OutputKey::HdKey(key) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Output descriptor string with its BIP-380 checksum
+    pub fn descriptor(&self) -> Result<String> {
+        let mut descriptor = self.key.descriptor_key()?;
+        for expression in self.script_expressions.iter().rev() {
+            descriptor = format!("{}({descriptor})", expression.name());
+        }
+        let checksum = descriptor_checksum(&descriptor)?;
+        Ok(format!("{descriptor}#{checksum}"))
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for CryptoOutput {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        for expression in &self.script_expressions {
+            e.tag(Tag::Unassigned(expression.tag()))?;
+        }
+        self.key.encode_tagged(e, ctx)
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for CryptoOutput {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Top-level crypto-output bodies are untagged; embedded ones carry tag 308
+        let mut tag = read_tag(d)?;
+        if tag == cbor::tags::CRYPTO_OUTPUT {
+            tag = read_tag(d)?;
+        }
+
+        let mut script_expressions = Vec::new();
+        while let Some(expression) = ScriptExpression::from_tag(tag) {
+            script_expressions.push(expression);
+            tag = read_tag(d)?;
+        }
+
+        Ok(Self {
+            script_expressions,
+            key: OutputKey::decode_with_tag(tag, d, ctx)?,
+        })
+    }
+}
+
+fn read_tag(d: &mut Decoder<'_>) -> std::result::Result<u64, minicbor::decode::Error> {
+    match d.tag()? {
+        Tag::Unassigned(value) => Ok(value),
+        other => Err(minicbor::decode::Error::message(format!(
+            "unexpected tag {other:?} in crypto-output"
+        ))),
+    }
+}
+
+/// BIP-380 descriptor checksum
+fn descriptor_checksum(descriptor: &str) -> Result<String> {
+    fn polymod(c: u64, value: u64) -> u64 {
+        let c0 = c >> 35;
+        let mut c = ((c & 0x7_ffff_ffff) << 5) ^ value;
+        for (bit, generator) in [
+            0xf5_dee5_1989,
+            0xa9_fdca_3312,
+            0x1b_ab10_e32d,
+            0x37_06b1_677a,
+            0x64_4d62_6ffd,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if c0 & (1 << bit) != 0 {
+                c ^= generator;
+            }
+        }
+        c
+    }
+
+    let mut c = 1u64;
+    let mut class = 0u64;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let position = CHECKSUM_INPUT_CHARSET.find(ch).ok_or_else(|| {
+            Error::InvalidKeystonePayload(format!("Invalid descriptor character '{ch}'"))
+        })? as u64;
+        c = polymod(c, position & 31);
+        class = class * 3 + (position >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = polymod(c, class);
+    }
+    for _ in 0..8 {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+
+    Ok((0..8)
+        .map(|j| CHECKSUM_CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+
+    fn sample_hdkey() -> CryptoHdKey {
+        CryptoHdKey::new(
+            hex::decode("035a784662a4a20a65bf6aab9ae98a6c068a81c52e4b032c0fb5400c706cfccc56")
+                .unwrap(),
+            hex::decode("47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141")
+                .unwrap(),
+        )
+        .with_origin(
+            CryptoKeyPath::from_str("m/0'")
+                .unwrap()
+                .with_source_fingerprint([0x34, 0x42, 0x19, 0x3e]),
+        )
+        .with_parent_fingerprint([0x34, 0x42, 0x19, 0x3e])
+    }
+
+    #[test]
+    fn test_descriptor_checksum() {
+        let descriptor = "sh(multi(2,[00000000/111'/222]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL,xpub68NZiKmJWnxxS6aaHmn81bvJeTESw724CRDs6HbuccFQN9Ku14VQrADWgqbhhTHBaohPX4CjNLf9fq9MYo6oDaPPLPxSb7gwQN3ih19Zm4Y/0))";
+        assert_eq!(descriptor_checksum(descriptor).unwrap(), "tjg09x5t");
+    }
+
+    #[test]
+    fn test_nested_segwit_output_roundtrip() {
+        let output = CryptoOutput::new(
+            vec![
+                ScriptExpression::ScriptHash,
+                ScriptExpression::WitnessPublicKeyHash,
+            ],
+            OutputKey::HdKey(sample_hdkey()),
+        );
+
+        let bytes = output.to_cbor().unwrap();
+        let decoded = CryptoOutput::from_cbor(&bytes).unwrap();
+        assert_eq!(decoded, output);
+
+        let descriptor = decoded.descriptor().unwrap();
+        assert!(descriptor.starts_with("sh(wpkh([3442193e/0']xpub68Gmy5Edvgib"));
+        assert_eq!(descriptor.len(), descriptor.find('#').unwrap() + 9);
+    }
+
+    #[test]
+    fn test_multisig_output() {
+        let key = OutputKey::EcKey(CryptoEcKey::new(vec![0x02; 33]));
+        let output = CryptoOutput::new(
+            vec![ScriptExpression::WitnessScriptHash],
+            OutputKey::Multi(MultiKey {
+                threshold: 1,
+                keys: vec![key.clone(), key],
+                sorted: true,
+            }),
+        );
+
+        let decoded = CryptoOutput::from_cbor(&output.to_cbor().unwrap()).unwrap();
+        assert_eq!(decoded, output);
+        assert!(
+            decoded
+                .descriptor()
+                .unwrap()
+                .starts_with("wsh(sortedmulti(1,0202")
+        );
+    }
+
+    #[test]
+    fn test_nested_multikey_is_rejected() {
+        let key = OutputKey::EcKey(CryptoEcKey::new(vec![0x02; 33]));
+        let inner = OutputKey::Multi(MultiKey {
+            threshold: 1,
+            keys: vec![key],
+            sorted: false,
+        });
+        let output = CryptoOutput::new(
+            vec![ScriptExpression::WitnessScriptHash],
+            OutputKey::Multi(MultiKey {
+                threshold: 1,
+                keys: vec![inner],
+                sorted: false,
+            }),
+        );
+
+        let err = CryptoOutput::from_cbor(&output.to_cbor().unwrap()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("multikey keys cannot be multikeys")
+        );
+    }
+}
@@ -2,6 +2,14 @@
 //!
 //! Reference: archive/keystone-sdk-rust/libs/ur-registry/src/ethereum/
 
+pub mod personal_message;
+pub mod transaction;
+pub mod typed_data;
+
+pub use personal_message::{OriginCheck, PersonalMessage, SiweMessage};
+pub use transaction::{ChainIdCheck, EthTransaction, EthTxType};
+pub use typed_data::{TypedData, TypedDataRisk};
+
 use crate::error::{Error, Result};
 use crate::keystone::cbor;
 use crate::keystone::crypto_keypath::CryptoKeyPath;
@@ -100,6 +108,23 @@ impl EthSignRequest {
         }
     }
 
+    /// Create a new EIP-712 typed data sign request
+    pub fn new_typed_data(
+        sign_data: Vec<u8>,
+        derivation_path: CryptoKeyPath,
+        chain_id: Option<i128>,
+    ) -> Self {
+        Self {
+            request_id: Some(Uuid::new_v4()),
+            sign_data,
+            data_type: EthDataType::TypedData,
+            chain_id,
+            derivation_path,
+            address: None,
+            origin: None,
+        }
+    }
+
     /// Create a new personal message sign request
     pub fn new_personal_message(sign_data: Vec<u8>, derivation_path: CryptoKeyPath) -> Self {
         Self {
@@ -125,6 +150,51 @@ impl EthSignRequest {
         self
     }
 
+    /// Decode `sign_data` as a transaction
+    pub fn decode_transaction(&self) -> Result<EthTransaction> {
+        match self.data_type {
+            EthDataType::Transaction | EthDataType::TypedTransaction => {
+                EthTransaction::decode(&self.sign_data)
+            }
+            other => Err(Error::InvalidKeystonePayload(format!(
+                "ETH sign request carries {:?}, not a transaction",
+                other
+            ))),
+        }
+    }
+
+    /// Parse `sign_data` as EIP-712 typed data
+    pub fn decode_typed_data(&self) -> Result<TypedData> {
+        match self.data_type {
+            EthDataType::TypedData => TypedData::parse(&self.sign_data),
+            other => Err(Error::InvalidKeystonePayload(format!(
+                "ETH sign request carries {:?}, not typed data",
+                other
+            ))),
+        }
+    }
+
+    /// Decode `sign_data` as a personal message
+    pub fn decode_personal_message(&self) -> Result<PersonalMessage> {
+        match self.data_type {
+            EthDataType::PersonalMessage => Ok(PersonalMessage::decode(&self.sign_data)),
+            other => Err(Error::InvalidKeystonePayload(format!(
+                "ETH sign request carries {:?}, not a personal message",
+                other
+            ))),
+        }
+    }
+
+    /// Check that a SIWE message's domain matches `origin`
+    pub fn check_siwe_origin(&self, siwe: &SiweMessage) -> OriginCheck {
+        siwe.check_origin(self.origin.as_deref())
+    }
+
+    /// Check that the transaction's embedded chain ID matches `chain_id`
+    pub fn check_chain_id(&self, transaction: &EthTransaction) -> ChainIdCheck {
+        ChainIdCheck::compare(self.chain_id, transaction.chain_id)
+    }
+
     /// Serialize the request as CBOR bytes
     pub fn to_cbor(&self) -> Result<Vec<u8>> {
         cbor::to_bytes(self)
@@ -0,0 +1,292 @@
+//! Personal-message (`personal_sign`) decoding and Sign-In-With-Ethereum parsing
+//!
+//! Reference: https://eips.ethereum.org/EIPS/eip-4361
+
+use crate::error::{Error, Result};
+
+const SIWE_HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+/// Decoded personal-message payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersonalMessage {
+    /// Printable UTF-8 text
+    Text(String),
+    /// Arbitrary bytes (typically a hash or binary blob)
+    Binary(Vec<u8>),
+}
+
+impl PersonalMessage {
+    /// Classify message bytes as text or binary
+    pub fn decode(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) if !text.is_empty() && is_printable(text) => {
+                PersonalMessage::Text(text.to_string())
+            }
+            _ => PersonalMessage::Binary(bytes.to_vec()),
+        }
+    }
+
+    /// Text content, if printable
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            PersonalMessage::Text(text) => Some(text),
+            PersonalMessage::Binary(_) => None,
+        }
+    }
+
+    /// Parse the message as Sign-In-With-Ethereum, if it is one
+    pub fn siwe(&self) -> Option<SiweMessage> {
+        self.text().and_then(|text| SiweMessage::parse(text).ok())
+    }
+}
+
+/// Parsed EIP-4361 Sign-In-With-Ethereum message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweMessage {
+    /// Requesting domain (RFC 3986 authority)
+    pub domain: String,
+    /// Account being signed in
+    pub address: String,
+    /// Human-readable statement
+    pub statement: Option<String>,
+    /// Subject URI
+    pub uri: Option<String>,
+    /// Message version (currently "1")
+    pub version: Option<String>,
+    /// EIP-155 chain ID
+    pub chain_id: Option<u64>,
+    /// Replay-protection nonce
+    pub nonce: Option<String>,
+    /// Issuance timestamp (RFC 3339)
+    pub issued_at: Option<String>,
+    /// Expiry timestamp (RFC 3339)
+    pub expiration_time: Option<String>,
+    /// Start of validity (RFC 3339)
+    pub not_before: Option<String>,
+    /// Application request identifier
+    pub request_id: Option<String>,
+    /// Additional resources
+    pub resources: Vec<String>,
+}
+
+impl SiweMessage {
+    /// Parse a SIWE message
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+
+        let header = lines.next().unwrap_or_default();
+        let authority = header
+            .strip_suffix(SIWE_HEADER_SUFFIX)
+            .ok_or_else(|| siwe_error("missing sign-in header"))?;
+        // The header may carry a scheme (`https://example.com wants you...`)
+        let domain = authority
+            .split_once("://")
+            .map_or(authority, |(_, rest)| rest)
+            .to_string();
+        if domain.is_empty() {
+            return Err(siwe_error("empty domain"));
+        }
+
+        let address = lines
+            .next()
+            .map(str::trim)
+            .filter(|line| line.starts_with("0x") && line.len() == 42)
+            .ok_or_else(|| siwe_error("missing account address"))?
+            .to_string();
+
+        let mut message = SiweMessage {
+            domain,
+            address,
+            statement: None,
+            uri: None,
+            version: None,
+            chain_id: None,
+            nonce: None,
+            issued_at: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        };
+
+        let mut in_resources = false;
+        for line in lines {
+            if in_resources {
+                if let Some(resource) = line.strip_prefix("- ") {
+                    message.resources.push(resource.to_string());
+                    continue;
+                }
+                in_resources = false;
+            }
+
+            let Some((key, value)) = line.split_once(": ") else {
+                if line == "Resources:" {
+                    in_resources = true;
+                } else if !line.is_empty() && message.uri.is_none() {
+                    message.statement = Some(line.to_string());
+                }
+                continue;
+            };
+            let value = value.to_string();
+            match key {
+                "URI" => message.uri = Some(value),
+                "Version" => message.version = Some(value),
+                "Chain ID" => {
+                    message.chain_id = Some(
+                        value
+                            .parse()
+                            .map_err(|_| siwe_error(&format!("invalid chain ID '{value}'")))?,
+                    )
+                }
+                "Nonce" => message.nonce = Some(value),
+                "Issued At" => message.issued_at = Some(value),
+                "Expiration Time" => message.expiration_time = Some(value),
+                "Not Before" => message.not_before = Some(value),
+                "Request ID" => message.request_id = Some(value),
+                _ if message.uri.is_none() => message.statement = Some(line.to_string()),
+                _ => {}
+            }
+        }
+
+        Ok(message)
+    }
+
+    /// Compare the SIWE domain against the request origin
+    pub fn check_origin(&self, origin: Option<&str>) -> OriginCheck {
+        let Some(origin) = origin else {
+            return OriginCheck::Unknown;
+        };
+        let origin_authority = authority(origin);
+        let domain = self.domain.to_ascii_lowercase();
+
+        // A domain without a port matches the origin host on any port
+        let matches = if domain.contains(':') {
+            origin_authority == domain
+        } else {
+            host(&origin_authority) == domain
+        };
+        if matches {
+            OriginCheck::Match
+        } else {
+            OriginCheck::Mismatch {
+                domain: self.domain.clone(),
+                origin: origin.to_string(),
+            }
+        }
+    }
+}
+
+/// Result of comparing a SIWE domain with the request origin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginCheck {
+    /// Domain and origin agree
+    Match,
+    /// The message asks to sign in to a different site than the one requesting it
+    Mismatch {
+        /// Domain named in the SIWE message
+        domain: String,
+        /// Origin declared by the sign request
+        origin: String,
+    },
+    /// The request carries no origin
+    Unknown,
+}
+
+impl OriginCheck {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            OriginCheck::Match => "match",
+            OriginCheck::Mismatch { .. } => "mismatch",
+            OriginCheck::Unknown => "unknown",
+        }
+    }
+}
+
+/// Lowercased `host[:port]` of a URL or bare authority
+fn authority(origin: &str) -> String {
+    let without_scheme = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let authority = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    authority.to_ascii_lowercase()
+}
+
+fn host(authority: &str) -> &str {
+    authority
+        .rsplit_once(':')
+        .map_or(authority, |(host, _)| host)
+}
+
+fn is_printable(text: &str) -> bool {
+    text.chars()
+        .all(|ch| !ch.is_control() || matches!(ch, '\n' | '\r' | '\t'))
+}
+
+fn siwe_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid SIWE message: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIWE: &str = "service.org wants you to sign in with your Ethereum account:
+0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2
+
+I accept the ServiceOrg Terms of Service: https://service.org/tos
+
+URI: https://service.org/login
+Version: 1
+Chain ID: 1
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z
+Expiration Time: 2021-10-01T16:25:24Z
+Resources:
+- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/
+- https://example.com/my-web2-claim.json";
+
+    #[test]
+    fn test_parse_siwe_message() {
+        let message = PersonalMessage::decode(SIWE.as_bytes());
+        let siwe = message.siwe().unwrap();
+
+        assert_eq!(siwe.domain, "service.org");
+        assert_eq!(siwe.address, "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+        assert_eq!(
+            siwe.statement.as_deref(),
+            Some("I accept the ServiceOrg Terms of Service: https://service.org/tos")
+        );
+        assert_eq!(siwe.chain_id, Some(1));
+        assert_eq!(siwe.nonce.as_deref(), Some("32891756"));
+        assert_eq!(siwe.issued_at.as_deref(), Some("2021-09-30T16:25:24Z"));
+        assert_eq!(
+            siwe.expiration_time.as_deref(),
+            Some("2021-10-01T16:25:24Z")
+        );
+        assert_eq!(siwe.resources.len(), 2);
+
+        assert_eq!(
+            siwe.check_origin(Some("https://service.org:8443/login")),
+            OriginCheck::Match
+        );
+        assert_eq!(
+            siwe.check_origin(Some("https://evil.example")).label(),
+            "mismatch"
+        );
+        assert_eq!(siwe.check_origin(None), OriginCheck::Unknown);
+    }
+
+    #[test]
+    fn test_binary_message_is_not_text() {
+        let message = PersonalMessage::decode(&[0xde, 0xad, 0x00, 0xbe, 0xef]);
+        assert_eq!(message.text(), None);
+        assert!(PersonalMessage::decode(b"hello\x07").text().is_none());
+        assert_eq!(PersonalMessage::decode(b"Hello").text(), Some("Hello"));
+        assert!(PersonalMessage::decode(b"Hello").siwe().is_none());
+    }
+}
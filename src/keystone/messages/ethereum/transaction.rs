@@ -0,0 +1,492 @@
+//! RLP decoding of Ethereum transactions for review before signing
+//!
+//! Supports legacy (optionally EIP-155), EIP-2930, EIP-1559 and EIP-4844
+//! transactions in their unsigned or signed forms.
+
+use crate::error::{Error, Result};
+
+/// Deepest RLP list nesting accepted
+///
+/// Transactions nest at most four levels (access list storage keys); the
+/// bound keeps crafted payloads from exhausting the stack.
+const MAX_RLP_DEPTH: usize = 16;
+
+/// Ethereum transaction envelope type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EthTxType {
+    /// Untyped legacy transaction
+    Legacy,
+    /// EIP-2930 access list transaction (type 1)
+    AccessList,
+    /// EIP-1559 dynamic fee transaction (type 2)
+    DynamicFee,
+    /// EIP-4844 blob transaction (type 3)
+    Blob,
+}
+
+impl EthTxType {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            EthTxType::Legacy => "legacy",
+            EthTxType::AccessList => "eip2930",
+            EthTxType::DynamicFee => "eip1559",
+            EthTxType::Blob => "eip4844",
+        }
+    }
+}
+
+/// Decoded Ethereum transaction fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthTransaction {
+    /// Envelope type
+    pub tx_type: EthTxType,
+    /// Chain ID embedded in the transaction (absent for pre-EIP-155 legacy)
+    pub chain_id: Option<u64>,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Gas price in wei (legacy and EIP-2930)
+    pub gas_price: Option<u128>,
+    /// Priority fee per gas in wei (EIP-1559 and later)
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Maximum fee per gas in wei (EIP-1559 and later)
+    pub max_fee_per_gas: Option<u128>,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient address; `None` for contract creation
+    pub to: Option<[u8; 20]>,
+    /// Transferred value in wei
+    pub value: u128,
+    /// Calldata
+    pub data: Vec<u8>,
+    /// Number of access list entries
+    pub access_list_len: usize,
+    /// Maximum fee per blob gas in wei (EIP-4844)
+    pub max_fee_per_blob_gas: Option<u128>,
+    /// Blob versioned hashes (EIP-4844)
+    pub blob_versioned_hashes: Vec<Vec<u8>>,
+    /// Whether the payload already carries a signature
+    pub signed: bool,
+}
+
+impl EthTransaction {
+    /// Decode a legacy RLP list or an EIP-2718 typed transaction envelope
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        match bytes.first() {
+            None => Err(tx_error("empty transaction")),
+            Some(&byte) if byte >= 0xc0 => Self::decode_legacy(bytes),
+            Some(0x01) => Self::decode_typed(EthTxType::AccessList, &bytes[1..]),
+            Some(0x02) => Self::decode_typed(EthTxType::DynamicFee, &bytes[1..]),
+            Some(0x03) => Self::decode_typed(EthTxType::Blob, &bytes[1..]),
+            Some(other) => Err(tx_error(&format!(
+                "unsupported transaction type 0x{other:02x}"
+            ))),
+        }
+    }
+
+    fn decode_legacy(bytes: &[u8]) -> Result<Self> {
+        let fields = decode_list(bytes)?;
+        if fields.len() != 6 && fields.len() != 9 {
+            return Err(tx_error(&format!(
+                "legacy transaction has {} fields",
+                fields.len()
+            )));
+        }
+
+        let (chain_id, signed) = if fields.len() == 9 {
+            let v = fields[6].as_u64()?;
+            let r = fields[7].as_bytes()?;
+            let s = fields[8].as_bytes()?;
+            if r.is_empty() && s.is_empty() {
+                // EIP-155 signing payload: [.., chain_id, 0, 0]
+                (Some(v), false)
+            } else if v >= 35 {
+                (Some((v - 35) / 2), true)
+            } else {
+                (None, true)
+            }
+        } else {
+            (None, false)
+        };
+
+        Ok(Self {
+            tx_type: EthTxType::Legacy,
+            chain_id,
+            nonce: fields[0].as_u64()?,
+            gas_price: Some(fields[1].as_u128()?),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_limit: fields[2].as_u64()?,
+            to: fields[3].as_address()?,
+            value: fields[4].as_u128()?,
+            data: fields[5].as_bytes()?.to_vec(),
+            access_list_len: 0,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
+            signed,
+        })
+    }
+
+    fn decode_typed(tx_type: EthTxType, payload: &[u8]) -> Result<Self> {
+        let fields = decode_list(payload)?;
+        let unsigned_len = match tx_type {
+            EthTxType::AccessList => 8,
+            EthTxType::DynamicFee => 9,
+            EthTxType::Blob => 11,
+            EthTxType::Legacy => unreachable!("legacy transactions are untyped"),
+        };
+        if fields.len() != unsigned_len && fields.len() != unsigned_len + 3 {
+            return Err(tx_error(&format!(
+                "{} transaction has {} fields",
+                tx_type.label(),
+                fields.len()
+            )));
+        }
+
+        // EIP-2930 keeps a single gas price; later types split it into two fees
+        let (gas_price, max_priority_fee_per_gas, max_fee_per_gas, rest) =
+            if tx_type == EthTxType::AccessList {
+                (Some(fields[2].as_u128()?), None, None, 3)
+            } else {
+                (
+                    None,
+                    Some(fields[2].as_u128()?),
+                    Some(fields[3].as_u128()?),
+                    4,
+                )
+            };
+
+        let (max_fee_per_blob_gas, blob_versioned_hashes) = if tx_type == EthTxType::Blob {
+            let hashes = fields[rest + 6]
+                .as_list()?
+                .iter()
+                .map(|hash| hash.as_bytes().map(<[u8]>::to_vec))
+                .collect::<Result<Vec<_>>>()?;
+            (Some(fields[rest + 5].as_u128()?), hashes)
+        } else {
+            (None, Vec::new())
+        };
+
+        Ok(Self {
+            tx_type,
+            chain_id: Some(fields[0].as_u64()?),
+            nonce: fields[1].as_u64()?,
+            gas_price,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit: fields[rest].as_u64()?,
+            to: fields[rest + 1].as_address()?,
+            value: fields[rest + 2].as_u128()?,
+            data: fields[rest + 3].as_bytes()?.to_vec(),
+            access_list_len: fields[rest + 4].as_list()?.len(),
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
+            signed: fields.len() == unsigned_len + 3,
+        })
+    }
+
+    /// Whether the transaction deploys a contract
+    pub fn is_contract_creation(&self) -> bool {
+        self.to.is_none()
+    }
+
+    /// First four calldata bytes, identifying the called function
+    pub fn function_selector(&self) -> Option<[u8; 4]> {
+        self.data.get(..4).map(|bytes| {
+            let mut selector = [0u8; 4];
+            selector.copy_from_slice(bytes);
+            selector
+        })
+    }
+
+    /// Upper bound on the execution fee in wei (`gas_limit * max fee`)
+    pub fn max_fee_wei(&self) -> Option<u128> {
+        let per_gas = self.max_fee_per_gas.or(self.gas_price)?;
+        per_gas.checked_mul(self.gas_limit as u128)
+    }
+}
+
+/// Result of comparing a request's chain ID with the transaction's own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainIdCheck {
+    /// Both chain IDs are present and equal
+    Match,
+    /// Both chain IDs are present and differ
+    Mismatch {
+        /// Chain ID declared by the sign request
+        request: i128,
+        /// Chain ID embedded in the transaction
+        transaction: u64,
+    },
+    /// At least one side does not declare a chain ID
+    Unknown,
+}
+
+impl ChainIdCheck {
+    /// Compare a request chain ID with a transaction chain ID
+    pub fn compare(request: Option<i128>, transaction: Option<u64>) -> Self {
+        match (request, transaction) {
+            (Some(request), Some(transaction)) if request == transaction as i128 => {
+                ChainIdCheck::Match
+            }
+            (Some(request), Some(transaction)) => ChainIdCheck::Mismatch {
+                request,
+                transaction,
+            },
+            _ => ChainIdCheck::Unknown,
+        }
+    }
+
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChainIdCheck::Match => "match",
+            ChainIdCheck::Mismatch { .. } => "mismatch",
+            ChainIdCheck::Unknown => "unknown",
+        }
+    }
+}
+
+/// Format a wei amount as decimal ether, e.g. `1.5 ETH`
+pub fn format_wei_as_eth(wei: u128) -> String {
+    format_units(wei, 18, "ETH")
+}
+
+/// Format a wei amount as decimal gwei, e.g. `30 gwei`
+pub fn format_wei_as_gwei(wei: u128) -> String {
+    format_units(wei, 9, "gwei")
+}
+
+fn format_units(value: u128, decimals: u32, unit: &str) -> String {
+    let scale = 10u128.pow(decimals);
+    let whole = value / scale;
+    let fraction = value % scale;
+    if fraction == 0 {
+        return format!("{whole} {unit}");
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{whole}.{} {unit}", fraction.trim_end_matches('0'))
+}
+
+/// Decoded RLP item
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rlp<'a> {
+    Bytes(&'a [u8]),
+    List(Vec<Rlp<'a>>),
+}
+
+impl<'a> Rlp<'a> {
+    fn as_bytes(&self) -> Result<&'a [u8]> {
+        match self {
+            Rlp::Bytes(bytes) => Ok(bytes),
+            Rlp::List(_) => Err(tx_error("expected byte string, found list")),
+        }
+    }
+
+    fn as_list(&self) -> Result<&[Rlp<'a>]> {
+        match self {
+            Rlp::List(items) => Ok(items),
+            Rlp::Bytes(_) => Err(tx_error("expected list, found byte string")),
+        }
+    }
+
+    fn as_u128(&self) -> Result<u128> {
+        let bytes = self.as_bytes()?;
+        if bytes.len() > 16 {
+            return Err(tx_error("integer does not fit in 128 bits"));
+        }
+        Ok(bytes
+            .iter()
+            .fold(0u128, |acc, byte| (acc << 8) | *byte as u128))
+    }
+
+    fn as_u64(&self) -> Result<u64> {
+        u64::try_from(self.as_u128()?).map_err(|_| tx_error("integer does not fit in 64 bits"))
+    }
+
+    fn as_address(&self) -> Result<Option<[u8; 20]>> {
+        match self.as_bytes()? {
+            [] => Ok(None),
+            bytes if bytes.len() == 20 => {
+                let mut address = [0u8; 20];
+                address.copy_from_slice(bytes);
+                Ok(Some(address))
+            }
+            bytes => Err(tx_error(&format!(
+                "invalid recipient length {}",
+                bytes.len()
+            ))),
+        }
+    }
+}
+
+/// Decode `bytes` as exactly one RLP list and return its items
+fn decode_list(bytes: &[u8]) -> Result<Vec<Rlp<'_>>> {
+    let (item, used) = decode_item(bytes, 0)?;
+    if used != bytes.len() {
+        return Err(tx_error("trailing bytes after transaction"));
+    }
+    match item {
+        Rlp::List(items) => Ok(items),
+        Rlp::Bytes(_) => Err(tx_error("transaction is not an RLP list")),
+    }
+}
+
+/// Decode one RLP item, returning it and the number of bytes consumed
+///
+/// `depth` counts the lists enclosing the item.
+fn decode_item(bytes: &[u8], depth: usize) -> Result<(Rlp<'_>, usize)> {
+    let prefix = *bytes
+        .first()
+        .ok_or_else(|| tx_error("unexpected end of data"))?;
+    match prefix {
+        0x00..=0x7f => Ok((Rlp::Bytes(&bytes[..1]), 1)),
+        0x80..=0xb7 => {
+            let len = (prefix - 0x80) as usize;
+            Ok((Rlp::Bytes(slice(bytes, 1, len)?), 1 + len))
+        }
+        0xb8..=0xbf => {
+            let len_of_len = (prefix - 0xb7) as usize;
+            let len = read_length(slice(bytes, 1, len_of_len)?)?;
+            let start = 1 + len_of_len;
+            Ok((Rlp::Bytes(slice(bytes, start, len)?), start + len))
+        }
+        0xc0..=0xf7 => {
+            let len = (prefix - 0xc0) as usize;
+            let items = decode_items(slice(bytes, 1, len)?, depth + 1)?;
+            Ok((Rlp::List(items), 1 + len))
+        }
+        0xf8..=0xff => {
+            let len_of_len = (prefix - 0xf7) as usize;
+            let len = read_length(slice(bytes, 1, len_of_len)?)?;
+            let start = 1 + len_of_len;
+            let items = decode_items(slice(bytes, start, len)?, depth + 1)?;
+            Ok((Rlp::List(items), start + len))
+        }
+    }
+}
+
+fn decode_items(mut payload: &[u8], depth: usize) -> Result<Vec<Rlp<'_>>> {
+    if depth > MAX_RLP_DEPTH {
+        return Err(tx_error(&format!(
+            "RLP lists nested deeper than {MAX_RLP_DEPTH}"
+        )));
+    }
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let (item, used) = decode_item(payload, depth)?;
+        items.push(item);
+        payload = &payload[used..];
+    }
+    Ok(items)
+}
+
+fn slice(bytes: &[u8], start: usize, len: usize) -> Result<&[u8]> {
+    start
+        .checked_add(len)
+        .and_then(|end| bytes.get(start..end))
+        .ok_or_else(|| tx_error("unexpected end of data"))
+}
+
+fn read_length(bytes: &[u8]) -> Result<usize> {
+    if bytes.len() > std::mem::size_of::<usize>() {
+        return Err(tx_error("RLP length overflow"));
+    }
+    Ok(bytes
+        .iter()
+        .fold(0usize, |acc, byte| (acc << 8) | *byte as usize))
+}
+
+fn tx_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Ethereum transaction: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_eip155_legacy() {
+        // EIP-155 example: nonce 9, 20 gwei, 21000 gas, 1 ETH, chain 1
+        let bytes = hex::decode(
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080",
+        )
+        .unwrap();
+        let tx = EthTransaction::decode(&bytes).unwrap();
+
+        assert_eq!(tx.tx_type, EthTxType::Legacy);
+        assert_eq!(tx.chain_id, Some(1));
+        assert_eq!(tx.nonce, 9);
+        assert_eq!(tx.gas_price, Some(20_000_000_000));
+        assert_eq!(tx.gas_limit, 21_000);
+        assert_eq!(tx.to, Some([0x35; 20]));
+        assert_eq!(format_wei_as_eth(tx.value), "1 ETH");
+        assert!(!tx.signed);
+    }
+
+    #[test]
+    fn test_decode_eip1559() {
+        // chain 5, nonce 1, tip 1.5 gwei, max 30 gwei, 21000 gas, 0.01 ETH, calldata a9059cbb
+        let bytes = hex::decode(
+            "02f305018459682f008506fc23ac00825208943535353535353535353535353535353535353535872386f2\
+             6fc1000084a9059cbbc0",
+        )
+        .unwrap();
+        let tx = EthTransaction::decode(&bytes).unwrap();
+
+        assert_eq!(tx.tx_type, EthTxType::DynamicFee);
+        assert_eq!(tx.chain_id, Some(5));
+        assert_eq!(tx.nonce, 1);
+        assert_eq!(
+            format_wei_as_gwei(tx.max_priority_fee_per_gas.unwrap()),
+            "1.5 gwei"
+        );
+        assert_eq!(format_wei_as_gwei(tx.max_fee_per_gas.unwrap()), "30 gwei");
+        assert_eq!(format_wei_as_eth(tx.value), "0.01 ETH");
+        assert_eq!(tx.function_selector(), Some([0xa9, 0x05, 0x9c, 0xbb]));
+        assert_eq!(tx.access_list_len, 0);
+    }
+
+    #[test]
+    fn test_chain_id_check() {
+        assert_eq!(ChainIdCheck::compare(Some(1), Some(1)), ChainIdCheck::Match);
+        assert_eq!(
+            ChainIdCheck::compare(Some(1), Some(137)),
+            ChainIdCheck::Mismatch {
+                request: 1,
+                transaction: 137
+            }
+        );
+        assert_eq!(ChainIdCheck::compare(None, Some(1)), ChainIdCheck::Unknown);
+    }
+
+    #[test]
+    fn test_rejects_truncated_transaction() {
+        assert!(EthTransaction::decode(&[0x02, 0xf8, 0x40, 0x01]).is_err());
+        assert!(EthTransaction::decode(&[0x7f]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_deeply_nested_lists() {
+        // 100k nested lists, each with a long-form header: a few bytes per
+        // level is enough to overflow the stack without a depth bound
+        let levels = 100_000u32;
+        let mut nested = Vec::with_capacity(5 * levels as usize + 1);
+        for level in 0..levels {
+            nested.push(0xfb);
+            nested.extend_from_slice(&(5 * (levels - 1 - level) + 1).to_be_bytes());
+        }
+        nested.push(0xc0);
+        let err = EthTransaction::decode(&nested).unwrap_err();
+        assert!(err.to_string().contains("nested deeper than 16"));
+
+        // Exactly MAX_RLP_DEPTH lists is still accepted
+        let mut lists = vec![0xc0];
+        while lists.len() < MAX_RLP_DEPTH {
+            lists.insert(0, 0xc0 + lists.len() as u8);
+        }
+        assert!(decode_list(&lists).is_ok());
+        lists.insert(0, 0xc0 + lists.len() as u8);
+        assert!(decode_list(&lists).is_err());
+    }
+}
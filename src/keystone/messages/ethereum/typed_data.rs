@@ -0,0 +1,534 @@
+//! EIP-712 typed-data parsing, hashing and risk detection
+//!
+//! Reference: https://eips.ethereum.org/EIPS/eip-712
+
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use tiny_keccak::{Hasher, Keccak};
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Largest uint256, used by "unlimited" ERC-20 approvals
+const MAX_UINT256: [u8; 32] = [0xff; 32];
+/// Largest uint160, used by unlimited Permit2 allowances
+const MAX_UINT160: [u8; 32] = {
+    let mut value = [0xff; 32];
+    let mut i = 0;
+    while i < 12 {
+        value[i] = 0;
+        i += 1;
+    }
+    value
+};
+
+/// One member of an EIP-712 struct type
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TypedField {
+    /// Member name
+    pub name: String,
+    /// Solidity type (e.g. `address`, `uint256`, `Person[]`)
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+/// Parsed EIP-712 typed-data payload (`eth_signTypedData_v4` JSON)
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypedData {
+    /// Struct type definitions
+    pub types: BTreeMap<String, Vec<TypedField>>,
+    /// Type of `message`
+    #[serde(rename = "primaryType")]
+    pub primary_type: String,
+    /// Domain values
+    pub domain: Map<String, Value>,
+    /// Message values
+    #[serde(default)]
+    pub message: Value,
+}
+
+/// High-risk typed-data pattern worth surfacing to the reviewer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypedDataRisk {
+    /// EIP-2612 / DAI-style `Permit` granting a token allowance
+    Permit,
+    /// Uniswap Permit2 allowance or transfer signature
+    Permit2,
+    /// Seaport marketplace order, which can transfer NFTs and tokens
+    SeaportOrder,
+    /// Allowance for the maximum representable amount
+    UnlimitedApproval,
+}
+
+impl TypedDataRisk {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            TypedDataRisk::Permit => "permit",
+            TypedDataRisk::Permit2 => "permit2",
+            TypedDataRisk::SeaportOrder => "seaport_order",
+            TypedDataRisk::UnlimitedApproval => "unlimited_approval",
+        }
+    }
+
+    /// Human-readable explanation
+    pub fn description(&self) -> &'static str {
+        match self {
+            TypedDataRisk::Permit => "signature grants a token allowance (ERC-2612 permit)",
+            TypedDataRisk::Permit2 => "signature grants a Permit2 token allowance or transfer",
+            TypedDataRisk::SeaportOrder => {
+                "signature creates a Seaport order that can transfer your assets"
+            }
+            TypedDataRisk::UnlimitedApproval => "approval amount is unlimited",
+        }
+    }
+}
+
+impl TypedData {
+    /// Parse typed-data JSON
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut typed: TypedData = serde_json::from_slice(bytes)
+            .map_err(|e| typed_error(&format!("invalid JSON: {e}")))?;
+
+        // Many dApps omit the domain type; derive it from the domain fields present
+        if !typed.types.contains_key(DOMAIN_TYPE) {
+            let fields = [
+                ("name", "string"),
+                ("version", "string"),
+                ("chainId", "uint256"),
+                ("verifyingContract", "address"),
+                ("salt", "bytes32"),
+            ]
+            .into_iter()
+            .filter(|(name, _)| typed.domain.contains_key(*name))
+            .map(|(name, type_name)| TypedField {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+            })
+            .collect();
+            typed.types.insert(DOMAIN_TYPE.to_string(), fields);
+        }
+
+        if !typed.types.contains_key(&typed.primary_type) {
+            return Err(typed_error(&format!(
+                "primary type '{}' is not defined",
+                typed.primary_type
+            )));
+        }
+        Ok(typed)
+    }
+
+    /// Domain `name`, if present
+    pub fn domain_name(&self) -> Option<&str> {
+        self.domain.get("name").and_then(Value::as_str)
+    }
+
+    /// Domain `chainId`, if present and numeric
+    pub fn domain_chain_id(&self) -> Option<u64> {
+        match self.domain.get("chainId")? {
+            Value::Number(number) => number.as_u64(),
+            Value::String(text) => parse_u64(text),
+            _ => None,
+        }
+    }
+
+    /// Domain `verifyingContract`, if present
+    pub fn verifying_contract(&self) -> Option<&str> {
+        self.domain.get("verifyingContract").and_then(Value::as_str)
+    }
+
+    /// `hashStruct(domain)`
+    pub fn domain_separator(&self) -> Result<[u8; 32]> {
+        self.hash_struct(DOMAIN_TYPE, &Value::Object(self.domain.clone()))
+    }
+
+    /// `hashStruct(message)`; `None` when the primary type is the domain itself
+    pub fn struct_hash(&self) -> Result<Option<[u8; 32]>> {
+        if self.primary_type == DOMAIN_TYPE {
+            return Ok(None);
+        }
+        self.hash_struct(&self.primary_type, &self.message)
+            .map(Some)
+    }
+
+    /// Digest that is actually signed: `keccak256(0x1901 ‖ domainSeparator ‖ hashStruct(message))`
+    pub fn signing_hash(&self) -> Result<[u8; 32]> {
+        let mut preimage = vec![0x19, 0x01];
+        preimage.extend_from_slice(&self.domain_separator()?);
+        if let Some(struct_hash) = self.struct_hash()? {
+            preimage.extend_from_slice(&struct_hash);
+        }
+        Ok(keccak256(&preimage))
+    }
+
+    /// `encodeType` for a struct type and its referenced types
+    pub fn encode_type(&self, type_name: &str) -> Result<String> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(type_name, &mut dependencies)?;
+        dependencies.remove(type_name);
+
+        let mut encoded = self.encode_single_type(type_name)?;
+        for dependency in dependencies {
+            encoded.push_str(&self.encode_single_type(&dependency)?);
+        }
+        Ok(encoded)
+    }
+
+    /// `typeHash` for a struct type
+    pub fn type_hash(&self, type_name: &str) -> Result<[u8; 32]> {
+        Ok(keccak256(self.encode_type(type_name)?.as_bytes()))
+    }
+
+    /// `hashStruct(value)` for a struct type
+    pub fn hash_struct(&self, type_name: &str, value: &Value) -> Result<[u8; 32]> {
+        let fields = self.fields(type_name)?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| typed_error(&format!("{type_name} value is not an object")))?;
+
+        let mut encoded = self.type_hash(type_name)?.to_vec();
+        for field in fields {
+            let member = object.get(&field.name).unwrap_or(&Value::Null);
+            encoded.extend_from_slice(&self.encode_value(&field.type_name, member)?);
+        }
+        Ok(keccak256(&encoded))
+    }
+
+    /// Detect well-known high-risk signature patterns
+    pub fn risk_flags(&self) -> Vec<TypedDataRisk> {
+        let mut flags = Vec::new();
+        let domain_name = self.domain_name().unwrap_or_default();
+        let primary = self.primary_type.as_str();
+
+        if domain_name == "Permit2"
+            || matches!(
+                primary,
+                "PermitSingle"
+                    | "PermitBatch"
+                    | "PermitTransferFrom"
+                    | "PermitBatchTransferFrom"
+                    | "PermitWitnessTransferFrom"
+            )
+        {
+            flags.push(TypedDataRisk::Permit2);
+        } else if primary == "Permit" {
+            flags.push(TypedDataRisk::Permit);
+        }
+
+        if domain_name == "Seaport" || matches!(primary, "OrderComponents" | "BulkOrder") {
+            flags.push(TypedDataRisk::SeaportOrder);
+        }
+
+        if has_unlimited_amount(&self.message) {
+            flags.push(TypedDataRisk::UnlimitedApproval);
+        }
+        flags
+    }
+
+    fn fields(&self, type_name: &str) -> Result<&[TypedField]> {
+        self.types
+            .get(type_name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| typed_error(&format!("type '{type_name}' is not defined")))
+    }
+
+    fn encode_single_type(&self, type_name: &str) -> Result<String> {
+        let members: Vec<String> = self
+            .fields(type_name)?
+            .iter()
+            .map(|field| format!("{} {}", field.type_name, field.name))
+            .collect();
+        Ok(format!("{type_name}({})", members.join(",")))
+    }
+
+    fn collect_dependencies(&self, type_name: &str, found: &mut BTreeSet<String>) -> Result<()> {
+        if found.contains(type_name) {
+            return Ok(());
+        }
+        found.insert(type_name.to_string());
+        for field in self.fields(type_name)? {
+            let base = base_type(&field.type_name);
+            if self.types.contains_key(base) {
+                self.collect_dependencies(base, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `encodeData` for one member: always a 32-byte word
+    fn encode_value(&self, type_name: &str, value: &Value) -> Result<[u8; 32]> {
+        if let Some(element_type) = array_element_type(type_name) {
+            let items = value
+                .as_array()
+                .ok_or_else(|| typed_error(&format!("{type_name} value is not an array")))?;
+            let mut encoded = Vec::with_capacity(items.len() * 32);
+            for item in items {
+                encoded.extend_from_slice(&self.encode_value(element_type, item)?);
+            }
+            return Ok(keccak256(&encoded));
+        }
+
+        if self.types.contains_key(type_name) {
+            return self.hash_struct(type_name, value);
+        }
+
+        match type_name {
+            "string" => {
+                let text = value
+                    .as_str()
+                    .ok_or_else(|| typed_error("string value expected"))?;
+                Ok(keccak256(text.as_bytes()))
+            }
+            "bytes" => Ok(keccak256(&parse_hex_value(value)?)),
+            "bool" => {
+                let flag = match value {
+                    Value::Bool(flag) => *flag,
+                    Value::String(text) => text == "true",
+                    _ => return Err(typed_error("bool value expected")),
+                };
+                let mut word = [0u8; 32];
+                word[31] = flag as u8;
+                Ok(word)
+            }
+            "address" => {
+                let bytes = parse_hex_value(value)?;
+                if bytes.len() != 20 {
+                    return Err(typed_error("address must be 20 bytes"));
+                }
+                let mut word = [0u8; 32];
+                word[12..].copy_from_slice(&bytes);
+                Ok(word)
+            }
+            _ if type_name.starts_with("bytes") => {
+                let bytes = parse_hex_value(value)?;
+                if bytes.len() > 32 {
+                    return Err(typed_error(&format!("{type_name} value is too long")));
+                }
+                let mut word = [0u8; 32];
+                word[..bytes.len()].copy_from_slice(&bytes);
+                Ok(word)
+            }
+            _ if type_name.starts_with("uint") || type_name.starts_with("int") => {
+                parse_integer(value)
+            }
+            _ => Err(typed_error(&format!("unsupported type '{type_name}'"))),
+        }
+    }
+}
+
+/// Keccak-256 digest
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Strip array suffixes: `Person[][3]` → `Person`
+fn base_type(type_name: &str) -> &str {
+    type_name.split('[').next().unwrap_or(type_name)
+}
+
+/// Element type of an array type: `Person[][3]` → `Person[]`
+fn array_element_type(type_name: &str) -> Option<&str> {
+    if !type_name.ends_with(']') {
+        return None;
+    }
+    type_name.rfind('[').map(|index| &type_name[..index])
+}
+
+fn parse_hex_value(value: &Value) -> Result<Vec<u8>> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| typed_error("hex string value expected"))?;
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    hex::decode(digits).map_err(|e| typed_error(&format!("invalid hex '{text}': {e}")))
+}
+
+/// Parse a JSON number or decimal/hex string into a 256-bit two's complement word
+fn parse_integer(value: &Value) -> Result<[u8; 32]> {
+    let text = match value {
+        Value::Number(number) if number.is_i64() || number.is_u64() => number.to_string(),
+        Value::String(text) => text.trim().to_string(),
+        _ => return Err(typed_error("integer value expected")),
+    };
+
+    let (negative, magnitude) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    let (radix, digits) = match magnitude
+        .strip_prefix("0x")
+        .or_else(|| magnitude.strip_prefix("0X"))
+    {
+        Some(hex_digits) => (16, hex_digits),
+        None => (10, magnitude),
+    };
+    if digits.is_empty() {
+        return Err(typed_error(&format!("invalid integer '{text}'")));
+    }
+
+    let mut word = [0u8; 32];
+    for ch in digits.chars() {
+        let digit = ch
+            .to_digit(radix)
+            .ok_or_else(|| typed_error(&format!("invalid integer '{text}'")))?;
+        let mut carry = digit;
+        for byte in word.iter_mut().rev() {
+            let next = *byte as u32 * radix + carry;
+            *byte = next as u8;
+            carry = next >> 8;
+        }
+        if carry != 0 {
+            return Err(typed_error(&format!("integer '{text}' overflows 256 bits")));
+        }
+    }
+
+    if negative {
+        for byte in word.iter_mut() {
+            *byte = !*byte;
+        }
+        for byte in word.iter_mut().rev() {
+            let (next, overflow) = byte.overflowing_add(1);
+            *byte = next;
+            if !overflow {
+                break;
+            }
+        }
+    }
+    Ok(word)
+}
+
+fn parse_u64(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x") {
+        Some(hex_digits) => u64::from_str_radix(hex_digits, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Look for an amount-like member set to the maximum uint256/uint160, or a
+/// DAI-style `allowed: true`
+fn has_unlimited_amount(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.iter().any(|(key, member)| {
+            let amount_key = matches!(key.as_str(), "value" | "amount" | "allowance");
+            let unlimited = amount_key
+                && parse_integer(member)
+                    .map(|word| word == MAX_UINT256 || word == MAX_UINT160)
+                    .unwrap_or(false);
+            let dai_allowed = key == "allowed" && member == &Value::Bool(true);
+            unlimited || dai_allowed || has_unlimited_amount(member)
+        }),
+        Value::Array(items) => items.iter().any(has_unlimited_amount),
+        _ => false,
+    }
+}
+
+fn typed_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid EIP-712 typed data: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_eip712_mail_example() {
+        let typed = TypedData::parse(MAIL.as_bytes()).unwrap();
+
+        assert_eq!(
+            typed.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(typed.domain_separator().unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(typed.struct_hash().unwrap().unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed.signing_hash().unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+        assert!(typed.risk_flags().is_empty());
+    }
+
+    #[test]
+    fn test_flags_unlimited_permit() {
+        let json = r#"{
+            "types": {
+                "Permit": [
+                    {"name": "owner", "type": "address"},
+                    {"name": "spender", "type": "address"},
+                    {"name": "value", "type": "uint256"},
+                    {"name": "nonce", "type": "uint256"},
+                    {"name": "deadline", "type": "uint256"}
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": {"name": "USD Coin", "version": "2", "chainId": "0x1",
+                       "verifyingContract": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"},
+            "message": {
+                "owner": "0x1111111111111111111111111111111111111111",
+                "spender": "0x2222222222222222222222222222222222222222",
+                "value": "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                "nonce": 0,
+                "deadline": "0xffffffff"
+            }
+        }"#;
+        let typed = TypedData::parse(json.as_bytes()).unwrap();
+
+        assert_eq!(typed.domain_chain_id(), Some(1));
+        assert_eq!(
+            typed.risk_flags(),
+            vec![TypedDataRisk::Permit, TypedDataRisk::UnlimitedApproval]
+        );
+        // Domain type is derived when omitted
+        assert!(typed.signing_hash().is_ok());
+    }
+
+    #[test]
+    fn test_negative_integer_encoding() {
+        let word = parse_integer(&Value::from(-1)).unwrap();
+        assert_eq!(word, MAX_UINT256);
+    }
+}
@@ -3,6 +3,10 @@
 //! Note: Hedera support is custom implementation for Helix wallet.
 //! Using tag numbers 9001/9002 for HBAR messages.
 
+pub mod transaction;
+
+pub use transaction::{HederaTransaction, HederaTransactionData, PayerCheck};
+
 use crate::error::Result;
 use crate::keystone::cbor;
 use crate::keystone::crypto_keypath::CryptoKeyPath;
@@ -60,6 +64,16 @@ impl HederaSignRequest {
         self
     }
 
+    /// Decode `transaction_bytes` as a Hedera transaction
+    pub fn decode_transaction(&self) -> Result<HederaTransaction> {
+        HederaTransaction::decode(&self.transaction_bytes)
+    }
+
+    /// Check that `account_id` is the account paying for the transaction
+    pub fn check_payer(&self, transaction: &HederaTransaction) -> PayerCheck {
+        PayerCheck::compare(self.account_id.as_deref(), transaction.payer.as_ref())
+    }
+
     /// Encode to CBOR bytes
     pub fn to_cbor(&self) -> Result<Vec<u8>> {
         cbor::to_bytes(self)
@@ -0,0 +1,748 @@
+//! Hedera protobuf transaction decoding for review before signing
+//!
+//! Accepts a `Transaction`, a `SignedTransaction` or a bare `TransactionBody`
+//! and decodes the fields a signer needs to see: transaction ID (and therefore
+//! the payer), node, fee, memo and the body type. Crypto transfers, token
+//! associations and contract calls are decoded; other body types are reported
+//! by name only.
+//!
+//! Reference: https://github.com/hashgraph/hedera-protobufs
+
+use crate::error::{Error, Result};
+
+const TINYBARS_PER_HBAR: u64 = 100_000_000;
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_LEN: u64 = 2;
+const WIRE_FIXED32: u64 = 5;
+
+/// Hedera entity ID (`shard.realm.num`)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HederaEntityId {
+    /// Shard number
+    pub shard: i64,
+    /// Realm number
+    pub realm: i64,
+    /// Entity number
+    pub num: i64,
+    /// Alias or EVM address used in place of `num`, if any
+    pub alias: Option<Vec<u8>>,
+}
+
+impl HederaEntityId {
+    /// Display form: `0.0.1234`, or `0.0.<alias hex>` for aliased accounts
+    pub fn display(&self) -> String {
+        match &self.alias {
+            Some(alias) => format!("{}.{}.{}", self.shard, self.realm, hex::encode(alias)),
+            None => format!("{}.{}.{}", self.shard, self.realm, self.num),
+        }
+    }
+
+    /// Whether this ID refers to the same entity as `text` (checksum suffixes are ignored)
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.trim();
+        let text = text.split_once('-').map_or(text, |(id, _)| id);
+        let mut parts = text.split('.');
+        let (Some(shard), Some(realm), Some(num), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return false;
+        };
+        if shard.parse() != Ok(self.shard) || realm.parse() != Ok(self.realm) {
+            return false;
+        }
+        match &self.alias {
+            Some(alias) => num
+                .strip_prefix("0x")
+                .unwrap_or(num)
+                .eq_ignore_ascii_case(&hex::encode(alias)),
+            None => num.parse() == Ok(self.num),
+        }
+    }
+}
+
+/// Signed balance change for one account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HederaAccountAmount {
+    /// Account whose balance changes
+    pub account: HederaEntityId,
+    /// Change in tinybars or token base units (negative = debit)
+    pub amount: i64,
+    /// Whether the debit spends an allowance
+    pub is_approval: bool,
+}
+
+/// NFT ownership change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HederaNftTransfer {
+    /// Current owner
+    pub sender: HederaEntityId,
+    /// New owner
+    pub receiver: HederaEntityId,
+    /// NFT serial number
+    pub serial_number: i64,
+}
+
+/// Transfers of one token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HederaTokenTransfers {
+    /// Token being moved
+    pub token: HederaEntityId,
+    /// Fungible balance changes
+    pub transfers: Vec<HederaAccountAmount>,
+    /// NFT ownership changes
+    pub nft_transfers: Vec<HederaNftTransfer>,
+    /// Decimals the sender expects the token to have
+    pub expected_decimals: Option<u32>,
+}
+
+/// Decoded transaction body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HederaTransactionData {
+    /// `CryptoTransfer`
+    CryptoTransfer {
+        /// HBAR balance changes
+        hbar_transfers: Vec<HederaAccountAmount>,
+        /// Token balance changes
+        token_transfers: Vec<HederaTokenTransfers>,
+    },
+    /// `TokenAssociate`
+    TokenAssociate {
+        /// Account gaining the associations
+        account: Option<HederaEntityId>,
+        /// Tokens to associate
+        tokens: Vec<HederaEntityId>,
+    },
+    /// `ContractCall`
+    ContractCall {
+        /// Contract being called
+        contract: Option<HederaEntityId>,
+        /// Gas limit
+        gas: i64,
+        /// Tinybars sent with the call
+        amount: i64,
+        /// ABI-encoded call data
+        function_parameters: Vec<u8>,
+    },
+    /// Any other body type, by protobuf field name
+    Other(&'static str),
+}
+
+impl HederaTransactionData {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            HederaTransactionData::CryptoTransfer { .. } => "crypto_transfer",
+            HederaTransactionData::TokenAssociate { .. } => "token_associate",
+            HederaTransactionData::ContractCall { .. } => "contract_call",
+            HederaTransactionData::Other(name) => name,
+        }
+    }
+}
+
+/// Decoded Hedera transaction body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HederaTransaction {
+    /// Serialized `TransactionBody`, the bytes that are actually signed
+    pub body_bytes: Vec<u8>,
+    /// Account paying for the transaction (from the transaction ID)
+    pub payer: Option<HederaEntityId>,
+    /// Transaction valid-start time as `(seconds, nanos)`
+    pub valid_start: Option<(i64, i32)>,
+    /// Whether this is a scheduled transaction
+    pub scheduled: bool,
+    /// Node the transaction is submitted to
+    pub node_account: Option<HederaEntityId>,
+    /// Maximum transaction fee in tinybars
+    pub transaction_fee: u64,
+    /// Validity window in seconds
+    pub valid_duration: Option<i64>,
+    /// Memo
+    pub memo: String,
+    /// Body type and its decoded fields
+    pub data: HederaTransactionData,
+}
+
+impl HederaTransaction {
+    /// Decode a `Transaction`, `SignedTransaction` or `TransactionBody`
+    ///
+    /// The wrappers are unambiguous once wire types are checked: neither
+    /// parses as a body, and a body does not parse as either wrapper.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let body_error = match Self::decode_body(bytes) {
+            Ok(transaction) => return Ok(transaction),
+            Err(err) => err,
+        };
+        let fields = fields(bytes)?;
+        let body_bytes = match field_bytes(&fields, 5) {
+            // Transaction.signedTransactionBytes
+            Some(signed) => field_bytes(&self::fields(signed)?, 1),
+            // Transaction.bodyBytes (deprecated), then SignedTransaction.bodyBytes
+            None => field_bytes(&fields, 4).or_else(|| field_bytes(&fields, 1)),
+        };
+        match body_bytes {
+            Some(body_bytes) => Self::decode_body(body_bytes),
+            None => Err(body_error),
+        }
+    }
+
+    /// Decode a serialized `TransactionBody`
+    pub fn decode_body(bytes: &[u8]) -> Result<Self> {
+        let mut transaction = HederaTransaction {
+            body_bytes: bytes.to_vec(),
+            payer: None,
+            valid_start: None,
+            scheduled: false,
+            node_account: None,
+            transaction_fee: 0,
+            valid_duration: None,
+            memo: String::new(),
+            data: HederaTransactionData::Other("unknown"),
+        };
+        let mut has_transaction_id = false;
+        let mut data = None;
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Bytes(id)) => {
+                    has_transaction_id = true;
+                    for field in fields(id)? {
+                        match (field.number, field.value) {
+                            (1, FieldValue::Bytes(timestamp)) => {
+                                transaction.valid_start = Some(decode_timestamp(timestamp)?)
+                            }
+                            (2, FieldValue::Bytes(account)) => {
+                                transaction.payer = Some(decode_entity_id(account)?)
+                            }
+                            (3, FieldValue::Varint(scheduled)) => {
+                                transaction.scheduled = scheduled != 0
+                            }
+                            (1..=3, _) => return Err(wire_type_error("transaction ID")),
+                            _ => {}
+                        }
+                    }
+                }
+                (2, FieldValue::Bytes(account)) => {
+                    transaction.node_account = Some(decode_entity_id(account)?)
+                }
+                (3, FieldValue::Varint(fee)) => transaction.transaction_fee = fee,
+                (4, FieldValue::Bytes(duration)) => {
+                    for field in fields(duration)? {
+                        if let (1, FieldValue::Varint(seconds)) = (field.number, field.value) {
+                            transaction.valid_duration = Some(seconds as i64);
+                        }
+                    }
+                }
+                (5, FieldValue::Varint(_)) => {} // generateRecord (deprecated)
+                (6, FieldValue::Bytes(memo)) => {
+                    transaction.memo = String::from_utf8_lossy(memo).into_owned()
+                }
+                (1..=6, _) => return Err(wire_type_error("transaction body")),
+                // The `data` oneof; later fields (custom fee limits, batch keys) are not surfaced
+                (number @ 7..=99, FieldValue::Bytes(body)) => {
+                    if data.is_some() {
+                        return Err(protobuf_error("transaction body has more than one type"));
+                    }
+                    data = Some(decode_data(number, body)?);
+                }
+                (number @ 7..=99, _) => {
+                    return Err(protobuf_error(&format!(
+                        "unexpected scalar in body field {number}"
+                    )));
+                }
+                _ => {}
+            }
+        }
+        if !has_transaction_id {
+            return Err(protobuf_error("transaction body has no transaction ID"));
+        }
+        transaction.data =
+            data.ok_or_else(|| protobuf_error("transaction body has no body type"))?;
+        Ok(transaction)
+    }
+
+    /// Net HBAR leaving the payer account, in tinybars (0 if the payer is credited)
+    pub fn payer_hbar_debit(&self) -> u64 {
+        let (Some(payer), HederaTransactionData::CryptoTransfer { hbar_transfers, .. }) =
+            (&self.payer, &self.data)
+        else {
+            return 0;
+        };
+        let net: i128 = hbar_transfers
+            .iter()
+            .filter(|transfer| transfer.account == *payer)
+            .map(|transfer| i128::from(transfer.amount))
+            .sum();
+        if net < 0 {
+            u64::try_from(-net).unwrap_or(u64::MAX)
+        } else {
+            0
+        }
+    }
+}
+
+/// Result of comparing a request's account ID with the transaction payer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayerCheck {
+    /// The request account pays for the transaction
+    Match,
+    /// The transaction is paid for by a different account
+    Mismatch {
+        /// Account ID declared by the sign request
+        account_id: String,
+        /// Payer from the transaction ID
+        payer: String,
+    },
+    /// The request declares no account ID or the transaction has no payer
+    Unknown,
+}
+
+impl PayerCheck {
+    /// Compare a request account ID with the transaction payer
+    pub fn compare(account_id: Option<&str>, payer: Option<&HederaEntityId>) -> Self {
+        match (account_id, payer) {
+            (Some(account_id), Some(payer)) if payer.matches(account_id) => PayerCheck::Match,
+            (Some(account_id), Some(payer)) => PayerCheck::Mismatch {
+                account_id: account_id.to_string(),
+                payer: payer.display(),
+            },
+            _ => PayerCheck::Unknown,
+        }
+    }
+
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            PayerCheck::Match => "match",
+            PayerCheck::Mismatch { .. } => "mismatch",
+            PayerCheck::Unknown => "unknown",
+        }
+    }
+}
+
+/// Format a tinybar amount as decimal HBAR, e.g. `1.5 HBAR`
+pub fn format_tinybars_as_hbar(tinybars: i64) -> String {
+    let sign = if tinybars < 0 { "-" } else { "" };
+    let magnitude = tinybars.unsigned_abs();
+    let whole = magnitude / TINYBARS_PER_HBAR;
+    let fraction = magnitude % TINYBARS_PER_HBAR;
+    if fraction == 0 {
+        return format!("{sign}{whole} HBAR");
+    }
+    let fraction = format!("{fraction:08}");
+    format!("{sign}{whole}.{} HBAR", fraction.trim_end_matches('0'))
+}
+
+fn decode_data(number: u64, body: &[u8]) -> Result<HederaTransactionData> {
+    Ok(match number {
+        7 => decode_contract_call(body)?,
+        14 => decode_crypto_transfer(body)?,
+        40 => decode_token_associate(body)?,
+        other => HederaTransactionData::Other(body_type_name(other)),
+    })
+}
+
+fn decode_crypto_transfer(bytes: &[u8]) -> Result<HederaTransactionData> {
+    let mut hbar_transfers = Vec::new();
+    let mut token_transfers = Vec::new();
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(list)) => {
+                for field in fields(list)? {
+                    match (field.number, field.value) {
+                        (1, FieldValue::Bytes(amount)) => {
+                            hbar_transfers.push(decode_account_amount(amount)?)
+                        }
+                        (1, _) => return Err(wire_type_error("transfer list")),
+                        _ => {}
+                    }
+                }
+            }
+            (2, FieldValue::Bytes(list)) => token_transfers.push(decode_token_transfers(list)?),
+            (1 | 2, _) => return Err(wire_type_error("crypto transfer")),
+            _ => {}
+        }
+    }
+    Ok(HederaTransactionData::CryptoTransfer {
+        hbar_transfers,
+        token_transfers,
+    })
+}
+
+fn decode_token_transfers(bytes: &[u8]) -> Result<HederaTokenTransfers> {
+    let mut token = None;
+    let mut transfers = Vec::new();
+    let mut nft_transfers = Vec::new();
+    let mut expected_decimals = None;
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(id)) => token = Some(decode_entity_id(id)?),
+            (2, FieldValue::Bytes(amount)) => transfers.push(decode_account_amount(amount)?),
+            (3, FieldValue::Bytes(nft)) => {
+                let mut sender = HederaEntityId::default();
+                let mut receiver = HederaEntityId::default();
+                let mut serial_number = 0;
+                for field in fields(nft)? {
+                    match (field.number, field.value) {
+                        (1, FieldValue::Bytes(id)) => sender = decode_entity_id(id)?,
+                        (2, FieldValue::Bytes(id)) => receiver = decode_entity_id(id)?,
+                        (3, FieldValue::Varint(serial)) => serial_number = serial as i64,
+                        (1..=3, _) => return Err(wire_type_error("NFT transfer")),
+                        _ => {}
+                    }
+                }
+                nft_transfers.push(HederaNftTransfer {
+                    sender,
+                    receiver,
+                    serial_number,
+                });
+            }
+            (4, FieldValue::Bytes(wrapper)) => {
+                // google.protobuf.UInt32Value
+                expected_decimals = Some(0);
+                for field in fields(wrapper)? {
+                    if let (1, FieldValue::Varint(value)) = (field.number, field.value) {
+                        expected_decimals = Some(value as u32);
+                    }
+                }
+            }
+            (1..=4, _) => return Err(wire_type_error("token transfer list")),
+            _ => {}
+        }
+    }
+    Ok(HederaTokenTransfers {
+        token: token.ok_or_else(|| protobuf_error("token transfer list has no token"))?,
+        transfers,
+        nft_transfers,
+        expected_decimals,
+    })
+}
+
+fn decode_account_amount(bytes: &[u8]) -> Result<HederaAccountAmount> {
+    let mut account = HederaEntityId::default();
+    let mut amount = 0;
+    let mut is_approval = false;
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(id)) => account = decode_entity_id(id)?,
+            // sint64: zigzag encoded
+            (2, FieldValue::Varint(value)) => amount = (value >> 1) as i64 ^ -((value & 1) as i64),
+            (3, FieldValue::Varint(value)) => is_approval = value != 0,
+            (1..=3, _) => return Err(wire_type_error("account amount")),
+            _ => {}
+        }
+    }
+    Ok(HederaAccountAmount {
+        account,
+        amount,
+        is_approval,
+    })
+}
+
+fn decode_token_associate(bytes: &[u8]) -> Result<HederaTransactionData> {
+    let mut account = None;
+    let mut tokens = Vec::new();
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(id)) => account = Some(decode_entity_id(id)?),
+            (2, FieldValue::Bytes(id)) => tokens.push(decode_entity_id(id)?),
+            (1 | 2, _) => return Err(wire_type_error("token associate")),
+            _ => {}
+        }
+    }
+    Ok(HederaTransactionData::TokenAssociate { account, tokens })
+}
+
+fn decode_contract_call(bytes: &[u8]) -> Result<HederaTransactionData> {
+    let mut contract = None;
+    let mut gas = 0;
+    let mut amount = 0;
+    let mut function_parameters = Vec::new();
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(id)) => contract = Some(decode_entity_id(id)?),
+            (2, FieldValue::Varint(value)) => gas = value as i64,
+            (3, FieldValue::Varint(value)) => amount = value as i64,
+            (4, FieldValue::Bytes(data)) => function_parameters = data.to_vec(),
+            (1..=4, _) => return Err(wire_type_error("contract call")),
+            _ => {}
+        }
+    }
+    Ok(HederaTransactionData::ContractCall {
+        contract,
+        gas,
+        amount,
+        function_parameters,
+    })
+}
+
+/// `AccountID`, `TokenID` and `ContractID` share the shard/realm/num layout;
+/// field 4 is the account alias or contract EVM address
+fn decode_entity_id(bytes: &[u8]) -> Result<HederaEntityId> {
+    let mut id = HederaEntityId::default();
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Varint(value)) => id.shard = value as i64,
+            (2, FieldValue::Varint(value)) => id.realm = value as i64,
+            (3, FieldValue::Varint(value)) => id.num = value as i64,
+            (4, FieldValue::Bytes(alias)) => id.alias = Some(alias.to_vec()),
+            (1..=4, _) => return Err(wire_type_error("entity ID")),
+            _ => {}
+        }
+    }
+    Ok(id)
+}
+
+fn decode_timestamp(bytes: &[u8]) -> Result<(i64, i32)> {
+    let mut seconds = 0;
+    let mut nanos = 0;
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Varint(value)) => seconds = value as i64,
+            (2, FieldValue::Varint(value)) => nanos = value as i32,
+            (1 | 2, _) => return Err(wire_type_error("timestamp")),
+            _ => {}
+        }
+    }
+    Ok((seconds, nanos))
+}
+
+fn body_type_name(number: u64) -> &'static str {
+    match number {
+        8 => "contract_create",
+        9 => "contract_update",
+        11 => "crypto_create_account",
+        12 => "crypto_delete",
+        15 => "crypto_update_account",
+        16 => "file_append",
+        17 => "file_create",
+        18 => "file_delete",
+        19 => "file_update",
+        22 => "contract_delete",
+        23 => "freeze",
+        24 => "consensus_create_topic",
+        25 => "consensus_update_topic",
+        26 => "consensus_delete_topic",
+        27 => "consensus_submit_message",
+        29 => "token_create",
+        31 => "token_freeze",
+        32 => "token_unfreeze",
+        33 => "token_grant_kyc",
+        34 => "token_revoke_kyc",
+        35 => "token_delete",
+        36 => "token_update",
+        37 => "token_mint",
+        38 => "token_burn",
+        39 => "token_wipe",
+        41 => "token_dissociate",
+        42 => "schedule_create",
+        43 => "schedule_delete",
+        44 => "schedule_sign",
+        45 => "token_fee_schedule_update",
+        46 => "token_pause",
+        47 => "token_unpause",
+        48 => "crypto_approve_allowance",
+        49 => "crypto_delete_allowance",
+        50 => "ethereum_transaction",
+        _ => "unknown",
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FieldValue<'a> {
+    Varint(u64),
+    Fixed,
+    Bytes(&'a [u8]),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Field<'a> {
+    number: u64,
+    value: FieldValue<'a>,
+}
+
+/// Split a protobuf message into its top-level fields
+fn fields(bytes: &[u8]) -> Result<Vec<Field<'_>>> {
+    let mut pos = 0;
+    let mut fields = Vec::new();
+    while pos < bytes.len() {
+        let key = read_varint(bytes, &mut pos)?;
+        let number = key >> 3;
+        if number == 0 {
+            return Err(protobuf_error("field number 0"));
+        }
+        let value = match key & 7 {
+            WIRE_VARINT => FieldValue::Varint(read_varint(bytes, &mut pos)?),
+            WIRE_FIXED64 => {
+                take(bytes, &mut pos, 8)?;
+                FieldValue::Fixed
+            }
+            WIRE_LEN => {
+                let len = usize::try_from(read_varint(bytes, &mut pos)?)
+                    .map_err(|_| protobuf_error("length overflow"))?;
+                FieldValue::Bytes(take(bytes, &mut pos, len)?)
+            }
+            WIRE_FIXED32 => {
+                take(bytes, &mut pos, 4)?;
+                FieldValue::Fixed
+            }
+            other => return Err(protobuf_error(&format!("unsupported wire type {other}"))),
+        };
+        fields.push(Field { number, value });
+    }
+    Ok(fields)
+}
+
+fn field_bytes<'a>(fields: &[Field<'a>], number: u64) -> Option<&'a [u8]> {
+    fields.iter().find_map(|field| match field.value {
+        FieldValue::Bytes(bytes) if field.number == number => Some(bytes),
+        _ => None,
+    })
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or_else(|| protobuf_error("truncated varint"))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(protobuf_error("varint too long"))
+}
+
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let end = pos
+        .checked_add(len)
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| protobuf_error("unexpected end of data"))?;
+    let slice = &bytes[*pos..end];
+    *pos = end;
+    Ok(slice)
+}
+
+fn wire_type_error(message: &str) -> Error {
+    protobuf_error(&format!("unexpected wire type in {message}"))
+}
+
+fn protobuf_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Hedera transaction: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn uint_field(number: u64, value: u64) -> Vec<u8> {
+        let mut out = varint(number << 3);
+        out.extend(varint(value));
+        out
+    }
+
+    fn len_field(number: u64, data: &[u8]) -> Vec<u8> {
+        let mut out = varint((number << 3) | 2);
+        out.extend(varint(data.len() as u64));
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn account(num: u64) -> Vec<u8> {
+        uint_field(3, num)
+    }
+
+    fn account_amount(num: u64, amount: i64) -> Vec<u8> {
+        let mut out = len_field(1, &account(num));
+        out.extend(uint_field(2, ((amount << 1) ^ (amount >> 63)) as u64));
+        out
+    }
+
+    /// `TransactionBody` paying 2.5 HBAR from 0.0.1001 to 0.0.2002
+    fn transfer_body() -> Vec<u8> {
+        let mut transaction_id = len_field(1, &uint_field(1, 1_700_000_000));
+        transaction_id.extend(len_field(2, &account(1001)));
+
+        let mut transfers = len_field(1, &account_amount(1001, -250_000_000));
+        transfers.extend(len_field(1, &account_amount(2002, 250_000_000)));
+        let crypto_transfer = len_field(1, &transfers);
+
+        let mut body = len_field(1, &transaction_id);
+        body.extend(len_field(2, &account(3)));
+        body.extend(uint_field(3, 100_000_000));
+        body.extend(len_field(4, &uint_field(1, 120)));
+        body.extend(len_field(6, b"invoice 42"));
+        body.extend(len_field(14, &crypto_transfer));
+        body
+    }
+
+    #[test]
+    fn test_decode_crypto_transfer_body() {
+        let tx = HederaTransaction::decode(&transfer_body()).unwrap();
+        assert_eq!(tx.payer.as_ref().unwrap().display(), "0.0.1001");
+        assert_eq!(tx.node_account.as_ref().unwrap().display(), "0.0.3");
+        assert_eq!(tx.transaction_fee, 100_000_000);
+        assert_eq!(tx.valid_duration, Some(120));
+        assert_eq!(tx.memo, "invoice 42");
+        assert_eq!(tx.data.label(), "crypto_transfer");
+        assert_eq!(
+            format_tinybars_as_hbar(tx.payer_hbar_debit() as i64),
+            "2.5 HBAR"
+        );
+        assert_eq!(tx.body_bytes, transfer_body());
+    }
+
+    #[test]
+    fn test_decode_wrapped_transaction_and_token_associate() {
+        let mut associate = len_field(1, &account(1001));
+        associate.extend(len_field(2, &account(456858)));
+        let mut body = len_field(1, &len_field(2, &account(1001)));
+        body.extend(len_field(40, &associate));
+
+        let signed = len_field(1, &body);
+        let transaction = len_field(5, &signed);
+        for bytes in [&body, &signed, &transaction] {
+            let tx = HederaTransaction::decode(bytes).unwrap();
+            assert_eq!(tx.body_bytes, body);
+            match tx.data {
+                HederaTransactionData::TokenAssociate { tokens, .. } => {
+                    assert_eq!(tokens[0].display(), "0.0.456858");
+                }
+                other => panic!("unexpected body {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_payer_check() {
+        let payer = HederaEntityId {
+            num: 1001,
+            ..Default::default()
+        };
+        assert_eq!(
+            PayerCheck::compare(Some("0.0.1001-abcde"), Some(&payer)),
+            PayerCheck::Match
+        );
+        assert_eq!(
+            PayerCheck::compare(Some("0.0.1002"), Some(&payer)).label(),
+            "mismatch"
+        );
+        assert_eq!(PayerCheck::compare(None, Some(&payer)), PayerCheck::Unknown);
+        assert!(HederaTransaction::decode(&[0x0a, 0x10, 0x1a, 0x20]).is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! Keystone base protobuf envelope
+//!
+//! Older Keystone flows (Tron, LTC, DASH and BCH through the Keystone app)
+//! do not use a chain-specific CBOR structure. Instead the request is a
+//! protobuf `Base` message, gzip-compressed, and carried either as the only
+//! field of a `keystone-sign-request` UR or as a plain CBOR byte string in a
+//! `bytes` UR.
+//!
+//! Reference: https://github.com/KeystoneHQ/keystone-sdk-base
+
+pub mod proto;
+
+pub use proto::{
+    Base, BaseContent, BasePayload, BasePayloadType, BaseTransaction, SignTransaction,
+    SignTransactionResult, TronTransaction, UtxoInput, UtxoOutput, UtxoTransaction,
+};
+
+use crate::error::{Error, Result};
+use crate::keystone::cbor;
+use flate2::read::GzDecoder;
+use minicbor::{Decoder, Encoder};
+use std::io::Read;
+
+/// gzip magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Upper bound on the decompressed protobuf size
+///
+/// Real envelopes are a few kilobytes; the cap stops a small QR payload from
+/// inflating into an arbitrarily large buffer.
+pub const MAX_DECOMPRESSED_LEN: u64 = 1024 * 1024;
+
+/// UR container the envelope arrived in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystoneWrapper {
+    /// `keystone-sign-request` UR (tagged map with the compressed bytes)
+    SignRequest,
+    /// `bytes` UR (bare CBOR byte string)
+    Bytes,
+}
+
+impl KeystoneWrapper {
+    /// UR type string for this container
+    pub fn ur_type(&self) -> &'static str {
+        match self {
+            KeystoneWrapper::SignRequest => "keystone-sign-request",
+            KeystoneWrapper::Bytes => "bytes",
+        }
+    }
+}
+
+/// gzip-compressed Keystone base protobuf envelope
+#[derive(Debug, Clone)]
+pub struct KeystoneSignRequest {
+    /// Compressed `Base` message exactly as carried by the UR
+    pub sign_data: Vec<u8>,
+    /// UR container the envelope arrived in
+    pub wrapper: KeystoneWrapper,
+}
+
+impl KeystoneSignRequest {
+    /// Wrap compressed envelope bytes in a `keystone-sign-request`
+    pub fn new(sign_data: Vec<u8>) -> Self {
+        Self {
+            sign_data,
+            wrapper: KeystoneWrapper::SignRequest,
+        }
+    }
+
+    /// Decompress and decode the `Base` message
+    pub fn decode_base(&self) -> Result<Base> {
+        Base::decode(&gunzip(&self.sign_data)?)
+    }
+
+    /// Tron transfer carried by the envelope, if this is a Tron sign request
+    ///
+    /// Keystone has no chain-specific UR type for Tron; its sign requests only
+    /// ever arrive in this envelope.
+    pub fn tron_transaction(&self) -> Result<Option<TronTransaction>> {
+        let content = self.decode_base()?.payload.map(|payload| payload.content);
+        Ok(match content {
+            Some(BaseContent::SignTransaction(sign_tx)) => match sign_tx.transaction {
+                BaseTransaction::Tron(tx) => Some(tx),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Recognise an envelope carried in a `bytes` UR
+    ///
+    /// Returns `None` when the payload is not a CBOR byte string holding a
+    /// gzip stream that decodes as a `Base` message, so callers can keep
+    /// treating it as opaque bytes.
+    pub fn from_bytes_ur(data: &[u8]) -> Option<Self> {
+        let sign_data = minicbor::decode::<minicbor::bytes::ByteVec>(data).ok()?;
+        if !sign_data.starts_with(&GZIP_MAGIC) {
+            return None;
+        }
+        let request = Self {
+            sign_data: sign_data.to_vec(),
+            wrapper: KeystoneWrapper::Bytes,
+        };
+        request.decode_base().ok()?;
+        Some(request)
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode a `keystone-sign-request` from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for KeystoneSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        match self.wrapper {
+            KeystoneWrapper::Bytes => {
+                e.bytes(&self.sign_data)?;
+            }
+            KeystoneWrapper::SignRequest => {
+                e.tag(minicbor::data::Tag::Unassigned(
+                    cbor::tags::KEYSTONE_SIGN_REQUEST,
+                ))?;
+                e.map(1)?;
+                e.u8(1)?;
+                e.bytes(&self.sign_data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for KeystoneSignRequest {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Keystone software leaves the top-level map untagged
+        if d.datatype()? == minicbor::data::Type::Tag {
+            let tag = d.tag()?;
+            if tag != minicbor::data::Tag::Unassigned(cbor::tags::KEYSTONE_SIGN_REQUEST) {
+                return Err(minicbor::decode::Error::message(
+                    "expected keystone-sign-request tag",
+                ));
+            }
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut sign_data = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => sign_data = Some(d.bytes()?.to_vec()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            sign_data: sign_data
+                .ok_or_else(|| minicbor::decode::Error::message("missing sign_data"))?,
+            wrapper: KeystoneWrapper::SignRequest,
+        })
+    }
+}
+
+/// Render an integer amount with `decimals` fractional digits and a unit
+///
+/// `SignTransaction.decimal` tells the wallet how to display the amount, so
+/// the same helper covers TRX (6), TRC-20 tokens and the UTXO coins (8).
+pub fn format_units(amount: u64, decimals: u32, unit: &str) -> String {
+    let Some(scale) = 10u64.checked_pow(decimals) else {
+        return format!("{amount} base units of {unit}");
+    };
+    let whole = amount / scale;
+    let fraction = amount % scale;
+    if fraction == 0 {
+        return format!("{whole} {unit}");
+    }
+    let fraction = format!("{fraction:0width$}", width = decimals as usize);
+    format!("{whole}.{} {unit}", fraction.trim_end_matches('0'))
+}
+
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Err(envelope_error("sign data is not gzip-compressed"));
+    }
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes)
+        .take(MAX_DECOMPRESSED_LEN + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| envelope_error(&format!("gzip decode failed: {e}")))?;
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_LEN {
+        return Err(envelope_error(&format!(
+            "decompressed size exceeds {MAX_DECOMPRESSED_LEN} bytes"
+        )));
+    }
+    Ok(decompressed)
+}
+
+fn envelope_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Keystone base envelope: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    // Base { version: 1, data: Payload { type: SIGN_TX, xfp: "73c5da0a",
+    //   signTx: { coinCode: "LTC", ltcTx: { fee: 1000 } } } }
+    const BASE: &[u8] =
+        b"\x08\x01\x1a\x18\x08\x02\x12\x0873c5da0a\x22\x0a\x0a\x03LTC\x62\x03\x08\xe8\x07";
+
+    #[test]
+    fn test_sign_request_roundtrip_and_decode() {
+        let request = KeystoneSignRequest::new(gzip(BASE));
+
+        let bytes = request.to_cbor().unwrap();
+        let decoded = KeystoneSignRequest::from_cbor(&bytes).unwrap();
+        assert_eq!(decoded.sign_data, request.sign_data);
+
+        let base = decoded.decode_base().unwrap();
+        let payload = base.payload.unwrap();
+        assert_eq!(payload.kind, BasePayloadType::SignTransaction);
+        let BaseContent::SignTransaction(sign_tx) = payload.content else {
+            panic!("expected sign transaction");
+        };
+        assert_eq!(sign_tx.coin_code, "LTC");
+        let BaseTransaction::Utxo(tx) = sign_tx.transaction else {
+            panic!("expected utxo transaction");
+        };
+        assert_eq!(tx.fee, 1000);
+    }
+
+    #[test]
+    fn test_tron_sign_request() {
+        // Base { version: 1, data: Payload { type: SIGN_TX, xfp: "73c5da0a",
+        //   signTx: { coinCode: "TRON", tronTx: { token: "TRX",
+        //     to: "TKCs...", value: "1000000", fee: 100000 } } } }
+        const TRON: &[u8] = b"\x08\x01\x1a\x4c\x08\x02\x12\x0873c5da0a\x22\x3e\x0a\x04TRON\x42\x36\
+            \x0a\x03TRX\x22\x22TKCsXtfKfH2d6aEaQCctybDC9uaA3MSj2h\x32\x071000000\x48\xa0\x8d\x06";
+
+        // Untagged, as Keystone software sends it
+        let mut bytes = vec![0xa1, 0x01];
+        let compressed = gzip(TRON);
+        bytes.extend(minicbor::to_vec(minicbor::bytes::ByteVec::from(compressed)).unwrap());
+        let request = KeystoneSignRequest::from_cbor(&bytes).unwrap();
+
+        let tron = request.tron_transaction().unwrap().unwrap();
+        assert_eq!(tron.token, "TRX");
+        assert_eq!(tron.to, "TKCsXtfKfH2d6aEaQCctybDC9uaA3MSj2h");
+        assert_eq!(tron.value, "1000000");
+        assert_eq!(tron.fee, 100_000);
+
+        let ltc = KeystoneSignRequest::new(gzip(BASE));
+        assert!(ltc.tron_transaction().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bytes_ur_detection() {
+        let compressed = gzip(BASE);
+        let data = minicbor::to_vec(minicbor::bytes::ByteVec::from(compressed.clone())).unwrap();
+        let request = KeystoneSignRequest::from_bytes_ur(&data).unwrap();
+        assert_eq!(request.wrapper, KeystoneWrapper::Bytes);
+        assert_eq!(request.to_cbor().unwrap(), data);
+
+        let plain = minicbor::to_vec(minicbor::bytes::ByteVec::from(b"hello".to_vec())).unwrap();
+        assert!(KeystoneSignRequest::from_bytes_ur(&plain).is_none());
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(1_500_000, 6, "TRX"), "1.5 TRX");
+        assert_eq!(format_units(30_000, 8, "LTC"), "0.0003 LTC");
+        assert_eq!(format_units(7, 0, "DASH"), "7 DASH");
+    }
+
+    #[test]
+    fn test_rejects_oversized_envelope() {
+        let request = KeystoneSignRequest::new(gzip(&vec![0u8; MAX_DECOMPRESSED_LEN as usize + 1]));
+        assert!(request.decode_base().is_err());
+    }
+}
@@ -0,0 +1,624 @@
+//! Keystone base protobuf messages (`Base` / `Payload` / `SignTransaction`)
+//!
+//! Only the envelope and the transaction kinds we can review are typed:
+//! Tron transfers and the shared UTXO layout used by LTC, DASH and BCH. Any
+//! other transaction kind is reported by field number and coin code.
+//!
+//! Reference: https://github.com/KeystoneHQ/keystone-sdk-base (protos/)
+
+use crate::error::{Error, Result};
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_LEN: u64 = 2;
+const WIRE_FIXED32: u64 = 5;
+
+/// Top-level `Base` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base {
+    /// Protocol version
+    pub version: i64,
+    /// Free-form description (usually `keystone qrcode`)
+    pub description: String,
+    /// Request or response payload
+    pub payload: Option<BasePayload>,
+    /// Device type string, when the sender set one
+    pub device_type: Option<String>,
+}
+
+impl Base {
+    /// Decode a protobuf `Base` message
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut base = Base {
+            version: 0,
+            description: String::new(),
+            payload: None,
+            device_type: None,
+        };
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Varint(value)) => base.version = value as i64,
+                (2, FieldValue::Bytes(value)) => base.description = utf8(value, "description")?,
+                (3, FieldValue::Bytes(value)) => base.payload = Some(BasePayload::decode(value)?),
+                (6, FieldValue::Bytes(value)) => {
+                    base.device_type = Some(utf8(value, "deviceType")?)
+                }
+                _ => {}
+            }
+        }
+        Ok(base)
+    }
+}
+
+/// `Payload.Type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasePayloadType {
+    /// Wallet sync (account export)
+    Sync,
+    /// Transaction signing
+    SignTransaction,
+    /// Message signing
+    SignMessage,
+    /// Multisig transaction signing
+    SignMultiSig,
+    /// Multisig wallet sync
+    SyncMultiSig,
+    /// Ethereum transaction signing
+    SignEthTransaction,
+    /// Address verification
+    VerifyAddress,
+    /// Any other type value
+    Other(u64),
+}
+
+impl BasePayloadType {
+    fn from_value(value: u64) -> Self {
+        match value {
+            1 => BasePayloadType::Sync,
+            2 => BasePayloadType::SignTransaction,
+            3 => BasePayloadType::SignMessage,
+            4 => BasePayloadType::SignMultiSig,
+            5 => BasePayloadType::SyncMultiSig,
+            6 => BasePayloadType::SignEthTransaction,
+            7 => BasePayloadType::VerifyAddress,
+            other => BasePayloadType::Other(other),
+        }
+    }
+
+    /// Short lowercase label for output
+    pub fn label(&self) -> String {
+        match self {
+            BasePayloadType::Sync => "sync".to_string(),
+            BasePayloadType::SignTransaction => "sign_tx".to_string(),
+            BasePayloadType::SignMessage => "sign_msg".to_string(),
+            BasePayloadType::SignMultiSig => "sign_multi_sig".to_string(),
+            BasePayloadType::SyncMultiSig => "sync_multi_sig".to_string(),
+            BasePayloadType::SignEthTransaction => "sign_eth_tx".to_string(),
+            BasePayloadType::VerifyAddress => "verify_address".to_string(),
+            BasePayloadType::Other(value) => format!("unknown({value})"),
+        }
+    }
+}
+
+/// `Payload` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasePayload {
+    /// Payload type
+    pub kind: BasePayloadType,
+    /// Master fingerprint of the wallet, as hex
+    pub xfp: String,
+    /// Payload content
+    pub content: BaseContent,
+}
+
+impl BasePayload {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut kind = BasePayloadType::Other(0);
+        let mut xfp = String::new();
+        let mut content = BaseContent::None;
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Varint(value)) => kind = BasePayloadType::from_value(value),
+                (2, FieldValue::Bytes(value)) => xfp = utf8(value, "xfp")?,
+                (4, FieldValue::Bytes(value)) => {
+                    content = BaseContent::SignTransaction(SignTransaction::decode(value)?)
+                }
+                (7, FieldValue::Bytes(value)) => {
+                    content =
+                        BaseContent::SignTransactionResult(SignTransactionResult::decode(value)?)
+                }
+                (number, FieldValue::Bytes(_)) if number >= 3 => {
+                    content = BaseContent::Other { field: number }
+                }
+                _ => {}
+            }
+        }
+        Ok(Self { kind, xfp, content })
+    }
+}
+
+/// `Payload.Content` oneof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaseContent {
+    /// No content field present
+    None,
+    /// Transaction to sign
+    SignTransaction(SignTransaction),
+    /// Signed transaction returned by the wallet
+    SignTransactionResult(SignTransactionResult),
+    /// Content kind that is not decoded (sync, message, multisig, ...)
+    Other {
+        /// Protobuf field number of the content
+        field: u64,
+    },
+}
+
+/// `SignTransaction` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignTransaction {
+    /// Coin code (e.g. `TRON`, `LTC`, `DASH`, `BCH`)
+    pub coin_code: String,
+    /// Caller-chosen request identifier
+    pub sign_id: String,
+    /// BIP32 path of the signing key
+    pub hd_path: String,
+    /// Creation time in milliseconds
+    pub timestamp: i64,
+    /// Decimals of the coin's display unit
+    pub decimal: i64,
+    /// Chain transaction
+    pub transaction: BaseTransaction,
+}
+
+impl SignTransaction {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut sign_tx = SignTransaction {
+            coin_code: String::new(),
+            sign_id: String::new(),
+            hd_path: String::new(),
+            timestamp: 0,
+            decimal: 0,
+            transaction: BaseTransaction::None,
+        };
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Bytes(value)) => sign_tx.coin_code = utf8(value, "coinCode")?,
+                (2, FieldValue::Bytes(value)) => sign_tx.sign_id = utf8(value, "signId")?,
+                (3, FieldValue::Bytes(value)) => sign_tx.hd_path = utf8(value, "hdPath")?,
+                (4, FieldValue::Varint(value)) => sign_tx.timestamp = value as i64,
+                (5, FieldValue::Varint(value)) => sign_tx.decimal = value as i64,
+                (8, FieldValue::Bytes(value)) => {
+                    sign_tx.transaction = BaseTransaction::Tron(TronTransaction::decode(value)?)
+                }
+                (10..=12, FieldValue::Bytes(value)) => {
+                    sign_tx.transaction = BaseTransaction::Utxo(UtxoTransaction::decode(value)?)
+                }
+                (number, FieldValue::Bytes(_)) if number >= 6 => {
+                    sign_tx.transaction = BaseTransaction::Other { field: number }
+                }
+                _ => {}
+            }
+        }
+        Ok(sign_tx)
+    }
+}
+
+/// `SignTransaction.Transaction` oneof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaseTransaction {
+    /// No transaction field present
+    None,
+    /// Tron transfer (`tronTx`)
+    Tron(TronTransaction),
+    /// UTXO transaction (`bchTx`, `dashTx`, `ltcTx`)
+    Utxo(UtxoTransaction),
+    /// Transaction kind that is not decoded
+    Other {
+        /// Protobuf field number of the transaction
+        field: u64,
+    },
+}
+
+/// `TronTx` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TronTransaction {
+    /// Token name (`TRX` for native transfers, token ID or symbol otherwise)
+    pub token: String,
+    /// TRC-20 contract address, empty for native transfers
+    pub contract_address: String,
+    /// Sender address
+    pub from: String,
+    /// Recipient address
+    pub to: String,
+    /// Memo
+    pub memo: String,
+    /// Amount in the token's smallest unit, as a decimal string
+    pub value: String,
+    /// Fee limit in SUN
+    pub fee: i64,
+}
+
+impl TronTransaction {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut tx = TronTransaction {
+            token: String::new(),
+            contract_address: String::new(),
+            from: String::new(),
+            to: String::new(),
+            memo: String::new(),
+            value: String::new(),
+            fee: 0,
+        };
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Bytes(value)) => tx.token = utf8(value, "token")?,
+                (2, FieldValue::Bytes(value)) => {
+                    tx.contract_address = utf8(value, "contractAddress")?
+                }
+                (3, FieldValue::Bytes(value)) => tx.from = utf8(value, "from")?,
+                (4, FieldValue::Bytes(value)) => tx.to = utf8(value, "to")?,
+                (5, FieldValue::Bytes(value)) => tx.memo = utf8(value, "memo")?,
+                (6, FieldValue::Bytes(value)) => tx.value = utf8(value, "value")?,
+                (9, FieldValue::Varint(value)) => tx.fee = value as i64,
+                _ => {}
+            }
+        }
+        Ok(tx)
+    }
+
+    /// Whether the transfer moves a TRC-20 token rather than TRX
+    pub fn is_trc20(&self) -> bool {
+        !self.contract_address.is_empty()
+    }
+}
+
+/// Input of a UTXO transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoInput {
+    /// Previous transaction hash (hex)
+    pub hash: String,
+    /// Output index in the previous transaction
+    pub index: i64,
+    /// Value of the spent output in satoshis
+    pub value: i64,
+    /// BIP32 path of the key that owns the output
+    pub owner_key_path: String,
+}
+
+/// Output of a UTXO transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoOutput {
+    /// Destination address
+    pub address: String,
+    /// Amount in satoshis
+    pub value: i64,
+    /// Whether the output returns change to the wallet
+    pub is_change: bool,
+    /// BIP32 path of the change address, when `is_change`
+    pub change_address_path: String,
+}
+
+/// `LtcTx` / `DashTx` / `BchTx` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoTransaction {
+    /// Fee in satoshis
+    pub fee: i64,
+    /// Dust threshold in satoshis
+    pub dust_threshold: i64,
+    /// Memo
+    pub memo: String,
+    /// Spent outputs
+    pub inputs: Vec<UtxoInput>,
+    /// New outputs
+    pub outputs: Vec<UtxoOutput>,
+}
+
+impl UtxoTransaction {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut tx = UtxoTransaction {
+            fee: 0,
+            dust_threshold: 0,
+            memo: String::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        };
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Varint(value)) => tx.fee = value as i64,
+                (2, FieldValue::Varint(value)) => tx.dust_threshold = value as i64,
+                (3, FieldValue::Bytes(value)) => tx.memo = utf8(value, "memo")?,
+                (4, FieldValue::Bytes(value)) => tx.inputs.push(decode_input(value)?),
+                (5, FieldValue::Bytes(value)) => tx.outputs.push(decode_output(value)?),
+                _ => {}
+            }
+        }
+        Ok(tx)
+    }
+
+    /// Total sent to non-change outputs, in satoshis
+    pub fn external_total(&self) -> i64 {
+        self.outputs
+            .iter()
+            .filter(|output| !output.is_change)
+            .map(|output| output.value)
+            .sum()
+    }
+}
+
+fn decode_input(bytes: &[u8]) -> Result<UtxoInput> {
+    let mut input = UtxoInput {
+        hash: String::new(),
+        index: 0,
+        value: 0,
+        owner_key_path: String::new(),
+    };
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(value)) => input.hash = utf8(value, "hash")?,
+            (2, FieldValue::Varint(value)) => input.index = value as i64,
+            (3, FieldValue::Bytes(utxo)) => {
+                for field in fields(utxo)? {
+                    if let (3, FieldValue::Varint(value)) = (field.number, field.value) {
+                        input.value = value as i64;
+                    }
+                }
+            }
+            (4, FieldValue::Bytes(value)) => input.owner_key_path = utf8(value, "ownerKeyPath")?,
+            _ => {}
+        }
+    }
+    Ok(input)
+}
+
+fn decode_output(bytes: &[u8]) -> Result<UtxoOutput> {
+    let mut output = UtxoOutput {
+        address: String::new(),
+        value: 0,
+        is_change: false,
+        change_address_path: String::new(),
+    };
+    for field in fields(bytes)? {
+        match (field.number, field.value) {
+            (1, FieldValue::Bytes(value)) => output.address = utf8(value, "address")?,
+            (2, FieldValue::Varint(value)) => output.value = value as i64,
+            (3, FieldValue::Varint(value)) => output.is_change = value != 0,
+            (4, FieldValue::Bytes(value)) => {
+                output.change_address_path = utf8(value, "changeAddressPath")?
+            }
+            _ => {}
+        }
+    }
+    Ok(output)
+}
+
+/// `SignTransactionResult` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignTransactionResult {
+    /// Identifier echoed from the request
+    pub sign_id: String,
+    /// Transaction ID of the signed transaction
+    pub tx_id: String,
+    /// Signed raw transaction (hex)
+    pub raw_tx: String,
+}
+
+impl SignTransactionResult {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut result = SignTransactionResult {
+            sign_id: String::new(),
+            tx_id: String::new(),
+            raw_tx: String::new(),
+        };
+        for field in fields(bytes)? {
+            match (field.number, field.value) {
+                (1, FieldValue::Bytes(value)) => result.sign_id = utf8(value, "signId")?,
+                (2, FieldValue::Bytes(value)) => result.tx_id = utf8(value, "txId")?,
+                (3, FieldValue::Bytes(value)) => result.raw_tx = utf8(value, "rawTx")?,
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FieldValue<'a> {
+    Varint(u64),
+    Fixed,
+    Bytes(&'a [u8]),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Field<'a> {
+    number: u64,
+    value: FieldValue<'a>,
+}
+
+/// Split a protobuf message into its top-level fields
+fn fields(bytes: &[u8]) -> Result<Vec<Field<'_>>> {
+    let mut pos = 0;
+    let mut fields = Vec::new();
+    while pos < bytes.len() {
+        let key = read_varint(bytes, &mut pos)?;
+        let number = key >> 3;
+        if number == 0 {
+            return Err(protobuf_error("field number 0"));
+        }
+        let value = match key & 7 {
+            WIRE_VARINT => FieldValue::Varint(read_varint(bytes, &mut pos)?),
+            WIRE_FIXED64 => {
+                take(bytes, &mut pos, 8)?;
+                FieldValue::Fixed
+            }
+            WIRE_LEN => {
+                let len = usize::try_from(read_varint(bytes, &mut pos)?)
+                    .map_err(|_| protobuf_error("length overflow"))?;
+                FieldValue::Bytes(take(bytes, &mut pos, len)?)
+            }
+            WIRE_FIXED32 => {
+                take(bytes, &mut pos, 4)?;
+                FieldValue::Fixed
+            }
+            other => return Err(protobuf_error(&format!("unsupported wire type {other}"))),
+        };
+        fields.push(Field { number, value });
+    }
+    Ok(fields)
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or_else(|| protobuf_error("truncated varint"))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(protobuf_error("varint too long"))
+}
+
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let end = pos
+        .checked_add(len)
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| protobuf_error("unexpected end of data"))?;
+    let slice = &bytes[*pos..end];
+    *pos = end;
+    Ok(slice)
+}
+
+fn utf8(bytes: &[u8], field: &str) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| protobuf_error(&format!("{field} is not UTF-8")))
+}
+
+fn protobuf_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Keystone base protobuf: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn len_field(number: u64, bytes: &[u8]) -> Vec<u8> {
+        [
+            varint(number << 3 | WIRE_LEN),
+            varint(bytes.len() as u64),
+            bytes.to_vec(),
+        ]
+        .concat()
+    }
+
+    fn varint_field(number: u64, value: u64) -> Vec<u8> {
+        [varint(number << 3 | WIRE_VARINT), varint(value)].concat()
+    }
+
+    fn tron_base() -> Vec<u8> {
+        let tron = [
+            len_field(1, b"TRX"),
+            len_field(3, b"TXhtYr8nmgiSp3dY3cSfiKBjed3zN8teHS"),
+            len_field(4, b"TKCsXtfKfH2d6aEaQCctybDC9uaA3MSj2h"),
+            len_field(6, b"1000000"),
+            varint_field(9, 100_000),
+        ]
+        .concat();
+        let sign_tx = [
+            len_field(1, b"TRON"),
+            len_field(2, b"4c9a7d1f-1111-4222-8333-944455556666"),
+            len_field(3, b"m/44'/195'/0'/0/0"),
+            varint_field(4, 1_700_000_000_000),
+            varint_field(5, 6),
+            len_field(8, &tron),
+        ]
+        .concat();
+        let payload = [
+            varint_field(1, 2),
+            len_field(2, b"73c5da0a"),
+            len_field(4, &sign_tx),
+        ]
+        .concat();
+        [
+            varint_field(1, 1),
+            len_field(2, b"keystone qrcode"),
+            len_field(3, &payload),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_decode_tron_sign_transaction() {
+        let base = Base::decode(&tron_base()).unwrap();
+
+        assert_eq!(base.version, 1);
+        assert_eq!(base.description, "keystone qrcode");
+        let payload = base.payload.unwrap();
+        assert_eq!(payload.kind, BasePayloadType::SignTransaction);
+        assert_eq!(payload.xfp, "73c5da0a");
+        let BaseContent::SignTransaction(sign_tx) = payload.content else {
+            panic!("expected sign transaction");
+        };
+        assert_eq!(sign_tx.coin_code, "TRON");
+        assert_eq!(sign_tx.hd_path, "m/44'/195'/0'/0/0");
+        assert_eq!(sign_tx.decimal, 6);
+        let BaseTransaction::Tron(tron) = sign_tx.transaction else {
+            panic!("expected tron transaction");
+        };
+        assert_eq!(tron.to, "TKCsXtfKfH2d6aEaQCctybDC9uaA3MSj2h");
+        assert_eq!(tron.value, "1000000");
+        assert_eq!(tron.fee, 100_000);
+        assert!(!tron.is_trc20());
+    }
+
+    #[test]
+    fn test_decode_ltc_transaction_totals() {
+        let utxo = varint_field(3, 50_000);
+        let input = [len_field(1, b"ab"), varint_field(2, 1), len_field(3, &utxo)].concat();
+        let pay = [len_field(1, b"ltc1qdest"), varint_field(2, 30_000)].concat();
+        let change = [
+            len_field(1, b"ltc1qchange"),
+            varint_field(2, 19_000),
+            varint_field(3, 1),
+        ]
+        .concat();
+        let ltc = [
+            varint_field(1, 1_000),
+            len_field(4, &input),
+            len_field(5, &pay),
+            len_field(5, &change),
+        ]
+        .concat();
+        let sign_tx = [len_field(1, b"LTC"), len_field(12, &ltc)].concat();
+
+        let decoded = SignTransaction::decode(&sign_tx).unwrap();
+        let BaseTransaction::Utxo(tx) = decoded.transaction else {
+            panic!("expected utxo transaction");
+        };
+        assert_eq!(tx.fee, 1_000);
+        assert_eq!(tx.inputs[0].value, 50_000);
+        assert!(tx.outputs[1].is_change);
+        assert_eq!(tx.external_total(), 30_000);
+    }
+
+    #[test]
+    fn test_undecoded_transaction_kind_is_reported() {
+        let sign_tx = [len_field(1, b"DOT"), len_field(19, b"\x0a\x00")].concat();
+        let decoded = SignTransaction::decode(&sign_tx).unwrap();
+        assert_eq!(decoded.transaction, BaseTransaction::Other { field: 19 });
+    }
+}
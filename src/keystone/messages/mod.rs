@@ -1,15 +1,58 @@
 //! Keystone message types for different blockchains
 
+pub mod aptos;
+pub mod bitcoin;
+pub mod bytes;
+pub mod cardano;
+pub mod cosmos;
 pub mod crypto_account;
+pub mod crypto_hdkey;
+pub mod crypto_multi_accounts;
+pub mod crypto_output;
 pub mod ethereum;
 pub mod hedera;
+pub mod keystone_base;
+pub mod near;
 pub mod solana;
 pub mod stellar;
+pub mod sui;
 pub mod xrp;
 
+pub use aptos::{
+    AptosEntryFunction, AptosPayload, AptosSignRequest, AptosSignType, AptosSignature,
+    AptosTransaction,
+};
+pub use bitcoin::{CryptoPsbt, InputSignStatus, Psbt, PsbtInput, PsbtOutput, ScriptType};
+pub use bytes::{Bytes, BytesContent, BytesFraming};
+pub use cardano::{CardanoCertKey, CardanoSignRequest, CardanoSignature, CardanoUtxo};
+pub use cosmos::{
+    AminoMsg, AminoSignDoc, CosmosAccount, CosmosCoin, CosmosDataType, CosmosSignRequest,
+    CosmosSignature,
+};
 pub use crypto_account::CryptoAccount;
-pub use ethereum::{EthDataType, EthSignRequest, EthSignature};
-pub use hedera::{HederaSignRequest, HederaSignature};
-pub use solana::{SolanaSignRequest, SolanaSignature};
-pub use stellar::{StellarSignRequest, StellarSignType, StellarSignature};
-pub use xrp::{XrpSignRequest, XrpSignature};
+pub use crypto_hdkey::{CryptoCoinInfo, CryptoHdKey};
+pub use crypto_multi_accounts::CryptoMultiAccounts;
+pub use crypto_output::{CryptoEcKey, CryptoOutput, MultiKey, OutputKey, ScriptExpression};
+pub use ethereum::{
+    ChainIdCheck, EthDataType, EthSignRequest, EthSignature, EthTransaction, EthTxType,
+    OriginCheck, PersonalMessage, SiweMessage, TypedData, TypedDataRisk,
+};
+pub use hedera::{
+    HederaSignRequest, HederaSignature, HederaTransaction, HederaTransactionData, PayerCheck,
+};
+pub use keystone_base::{
+    Base, BaseContent, BasePayload, BasePayloadType, BaseTransaction, KeystoneSignRequest,
+    KeystoneWrapper, SignTransaction, SignTransactionResult, TronTransaction, UtxoTransaction,
+};
+pub use near::{NearSignRequest, NearSignature};
+pub use solana::{
+    SolanaInstruction, SolanaMessage, SolanaMessageVersion, SolanaSignRequest, SolanaSignature,
+};
+pub use stellar::{
+    StellarAsset, StellarMemo, StellarOperation, StellarOperationBody, StellarSignRequest,
+    StellarSignType, StellarSignature, StellarTransaction,
+};
+pub use sui::{SuiIntentMessage, SuiIntentScope, SuiSignRequest, SuiSignature};
+pub use xrp::{
+    XrpAmount, XrpSignRequest, XrpSignature, XrpTransaction, XrpTransactionKind, XrpWarning,
+};
@@ -0,0 +1,382 @@
+//! NEAR signature request and response types
+//!
+//! A single request can carry several borsh-encoded transactions (for example
+//! a storage deposit followed by a transfer); the wallet answers with one
+//! signature per transaction, in order.
+//!
+//! Reference: https://github.com/KeystoneHQ/keystone-sdk-rust
+
+use crate::error::Result;
+use crate::keystone::cbor;
+use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::data::Type;
+use minicbor::{Decoder, Encoder};
+use uuid::Uuid;
+
+/// NEAR signature request
+#[derive(Debug, Clone)]
+pub struct NearSignRequest {
+    /// Optional request identifier
+    pub request_id: Option<Uuid>,
+    /// Borsh-encoded transactions to sign, in order
+    pub sign_data: Vec<Vec<u8>>,
+    /// BIP44 derivation path (typically m/44'/397'/0')
+    pub derivation_path: CryptoKeyPath,
+    /// Optional signer account (named account or implicit account bytes)
+    pub account: Option<Vec<u8>>,
+    /// Optional application origin string
+    pub origin: Option<String>,
+}
+
+impl NearSignRequest {
+    /// Construct a new NEAR sign request
+    pub fn new(
+        sign_data: Vec<Vec<u8>>,
+        derivation_path: CryptoKeyPath,
+        request_id: Option<Uuid>,
+    ) -> Self {
+        Self {
+            request_id,
+            sign_data,
+            derivation_path,
+            account: None,
+            origin: None,
+        }
+    }
+
+    /// Attach the signer account
+    pub fn with_account(mut self, account: Vec<u8>) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    /// Attach an origin descriptor (e.g. dApp name)
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Signer account as text when it is a named account ID
+    pub fn account_id(&self) -> Option<&str> {
+        self.account
+            .as_deref()
+            .and_then(|account| std::str::from_utf8(account).ok())
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 2; // sign_data + derivation_path
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        if self.account.is_some() {
+            len += 1;
+        }
+        if self.origin.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for NearSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::NEAR_SIGN_REQUEST,
+        ))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.array(self.sign_data.len() as u64)?;
+        for transaction in &self.sign_data {
+            e.bytes(transaction)?;
+        }
+
+        e.u8(3)?;
+        self.derivation_path.encode(e, ctx)?;
+
+        if let Some(ref account) = self.account {
+            e.u8(4)?;
+            e.bytes(account)?;
+        }
+
+        if let Some(ref origin) = self.origin {
+            e.u8(5)?;
+            e.str(origin)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for NearSignRequest {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Device output leaves the top-level map untagged
+        if d.datatype()? == Type::Tag {
+            let tag = d.tag()?;
+            if tag != minicbor::data::Tag::Unassigned(cbor::tags::NEAR_SIGN_REQUEST) {
+                return Err(minicbor::decode::Error::message(
+                    "expected near-sign-request tag",
+                ));
+            }
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut sign_data = None;
+        let mut derivation_path = None;
+        let mut account = None;
+        let mut origin = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => sign_data = Some(decode_bytes_array(d)?),
+                3 => derivation_path = Some(CryptoKeyPath::decode(d, ctx)?),
+                4 => account = Some(d.bytes()?.to_vec()),
+                5 => origin = Some(d.str()?.to_string()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            sign_data: sign_data
+                .ok_or_else(|| minicbor::decode::Error::message("missing sign_data"))?,
+            derivation_path: derivation_path
+                .ok_or_else(|| minicbor::decode::Error::message("missing derivation_path"))?,
+            account,
+            origin,
+        })
+    }
+}
+
+/// NEAR signature response
+#[derive(Debug, Clone)]
+pub struct NearSignature {
+    /// Request identifier echo
+    pub request_id: Option<Uuid>,
+    /// Ed25519 signatures, one per transaction in the request
+    pub signatures: Vec<Vec<u8>>,
+}
+
+impl NearSignature {
+    /// Create a new signature container
+    pub fn new(signatures: Vec<Vec<u8>>, request_id: Option<Uuid>) -> Self {
+        Self {
+            request_id,
+            signatures,
+        }
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 1; // signatures
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for NearSignature {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(cbor::tags::NEAR_SIGNATURE))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.array(self.signatures.len() as u64)?;
+        for signature in &self.signatures {
+            e.bytes(signature)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for NearSignature {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        // Device output leaves the top-level map untagged
+        if d.datatype()? == Type::Tag {
+            let tag = d.tag()?;
+            if tag != minicbor::data::Tag::Unassigned(cbor::tags::NEAR_SIGNATURE) {
+                return Err(minicbor::decode::Error::message(
+                    "expected near-signature tag",
+                ));
+            }
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut signatures = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => signatures = Some(decode_bytes_array(d)?),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            signatures: signatures
+                .ok_or_else(|| minicbor::decode::Error::message("missing signature"))?,
+        })
+    }
+}
+
+fn decode_bytes_array(
+    d: &mut Decoder<'_>,
+) -> std::result::Result<Vec<Vec<u8>>, minicbor::decode::Error> {
+    let len = d
+        .array()?
+        .ok_or_else(|| minicbor::decode::Error::message("expected definite-length array"))?;
+    (0..len).map(|_| d.bytes().map(<[u8]>::to_vec)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_sign_request_roundtrip() {
+        let path = CryptoKeyPath::from_str("m/44'/397'/0'").unwrap();
+        let request_id = Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap());
+        let transactions = vec![vec![0x01, 0x02], vec![0x03]];
+        let request = NearSignRequest::new(transactions.clone(), path.clone(), request_id)
+            .with_account(b"alice.near".to_vec())
+            .with_origin("near wallet".to_string());
+
+        let bytes = request.to_cbor().unwrap();
+        let decoded = NearSignRequest::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.request_id, request_id);
+        assert_eq!(decoded.sign_data, transactions);
+        assert_eq!(decoded.derivation_path, path);
+        assert_eq!(decoded.account_id(), Some("alice.near"));
+        assert_eq!(decoded.origin.as_deref(), Some("near wallet"));
+    }
+
+    /// Device wire layout: untagged top-level map, key path as index/hardened
+    /// pairs with a uint32 source fingerprint, one borsh transfer of 1 NEAR
+    #[test]
+    fn test_decode_device_layout() {
+        let bytes = hex::decode(concat!(
+            "a401d825509b1deb4d3b7d4bad9bdd2b0d7b3dcb6d028158780a000000616c69",
+            "63652e6e656172005a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a",
+            "5a5a5a5a5a5a5a5a010000000000000008000000626f622e6e65617211111111",
+            "1111111111111111111111111111111111111111111111111111111101000000",
+            "03000000a1edccce1bc2d300000000000003d90130a20186182cf519018df5",
+            "00f5021a73c5da0a056a6e65617277616c6c6574",
+        ))
+        .unwrap();
+
+        let request = NearSignRequest::from_cbor(&bytes).unwrap();
+        assert_eq!(
+            request.request_id,
+            Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap())
+        );
+        assert_eq!(request.sign_data.len(), 1);
+        assert_eq!(request.sign_data[0].len(), 120);
+        assert!(request.sign_data[0][4..14].starts_with(b"alice.near"));
+        assert_eq!(request.derivation_path.to_string(), "m/44'/397'/0'");
+        assert_eq!(
+            request.derivation_path.source_fingerprint,
+            Some([0x73, 0xc5, 0xda, 0x0a])
+        );
+        assert_eq!(request.origin.as_deref(), Some("nearwallet"));
+
+        let signature = NearSignature::from_cbor(
+            &hex::decode(concat!(
+                "a201d825509b1deb4d3b7d4bad9bdd2b0d7b3dcb6d028158407e7e7e7e7e7e7e",
+                "7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e",
+                "7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e7e",
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(signature.request_id, request.request_id);
+        assert_eq!(signature.signatures, vec![vec![0x7e; 64]]);
+    }
+
+    #[test]
+    fn test_near_signature_roundtrip() {
+        let sig = NearSignature::new(vec![vec![0u8; 64], vec![1u8; 64]], None);
+
+        let bytes = sig.to_cbor().unwrap();
+        let decoded = NearSignature::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.signatures, sig.signatures);
+    }
+}
@@ -0,0 +1,690 @@
+//! Solana transaction message decoding for review before signing
+//!
+//! Parses legacy and v0 messages (header, account keys, recent blockhash,
+//! compiled instructions and address-table lookups) and decodes instructions
+//! of well-known programs.
+//!
+//! Reference: https://solana.com/docs/core/transactions
+
+use crate::error::{Error, Result};
+
+/// System program
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+/// SPL Token program
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// SPL Token-2022 program
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+/// Compute Budget program
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+/// Associated Token Account program
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Message format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolanaMessageVersion {
+    /// Original unversioned format
+    Legacy,
+    /// Versioned format with address-table lookups
+    V0,
+}
+
+impl SolanaMessageVersion {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolanaMessageVersion::Legacy => "legacy",
+            SolanaMessageVersion::V0 => "v0",
+        }
+    }
+}
+
+/// Message header describing signer and writable account ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolanaMessageHeader {
+    /// Number of signatures required (the first N account keys)
+    pub num_required_signatures: u8,
+    /// Number of signer accounts that are read-only
+    pub num_readonly_signed_accounts: u8,
+    /// Number of non-signer accounts that are read-only
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+/// Instruction with account references as indexes into the message accounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledInstruction {
+    /// Index of the program account
+    pub program_id_index: u8,
+    /// Indexes of the instruction's accounts
+    pub accounts: Vec<u8>,
+    /// Opaque instruction data
+    pub data: Vec<u8>,
+}
+
+/// Accounts loaded from an on-chain address lookup table (v0 only)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressTableLookup {
+    /// Lookup table address
+    pub account_key: [u8; 32],
+    /// Table indexes loaded as writable
+    pub writable_indexes: Vec<u8>,
+    /// Table indexes loaded as read-only
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// Decoded Solana transaction message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolanaMessage {
+    /// Message format
+    pub version: SolanaMessageVersion,
+    /// Signer / writable ranges
+    pub header: SolanaMessageHeader,
+    /// Static account keys
+    pub account_keys: Vec<[u8; 32]>,
+    /// Recent blockhash
+    pub recent_blockhash: [u8; 32],
+    /// Compiled instructions
+    pub instructions: Vec<CompiledInstruction>,
+    /// Address-table lookups (empty for legacy messages)
+    pub address_table_lookups: Vec<AddressTableLookup>,
+}
+
+/// Instruction of a well-known program, with accounts resolved to addresses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolanaInstruction {
+    /// System program SOL transfer
+    SystemTransfer {
+        /// Funding account
+        from: String,
+        /// Recipient account
+        to: String,
+        /// Amount in lamports
+        lamports: u64,
+    },
+    /// SPL Token `Transfer` / `TransferChecked`
+    TokenTransfer {
+        /// Source token account
+        source: String,
+        /// Destination token account
+        destination: String,
+        /// Owner or delegate authorising the transfer
+        authority: String,
+        /// Token mint (`TransferChecked` only)
+        mint: Option<String>,
+        /// Amount in base units
+        amount: u64,
+        /// Mint decimals (`TransferChecked` only)
+        decimals: Option<u8>,
+    },
+    /// SPL Token `Approve` / `ApproveChecked`
+    TokenApprove {
+        /// Token account granting the allowance
+        source: String,
+        /// Delegate receiving the allowance
+        delegate: String,
+        /// Owner of the source account
+        owner: String,
+        /// Token mint (`ApproveChecked` only)
+        mint: Option<String>,
+        /// Allowance in base units
+        amount: u64,
+        /// Mint decimals (`ApproveChecked` only)
+        decimals: Option<u8>,
+    },
+    /// Compute Budget `SetComputeUnitLimit`
+    SetComputeUnitLimit {
+        /// Compute unit limit
+        units: u32,
+    },
+    /// Compute Budget `SetComputeUnitPrice`
+    SetComputeUnitPrice {
+        /// Priority fee in micro-lamports per compute unit
+        micro_lamports: u64,
+    },
+    /// Compute Budget `RequestHeapFrame`
+    RequestHeapFrame {
+        /// Heap size in bytes
+        bytes: u32,
+    },
+    /// Associated Token Account `Create` / `CreateIdempotent`
+    CreateAssociatedTokenAccount {
+        /// Account paying for the new token account
+        payer: String,
+        /// Associated token account being created
+        account: String,
+        /// Wallet that will own the token account
+        wallet: String,
+        /// Token mint
+        mint: String,
+        /// Whether the instruction succeeds if the account already exists
+        idempotent: bool,
+    },
+    /// Instruction of an unrecognised program (or unrecognised opcode)
+    Unknown {
+        /// Program address
+        program: String,
+        /// Instruction accounts
+        accounts: Vec<String>,
+        /// Raw instruction data
+        data: Vec<u8>,
+    },
+}
+
+impl SolanaInstruction {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolanaInstruction::SystemTransfer { .. } => "system_transfer",
+            SolanaInstruction::TokenTransfer { .. } => "token_transfer",
+            SolanaInstruction::TokenApprove { .. } => "token_approve",
+            SolanaInstruction::SetComputeUnitLimit { .. } => "set_compute_unit_limit",
+            SolanaInstruction::SetComputeUnitPrice { .. } => "set_compute_unit_price",
+            SolanaInstruction::RequestHeapFrame { .. } => "request_heap_frame",
+            SolanaInstruction::CreateAssociatedTokenAccount { .. } => {
+                "create_associated_token_account"
+            }
+            SolanaInstruction::Unknown { .. } => "unknown",
+        }
+    }
+
+    /// Account whose funds this instruction moves or exposes, if any
+    pub fn funds_source(&self) -> Option<&str> {
+        match self {
+            SolanaInstruction::SystemTransfer { from, .. } => Some(from),
+            SolanaInstruction::TokenTransfer { source, .. }
+            | SolanaInstruction::TokenApprove { source, .. } => Some(source),
+            SolanaInstruction::CreateAssociatedTokenAccount { payer, .. } => Some(payer),
+            _ => None,
+        }
+    }
+}
+
+impl SolanaMessage {
+    /// Parse serialized message bytes
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+
+        let first = reader.u8()?;
+        let (version, num_required_signatures) = if first & 0x80 != 0 {
+            match first & 0x7f {
+                0 => (SolanaMessageVersion::V0, reader.u8()?),
+                other => return Err(message_error(&format!("unsupported version {other}"))),
+            }
+        } else {
+            (SolanaMessageVersion::Legacy, first)
+        };
+        let header = SolanaMessageHeader {
+            num_required_signatures,
+            num_readonly_signed_accounts: reader.u8()?,
+            num_readonly_unsigned_accounts: reader.u8()?,
+        };
+
+        let key_count = reader.short_vec_len()?;
+        // The count is attacker-controlled; never reserve more keys than the
+        // remaining bytes could hold
+        let mut account_keys = Vec::with_capacity(key_count.min(reader.remaining() / 32));
+        for _ in 0..key_count {
+            account_keys.push(reader.array32()?);
+        }
+        let recent_blockhash = reader.array32()?;
+
+        let instruction_count = reader.short_vec_len()?;
+        // Each instruction takes at least three bytes
+        let mut instructions = Vec::with_capacity(instruction_count.min(reader.remaining() / 3));
+        for _ in 0..instruction_count {
+            let program_id_index = reader.u8()?;
+            let accounts = reader.short_vec_bytes()?;
+            let data = reader.short_vec_bytes()?;
+            instructions.push(CompiledInstruction {
+                program_id_index,
+                accounts,
+                data,
+            });
+        }
+
+        let mut address_table_lookups = Vec::new();
+        if version == SolanaMessageVersion::V0 {
+            let lookup_count = reader.short_vec_len()?;
+            for _ in 0..lookup_count {
+                address_table_lookups.push(AddressTableLookup {
+                    account_key: reader.array32()?,
+                    writable_indexes: reader.short_vec_bytes()?,
+                    readonly_indexes: reader.short_vec_bytes()?,
+                });
+            }
+        }
+
+        if reader.pos != bytes.len() {
+            return Err(message_error(&format!(
+                "{} trailing bytes",
+                bytes.len() - reader.pos
+            )));
+        }
+        if usize::from(header.num_required_signatures) > account_keys.len() {
+            return Err(message_error("more signers than account keys"));
+        }
+
+        let message = Self {
+            version,
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+            address_table_lookups,
+        };
+        for instruction in &message.instructions {
+            let max_index = instruction
+                .accounts
+                .iter()
+                .chain(std::iter::once(&instruction.program_id_index))
+                .max()
+                .copied()
+                .unwrap_or_default();
+            if usize::from(max_index) >= message.total_accounts() {
+                return Err(message_error(&format!(
+                    "instruction references account {max_index} of {}",
+                    message.total_accounts()
+                )));
+            }
+        }
+        Ok(message)
+    }
+
+    /// Static keys plus accounts loaded from lookup tables
+    pub fn total_accounts(&self) -> usize {
+        self.account_keys.len()
+            + self
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum::<usize>()
+    }
+
+    /// Fee payer (first signer)
+    pub fn fee_payer(&self) -> Option<String> {
+        self.account_keys
+            .first()
+            .map(|key| bs58::encode(key).into_string())
+    }
+
+    /// Whether the account at `index` must sign
+    pub fn is_signer(&self, index: usize) -> bool {
+        index < usize::from(self.header.num_required_signatures)
+    }
+
+    /// Whether the account at `index` is writable
+    pub fn is_writable(&self, index: usize) -> bool {
+        let signers = usize::from(self.header.num_required_signatures);
+        let static_keys = self.account_keys.len();
+        if index < signers {
+            return index < signers - usize::from(self.header.num_readonly_signed_accounts);
+        }
+        if index < static_keys {
+            return index < static_keys - usize::from(self.header.num_readonly_unsigned_accounts);
+        }
+        // Loaded accounts: all writable lookups come before all read-only lookups
+        let writable_loaded: usize = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len())
+            .sum();
+        index < static_keys + writable_loaded
+    }
+
+    /// Address of the account at `index`; loaded accounts render as `table[index]`
+    pub fn account_address(&self, index: usize) -> String {
+        if let Some(key) = self.account_keys.get(index) {
+            return bs58::encode(key).into_string();
+        }
+
+        let mut offset = index - self.account_keys.len().min(index);
+        let loaded = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| (lookup, &lookup.writable_indexes))
+            .chain(
+                self.address_table_lookups
+                    .iter()
+                    .map(|lookup| (lookup, &lookup.readonly_indexes)),
+            );
+        for (lookup, indexes) in loaded {
+            if let Some(table_index) = indexes.get(offset) {
+                return format!(
+                    "{}[{}]",
+                    bs58::encode(lookup.account_key).into_string(),
+                    table_index
+                );
+            }
+            offset -= indexes.len();
+        }
+        format!("#{index}")
+    }
+
+    /// Decode every instruction, resolving account indexes to addresses
+    pub fn decoded_instructions(&self) -> Vec<SolanaInstruction> {
+        self.instructions
+            .iter()
+            .map(|instruction| self.decode_instruction(instruction))
+            .collect()
+    }
+
+    /// Sum of System program SOL transfers, in lamports
+    pub fn total_sol_transferred(&self) -> u64 {
+        self.decoded_instructions()
+            .iter()
+            .map(|instruction| match instruction {
+                SolanaInstruction::SystemTransfer { lamports, .. } => *lamports,
+                _ => 0,
+            })
+            .fold(0u64, u64::saturating_add)
+    }
+
+    fn decode_instruction(&self, instruction: &CompiledInstruction) -> SolanaInstruction {
+        let program = self.account_address(usize::from(instruction.program_id_index));
+        let accounts: Vec<String> = instruction
+            .accounts
+            .iter()
+            .map(|index| self.account_address(usize::from(*index)))
+            .collect();
+        let data = instruction.data.as_slice();
+        let account = |index: usize| accounts.get(index).cloned();
+
+        let decoded = match program.as_str() {
+            SYSTEM_PROGRAM_ID => match (read_u32(data, 0), read_u64(data, 4)) {
+                (Some(2), Some(lamports)) => Some(SolanaInstruction::SystemTransfer {
+                    from: account(0).unwrap_or_default(),
+                    to: account(1).unwrap_or_default(),
+                    lamports,
+                }),
+                _ => None,
+            },
+            TOKEN_PROGRAM_ID | TOKEN_2022_PROGRAM_ID if accounts.len() >= 3 => {
+                match (data.first(), read_u64(data, 1)) {
+                    (Some(3), Some(amount)) => Some(SolanaInstruction::TokenTransfer {
+                        source: accounts[0].clone(),
+                        destination: accounts[1].clone(),
+                        authority: accounts[2].clone(),
+                        mint: None,
+                        amount,
+                        decimals: None,
+                    }),
+                    (Some(4), Some(amount)) => Some(SolanaInstruction::TokenApprove {
+                        source: accounts[0].clone(),
+                        delegate: accounts[1].clone(),
+                        owner: accounts[2].clone(),
+                        mint: None,
+                        amount,
+                        decimals: None,
+                    }),
+                    (Some(12), Some(amount)) if accounts.len() >= 4 => {
+                        Some(SolanaInstruction::TokenTransfer {
+                            source: accounts[0].clone(),
+                            destination: accounts[2].clone(),
+                            authority: accounts[3].clone(),
+                            mint: Some(accounts[1].clone()),
+                            amount,
+                            decimals: data.get(9).copied(),
+                        })
+                    }
+                    (Some(13), Some(amount)) if accounts.len() >= 4 => {
+                        Some(SolanaInstruction::TokenApprove {
+                            source: accounts[0].clone(),
+                            delegate: accounts[2].clone(),
+                            owner: accounts[3].clone(),
+                            mint: Some(accounts[1].clone()),
+                            amount,
+                            decimals: data.get(9).copied(),
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            COMPUTE_BUDGET_PROGRAM_ID => match data.first() {
+                Some(1) => {
+                    read_u32(data, 1).map(|bytes| SolanaInstruction::RequestHeapFrame { bytes })
+                }
+                Some(2) => {
+                    read_u32(data, 1).map(|units| SolanaInstruction::SetComputeUnitLimit { units })
+                }
+                Some(3) => read_u64(data, 1).map(|micro_lamports| {
+                    SolanaInstruction::SetComputeUnitPrice { micro_lamports }
+                }),
+                _ => None,
+            },
+            ASSOCIATED_TOKEN_PROGRAM_ID if accounts.len() >= 4 => {
+                let idempotent = match data.first() {
+                    None | Some(0) => Some(false),
+                    Some(1) => Some(true),
+                    _ => None,
+                };
+                idempotent.map(
+                    |idempotent| SolanaInstruction::CreateAssociatedTokenAccount {
+                        payer: accounts[0].clone(),
+                        account: accounts[1].clone(),
+                        wallet: accounts[2].clone(),
+                        mint: accounts[3].clone(),
+                        idempotent,
+                    },
+                )
+            }
+            _ => None,
+        };
+
+        decoded.unwrap_or_else(|| SolanaInstruction::Unknown {
+            program,
+            accounts,
+            data: instruction.data.clone(),
+        })
+    }
+}
+
+/// Format lamports as SOL without floating point (e.g. `0.5 SOL`)
+pub fn format_lamports_as_sol(lamports: u64) -> String {
+    let whole = lamports / LAMPORTS_PER_SOL;
+    let fraction = lamports % LAMPORTS_PER_SOL;
+    if fraction == 0 {
+        return format!("{whole} SOL");
+    }
+    let fraction = format!("{fraction:09}");
+    format!("{whole}.{} SOL", fraction.trim_end_matches('0'))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| message_error("unexpected end of message"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array32(&mut self) -> Result<[u8; 32]> {
+        let mut out = [0u8; 32];
+        out.copy_from_slice(self.take(32)?);
+        Ok(out)
+    }
+
+    /// Compact-u16 ("shortvec") length prefix
+    fn short_vec_len(&mut self) -> Result<usize> {
+        let mut value = 0usize;
+        for shift in [0, 7, 14] {
+            let byte = self.u8()?;
+            value |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return if value > usize::from(u16::MAX) {
+                    Err(message_error("compact-u16 length out of range"))
+                } else {
+                    Ok(value)
+                };
+            }
+        }
+        Err(message_error("invalid compact-u16 length"))
+    }
+
+    fn short_vec_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.short_vec_len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn message_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Solana message: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> [u8; 32] {
+        bs58::decode(text).into_vec().unwrap().try_into().unwrap()
+    }
+
+    /// Legacy message: compute price + SOL transfer from the fee payer
+    fn legacy_transfer() -> Vec<u8> {
+        let payer = [0x11u8; 32];
+        let recipient = [0x22u8; 32];
+        let mut bytes = vec![1, 0, 2, 4];
+        bytes.extend_from_slice(&payer);
+        bytes.extend_from_slice(&recipient);
+        bytes.extend_from_slice(&key(SYSTEM_PROGRAM_ID));
+        bytes.extend_from_slice(&key(COMPUTE_BUDGET_PROGRAM_ID));
+        bytes.extend_from_slice(&[0x33; 32]); // blockhash
+        bytes.push(2); // instructions
+        // ComputeBudget SetComputeUnitPrice(5000)
+        bytes.extend_from_slice(&[3, 0, 9, 3]);
+        bytes.extend_from_slice(&5000u64.to_le_bytes());
+        // System Transfer(0.5 SOL)
+        bytes.extend_from_slice(&[2, 2, 0, 1, 12]);
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&500_000_000u64.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_parse_legacy_transfer() {
+        let message = SolanaMessage::parse(&legacy_transfer()).unwrap();
+        assert_eq!(message.version, SolanaMessageVersion::Legacy);
+        assert_eq!(message.account_keys.len(), 4);
+        assert!(message.is_signer(0) && message.is_writable(0));
+        assert!(message.is_writable(1) && !message.is_signer(1));
+        assert!(!message.is_writable(2));
+
+        let instructions = message.decoded_instructions();
+        assert_eq!(
+            instructions[0],
+            SolanaInstruction::SetComputeUnitPrice {
+                micro_lamports: 5000
+            }
+        );
+        match &instructions[1] {
+            SolanaInstruction::SystemTransfer { from, to, lamports } => {
+                assert_eq!(Some(from.clone()), message.fee_payer());
+                assert_eq!(to, &bs58::encode([0x22u8; 32]).into_string());
+                assert_eq!(*lamports, 500_000_000);
+            }
+            other => panic!("unexpected instruction {other:?}"),
+        }
+        assert_eq!(
+            format_lamports_as_sol(message.total_sol_transferred()),
+            "0.5 SOL"
+        );
+    }
+
+    #[test]
+    fn test_parse_v0_token_transfer_with_lookup() {
+        let owner = [0x44u8; 32];
+        let source = [0x55u8; 32];
+        let table = [0x66u8; 32];
+        let mut bytes = vec![0x80, 1, 0, 1, 3];
+        bytes.extend_from_slice(&owner);
+        bytes.extend_from_slice(&source);
+        bytes.extend_from_slice(&key(TOKEN_PROGRAM_ID));
+        bytes.extend_from_slice(&[0x77; 32]); // blockhash
+        bytes.push(1);
+        // TransferChecked: source=1, mint=4 (loaded ro), dest=3 (loaded rw), owner=0
+        bytes.extend_from_slice(&[2, 4, 1, 4, 3, 0, 10, 12]);
+        bytes.extend_from_slice(&1_500_000u64.to_le_bytes());
+        bytes.push(6);
+        // one lookup: writable [7], read-only [9]
+        bytes.push(1);
+        bytes.extend_from_slice(&table);
+        bytes.extend_from_slice(&[1, 7, 1, 9]);
+
+        let message = SolanaMessage::parse(&bytes).unwrap();
+        assert_eq!(message.version, SolanaMessageVersion::V0);
+        assert_eq!(message.total_accounts(), 5);
+        assert!(message.is_writable(3));
+        assert!(!message.is_writable(4));
+
+        let table_address = bs58::encode(table).into_string();
+        match &message.decoded_instructions()[0] {
+            SolanaInstruction::TokenTransfer {
+                source: from,
+                destination,
+                mint,
+                amount,
+                decimals,
+                ..
+            } => {
+                assert_eq!(from, &bs58::encode(source).into_string());
+                assert_eq!(destination, &format!("{table_address}[7]"));
+                assert_eq!(
+                    mint.as_deref(),
+                    Some(format!("{table_address}[9]").as_str())
+                );
+                assert_eq!(*amount, 1_500_000);
+                assert_eq!(*decimals, Some(6));
+            }
+            other => panic!("unexpected instruction {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_out_of_range_messages() {
+        let bytes = legacy_transfer();
+        assert!(SolanaMessage::parse(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut bad_index = bytes.clone();
+        let program_index = bad_index.len() - 17;
+        bad_index[program_index] = 9;
+        assert!(SolanaMessage::parse(&bad_index).is_err());
+    }
+
+    #[test]
+    fn test_rejects_compact_u16_lengths_past_u16_max() {
+        // Header, then an account key count of 2^21 - 1
+        let err = SolanaMessage::parse(&[1, 0, 0, 0xff, 0xff, 0x7f]).unwrap_err();
+        assert!(err.to_string().contains("compact-u16 length out of range"));
+
+        // u16::MAX keys is a valid count but the message holds none of them
+        let err = SolanaMessage::parse(&[1, 0, 0, 0xff, 0xff, 0x03]).unwrap_err();
+        assert!(err.to_string().contains("unexpected end of message"));
+    }
+}
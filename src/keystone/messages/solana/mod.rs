@@ -2,6 +2,10 @@
 //!
 //! Reference: https://github.com/KeystoneHQ/keystone-sdk-rust
 
+pub mod message;
+
+pub use message::{SolanaInstruction, SolanaMessage, SolanaMessageVersion};
+
 use crate::error::Result;
 use crate::keystone::cbor;
 use crate::keystone::crypto_keypath::CryptoKeyPath;
@@ -42,6 +46,11 @@ impl SolanaSignRequest {
         self
     }
 
+    /// Parse `transaction` as a legacy or v0 message
+    pub fn decode_message(&self) -> Result<SolanaMessage> {
+        SolanaMessage::parse(&self.transaction)
+    }
+
     fn map_len(&self) -> u64 {
         let mut len = 2; // transaction + derivation_path
         if self.request_id.is_some() {
@@ -2,6 +2,13 @@
 //!
 //! Reference: https://github.com/KeystoneHQ/keystone-sdk-rust
 
+pub mod strkey;
+pub mod xdr;
+
+pub use xdr::{
+    StellarAsset, StellarMemo, StellarOperation, StellarOperationBody, StellarTransaction,
+};
+
 use crate::error::{Error, Result};
 use crate::keystone::cbor;
 use crate::keystone::crypto_keypath::CryptoKeyPath;
@@ -127,6 +134,16 @@ impl StellarSignRequest {
         self
     }
 
+    /// Decode `sign_data` as an XDR transaction (only for `Transaction` requests)
+    pub fn decode_transaction(&self) -> Result<StellarTransaction> {
+        if self.sign_type != StellarSignType::Transaction {
+            return Err(Error::InvalidKeystonePayload(
+                "Stellar sign data is not a transaction".to_string(),
+            ));
+        }
+        StellarTransaction::decode(&self.sign_data)
+    }
+
     /// Encode to CBOR bytes
     pub fn to_cbor(&self) -> Result<Vec<u8>> {
         cbor::to_bytes(self)
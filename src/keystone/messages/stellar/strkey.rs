@@ -0,0 +1,105 @@
+//! Stellar "strkey" address encoding (base32 with version byte and CRC16)
+//!
+//! Reference: https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0023.md
+
+/// Account ID (`G...`)
+pub const VERSION_ACCOUNT_ID: u8 = 6 << 3;
+/// Muxed account (`M...`)
+pub const VERSION_MUXED_ACCOUNT: u8 = 12 << 3;
+/// Pre-authorized transaction hash (`T...`)
+pub const VERSION_PRE_AUTH_TX: u8 = 19 << 3;
+/// SHA-256 hash-x signer (`X...`)
+pub const VERSION_SHA256_HASH: u8 = 23 << 3;
+/// Signed-payload signer (`P...`)
+pub const VERSION_SIGNED_PAYLOAD: u8 = 15 << 3;
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode a payload with the given version byte
+pub fn encode(version: u8, payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + 3);
+    data.push(version);
+    data.extend_from_slice(payload);
+    let checksum = crc16_xmodem(&data);
+    data.extend_from_slice(&checksum.to_le_bytes());
+
+    let mut output = String::with_capacity(data.len() * 8 / 5 + 1);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+/// Encode a raw ed25519 public key as a `G...` account ID
+pub fn encode_account_id(key: &[u8; 32]) -> String {
+    encode(VERSION_ACCOUNT_ID, key)
+}
+
+/// Decode a strkey, returning its version byte and payload if the checksum matches
+pub fn decode(text: &str) -> Option<(u8, Vec<u8>)> {
+    let mut data = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for ch in text.bytes() {
+        let value = ALPHABET.iter().position(|c| *c == ch)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+
+    if data.len() < 3 {
+        return None;
+    }
+    let (body, checksum) = data.split_at(data.len() - 2);
+    if crc16_xmodem(body).to_le_bytes() != checksum {
+        return None;
+    }
+    Some((body[0], body[1..].to_vec()))
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_id_round_trip() {
+        let address = encode_account_id(&[0u8; 32]);
+        assert_eq!(
+            address,
+            "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"
+        );
+        assert_eq!(decode(&address), Some((VERSION_ACCOUNT_ID, vec![0u8; 32])));
+
+        let mut corrupted = address.into_bytes();
+        corrupted[10] = b'B';
+        assert_eq!(decode(std::str::from_utf8(&corrupted).unwrap()), None);
+    }
+}
@@ -0,0 +1,914 @@
+//! Stellar XDR transaction decoding for review before signing
+//!
+//! Accepts either a `TransactionSignaturePayload` (network ID followed by the
+//! tagged transaction, which is what the wallet hashes and signs) or a
+//! `TransactionEnvelope` (v0, v1 or fee bump). Classic operations are
+//! decoded; Soroban resource data is detected but not decoded.
+//!
+//! Reference: https://github.com/stellar/stellar-xdr
+
+use crate::error::{Error, Result};
+use sha2::{Digest, Sha256};
+
+use super::strkey;
+
+const ENVELOPE_TYPE_TX_V0: u32 = 0;
+const ENVELOPE_TYPE_TX: u32 = 2;
+const ENVELOPE_TYPE_TX_FEE_BUMP: u32 = 5;
+
+const PUBLIC_NETWORK_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+const STROOPS_PER_LUMEN: i64 = 10_000_000;
+
+/// Network the transaction is bound to (signature payloads only)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarNetwork {
+    /// Stellar public network
+    Public,
+    /// SDF test network
+    Testnet,
+    /// Any other network, by network ID
+    Other([u8; 32]),
+}
+
+impl StellarNetwork {
+    fn from_id(id: [u8; 32]) -> Self {
+        if id == network_id(PUBLIC_NETWORK_PASSPHRASE) {
+            StellarNetwork::Public
+        } else if id == network_id(TESTNET_PASSPHRASE) {
+            StellarNetwork::Testnet
+        } else {
+            StellarNetwork::Other(id)
+        }
+    }
+
+    /// Short label used in output rendering
+    pub fn label(&self) -> String {
+        match self {
+            StellarNetwork::Public => "public".to_string(),
+            StellarNetwork::Testnet => "testnet".to_string(),
+            StellarNetwork::Other(id) => hex::encode(id),
+        }
+    }
+}
+
+/// Asset reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarAsset {
+    /// Lumens
+    Native,
+    /// Issued asset
+    Credit {
+        /// Asset code (1-12 characters)
+        code: String,
+        /// Issuer account ID
+        issuer: String,
+    },
+    /// Liquidity pool share (trustlines only)
+    PoolShare {
+        /// First pool asset
+        asset_a: Box<StellarAsset>,
+        /// Second pool asset
+        asset_b: Box<StellarAsset>,
+        /// Pool fee in basis points
+        fee: i32,
+    },
+}
+
+impl StellarAsset {
+    /// Display form: `XLM`, `CODE:ISSUER` or `pool(A/B)`
+    pub fn display(&self) -> String {
+        match self {
+            StellarAsset::Native => "XLM".to_string(),
+            StellarAsset::Credit { code, issuer } => format!("{code}:{issuer}"),
+            StellarAsset::PoolShare {
+                asset_a, asset_b, ..
+            } => format!("pool({}/{})", asset_a.display(), asset_b.display()),
+        }
+    }
+}
+
+/// Offer price as a fraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StellarPrice {
+    /// Numerator
+    pub n: i32,
+    /// Denominator
+    pub d: i32,
+}
+
+/// Transaction memo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarMemo {
+    /// No memo
+    None,
+    /// Text memo (up to 28 bytes)
+    Text(String),
+    /// Numeric memo
+    Id(u64),
+    /// Hash memo
+    Hash([u8; 32]),
+    /// Return-hash memo
+    Return([u8; 32]),
+}
+
+/// Validity window in UNIX seconds (0 = unbounded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StellarTimeBounds {
+    /// Earliest valid close time
+    pub min_time: u64,
+    /// Latest valid close time
+    pub max_time: u64,
+}
+
+/// Decoded operation body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarOperationBody {
+    /// Create and fund a new account
+    CreateAccount {
+        /// New account
+        destination: String,
+        /// Initial balance in stroops
+        starting_balance: i64,
+    },
+    /// Send an asset
+    Payment {
+        /// Recipient
+        destination: String,
+        /// Asset sent
+        asset: StellarAsset,
+        /// Amount in stroops
+        amount: i64,
+    },
+    /// Path payment fixing the received amount
+    PathPaymentStrictReceive {
+        /// Asset debited
+        send_asset: StellarAsset,
+        /// Maximum debited, in stroops
+        send_max: i64,
+        /// Recipient
+        destination: String,
+        /// Asset credited
+        dest_asset: StellarAsset,
+        /// Amount credited, in stroops
+        dest_amount: i64,
+        /// Intermediate assets
+        path: Vec<StellarAsset>,
+    },
+    /// Path payment fixing the sent amount
+    PathPaymentStrictSend {
+        /// Asset debited
+        send_asset: StellarAsset,
+        /// Amount debited, in stroops
+        send_amount: i64,
+        /// Recipient
+        destination: String,
+        /// Asset credited
+        dest_asset: StellarAsset,
+        /// Minimum credited, in stroops
+        dest_min: i64,
+        /// Intermediate assets
+        path: Vec<StellarAsset>,
+    },
+    /// Create, update or delete an offer (`ManageSellOffer`, `ManageBuyOffer`, `CreatePassiveSellOffer`)
+    ManageOffer {
+        /// Which offer operation this is
+        kind: StellarOfferKind,
+        /// Asset sold
+        selling: StellarAsset,
+        /// Asset bought
+        buying: StellarAsset,
+        /// Amount in stroops (sold for sell offers, bought for buy offers)
+        amount: i64,
+        /// Price of one unit
+        price: StellarPrice,
+        /// Offer ID (0 creates a new offer; absent for passive offers)
+        offer_id: Option<i64>,
+    },
+    /// Change account settings
+    SetOptions {
+        /// New inflation destination
+        inflation_dest: Option<String>,
+        /// Flags to clear
+        clear_flags: Option<u32>,
+        /// Flags to set
+        set_flags: Option<u32>,
+        /// New master key weight
+        master_weight: Option<u32>,
+        /// New low threshold
+        low_threshold: Option<u32>,
+        /// New medium threshold
+        med_threshold: Option<u32>,
+        /// New high threshold
+        high_threshold: Option<u32>,
+        /// New home domain
+        home_domain: Option<String>,
+        /// Signer to add, update or remove (weight 0) as `(key, weight)`
+        signer: Option<(String, u32)>,
+    },
+    /// Create, update or remove a trustline
+    ChangeTrust {
+        /// Trusted asset or pool
+        line: StellarAsset,
+        /// Trust limit in stroops (0 removes the trustline)
+        limit: i64,
+    },
+    /// Legacy trustline authorization
+    AllowTrust {
+        /// Trustor account
+        trustor: String,
+        /// Asset code
+        asset_code: String,
+        /// Authorization flags
+        authorize: u32,
+    },
+    /// Merge the source account into another
+    AccountMerge {
+        /// Account receiving the balance
+        destination: String,
+    },
+    /// Run inflation (deprecated)
+    Inflation,
+    /// Set or delete an account data entry
+    ManageData {
+        /// Entry name
+        name: String,
+        /// Entry value; `None` deletes the entry
+        value: Option<Vec<u8>>,
+    },
+    /// Bump the account sequence number
+    BumpSequence {
+        /// New sequence number
+        bump_to: i64,
+    },
+}
+
+/// Offer operation variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StellarOfferKind {
+    /// `ManageSellOffer`
+    Sell,
+    /// `ManageBuyOffer`
+    Buy,
+    /// `CreatePassiveSellOffer`
+    PassiveSell,
+}
+
+impl StellarOperationBody {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            StellarOperationBody::CreateAccount { .. } => "create_account",
+            StellarOperationBody::Payment { .. } => "payment",
+            StellarOperationBody::PathPaymentStrictReceive { .. } => "path_payment_strict_receive",
+            StellarOperationBody::PathPaymentStrictSend { .. } => "path_payment_strict_send",
+            StellarOperationBody::ManageOffer {
+                kind: StellarOfferKind::Sell,
+                ..
+            } => "manage_sell_offer",
+            StellarOperationBody::ManageOffer {
+                kind: StellarOfferKind::Buy,
+                ..
+            } => "manage_buy_offer",
+            StellarOperationBody::ManageOffer {
+                kind: StellarOfferKind::PassiveSell,
+                ..
+            } => "create_passive_sell_offer",
+            StellarOperationBody::SetOptions { .. } => "set_options",
+            StellarOperationBody::ChangeTrust { .. } => "change_trust",
+            StellarOperationBody::AllowTrust { .. } => "allow_trust",
+            StellarOperationBody::AccountMerge { .. } => "account_merge",
+            StellarOperationBody::Inflation => "inflation",
+            StellarOperationBody::ManageData { .. } => "manage_data",
+            StellarOperationBody::BumpSequence { .. } => "bump_sequence",
+        }
+    }
+}
+
+/// Decoded operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarOperation {
+    /// Per-operation source account override
+    pub source_account: Option<String>,
+    /// Operation body
+    pub body: StellarOperationBody,
+}
+
+/// Fee-bump wrapper details
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarFeeBump {
+    /// Account paying the bumped fee
+    pub fee_source: String,
+    /// Total fee in stroops
+    pub fee: i64,
+}
+
+/// Decoded Stellar transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarTransaction {
+    /// Network (known only for signature payloads)
+    pub network: Option<StellarNetwork>,
+    /// Source account
+    pub source_account: String,
+    /// Maximum fee in stroops
+    pub fee: u32,
+    /// Sequence number
+    pub sequence: i64,
+    /// Validity window
+    pub time_bounds: Option<StellarTimeBounds>,
+    /// Memo
+    pub memo: StellarMemo,
+    /// Operations
+    pub operations: Vec<StellarOperation>,
+    /// Fee-bump wrapper, if any
+    pub fee_bump: Option<StellarFeeBump>,
+    /// Whether the transaction carries Soroban resource data
+    pub soroban: bool,
+}
+
+impl StellarTransaction {
+    /// Decode a `TransactionSignaturePayload` or `TransactionEnvelope`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let first_word = bytes
+            .get(..4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()));
+        if matches!(
+            first_word,
+            Some(ENVELOPE_TYPE_TX_V0 | ENVELOPE_TYPE_TX | ENVELOPE_TYPE_TX_FEE_BUMP)
+        ) {
+            if let Ok(transaction) = Self::decode_envelope(bytes) {
+                return Ok(transaction);
+            }
+        }
+        Self::decode_signature_payload(bytes)
+    }
+
+    /// Decode a `TransactionSignaturePayload`
+    pub fn decode_signature_payload(bytes: &[u8]) -> Result<Self> {
+        let mut reader = XdrReader::new(bytes);
+        let network = StellarNetwork::from_id(reader.array32()?);
+        let mut transaction = match reader.u32()? {
+            ENVELOPE_TYPE_TX => reader.transaction()?,
+            ENVELOPE_TYPE_TX_FEE_BUMP => reader.fee_bump_transaction()?,
+            other => return Err(xdr_error(&format!("unsupported payload type {other}"))),
+        };
+        reader.finish(transaction.soroban)?;
+        transaction.network = Some(network);
+        Ok(transaction)
+    }
+
+    /// Decode a `TransactionEnvelope`
+    pub fn decode_envelope(bytes: &[u8]) -> Result<Self> {
+        let mut reader = XdrReader::new(bytes);
+        let transaction = match reader.u32()? {
+            ENVELOPE_TYPE_TX_V0 => reader.transaction_v0()?,
+            ENVELOPE_TYPE_TX => reader.transaction()?,
+            ENVELOPE_TYPE_TX_FEE_BUMP => reader.fee_bump_transaction()?,
+            other => return Err(xdr_error(&format!("unsupported envelope type {other}"))),
+        };
+        if !transaction.soroban {
+            reader.skip_signatures()?;
+        }
+        reader.finish(transaction.soroban)?;
+        Ok(transaction)
+    }
+
+    /// Sum of native (XLM) payments and account funding, in stroops
+    pub fn total_native_sent(&self) -> i64 {
+        self.operations
+            .iter()
+            .map(|operation| match &operation.body {
+                StellarOperationBody::Payment {
+                    asset: StellarAsset::Native,
+                    amount,
+                    ..
+                } => *amount,
+                StellarOperationBody::CreateAccount {
+                    starting_balance, ..
+                } => *starting_balance,
+                _ => 0,
+            })
+            .fold(0i64, i64::saturating_add)
+    }
+}
+
+/// Format stroops as a decimal amount without floating point (e.g. `12.5`)
+pub fn format_stroops(stroops: i64) -> String {
+    let sign = if stroops < 0 { "-" } else { "" };
+    let magnitude = stroops.unsigned_abs();
+    let whole = magnitude / STROOPS_PER_LUMEN as u64;
+    let fraction = magnitude % STROOPS_PER_LUMEN as u64;
+    if fraction == 0 {
+        return format!("{sign}{whole}");
+    }
+    let fraction = format!("{fraction:07}");
+    format!("{sign}{whole}.{}", fraction.trim_end_matches('0'))
+}
+
+fn network_id(passphrase: &str) -> [u8; 32] {
+    Sha256::digest(passphrase.as_bytes()).into()
+}
+
+struct XdrReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> XdrReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| xdr_error("unexpected end of data"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Reject trailing data unless decoding stopped at an undecoded Soroban extension
+    fn finish(&self, partial: bool) -> Result<()> {
+        if partial || self.pos == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(xdr_error(&format!(
+                "{} trailing bytes",
+                self.bytes.len() - self.pos
+            )))
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(self.u64()? as i64)
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.u32()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(xdr_error(&format!("invalid bool {other}"))),
+        }
+    }
+
+    fn array32(&mut self) -> Result<[u8; 32]> {
+        Ok(self.take(32)?.try_into().unwrap())
+    }
+
+    fn var_opaque(&mut self, max: usize) -> Result<Vec<u8>> {
+        let len = self.u32()? as usize;
+        if len > max {
+            return Err(xdr_error(&format!("field of {len} bytes exceeds {max}")));
+        }
+        let data = self.take(len)?.to_vec();
+        self.take((4 - len % 4) % 4)?;
+        Ok(data)
+    }
+
+    fn string(&mut self, max: usize) -> Result<String> {
+        let data = self.var_opaque(max)?;
+        Ok(String::from_utf8_lossy(&data).into_owned())
+    }
+
+    fn optional<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<Option<T>> {
+        if self.bool()? {
+            read(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn account_id(&mut self) -> Result<String> {
+        match self.u32()? {
+            0 => Ok(strkey::encode_account_id(&self.array32()?)),
+            other => Err(xdr_error(&format!("unsupported public key type {other}"))),
+        }
+    }
+
+    fn muxed_account(&mut self) -> Result<String> {
+        match self.u32()? {
+            0 => Ok(strkey::encode_account_id(&self.array32()?)),
+            0x100 => {
+                let id = self.u64()?;
+                let key = self.array32()?;
+                let mut payload = key.to_vec();
+                payload.extend_from_slice(&id.to_be_bytes());
+                Ok(strkey::encode(strkey::VERSION_MUXED_ACCOUNT, &payload))
+            }
+            other => Err(xdr_error(&format!(
+                "unsupported muxed account type {other}"
+            ))),
+        }
+    }
+
+    fn signer_key(&mut self) -> Result<String> {
+        match self.u32()? {
+            0 => Ok(strkey::encode_account_id(&self.array32()?)),
+            1 => Ok(strkey::encode(
+                strkey::VERSION_PRE_AUTH_TX,
+                &self.array32()?,
+            )),
+            2 => Ok(strkey::encode(
+                strkey::VERSION_SHA256_HASH,
+                &self.array32()?,
+            )),
+            3 => {
+                let mut payload = self.array32()?.to_vec();
+                let signed = self.var_opaque(64)?;
+                payload.extend_from_slice(&(signed.len() as u32).to_be_bytes());
+                payload.extend_from_slice(&signed);
+                payload.resize(payload.len().div_ceil(4) * 4, 0);
+                Ok(strkey::encode(strkey::VERSION_SIGNED_PAYLOAD, &payload))
+            }
+            other => Err(xdr_error(&format!("unsupported signer key type {other}"))),
+        }
+    }
+
+    fn asset_code(&mut self, len: usize) -> Result<String> {
+        let code = self.take(len)?;
+        let end = code.iter().position(|b| *b == 0).unwrap_or(len);
+        Ok(String::from_utf8_lossy(&code[..end]).into_owned())
+    }
+
+    fn asset(&mut self) -> Result<StellarAsset> {
+        match self.u32()? {
+            0 => Ok(StellarAsset::Native),
+            1 => Ok(StellarAsset::Credit {
+                code: self.asset_code(4)?,
+                issuer: self.account_id()?,
+            }),
+            2 => Ok(StellarAsset::Credit {
+                code: self.asset_code(12)?,
+                issuer: self.account_id()?,
+            }),
+            other => Err(xdr_error(&format!("unsupported asset type {other}"))),
+        }
+    }
+
+    fn change_trust_asset(&mut self) -> Result<StellarAsset> {
+        let start = self.pos;
+        if self.u32()? != 3 {
+            self.pos = start;
+            return self.asset();
+        }
+        match self.u32()? {
+            0 => Ok(StellarAsset::PoolShare {
+                asset_a: Box::new(self.asset()?),
+                asset_b: Box::new(self.asset()?),
+                fee: self.i32()?,
+            }),
+            other => Err(xdr_error(&format!(
+                "unsupported liquidity pool type {other}"
+            ))),
+        }
+    }
+
+    fn assets(&mut self, max: usize) -> Result<Vec<StellarAsset>> {
+        let len = self.u32()? as usize;
+        if len > max {
+            return Err(xdr_error(&format!("path of {len} assets exceeds {max}")));
+        }
+        (0..len).map(|_| self.asset()).collect()
+    }
+
+    fn price(&mut self) -> Result<StellarPrice> {
+        Ok(StellarPrice {
+            n: self.i32()?,
+            d: self.i32()?,
+        })
+    }
+
+    fn time_bounds(&mut self) -> Result<StellarTimeBounds> {
+        Ok(StellarTimeBounds {
+            min_time: self.u64()?,
+            max_time: self.u64()?,
+        })
+    }
+
+    fn memo(&mut self) -> Result<StellarMemo> {
+        match self.u32()? {
+            0 => Ok(StellarMemo::None),
+            1 => Ok(StellarMemo::Text(self.string(28)?)),
+            2 => Ok(StellarMemo::Id(self.u64()?)),
+            3 => Ok(StellarMemo::Hash(self.array32()?)),
+            4 => Ok(StellarMemo::Return(self.array32()?)),
+            other => Err(xdr_error(&format!("unsupported memo type {other}"))),
+        }
+    }
+
+    /// `Preconditions`; only the time bounds are surfaced
+    fn preconditions(&mut self) -> Result<Option<StellarTimeBounds>> {
+        match self.u32()? {
+            0 => Ok(None),
+            1 => self.time_bounds().map(Some),
+            2 => {
+                let time_bounds = self.optional(Self::time_bounds)?;
+                self.optional(|r| r.take(8).map(|_| ()))?; // ledger bounds
+                self.optional(Self::i64)?; // min sequence number
+                self.u64()?; // min sequence age
+                self.u32()?; // min sequence ledger gap
+                let extra_signers = self.u32()?;
+                if extra_signers > 2 {
+                    return Err(xdr_error("too many extra signers"));
+                }
+                for _ in 0..extra_signers {
+                    self.signer_key()?;
+                }
+                Ok(time_bounds)
+            }
+            other => Err(xdr_error(&format!("unsupported precondition type {other}"))),
+        }
+    }
+
+    fn operations(&mut self) -> Result<Vec<StellarOperation>> {
+        let count = self.u32()? as usize;
+        if count > 100 {
+            return Err(xdr_error(&format!("{count} operations exceeds 100")));
+        }
+        (0..count).map(|_| self.operation()).collect()
+    }
+
+    fn operation(&mut self) -> Result<StellarOperation> {
+        let source_account = self.optional(Self::muxed_account)?;
+        let body = match self.u32()? {
+            0 => StellarOperationBody::CreateAccount {
+                destination: self.account_id()?,
+                starting_balance: self.i64()?,
+            },
+            1 => StellarOperationBody::Payment {
+                destination: self.muxed_account()?,
+                asset: self.asset()?,
+                amount: self.i64()?,
+            },
+            2 => StellarOperationBody::PathPaymentStrictReceive {
+                send_asset: self.asset()?,
+                send_max: self.i64()?,
+                destination: self.muxed_account()?,
+                dest_asset: self.asset()?,
+                dest_amount: self.i64()?,
+                path: self.assets(5)?,
+            },
+            3 => StellarOperationBody::ManageOffer {
+                kind: StellarOfferKind::Sell,
+                selling: self.asset()?,
+                buying: self.asset()?,
+                amount: self.i64()?,
+                price: self.price()?,
+                offer_id: Some(self.i64()?),
+            },
+            4 => StellarOperationBody::ManageOffer {
+                kind: StellarOfferKind::PassiveSell,
+                selling: self.asset()?,
+                buying: self.asset()?,
+                amount: self.i64()?,
+                price: self.price()?,
+                offer_id: None,
+            },
+            5 => StellarOperationBody::SetOptions {
+                inflation_dest: self.optional(Self::account_id)?,
+                clear_flags: self.optional(Self::u32)?,
+                set_flags: self.optional(Self::u32)?,
+                master_weight: self.optional(Self::u32)?,
+                low_threshold: self.optional(Self::u32)?,
+                med_threshold: self.optional(Self::u32)?,
+                high_threshold: self.optional(Self::u32)?,
+                home_domain: self.optional(|r| r.string(32))?,
+                signer: self.optional(|r| Ok((r.signer_key()?, r.u32()?)))?,
+            },
+            6 => StellarOperationBody::ChangeTrust {
+                line: self.change_trust_asset()?,
+                limit: self.i64()?,
+            },
+            7 => {
+                let trustor = self.account_id()?;
+                let asset_code = match self.u32()? {
+                    1 => self.asset_code(4)?,
+                    2 => self.asset_code(12)?,
+                    other => {
+                        return Err(xdr_error(&format!("unsupported asset code type {other}")));
+                    }
+                };
+                StellarOperationBody::AllowTrust {
+                    trustor,
+                    asset_code,
+                    authorize: self.u32()?,
+                }
+            }
+            8 => StellarOperationBody::AccountMerge {
+                destination: self.muxed_account()?,
+            },
+            9 => StellarOperationBody::Inflation,
+            10 => StellarOperationBody::ManageData {
+                name: self.string(64)?,
+                value: self.optional(|r| r.var_opaque(64))?,
+            },
+            11 => StellarOperationBody::BumpSequence {
+                bump_to: self.i64()?,
+            },
+            12 => StellarOperationBody::ManageOffer {
+                kind: StellarOfferKind::Buy,
+                selling: self.asset()?,
+                buying: self.asset()?,
+                amount: self.i64()?,
+                price: self.price()?,
+                offer_id: Some(self.i64()?),
+            },
+            13 => StellarOperationBody::PathPaymentStrictSend {
+                send_asset: self.asset()?,
+                send_amount: self.i64()?,
+                destination: self.muxed_account()?,
+                dest_asset: self.asset()?,
+                dest_min: self.i64()?,
+                path: self.assets(5)?,
+            },
+            other => return Err(xdr_error(&format!("unsupported operation type {other}"))),
+        };
+        Ok(StellarOperation {
+            source_account,
+            body,
+        })
+    }
+
+    /// `Transaction` (v1)
+    fn transaction(&mut self) -> Result<StellarTransaction> {
+        let source_account = self.muxed_account()?;
+        let fee = self.u32()?;
+        let sequence = self.i64()?;
+        let time_bounds = self.preconditions()?;
+        let memo = self.memo()?;
+        let operations = self.operations()?;
+        let soroban = match self.u32()? {
+            0 => false,
+            1 => true,
+            other => return Err(xdr_error(&format!("unsupported transaction ext {other}"))),
+        };
+        Ok(StellarTransaction {
+            network: None,
+            source_account,
+            fee,
+            sequence,
+            time_bounds,
+            memo,
+            operations,
+            fee_bump: None,
+            soroban,
+        })
+    }
+
+    /// `TransactionV0` (pre-protocol-13 envelopes)
+    fn transaction_v0(&mut self) -> Result<StellarTransaction> {
+        let source_account = strkey::encode_account_id(&self.array32()?);
+        let fee = self.u32()?;
+        let sequence = self.i64()?;
+        let time_bounds = self.optional(Self::time_bounds)?;
+        let memo = self.memo()?;
+        let operations = self.operations()?;
+        if self.u32()? != 0 {
+            return Err(xdr_error("unsupported v0 transaction ext"));
+        }
+        Ok(StellarTransaction {
+            network: None,
+            source_account,
+            fee,
+            sequence,
+            time_bounds,
+            memo,
+            operations,
+            fee_bump: None,
+            soroban: false,
+        })
+    }
+
+    /// `FeeBumpTransaction` wrapping a v1 envelope
+    fn fee_bump_transaction(&mut self) -> Result<StellarTransaction> {
+        let fee_source = self.muxed_account()?;
+        let fee = self.i64()?;
+        if self.u32()? != ENVELOPE_TYPE_TX {
+            return Err(xdr_error("fee bump must wrap a v1 transaction"));
+        }
+        let mut inner = self.transaction()?;
+        if !inner.soroban {
+            self.skip_signatures()?;
+            if self.u32()? != 0 {
+                return Err(xdr_error("unsupported fee bump ext"));
+            }
+        }
+        inner.fee_bump = Some(StellarFeeBump { fee_source, fee });
+        Ok(inner)
+    }
+
+    /// `DecoratedSignature signatures<20>`
+    fn skip_signatures(&mut self) -> Result<()> {
+        let count = self.u32()?;
+        if count > 20 {
+            return Err(xdr_error(&format!("{count} signatures exceeds 20")));
+        }
+        for _ in 0..count {
+            self.take(4)?; // signature hint
+            self.var_opaque(64)?;
+        }
+        Ok(())
+    }
+}
+
+fn xdr_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Stellar XDR: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: u32) -> [u8; 4] {
+        value.to_be_bytes()
+    }
+
+    /// v1 transaction: one native payment plus a USDC trustline, text memo, time bounds
+    fn transaction_xdr() -> Vec<u8> {
+        let mut tx = Vec::new();
+        tx.extend_from_slice(&word(0)); // source: ed25519
+        tx.extend_from_slice(&[0x11; 32]);
+        tx.extend_from_slice(&word(200)); // fee
+        tx.extend_from_slice(&42i64.to_be_bytes()); // sequence
+        tx.extend_from_slice(&word(1)); // PRECOND_TIME
+        tx.extend_from_slice(&0u64.to_be_bytes());
+        tx.extend_from_slice(&1_700_000_000u64.to_be_bytes());
+        tx.extend_from_slice(&word(1)); // MEMO_TEXT
+        tx.extend_from_slice(&word(5));
+        tx.extend_from_slice(b"hello\0\0\0");
+        tx.extend_from_slice(&word(2)); // operations
+
+        tx.extend_from_slice(&word(0)); // no op source
+        tx.extend_from_slice(&word(1)); // PAYMENT
+        tx.extend_from_slice(&word(0));
+        tx.extend_from_slice(&[0x22; 32]);
+        tx.extend_from_slice(&word(0)); // native
+        tx.extend_from_slice(&125_000_000i64.to_be_bytes());
+
+        tx.extend_from_slice(&word(0));
+        tx.extend_from_slice(&word(6)); // CHANGE_TRUST
+        tx.extend_from_slice(&word(1)); // alphanum4
+        tx.extend_from_slice(b"USDC");
+        tx.extend_from_slice(&word(0));
+        tx.extend_from_slice(&[0x33; 32]);
+        tx.extend_from_slice(&i64::MAX.to_be_bytes());
+
+        tx.extend_from_slice(&word(0)); // ext
+        tx
+    }
+
+    #[test]
+    fn test_decode_signature_payload() {
+        let mut payload = network_id(PUBLIC_NETWORK_PASSPHRASE).to_vec();
+        payload.extend_from_slice(&word(ENVELOPE_TYPE_TX));
+        payload.extend_from_slice(&transaction_xdr());
+
+        let tx = StellarTransaction::decode(&payload).unwrap();
+        assert_eq!(tx.network, Some(StellarNetwork::Public));
+        assert_eq!(tx.source_account, strkey::encode_account_id(&[0x11; 32]));
+        assert_eq!(tx.fee, 200);
+        assert_eq!(tx.sequence, 42);
+        assert_eq!(tx.memo, StellarMemo::Text("hello".to_string()));
+        assert_eq!(tx.time_bounds.unwrap().max_time, 1_700_000_000);
+        assert_eq!(tx.operations.len(), 2);
+        assert_eq!(format_stroops(tx.total_native_sent()), "12.5");
+        match &tx.operations[1].body {
+            StellarOperationBody::ChangeTrust { line, .. } => {
+                assert!(line.display().starts_with("USDC:G"));
+            }
+            other => panic!("unexpected operation {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_envelope_with_signature() {
+        let mut envelope = word(ENVELOPE_TYPE_TX).to_vec();
+        envelope.extend_from_slice(&transaction_xdr());
+        envelope.extend_from_slice(&word(1)); // one signature
+        envelope.extend_from_slice(&[0xaa; 4]);
+        envelope.extend_from_slice(&word(64));
+        envelope.extend_from_slice(&[0xbb; 64]);
+
+        let tx = StellarTransaction::decode(&envelope).unwrap();
+        assert_eq!(tx.network, None);
+        assert_eq!(tx.operations[0].body.label(), "payment");
+
+        assert!(StellarTransaction::decode(&envelope[..envelope.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Sui intent message decoding
+//!
+//! Every Sui signature covers `intent || bcs(value)`, where the three intent
+//! bytes name what is being signed (scope), the intent version and the
+//! application. The wallet hashes the whole message with Blake2b-256 before
+//! signing. Personal messages are unwrapped; transaction data is left as BCS.
+//!
+//! Reference: https://github.com/MystenLabs/sui/blob/main/crates/shared-crypto/src/intent.rs
+
+use crate::error::{Error, Result};
+
+/// What an intent message asks the wallet to sign
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuiIntentScope {
+    /// Transaction data (a transaction to execute)
+    TransactionData,
+    /// Transaction effects (validator use)
+    TransactionEffects,
+    /// Checkpoint summary (validator use)
+    CheckpointSummary,
+    /// Arbitrary personal message
+    PersonalMessage,
+    /// Sender-signed transaction (validator use)
+    SenderSignedTransaction,
+    /// Proof of possession for validator keys
+    ProofOfPossession,
+    /// Any other scope byte
+    Other(u8),
+}
+
+impl SuiIntentScope {
+    fn from_byte(byte: u8) -> Self {
+        match byte {
+            0 => SuiIntentScope::TransactionData,
+            1 => SuiIntentScope::TransactionEffects,
+            2 => SuiIntentScope::CheckpointSummary,
+            3 => SuiIntentScope::PersonalMessage,
+            4 => SuiIntentScope::SenderSignedTransaction,
+            5 => SuiIntentScope::ProofOfPossession,
+            other => SuiIntentScope::Other(other),
+        }
+    }
+
+    /// Short lowercase label for output
+    pub fn label(&self) -> String {
+        match self {
+            SuiIntentScope::TransactionData => "transaction_data".to_string(),
+            SuiIntentScope::TransactionEffects => "transaction_effects".to_string(),
+            SuiIntentScope::CheckpointSummary => "checkpoint_summary".to_string(),
+            SuiIntentScope::PersonalMessage => "personal_message".to_string(),
+            SuiIntentScope::SenderSignedTransaction => "sender_signed_transaction".to_string(),
+            SuiIntentScope::ProofOfPossession => "proof_of_possession".to_string(),
+            SuiIntentScope::Other(byte) => format!("unknown({byte})"),
+        }
+    }
+
+    /// Whether a user wallet should ever be asked to sign this scope
+    pub fn is_user_facing(&self) -> bool {
+        matches!(
+            self,
+            SuiIntentScope::TransactionData | SuiIntentScope::PersonalMessage
+        )
+    }
+}
+
+/// Decoded intent header plus the BCS value that follows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiIntentMessage {
+    /// Intent scope
+    pub scope: SuiIntentScope,
+    /// Intent version (0 today)
+    pub version: u8,
+    /// Application ID (0 = Sui, 1 = Narwhal, 2 = Consensus)
+    pub app_id: u8,
+    /// BCS-encoded value being signed
+    pub value: Vec<u8>,
+}
+
+impl SuiIntentMessage {
+    /// Split an intent message into its header and value
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        match bytes {
+            [scope, version, app_id, value @ ..] => Ok(Self {
+                scope: SuiIntentScope::from_byte(*scope),
+                version: *version,
+                app_id: *app_id,
+                value: value.to_vec(),
+            }),
+            _ => Err(intent_error("shorter than the 3-byte intent")),
+        }
+    }
+
+    /// Application name for well-known IDs
+    pub fn app_label(&self) -> Option<&'static str> {
+        match self.app_id {
+            0 => Some("sui"),
+            1 => Some("narwhal"),
+            2 => Some("consensus"),
+            _ => None,
+        }
+    }
+
+    /// Message bytes of a personal message intent
+    ///
+    /// The value is a BCS `vector<u8>`: a ULEB128 length followed by the bytes.
+    pub fn personal_message(&self) -> Result<Vec<u8>> {
+        if self.scope != SuiIntentScope::PersonalMessage {
+            return Err(intent_error("not a personal message"));
+        }
+        let mut len = 0usize;
+        for (index, byte) in self.value.iter().enumerate().take(5) {
+            len |= usize::from(byte & 0x7f) << (7 * index);
+            if byte & 0x80 == 0 {
+                let body = &self.value[index + 1..];
+                if body.len() != len {
+                    return Err(intent_error(&format!(
+                        "personal message length {} does not match {} bytes",
+                        len,
+                        body.len()
+                    )));
+                }
+                return Ok(body.to_vec());
+            }
+        }
+        Err(intent_error("invalid personal message length"))
+    }
+}
+
+fn intent_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid Sui intent message: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_personal_message() {
+        let bytes = [&[3u8, 0, 0, 5][..], b"hello"].concat();
+        let intent = SuiIntentMessage::parse(&bytes).unwrap();
+
+        assert_eq!(intent.scope, SuiIntentScope::PersonalMessage);
+        assert_eq!(intent.app_label(), Some("sui"));
+        assert_eq!(intent.personal_message().unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_transaction_scope_and_short_input() {
+        let intent = SuiIntentMessage::parse(&[0, 0, 0, 0x00, 0x01]).unwrap();
+        assert_eq!(intent.scope, SuiIntentScope::TransactionData);
+        assert!(intent.scope.is_user_facing());
+        assert!(intent.personal_message().is_err());
+
+        assert!(!SuiIntentScope::from_byte(1).is_user_facing());
+        assert!(SuiIntentMessage::parse(&[3, 0]).is_err());
+    }
+}
@@ -0,0 +1,359 @@
+//! Sui (SUI) signature request and response types
+//!
+//! Reference: https://github.com/KeystoneHQ/keystone-sdk-rust
+
+pub mod intent;
+
+pub use intent::{SuiIntentMessage, SuiIntentScope};
+
+use crate::error::Result;
+use crate::keystone::cbor;
+use crate::keystone::crypto_keypath::CryptoKeyPath;
+use minicbor::{Decoder, Encoder};
+use uuid::Uuid;
+
+/// Sui signature request
+#[derive(Debug, Clone)]
+pub struct SuiSignRequest {
+    /// Optional request identifier
+    pub request_id: Option<Uuid>,
+    /// Intent message: 3-byte intent followed by the BCS value to sign
+    pub intent_message: Vec<u8>,
+    /// Derivation paths, one per signer
+    pub derivation_paths: Vec<CryptoKeyPath>,
+    /// Optional 32-byte account addresses, one per signer
+    pub addresses: Vec<Vec<u8>>,
+    /// Optional application origin string
+    pub origin: Option<String>,
+}
+
+impl SuiSignRequest {
+    /// Construct a new Sui sign request
+    pub fn new(
+        intent_message: Vec<u8>,
+        derivation_paths: Vec<CryptoKeyPath>,
+        request_id: Option<Uuid>,
+    ) -> Self {
+        Self {
+            request_id,
+            intent_message,
+            derivation_paths,
+            addresses: Vec::new(),
+            origin: None,
+        }
+    }
+
+    /// Attach the signing account addresses
+    pub fn with_addresses(mut self, addresses: Vec<Vec<u8>>) -> Self {
+        self.addresses = addresses;
+        self
+    }
+
+    /// Attach an origin descriptor (e.g. dApp name)
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Split `intent_message` into its intent header and value
+    pub fn decode_intent(&self) -> Result<SuiIntentMessage> {
+        SuiIntentMessage::parse(&self.intent_message)
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 2; // intent_message + derivation_paths
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        if !self.addresses.is_empty() {
+            len += 1;
+        }
+        if self.origin.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for SuiSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(
+            cbor::tags::SUI_SIGN_REQUEST,
+        ))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.bytes(&self.intent_message)?;
+
+        e.u8(3)?;
+        e.array(self.derivation_paths.len() as u64)?;
+        for path in &self.derivation_paths {
+            path.encode(e, ctx)?;
+        }
+
+        if !self.addresses.is_empty() {
+            e.u8(4)?;
+            e.array(self.addresses.len() as u64)?;
+            for address in &self.addresses {
+                e.bytes(address)?;
+            }
+        }
+
+        if let Some(ref origin) = self.origin {
+            e.u8(5)?;
+            e.str(origin)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for SuiSignRequest {
+    fn decode(
+        d: &mut Decoder<'b>,
+        ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::SUI_SIGN_REQUEST) {
+            return Err(minicbor::decode::Error::message(
+                "expected sui-sign-request tag",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut intent_message = None;
+        let mut derivation_paths = None;
+        let mut addresses = Vec::new();
+        let mut origin = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => intent_message = Some(d.bytes()?.to_vec()),
+                3 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length array")
+                    })?;
+                    let mut paths = Vec::with_capacity(len as usize);
+                    for _ in 0..len {
+                        paths.push(CryptoKeyPath::decode(d, ctx)?);
+                    }
+                    derivation_paths = Some(paths);
+                }
+                4 => {
+                    let len = d.array()?.ok_or_else(|| {
+                        minicbor::decode::Error::message("expected definite-length array")
+                    })?;
+                    for _ in 0..len {
+                        addresses.push(d.bytes()?.to_vec());
+                    }
+                }
+                5 => origin = Some(d.str()?.to_string()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            intent_message: intent_message
+                .ok_or_else(|| minicbor::decode::Error::message("missing intent_message"))?,
+            derivation_paths: derivation_paths
+                .ok_or_else(|| minicbor::decode::Error::message("missing derivation_paths"))?,
+            addresses,
+            origin,
+        })
+    }
+}
+
+/// Sui signature response
+#[derive(Debug, Clone)]
+pub struct SuiSignature {
+    /// Request identifier echo
+    pub request_id: Option<Uuid>,
+    /// Signature bytes (64 bytes Ed25519 signature)
+    pub signature: Vec<u8>,
+    /// Optional attested public key
+    pub public_key: Option<Vec<u8>>,
+}
+
+impl SuiSignature {
+    /// Create a new signature container
+    pub fn new(signature: Vec<u8>, request_id: Option<Uuid>) -> Self {
+        Self {
+            request_id,
+            signature,
+            public_key: None,
+        }
+    }
+
+    /// Attach a public key to the signature payload
+    pub fn with_public_key(mut self, public_key: Vec<u8>) -> Self {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    fn map_len(&self) -> u64 {
+        let mut len = 1; // signature
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        if self.public_key.is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Encode to CBOR bytes
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        cbor::to_bytes(self)
+    }
+
+    /// Decode from CBOR bytes
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        cbor::from_bytes(bytes)
+    }
+}
+
+impl minicbor::Encode<()> for SuiSignature {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut (),
+    ) -> std::result::Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(minicbor::data::Tag::Unassigned(cbor::tags::SUI_SIGNATURE))?;
+        e.map(self.map_len())?;
+
+        if let Some(ref uuid) = self.request_id {
+            e.u8(1)?;
+            e.tag(minicbor::data::Tag::Unassigned(cbor::tags::UUID))?;
+            e.bytes(uuid.as_bytes())?;
+        }
+
+        e.u8(2)?;
+        e.bytes(&self.signature)?;
+
+        if let Some(ref public_key) = self.public_key {
+            e.u8(3)?;
+            e.bytes(public_key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b, ()> for SuiSignature {
+    fn decode(
+        d: &mut Decoder<'b>,
+        _ctx: &mut (),
+    ) -> std::result::Result<Self, minicbor::decode::Error> {
+        let tag = d.tag()?;
+        if tag != minicbor::data::Tag::Unassigned(cbor::tags::SUI_SIGNATURE) {
+            return Err(minicbor::decode::Error::message(
+                "expected sui-signature tag",
+            ));
+        }
+
+        let map_len = d
+            .map()?
+            .ok_or_else(|| minicbor::decode::Error::message("expected definite-length map"))?;
+
+        let mut request_id = None;
+        let mut signature = None;
+        let mut public_key = None;
+
+        for _ in 0..map_len {
+            let key = d.u8()?;
+            match key {
+                1 => {
+                    let tag = d.tag()?;
+                    if tag != minicbor::data::Tag::Unassigned(cbor::tags::UUID) {
+                        return Err(minicbor::decode::Error::message("expected UUID tag"));
+                    }
+                    let bytes = d.bytes()?;
+                    request_id = Some(Uuid::from_slice(bytes).map_err(|e| {
+                        minicbor::decode::Error::message(format!("invalid UUID: {}", e))
+                    })?);
+                }
+                2 => signature = Some(d.bytes()?.to_vec()),
+                3 => public_key = Some(d.bytes()?.to_vec()),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(Self {
+            request_id,
+            signature: signature
+                .ok_or_else(|| minicbor::decode::Error::message("missing signature"))?,
+            public_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sui_sign_request_roundtrip() {
+        let paths = vec![CryptoKeyPath::from_str("m/44'/784'/0'/0'/0'").unwrap()];
+        let request_id = Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap());
+        let intent = vec![0, 0, 0, 0x00, 0x01];
+        let request = SuiSignRequest::new(intent.clone(), paths.clone(), request_id)
+            .with_addresses(vec![vec![0xcc; 32]])
+            .with_origin("sui wallet".to_string());
+
+        let bytes = request.to_cbor().unwrap();
+        let decoded = SuiSignRequest::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.request_id, request_id);
+        assert_eq!(decoded.intent_message, intent);
+        assert_eq!(decoded.derivation_paths, paths);
+        assert_eq!(decoded.addresses, vec![vec![0xcc; 32]]);
+        assert_eq!(decoded.origin.as_deref(), Some("sui wallet"));
+    }
+
+    #[test]
+    fn test_sui_signature_roundtrip() {
+        let sig = SuiSignature::new(vec![0u8; 64], None).with_public_key(vec![1u8; 32]);
+
+        let bytes = sig.to_cbor().unwrap();
+        let decoded = SuiSignature::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.signature, sig.signature);
+        assert_eq!(decoded.public_key, sig.public_key);
+    }
+}
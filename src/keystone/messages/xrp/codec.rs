@@ -0,0 +1,568 @@
+//! Canonical XRPL binary serialization and signing hashes
+//!
+//! Covers the field types used by common transactions (integers, hashes,
+//! amounts, blobs, accounts, memos, signer lists, paths). Fields outside the
+//! table are rejected rather than skipped, since an incomplete serialization
+//! would produce a hash that does not match what the wallet signs.
+//!
+//! Reference: https://xrpl.org/docs/references/protocol/binary-format
+
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha512};
+
+/// Prefix for single-signature signing data (`STX\0`)
+pub const SIGNING_PREFIX: [u8; 4] = [0x53, 0x54, 0x58, 0x00];
+
+const TYPE_UINT16: u8 = 1;
+const TYPE_UINT32: u8 = 2;
+const TYPE_HASH128: u8 = 4;
+const TYPE_HASH256: u8 = 5;
+const TYPE_AMOUNT: u8 = 6;
+const TYPE_BLOB: u8 = 7;
+const TYPE_ACCOUNT: u8 = 8;
+const TYPE_OBJECT: u8 = 14;
+const TYPE_ARRAY: u8 = 15;
+const TYPE_UINT8: u8 = 16;
+const TYPE_PATHSET: u8 = 18;
+const TYPE_VECTOR256: u8 = 19;
+
+const OBJECT_END_MARKER: u8 = 0xe1;
+const ARRAY_END_MARKER: u8 = 0xf1;
+
+/// Field name, type code, field code, included when signing
+const FIELDS: &[(&str, u8, u8, bool)] = &[
+    ("TransactionType", TYPE_UINT16, 2, true),
+    ("SignerWeight", TYPE_UINT16, 3, true),
+    ("TransferFee", TYPE_UINT16, 4, true),
+    ("NetworkID", TYPE_UINT32, 1, true),
+    ("Flags", TYPE_UINT32, 2, true),
+    ("SourceTag", TYPE_UINT32, 3, true),
+    ("Sequence", TYPE_UINT32, 4, true),
+    ("Expiration", TYPE_UINT32, 10, true),
+    ("TransferRate", TYPE_UINT32, 11, true),
+    ("WalletSize", TYPE_UINT32, 12, true),
+    ("DestinationTag", TYPE_UINT32, 14, true),
+    ("QualityIn", TYPE_UINT32, 20, true),
+    ("QualityOut", TYPE_UINT32, 21, true),
+    ("OfferSequence", TYPE_UINT32, 25, true),
+    ("LastLedgerSequence", TYPE_UINT32, 27, true),
+    ("SetFlag", TYPE_UINT32, 33, true),
+    ("ClearFlag", TYPE_UINT32, 34, true),
+    ("SignerQuorum", TYPE_UINT32, 35, true),
+    ("CancelAfter", TYPE_UINT32, 36, true),
+    ("FinishAfter", TYPE_UINT32, 37, true),
+    ("SettleDelay", TYPE_UINT32, 39, true),
+    ("TicketCount", TYPE_UINT32, 40, true),
+    ("TicketSequence", TYPE_UINT32, 41, true),
+    ("NFTokenTaxon", TYPE_UINT32, 42, true),
+    ("EmailHash", TYPE_HASH128, 1, true),
+    ("AccountTxnID", TYPE_HASH256, 9, true),
+    ("NFTokenID", TYPE_HASH256, 10, true),
+    ("InvoiceID", TYPE_HASH256, 17, true),
+    ("Channel", TYPE_HASH256, 22, true),
+    ("CheckID", TYPE_HASH256, 24, true),
+    ("NFTokenBuyOffer", TYPE_HASH256, 28, true),
+    ("NFTokenSellOffer", TYPE_HASH256, 29, true),
+    ("Amount", TYPE_AMOUNT, 1, true),
+    ("LimitAmount", TYPE_AMOUNT, 3, true),
+    ("TakerPays", TYPE_AMOUNT, 4, true),
+    ("TakerGets", TYPE_AMOUNT, 5, true),
+    ("Fee", TYPE_AMOUNT, 8, true),
+    ("SendMax", TYPE_AMOUNT, 9, true),
+    ("DeliverMin", TYPE_AMOUNT, 10, true),
+    ("NFTokenBrokerFee", TYPE_AMOUNT, 19, true),
+    ("PublicKey", TYPE_BLOB, 1, true),
+    ("MessageKey", TYPE_BLOB, 2, true),
+    ("SigningPubKey", TYPE_BLOB, 3, true),
+    ("TxnSignature", TYPE_BLOB, 4, false),
+    ("URI", TYPE_BLOB, 5, true),
+    ("Domain", TYPE_BLOB, 7, true),
+    ("MemoType", TYPE_BLOB, 12, true),
+    ("MemoData", TYPE_BLOB, 13, true),
+    ("MemoFormat", TYPE_BLOB, 14, true),
+    ("Account", TYPE_ACCOUNT, 1, true),
+    ("Owner", TYPE_ACCOUNT, 2, true),
+    ("Destination", TYPE_ACCOUNT, 3, true),
+    ("Issuer", TYPE_ACCOUNT, 4, true),
+    ("RegularKey", TYPE_ACCOUNT, 8, true),
+    ("NFTokenMinter", TYPE_ACCOUNT, 9, true),
+    ("Memo", TYPE_OBJECT, 10, true),
+    ("SignerEntry", TYPE_OBJECT, 11, true),
+    ("Signer", TYPE_OBJECT, 16, true),
+    ("Signers", TYPE_ARRAY, 3, false),
+    ("SignerEntries", TYPE_ARRAY, 4, true),
+    ("Memos", TYPE_ARRAY, 9, true),
+    ("TickSize", TYPE_UINT8, 16, true),
+    ("Paths", TYPE_PATHSET, 1, true),
+    ("NFTokenOffers", TYPE_VECTOR256, 4, true),
+];
+
+const TRANSACTION_TYPES: &[(&str, u16)] = &[
+    ("Payment", 0),
+    ("EscrowCreate", 1),
+    ("EscrowFinish", 2),
+    ("AccountSet", 3),
+    ("EscrowCancel", 4),
+    ("SetRegularKey", 5),
+    ("OfferCreate", 7),
+    ("OfferCancel", 8),
+    ("TicketCreate", 10),
+    ("SignerListSet", 12),
+    ("PaymentChannelCreate", 13),
+    ("PaymentChannelFund", 14),
+    ("PaymentChannelClaim", 15),
+    ("CheckCreate", 16),
+    ("CheckCash", 17),
+    ("CheckCancel", 18),
+    ("DepositPreauth", 19),
+    ("TrustSet", 20),
+    ("AccountDelete", 21),
+    ("NFTokenMint", 25),
+    ("NFTokenBurn", 26),
+    ("NFTokenCreateOffer", 27),
+    ("NFTokenCancelOffer", 28),
+    ("NFTokenAcceptOffer", 29),
+    ("Clawback", 30),
+];
+
+/// Serialize a transaction JSON object; `signing` omits non-signing fields
+pub fn serialize(transaction: &Value, signing: bool) -> Result<Vec<u8>> {
+    let object = transaction
+        .as_object()
+        .ok_or_else(|| codec_error("transaction is not a JSON object"))?;
+    let mut out = Vec::new();
+    write_fields(&mut out, object, signing)?;
+    Ok(out)
+}
+
+/// Bytes a single signer signs: `STX\0` followed by the signing serialization
+pub fn signing_data(transaction: &Value) -> Result<Vec<u8>> {
+    let mut data = SIGNING_PREFIX.to_vec();
+    data.extend(serialize(transaction, true)?);
+    Ok(data)
+}
+
+/// SHA-512Half of the signing data, the digest secp256k1 keys sign
+pub fn signing_hash(transaction: &Value) -> Result<[u8; 32]> {
+    Ok(sha512_half(&signing_data(transaction)?))
+}
+
+/// First 32 bytes of SHA-512
+pub fn sha512_half(data: &[u8]) -> [u8; 32] {
+    let digest = Sha512::digest(data);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&digest[..32]);
+    hash
+}
+
+/// Decode a classic `r...` address to its 20-byte account ID
+pub fn decode_address(address: &str) -> Result<[u8; 20]> {
+    let decoded = bs58::decode(address)
+        .with_alphabet(bs58::Alphabet::RIPPLE)
+        .with_check(Some(0))
+        .into_vec()
+        .map_err(|err| codec_error(&format!("invalid address {address}: {err}")))?;
+    decoded
+        .get(1..)
+        .and_then(|id| <[u8; 20]>::try_from(id).ok())
+        .ok_or_else(|| codec_error(&format!("invalid address {address}: wrong length")))
+}
+
+/// Encode a 20-byte account ID as a classic `r...` address
+pub fn encode_address(account_id: &[u8; 20]) -> String {
+    bs58::encode(account_id)
+        .with_alphabet(bs58::Alphabet::RIPPLE)
+        .with_check_version(0)
+        .into_string()
+}
+
+/// Numeric transaction type for a `TransactionType` name
+pub fn transaction_type_code(name: &str) -> Option<u16> {
+    TRANSACTION_TYPES
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, code)| *code)
+}
+
+fn write_fields(out: &mut Vec<u8>, object: &Map<String, Value>, signing: bool) -> Result<()> {
+    let mut fields = Vec::with_capacity(object.len());
+    for (name, value) in object {
+        let &(_, type_code, field_code, is_signing) = FIELDS
+            .iter()
+            .find(|(known, ..)| known == name)
+            .ok_or_else(|| codec_error(&format!("unsupported field {name}")))?;
+        if signing && !is_signing {
+            continue;
+        }
+        fields.push((type_code, field_code, name.as_str(), value));
+    }
+    fields.sort_by_key(|(type_code, field_code, ..)| (*type_code, *field_code));
+
+    for (type_code, field_code, name, value) in fields {
+        write_field_header(out, type_code, field_code);
+        write_value(out, type_code, name, value, signing)?;
+    }
+    Ok(())
+}
+
+fn write_field_header(out: &mut Vec<u8>, type_code: u8, field_code: u8) {
+    match (type_code < 16, field_code < 16) {
+        (true, true) => out.push((type_code << 4) | field_code),
+        (true, false) => out.extend_from_slice(&[type_code << 4, field_code]),
+        (false, true) => out.extend_from_slice(&[field_code, type_code]),
+        (false, false) => out.extend_from_slice(&[0, type_code, field_code]),
+    }
+}
+
+fn write_value(
+    out: &mut Vec<u8>,
+    type_code: u8,
+    name: &str,
+    value: &Value,
+    signing: bool,
+) -> Result<()> {
+    match type_code {
+        TYPE_UINT8 => out.push(uint_value(name, value, u8::MAX.into())? as u8),
+        TYPE_UINT16 if name == "TransactionType" => {
+            let code = match value {
+                Value::String(text) => transaction_type_code(text)
+                    .ok_or_else(|| codec_error(&format!("unsupported transaction type {text}")))?,
+                other => uint_value(name, other, u16::MAX.into())? as u16,
+            };
+            out.extend_from_slice(&code.to_be_bytes());
+        }
+        TYPE_UINT16 => {
+            out.extend_from_slice(&(uint_value(name, value, u16::MAX.into())? as u16).to_be_bytes())
+        }
+        TYPE_UINT32 => {
+            out.extend_from_slice(&(uint_value(name, value, u32::MAX.into())? as u32).to_be_bytes())
+        }
+        TYPE_HASH128 => out.extend(hex_value(name, value, Some(16))?),
+        TYPE_HASH256 => out.extend(hex_value(name, value, Some(32))?),
+        TYPE_AMOUNT => out.extend(encode_amount(value)?),
+        TYPE_BLOB => write_vl(out, &hex_value(name, value, None)?),
+        TYPE_ACCOUNT => {
+            let address = value
+                .as_str()
+                .ok_or_else(|| codec_error(&format!("{name} is not an address")))?;
+            write_vl(out, &decode_address(address)?);
+        }
+        TYPE_OBJECT => {
+            let object = value
+                .as_object()
+                .ok_or_else(|| codec_error(&format!("{name} is not an object")))?;
+            write_fields(out, object, signing)?;
+            out.push(OBJECT_END_MARKER);
+        }
+        TYPE_ARRAY => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| codec_error(&format!("{name} is not an array")))?;
+            for item in items {
+                // Each element is a single-key wrapper object, e.g. {"Memo": {...}}
+                let wrapper = item
+                    .as_object()
+                    .filter(|wrapper| wrapper.len() == 1)
+                    .ok_or_else(|| codec_error(&format!("{name} element is not a wrapper")))?;
+                write_fields(out, wrapper, signing)?;
+            }
+            out.push(ARRAY_END_MARKER);
+        }
+        TYPE_PATHSET => write_paths(out, value)?,
+        TYPE_VECTOR256 => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| codec_error(&format!("{name} is not an array")))?;
+            let mut data = Vec::with_capacity(items.len() * 32);
+            for item in items {
+                data.extend(hex_value(name, item, Some(32))?);
+            }
+            write_vl(out, &data);
+        }
+        other => return Err(codec_error(&format!("unsupported type code {other}"))),
+    }
+    Ok(())
+}
+
+fn uint_value(name: &str, value: &Value, max: u64) -> Result<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+        .filter(|number| *number <= max)
+        .ok_or_else(|| codec_error(&format!("{name} is not an integer in range")))
+}
+
+fn hex_value(name: &str, value: &Value, len: Option<usize>) -> Result<Vec<u8>> {
+    let bytes = value
+        .as_str()
+        .and_then(|text| hex::decode(text).ok())
+        .ok_or_else(|| codec_error(&format!("{name} is not hex")))?;
+    match len {
+        Some(len) if bytes.len() != len => Err(codec_error(&format!(
+            "{name} has {} bytes (expected {len})",
+            bytes.len()
+        ))),
+        _ => Ok(bytes),
+    }
+}
+
+fn write_vl(out: &mut Vec<u8>, data: &[u8]) {
+    let len = data.len();
+    if len <= 192 {
+        out.push(len as u8);
+    } else if len <= 12_480 {
+        let len = len - 193;
+        out.extend_from_slice(&[193 + (len >> 8) as u8, (len & 0xff) as u8]);
+    } else {
+        let len = len - 12_481;
+        out.extend_from_slice(&[
+            241 + (len >> 16) as u8,
+            ((len >> 8) & 0xff) as u8,
+            (len & 0xff) as u8,
+        ]);
+    }
+    out.extend_from_slice(data);
+}
+
+fn write_paths(out: &mut Vec<u8>, value: &Value) -> Result<()> {
+    let paths = value
+        .as_array()
+        .ok_or_else(|| codec_error("Paths is not an array"))?;
+    for (index, path) in paths.iter().enumerate() {
+        if index > 0 {
+            out.push(0xff); // path boundary
+        }
+        let steps = path
+            .as_array()
+            .ok_or_else(|| codec_error("path is not an array"))?;
+        for step in steps {
+            let mut step_type = 0u8;
+            let mut data = Vec::new();
+            if let Some(account) = step.get("account").and_then(Value::as_str) {
+                step_type |= 0x01;
+                data.extend(decode_address(account)?);
+            }
+            if let Some(currency) = step.get("currency").and_then(Value::as_str) {
+                step_type |= 0x10;
+                data.extend(encode_currency(currency, true)?);
+            }
+            if let Some(issuer) = step.get("issuer").and_then(Value::as_str) {
+                step_type |= 0x20;
+                data.extend(decode_address(issuer)?);
+            }
+            if step_type == 0 {
+                return Err(codec_error("empty path step"));
+            }
+            out.push(step_type);
+            out.extend(data);
+        }
+    }
+    out.push(0x00);
+    Ok(())
+}
+
+/// Encode an amount: drops as a string, or an issued-currency object
+pub fn encode_amount(value: &Value) -> Result<Vec<u8>> {
+    match value {
+        Value::String(drops) => {
+            let drops: u64 = drops
+                .parse()
+                .ok()
+                .filter(|drops| *drops <= 100_000_000_000_000_000)
+                .ok_or_else(|| codec_error(&format!("invalid XRP amount {drops}")))?;
+            Ok((0x4000_0000_0000_0000 | drops).to_be_bytes().to_vec())
+        }
+        Value::Object(object) => {
+            let field = |key: &str| {
+                object
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| codec_error(&format!("issued amount has no {key}")))
+            };
+            let mut out = encode_issued_value(field("value")?)?.to_vec();
+            out.extend(encode_currency(field("currency")?, false)?);
+            out.extend(decode_address(field("issuer")?)?);
+            Ok(out)
+        }
+        _ => Err(codec_error("amount is neither drops nor an issued amount")),
+    }
+}
+
+fn encode_currency(code: &str, allow_xrp: bool) -> Result<[u8; 20]> {
+    let mut out = [0u8; 20];
+    if code.len() == 40 {
+        let bytes = hex::decode(code).map_err(|_| codec_error("invalid currency hex"))?;
+        out.copy_from_slice(&bytes);
+        return Ok(out);
+    }
+    if code == "XRP" {
+        return if allow_xrp {
+            Ok(out)
+        } else {
+            Err(codec_error("issued amount cannot use currency XRP"))
+        };
+    }
+    if code.len() != 3 || !code.is_ascii() {
+        return Err(codec_error(&format!("invalid currency code {code}")));
+    }
+    out[12..15].copy_from_slice(code.as_bytes());
+    Ok(out)
+}
+
+/// Issued-currency value: 1 bit "not XRP", 1 bit sign, 8 bits exponent, 54 bits mantissa
+fn encode_issued_value(text: &str) -> Result<[u8; 8]> {
+    let invalid = || codec_error(&format!("invalid issued amount {text}"));
+    let out_of_range = || codec_error(&format!("{text} is out of range"));
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (number, exponent_text) = match unsigned.split_once(['e', 'E']) {
+        Some((number, exponent)) => (number, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let mut exponent: i32 = match exponent_text {
+        Some(exponent) => exponent.parse().map_err(|_| invalid())?,
+        None => 0,
+    };
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    let digits: String = format!("{whole}{fraction}");
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    // Exponent and digit counts come from dApp JSON; never let them wrap
+    exponent = i32::try_from(fraction.len())
+        .ok()
+        .and_then(|len| exponent.checked_sub(len))
+        .ok_or_else(out_of_range)?;
+
+    let digits = digits.trim_start_matches('0');
+    let significant = digits.trim_end_matches('0');
+    if significant.is_empty() {
+        return Ok(0x8000_0000_0000_0000u64.to_be_bytes());
+    }
+    if significant.len() > 16 {
+        return Err(codec_error(&format!(
+            "{text} has more than 16 significant digits"
+        )));
+    }
+    exponent = i32::try_from(digits.len() - significant.len())
+        .ok()
+        .and_then(|zeros| exponent.checked_add(zeros))
+        .ok_or_else(out_of_range)?;
+    let mut mantissa: u64 = significant.parse().map_err(|_| invalid())?;
+    while mantissa < 1_000_000_000_000_000 {
+        mantissa *= 10;
+        exponent = exponent.checked_sub(1).ok_or_else(out_of_range)?;
+    }
+    if !(-96..=80).contains(&exponent) {
+        return Err(out_of_range());
+    }
+
+    let mut bits = 0x8000_0000_0000_0000u64 | mantissa | (((exponent + 97) as u64) << 54);
+    if !negative {
+        bits |= 0x4000_0000_0000_0000;
+    }
+    Ok(bits.to_be_bytes())
+}
+
+fn codec_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid XRP transaction: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const GENESIS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    #[test]
+    fn test_address_round_trip() {
+        let account_id = decode_address(GENESIS).unwrap();
+        assert_eq!(
+            hex::encode_upper(account_id),
+            "B5F762798A53D543A014CAF8B297CFF8F2F937E8"
+        );
+        assert_eq!(encode_address(&account_id), GENESIS);
+        assert_eq!(encode_address(&[0u8; 20]), "rrrrrrrrrrrrrrrrrrrrrhoLvTp");
+        assert!(decode_address("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTi").is_err());
+    }
+
+    #[test]
+    fn test_amount_encoding() {
+        assert_eq!(
+            hex::encode_upper(encode_amount(&json!("1000000")).unwrap()),
+            "40000000000F4240"
+        );
+        let usd =
+            encode_amount(&json!({"value": "1", "currency": "USD", "issuer": GENESIS})).unwrap();
+        assert_eq!(hex::encode_upper(&usd[..8]), "D4838D7EA4C68000");
+        assert_eq!(
+            hex::encode_upper(&usd[8..28]),
+            "0000000000000000000000005553440000000000"
+        );
+        assert_eq!(
+            encode_issued_value("0.001").unwrap(),
+            encode_issued_value("1e-3").unwrap()
+        );
+        assert!(
+            encode_amount(&json!({"value": "1", "currency": "XRP", "issuer": GENESIS})).is_err()
+        );
+    }
+
+    #[test]
+    fn test_extreme_exponents_are_rejected() {
+        for value in [
+            "10e2147483647",
+            "1.5e-2147483648",
+            "1e-2147483648",
+            "100e2147483646",
+            "1e96",
+            "1e-200",
+        ] {
+            let err = encode_issued_value(value).unwrap_err();
+            assert!(err.to_string().contains("out of range"), "{value}: {err}");
+        }
+        assert!(encode_issued_value("1e99999999999").is_err());
+        assert_eq!(
+            encode_issued_value("10e79").unwrap(),
+            encode_issued_value("1e80").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_serializes_fields_in_canonical_order() {
+        let transaction = json!({
+            "Account": GENESIS,
+            "Destination": GENESIS,
+            "Amount": "1000000",
+            "Fee": "10",
+            "Flags": 2147483648u32,
+            "Sequence": 1,
+            "TransactionType": "Payment",
+            "TxnSignature": "00",
+        });
+        let account = "14B5F762798A53D543A014CAF8B297CFF8F2F937E8";
+        let expected = format!(
+            "120000{}{}{}{}81{account}83{account}",
+            "2280000000", "2400000001", "6140000000000F4240", "68400000000000000A"
+        );
+        assert_eq!(
+            hex::encode_upper(serialize(&transaction, true).unwrap()),
+            expected
+        );
+        assert!(hex::encode_upper(serialize(&transaction, false).unwrap()).contains("740100"));
+
+        let data = signing_data(&transaction).unwrap();
+        assert_eq!(&data[..4], b"STX\0");
+        assert_eq!(signing_hash(&transaction).unwrap(), sha512_half(&data));
+
+        let unknown = json!({"TransactionType": "Payment", "Bogus": 1});
+        assert!(serialize(&unknown, true).is_err());
+    }
+}
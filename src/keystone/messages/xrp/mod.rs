@@ -3,6 +3,11 @@
 //! XRP uses JSON-wrapped format (not standard CBOR)
 //! Reference: Keystone SDK uses JSON serialization
 
+pub mod codec;
+pub mod transaction;
+
+pub use transaction::{XrpAmount, XrpTransaction, XrpTransactionKind, XrpWarning};
+
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -43,6 +48,18 @@ impl XrpSignRequest {
         self
     }
 
+    /// Validate `transaction_json` as a typed XRPL transaction
+    pub fn decode_transaction(&self) -> Result<XrpTransaction> {
+        XrpTransaction::parse(&self.transaction_json)
+    }
+
+    /// SHA-512Half signing hash of the canonical binary serialization
+    pub fn signing_hash(&self) -> Result<[u8; 32]> {
+        let value: serde_json::Value = serde_json::from_str(&self.transaction_json)
+            .map_err(|e| Error::InvalidKeystonePayload(format!("Invalid XRP transaction: {e}")))?;
+        codec::signing_hash(&value)
+    }
+
     /// Encode to JSON bytes (for UR wrapping)
     pub fn to_json_bytes(&self) -> Result<Vec<u8>> {
         serde_json::to_vec(self).map_err(|e| Error::Cbor(format!("JSON encoding failed: {}", e)))
@@ -0,0 +1,411 @@
+//! Typed validation of XRPL transaction JSON
+//!
+//! Payment, TrustSet, OfferCreate and NFTokenCreateOffer are checked field by
+//! field (addresses, amounts, required fields); other transaction types only
+//! get the common fields checked.
+
+use super::codec::decode_address;
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+
+const DROPS_PER_XRP: u64 = 1_000_000;
+
+/// Largest valid XRP amount (100 billion XRP)
+const MAX_DROPS: u64 = 100_000_000_000 * DROPS_PER_XRP;
+
+/// `tfPartialPayment`: the destination may receive less than `Amount`
+pub const TF_PARTIAL_PAYMENT: u32 = 0x0002_0000;
+
+/// `tfSellNFToken`: the offer sells an NFT rather than buying one
+pub const TF_SELL_NFTOKEN: u32 = 0x0000_0001;
+
+/// Amount of XRP or an issued currency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XrpAmount {
+    /// XRP in drops
+    Drops(u64),
+    /// Issued currency
+    Issued {
+        /// Decimal value as given
+        value: String,
+        /// Currency code
+        currency: String,
+        /// Issuer address
+        issuer: String,
+    },
+}
+
+impl XrpAmount {
+    /// Display form: `1.5 XRP` or `10 USD (rIssuer...)`
+    pub fn display(&self) -> String {
+        match self {
+            XrpAmount::Drops(drops) => format_drops_as_xrp(*drops),
+            XrpAmount::Issued {
+                value,
+                currency,
+                issuer,
+            } => format!("{value} {currency} ({issuer})"),
+        }
+    }
+}
+
+/// Type-specific transaction fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XrpTransactionKind {
+    /// `Payment`
+    Payment {
+        /// Recipient
+        destination: String,
+        /// Amount to deliver
+        amount: XrpAmount,
+        /// Destination tag identifying the recipient at a shared address
+        destination_tag: Option<u32>,
+        /// Maximum the sender spends (cross-currency payments)
+        send_max: Option<XrpAmount>,
+        /// Minimum delivered (partial payments)
+        deliver_min: Option<XrpAmount>,
+    },
+    /// `TrustSet`
+    TrustSet {
+        /// Trust line currency, issuer and limit
+        limit_amount: XrpAmount,
+    },
+    /// `OfferCreate`
+    OfferCreate {
+        /// Amount the offer creator receives
+        taker_pays: XrpAmount,
+        /// Amount the offer creator gives up
+        taker_gets: XrpAmount,
+        /// Offer expiry (seconds since the Ripple epoch)
+        expiration: Option<u32>,
+        /// Offer to replace
+        offer_sequence: Option<u32>,
+    },
+    /// `NFTokenCreateOffer`
+    NFTokenCreateOffer {
+        /// NFT being offered or bid on
+        nftoken_id: String,
+        /// Asking or bid price
+        amount: XrpAmount,
+        /// Current NFT owner (buy offers only)
+        owner: Option<String>,
+        /// Only this account may accept the offer
+        destination: Option<String>,
+        /// Offer expiry (seconds since the Ripple epoch)
+        expiration: Option<u32>,
+        /// Whether this is a sell offer
+        sell: bool,
+    },
+    /// Any other transaction type (common fields only)
+    Other,
+}
+
+/// Review warning for an XRP transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XrpWarning {
+    /// Payment without a destination tag
+    MissingDestinationTag,
+    /// Payment with `tfPartialPayment` set
+    PartialPayment,
+    /// NFT sell offer for zero
+    ZeroPriceNftSale,
+}
+
+impl XrpWarning {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            XrpWarning::MissingDestinationTag => "missing_destination_tag",
+            XrpWarning::PartialPayment => "partial_payment",
+            XrpWarning::ZeroPriceNftSale => "zero_price_nft_sale",
+        }
+    }
+
+    /// Human-readable explanation
+    pub fn description(&self) -> &'static str {
+        match self {
+            XrpWarning::MissingDestinationTag => {
+                "payment has no destination tag; exchanges and custodians need one to credit the recipient"
+            }
+            XrpWarning::PartialPayment => {
+                "partial payment flag is set; the recipient may receive less than the stated amount"
+            }
+            XrpWarning::ZeroPriceNftSale => "NFT sell offer has a price of zero",
+        }
+    }
+}
+
+/// Validated XRPL transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XrpTransaction {
+    /// `TransactionType` name
+    pub transaction_type: String,
+    /// Sending account
+    pub account: String,
+    /// Fee in drops
+    pub fee: Option<u64>,
+    /// Account sequence number
+    pub sequence: Option<u32>,
+    /// Last ledger the transaction may be included in
+    pub last_ledger_sequence: Option<u32>,
+    /// Transaction flags
+    pub flags: u32,
+    /// Signing public key (hex), if set
+    pub signing_pub_key: Option<String>,
+    /// Number of memos attached
+    pub memo_count: usize,
+    /// Type-specific fields
+    pub kind: XrpTransactionKind,
+}
+
+impl XrpTransaction {
+    /// Parse and validate transaction JSON text
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|err| transaction_error(&format!("not JSON: {err}")))?;
+        Self::from_value(&value)
+    }
+
+    /// Validate a transaction JSON value
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| transaction_error("transaction is not a JSON object"))?;
+        let transaction_type = required_str(object, "TransactionType")?.to_string();
+
+        let kind = match transaction_type.as_str() {
+            "Payment" => XrpTransactionKind::Payment {
+                destination: required_address(object, "Destination")?,
+                amount: required_amount(object, "Amount")?,
+                destination_tag: optional_u32(object, "DestinationTag")?,
+                send_max: optional_amount(object, "SendMax")?,
+                deliver_min: optional_amount(object, "DeliverMin")?,
+            },
+            "TrustSet" => {
+                let limit_amount = required_amount(object, "LimitAmount")?;
+                if matches!(limit_amount, XrpAmount::Drops(_)) {
+                    return Err(transaction_error(
+                        "TrustSet LimitAmount must be an issued currency",
+                    ));
+                }
+                XrpTransactionKind::TrustSet { limit_amount }
+            }
+            "OfferCreate" => XrpTransactionKind::OfferCreate {
+                taker_pays: required_amount(object, "TakerPays")?,
+                taker_gets: required_amount(object, "TakerGets")?,
+                expiration: optional_u32(object, "Expiration")?,
+                offer_sequence: optional_u32(object, "OfferSequence")?,
+            },
+            "NFTokenCreateOffer" => {
+                let nftoken_id = required_str(object, "NFTokenID")?;
+                if nftoken_id.len() != 64 || hex::decode(nftoken_id).is_err() {
+                    return Err(transaction_error("NFTokenID must be 32 bytes of hex"));
+                }
+                let sell = optional_u32(object, "Flags")?.unwrap_or(0) & TF_SELL_NFTOKEN != 0;
+                let owner = optional_address(object, "Owner")?;
+                if !sell && owner.is_none() {
+                    return Err(transaction_error("NFT buy offer requires Owner"));
+                }
+                XrpTransactionKind::NFTokenCreateOffer {
+                    nftoken_id: nftoken_id.to_uppercase(),
+                    amount: required_amount(object, "Amount")?,
+                    owner,
+                    destination: optional_address(object, "Destination")?,
+                    expiration: optional_u32(object, "Expiration")?,
+                    sell,
+                }
+            }
+            _ => XrpTransactionKind::Other,
+        };
+
+        let fee = match optional_amount(object, "Fee")? {
+            Some(XrpAmount::Drops(drops)) => Some(drops),
+            Some(XrpAmount::Issued { .. }) => {
+                return Err(transaction_error("Fee must be in drops"));
+            }
+            None => None,
+        };
+        let signing_pub_key = match object.get("SigningPubKey") {
+            Some(Value::String(key)) if hex::decode(key).is_ok() => Some(key.clone()),
+            Some(_) => return Err(transaction_error("SigningPubKey must be hex")),
+            None => None,
+        };
+        let memo_count = match object.get("Memos") {
+            Some(Value::Array(memos)) => memos.len(),
+            Some(_) => return Err(transaction_error("Memos must be an array")),
+            None => 0,
+        };
+
+        Ok(Self {
+            transaction_type,
+            account: required_address(object, "Account")?,
+            fee,
+            sequence: optional_u32(object, "Sequence")?,
+            last_ledger_sequence: optional_u32(object, "LastLedgerSequence")?,
+            flags: optional_u32(object, "Flags")?.unwrap_or(0),
+            signing_pub_key,
+            memo_count,
+            kind,
+        })
+    }
+
+    /// Review warnings for this transaction
+    pub fn warnings(&self) -> Vec<XrpWarning> {
+        let mut warnings = Vec::new();
+        match &self.kind {
+            XrpTransactionKind::Payment {
+                destination_tag, ..
+            } => {
+                if destination_tag.is_none() {
+                    warnings.push(XrpWarning::MissingDestinationTag);
+                }
+                if self.flags & TF_PARTIAL_PAYMENT != 0 {
+                    warnings.push(XrpWarning::PartialPayment);
+                }
+            }
+            XrpTransactionKind::NFTokenCreateOffer {
+                amount: XrpAmount::Drops(0),
+                sell: true,
+                ..
+            } => warnings.push(XrpWarning::ZeroPriceNftSale),
+            _ => {}
+        }
+        warnings
+    }
+}
+
+/// Format a drop amount as decimal XRP, e.g. `1.5 XRP`
+pub fn format_drops_as_xrp(drops: u64) -> String {
+    let whole = drops / DROPS_PER_XRP;
+    let fraction = drops % DROPS_PER_XRP;
+    if fraction == 0 {
+        return format!("{whole} XRP");
+    }
+    let fraction = format!("{fraction:06}");
+    format!("{whole}.{} XRP", fraction.trim_end_matches('0'))
+}
+
+fn required_str<'a>(object: &'a Map<String, Value>, field: &str) -> Result<&'a str> {
+    object
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| transaction_error(&format!("missing {field}")))
+}
+
+fn required_address(object: &Map<String, Value>, field: &str) -> Result<String> {
+    optional_address(object, field)?.ok_or_else(|| transaction_error(&format!("missing {field}")))
+}
+
+fn optional_address(object: &Map<String, Value>, field: &str) -> Result<Option<String>> {
+    match object.get(field) {
+        Some(Value::String(address)) => {
+            decode_address(address)?;
+            Ok(Some(address.clone()))
+        }
+        Some(_) => Err(transaction_error(&format!("{field} must be an address"))),
+        None => Ok(None),
+    }
+}
+
+fn optional_u32(object: &Map<String, Value>, field: &str) -> Result<Option<u32>> {
+    match object.get(field) {
+        Some(value) => value
+            .as_u64()
+            .and_then(|number| u32::try_from(number).ok())
+            .map(Some)
+            .ok_or_else(|| transaction_error(&format!("{field} must be a 32-bit integer"))),
+        None => Ok(None),
+    }
+}
+
+fn required_amount(object: &Map<String, Value>, field: &str) -> Result<XrpAmount> {
+    optional_amount(object, field)?.ok_or_else(|| transaction_error(&format!("missing {field}")))
+}
+
+fn optional_amount(object: &Map<String, Value>, field: &str) -> Result<Option<XrpAmount>> {
+    let Some(value) = object.get(field) else {
+        return Ok(None);
+    };
+    let invalid = || transaction_error(&format!("{field} is not a valid amount"));
+    match value {
+        Value::String(drops) => {
+            let drops: u64 = drops.parse().map_err(|_| invalid())?;
+            if drops > MAX_DROPS {
+                return Err(invalid());
+            }
+            Ok(Some(XrpAmount::Drops(drops)))
+        }
+        Value::Object(amount) => {
+            let value = required_str(amount, "value")?;
+            if value.parse::<f64>().is_err() {
+                return Err(invalid());
+            }
+            let issuer = required_address(amount, "issuer")?;
+            Ok(Some(XrpAmount::Issued {
+                value: value.to_string(),
+                currency: required_str(amount, "currency")?.to_string(),
+                issuer,
+            }))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn transaction_error(message: &str) -> Error {
+    Error::InvalidKeystonePayload(format!("Invalid XRP transaction: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+    const BOB: &str = "rrrrrrrrrrrrrrrrrrrrBZbvji";
+
+    #[test]
+    fn test_payment_checks_and_warnings() {
+        let json = format!(
+            r#"{{"TransactionType":"Payment","Account":"{ALICE}","Destination":"{BOB}","Amount":"1500000","Fee":"12","Flags":131072}}"#
+        );
+        let tx = XrpTransaction::parse(&json).unwrap();
+        assert_eq!(tx.fee, Some(12));
+        match &tx.kind {
+            XrpTransactionKind::Payment { amount, .. } => {
+                assert_eq!(amount.display(), "1.5 XRP");
+            }
+            other => panic!("unexpected kind {other:?}"),
+        }
+        assert_eq!(
+            tx.warnings(),
+            vec![
+                XrpWarning::MissingDestinationTag,
+                XrpWarning::PartialPayment
+            ]
+        );
+
+        let bad_destination = json.replace(BOB, "rNotAnAddress");
+        assert!(XrpTransaction::parse(&bad_destination).is_err());
+    }
+
+    #[test]
+    fn test_trust_set_and_nft_offer_checks() {
+        let trust = format!(
+            r#"{{"TransactionType":"TrustSet","Account":"{ALICE}","LimitAmount":{{"currency":"USD","issuer":"{BOB}","value":"100"}}}}"#
+        );
+        assert!(XrpTransaction::parse(&trust).unwrap().warnings().is_empty());
+        let xrp_trust =
+            format!(r#"{{"TransactionType":"TrustSet","Account":"{ALICE}","LimitAmount":"100"}}"#);
+        assert!(XrpTransaction::parse(&xrp_trust).is_err());
+
+        let nft_id = "00".repeat(32);
+        let buy = format!(
+            r#"{{"TransactionType":"NFTokenCreateOffer","Account":"{ALICE}","NFTokenID":"{nft_id}","Amount":"0"}}"#
+        );
+        assert!(XrpTransaction::parse(&buy).is_err());
+        let sell = buy.replace(r#""Amount""#, r#""Flags":1,"Amount""#);
+        assert_eq!(
+            XrpTransaction::parse(&sell).unwrap().warnings(),
+            vec![XrpWarning::ZeroPriceNftSale]
+        );
+    }
+}
@@ -9,12 +9,16 @@ pub mod cbor;
 pub mod crypto_keypath;
 pub mod messages;
 pub mod multipart;
+pub mod registry;
 mod types;
 mod ur;
+pub mod verify;
 
 pub use crypto_keypath::{CryptoKeyPath, PathComponent};
 pub use messages::*;
+pub use registry::{CustomMessage, UrMessageRegistry, UrTypeHandler};
 pub use types::{KeystoneMessage, KeystoneMetadata, KeystonePayload, PayloadEncoding};
+pub use ur::{encode_ur_with_encoder, encode_ur_with_fragments};
 
 use crate::error::{Error, Result};
 use crate::qr::QrPayload;
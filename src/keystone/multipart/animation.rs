@@ -0,0 +1,276 @@
+//! Animated QR rendering for outbound multi-part UR payloads
+//!
+//! Combines [`MultiPartEncoder`] with [`QrEncoder`] into a timed frame
+//! sequence that a Keystone can scan from a screen, and exports it as an
+//! animated GIF or APNG.
+
+use crate::error::{Error, Result};
+use crate::keystone::KeystonePayload;
+use crate::qr::QrEncoder;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, GrayImage, Luma, imageops};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use super::{MultiPartEncoder, RECOMMENDED_FRAME_DELAY_MS};
+
+/// One rendered frame of an animated QR sequence
+#[derive(Debug, Clone)]
+pub struct QrFrame {
+    /// Position of this frame in the sequence (0-based)
+    pub index: usize,
+    /// UR string encoded in this frame
+    pub ur: String,
+    /// Rendered QR image
+    pub image: DynamicImage,
+    /// How long the frame should stay on screen
+    pub delay: Duration,
+}
+
+/// Timed, endless QR frame sequence for a UR payload
+///
+/// Single-part payloads repeat the same frame. Multi-part payloads keep
+/// drawing parts from the fountain encoder, so frames past
+/// [`fragment_count`](Self::fragment_count) carry fresh mixed parts instead
+/// of repeating the first pass (see [`MultiPartEncoder::with_fountain_ratio`]).
+pub struct AnimatedQr {
+    parts: MultiPartEncoder,
+    frames_emitted: usize,
+    encoder: QrEncoder,
+    frame_delay: Duration,
+}
+
+impl AnimatedQr {
+    /// Create an animation for raw UR data
+    pub fn new(ur_type: &str, data: &[u8], max_fragment_len: usize) -> Result<Self> {
+        Ok(Self::from_encoder(MultiPartEncoder::new(
+            ur_type,
+            data,
+            max_fragment_len,
+        )?))
+    }
+
+    /// Create an animation from an existing part encoder
+    pub fn from_encoder(parts: MultiPartEncoder) -> Self {
+        Self {
+            parts,
+            frames_emitted: 0,
+            encoder: QrEncoder::new(),
+            frame_delay: Duration::from_millis(RECOMMENDED_FRAME_DELAY_MS),
+        }
+    }
+
+    /// Create an animation for a Keystone payload
+    pub fn from_payload(payload: &KeystonePayload, max_fragment_len: usize) -> Result<Self> {
+        Self::new(&payload.ur_type, &payload.data, max_fragment_len)
+    }
+
+    /// Override the per-frame display time
+    pub fn with_frame_delay(mut self, delay: Duration) -> Self {
+        self.frame_delay = delay;
+        self
+    }
+
+    /// Use a custom QR encoder (e.g. a different ECC level)
+    pub fn with_qr_encoder(mut self, encoder: QrEncoder) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// Per-frame display time
+    pub fn frame_delay(&self) -> Duration {
+        self.frame_delay
+    }
+
+    /// Whether the payload needs more than one frame
+    pub fn is_animated(&self) -> bool {
+        self.parts.is_multipart()
+    }
+
+    /// Number of pure fragments in one pass over the payload
+    pub fn fragment_count(&self) -> usize {
+        self.parts.part_count()
+    }
+
+    /// Produce the next UR part without rendering it
+    pub fn next_ur(&mut self) -> String {
+        self.parts.next_part().ur_string
+    }
+
+    /// Render the next frame of the sequence
+    pub fn next_frame(&mut self) -> Result<QrFrame> {
+        let ur = self.next_ur();
+        // Uppercase UR text fits QR alphanumeric mode, giving smaller codes
+        let image = self.encoder.encode_string(&ur.to_ascii_uppercase())?;
+        let frame = QrFrame {
+            index: self.frames_emitted,
+            ur,
+            image,
+            delay: self.frame_delay,
+        };
+        self.frames_emitted += 1;
+        Ok(frame)
+    }
+
+    /// Render `count` consecutive frames
+    pub fn frames(&mut self, count: usize) -> Result<Vec<QrFrame>> {
+        (0..count).map(|_| self.next_frame()).collect()
+    }
+
+    /// Suggested frame count for a looping export: every fragment plus a
+    /// second pass of fountain parts to cover missed frames
+    pub fn recommended_frame_count(&self) -> usize {
+        if self.is_animated() {
+            self.fragment_count() * 2
+        } else {
+            1
+        }
+    }
+
+    /// Write `frame_count` frames as a looping animated GIF
+    pub fn write_gif<W: Write>(&mut self, writer: W, frame_count: usize) -> Result<()> {
+        let images = self.rendered_images(frame_count)?;
+        let delay = Delay::from_saturating_duration(self.frame_delay);
+
+        let mut encoder = GifEncoder::new_with_speed(writer, 10);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| Error::Image(format!("Failed to configure GIF: {e}")))?;
+        for image in images {
+            let rgba = DynamicImage::ImageLuma8(image).to_rgba8();
+            encoder
+                .encode_frame(Frame::from_parts(rgba, 0, 0, delay))
+                .map_err(|e| Error::Image(format!("Failed to encode GIF frame: {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// Write `frame_count` frames as a looping animated PNG
+    pub fn write_apng<W: Write>(&mut self, writer: W, frame_count: usize) -> Result<()> {
+        let images = self.rendered_images(frame_count)?;
+        let (width, height) = images[0].dimensions();
+        let delay_ms = self.frame_delay.as_millis().min(u16::MAX as u128) as u16;
+
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(images.len() as u32, 0)
+            .map_err(apng_error)?;
+        encoder
+            .set_frame_delay(delay_ms, 1000)
+            .map_err(apng_error)?;
+
+        let mut writer = encoder.write_header().map_err(apng_error)?;
+        for image in &images {
+            writer
+                .write_image_data(image.as_raw())
+                .map_err(apng_error)?;
+        }
+        writer.finish().map_err(apng_error)
+    }
+
+    /// Save `frame_count` frames to `path`, choosing GIF or APNG from the extension
+    pub fn save(&mut self, path: &Path, frame_count: usize) -> Result<()> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+
+        let writer =
+            |path: &Path| -> Result<BufWriter<File>> { Ok(BufWriter::new(File::create(path)?)) };
+        match extension.as_str() {
+            "gif" => self.write_gif(writer(path)?, frame_count),
+            "png" | "apng" => self.write_apng(writer(path)?, frame_count),
+            other => Err(Error::Image(format!(
+                "Unsupported animation format '{other}', expected gif, png or apng"
+            ))),
+        }
+    }
+
+    /// Render frames as grayscale images padded to a common size
+    fn rendered_images(&mut self, frame_count: usize) -> Result<Vec<GrayImage>> {
+        let frames = self.frames(frame_count.max(1))?;
+        let images: Vec<GrayImage> = frames.into_iter().map(|f| f.image.to_luma8()).collect();
+
+        // Sequence numbers can push a part into the next QR version
+        let width = images.iter().map(|i| i.width()).max().unwrap_or(0);
+        let height = images.iter().map(|i| i.height()).max().unwrap_or(0);
+
+        Ok(images
+            .into_iter()
+            .map(|image| {
+                if image.dimensions() == (width, height) {
+                    return image;
+                }
+                let mut canvas = GrayImage::from_pixel(width, height, Luma([255u8]));
+                let x = (width - image.width()) / 2;
+                let y = (height - image.height()) / 2;
+                imageops::overlay(&mut canvas, &image, x as i64, y as i64);
+                canvas
+            })
+            .collect())
+    }
+}
+
+impl Iterator for AnimatedQr {
+    type Item = Result<QrFrame>;
+
+    /// Endless frame stream; display each frame for `frame.delay`
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_frame())
+    }
+}
+
+fn apng_error(err: png::EncodingError) -> Error {
+    Error::Image(format!("Failed to encode APNG: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_part_repeats() {
+        let mut animation = AnimatedQr::new("bytes", b"short", 400).unwrap();
+        assert!(!animation.is_animated());
+        assert_eq!(animation.recommended_frame_count(), 1);
+
+        let first = animation.next_frame().unwrap();
+        let second = animation.next_frame().unwrap();
+        assert_eq!(first.ur, second.ur);
+        assert_eq!(second.index, 1);
+    }
+
+    #[test]
+    fn test_fountain_parts_continue_past_fragment_count() {
+        let data = vec![0x5au8; 300];
+        let mut animation = AnimatedQr::new("bytes", &data, 60).unwrap();
+        assert!(animation.is_animated());
+
+        let count = animation.fragment_count();
+        let parts: Vec<String> = (0..count * 2).map(|_| animation.next_ur()).collect();
+        assert_ne!(parts[0], parts[count]);
+    }
+
+    #[test]
+    fn test_gif_and_apng_export() {
+        let data = vec![0x42u8; 200];
+        let mut animation = AnimatedQr::new("bytes", &data, 60)
+            .unwrap()
+            .with_frame_delay(Duration::from_millis(100));
+        let frames = animation.fragment_count() + 1;
+
+        let mut gif = Vec::new();
+        animation.write_gif(&mut gif, frames).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+
+        let mut apng = Vec::new();
+        animation.write_apng(&mut apng, frames).unwrap();
+        assert!(apng.starts_with(b"\x89PNG"));
+        assert!(apng.windows(4).any(|w| w == b"acTL"));
+    }
+}
@@ -97,7 +97,8 @@ impl MultiPartDecoder {
         self.current_progress()
     }
 
-    fn current_progress(&self) -> Result<DecodeProgress> {
+    /// Progress snapshot for the fragments received so far
+    pub fn current_progress(&self) -> Result<DecodeProgress> {
         let decoder = self
             .decoder
             .as_ref()
@@ -154,15 +155,17 @@ impl MultiPartDecoder {
 
         let (sequence, total_parts) = parse_fragment_metadata(first_part);
 
+        let encoding = ur::payload_encoding(&ur_type, &message);
+
         Ok(KeystonePayload {
-            ur_type: ur_type.clone(),
+            ur_type,
             data: message,
             metadata: KeystoneMetadata {
                 sequence,
                 total_parts,
                 multipart: true,
             },
-            encoding: ur::payload_encoding(&ur_type),
+            encoding,
         })
     }
 
@@ -3,10 +3,20 @@
 use crate::error::Result;
 use crate::keystone::ur;
 
-/// Multi-part QR encoder with cyclic iteration
+use super::DEFAULT_FOUNTAIN_RATIO;
+
+/// Multi-part QR encoder
+///
+/// The first pass emits each pure fragment once. After that, parts are drawn
+/// from the underlying fountain encoder (mixed parts, per the UR spec) at the
+/// configured ratio, with the remaining frames cycling the pure fragments.
 pub struct MultiPartEncoder {
     parts: Vec<String>,
+    fountain: Option<ur::Encoder>,
+    fountain_ratio: f32,
+    fountain_credit: f32,
     current_index: usize,
+    emitted: usize,
 }
 
 /// Result of encoding operation
@@ -14,25 +24,42 @@ pub struct MultiPartEncoder {
 pub struct EncodeResult {
     /// The UR string for this part
     pub ur_string: String,
-    /// Current part number (1-indexed)
+    /// Part sequence number (1-indexed; exceeds `total_parts` for fountain parts)
     pub part_num: usize,
     /// Total number of parts
     pub total_parts: usize,
     /// Whether this is a multi-part encoding
     pub is_multipart: bool,
+    /// Whether this is a fresh fountain part generated after the first pass
+    pub is_fountain: bool,
 }
 
 impl MultiPartEncoder {
     /// Create a new multi-part encoder
     pub fn new(ur_type: &str, data: &[u8], max_fragment_len: usize) -> Result<Self> {
-        let (parts, _is_multipart) = ur::encode_ur_with_fragments(ur_type, data, max_fragment_len)?;
+        let (parts, fountain) = ur::encode_ur_with_encoder(ur_type, data, max_fragment_len)?;
 
         Ok(Self {
             parts,
+            fountain,
+            fountain_ratio: DEFAULT_FOUNTAIN_RATIO,
+            fountain_credit: 0.0,
             current_index: 0,
+            emitted: 0,
         })
     }
 
+    /// Set the share of frames after the first pass that are fresh fountain
+    /// parts (`0.0` cycles pure fragments only, `1.0` emits only fountain parts)
+    pub fn with_fountain_ratio(mut self, ratio: f32) -> Self {
+        self.fountain_ratio = if ratio.is_nan() {
+            DEFAULT_FOUNTAIN_RATIO
+        } else {
+            ratio.clamp(0.0, 1.0)
+        };
+        self
+    }
+
     /// Check if this is a multi-part encoding
     pub fn is_multipart(&self) -> bool {
         self.parts.len() > 1
@@ -43,15 +70,22 @@ impl MultiPartEncoder {
         self.parts.len()
     }
 
-    /// Get the next part (cyclic - wraps around)
+    /// Get the next part; never runs out
     pub fn next_part(&mut self) -> EncodeResult {
-        let part = &self.parts[self.current_index];
-        let result = EncodeResult {
-            ur_string: part.clone(),
-            part_num: self.current_index + 1,
-            total_parts: self.parts.len(),
-            is_multipart: self.is_multipart(),
-        };
+        let first_pass = self.emitted < self.parts.len();
+        self.emitted += 1;
+
+        if !first_pass && self.fountain.is_some() {
+            self.fountain_credit += self.fountain_ratio;
+            if self.fountain_credit >= 1.0 {
+                self.fountain_credit -= 1.0;
+                if let Some(result) = self.next_fountain_part() {
+                    return result;
+                }
+            }
+        }
+
+        let result = self.pure_part(self.current_index);
 
         // Advance to next part (cyclic)
         self.current_index = (self.current_index + 1) % self.parts.len();
@@ -59,28 +93,55 @@ impl MultiPartEncoder {
         result
     }
 
-    /// Get a specific part by index (0-based)
-    pub fn part_at(&self, index: usize) -> Option<EncodeResult> {
-        if index >= self.parts.len() {
-            return None;
+    fn next_fountain_part(&mut self) -> Option<EncodeResult> {
+        let fountain = self.fountain.as_mut()?;
+        match fountain.next_part() {
+            Ok(ur_string) => Some(EncodeResult {
+                ur_string,
+                part_num: fountain.current_index(),
+                total_parts: self.parts.len(),
+                is_multipart: true,
+                is_fountain: true,
+            }),
+            Err(e) => {
+                tracing::warn!("Fountain encoder failed, falling back to pure fragments: {e:?}");
+                self.fountain = None;
+                None
+            }
         }
+    }
 
-        Some(EncodeResult {
+    fn pure_part(&self, index: usize) -> EncodeResult {
+        EncodeResult {
             ur_string: self.parts[index].clone(),
             part_num: index + 1,
             total_parts: self.parts.len(),
             is_multipart: self.is_multipart(),
-        })
+            is_fountain: false,
+        }
     }
 
-    /// Get all parts as a vector
+    /// Get a specific part of the first pass by index (0-based)
+    pub fn part_at(&self, index: usize) -> Option<EncodeResult> {
+        if index >= self.parts.len() {
+            return None;
+        }
+
+        Some(self.pure_part(index))
+    }
+
+    /// Get all pure fragments (the first pass) as a vector
     pub fn all_parts(&self) -> Vec<String> {
         self.parts.clone()
     }
 
-    /// Reset the cyclic counter to the beginning
+    /// Restart from the first pure fragment
+    ///
+    /// Fountain parts are not rewound; the next ones generated are still fresh.
     pub fn reset(&mut self) {
         self.current_index = 0;
+        self.emitted = 0;
+        self.fountain_credit = 0.0;
     }
 }
 
@@ -112,4 +173,43 @@ mod tests {
         let part = encoder.next_part();
         assert_eq!(part.part_num, 1);
     }
+
+    #[test]
+    fn test_fountain_parts_after_first_pass() {
+        let data = vec![7u8; 300];
+        let mut encoder = MultiPartEncoder::new("test", &data, 60).unwrap();
+        let count = encoder.part_count();
+
+        for index in 0..count {
+            let part = encoder.next_part();
+            assert!(!part.is_fountain);
+            assert_eq!(part.part_num, index + 1);
+        }
+
+        let fresh = encoder.next_part();
+        assert!(fresh.is_fountain);
+        assert!(fresh.part_num > count);
+        assert!(!encoder.all_parts().contains(&fresh.ur_string));
+    }
+
+    #[test]
+    fn test_fountain_ratio() {
+        let data = vec![7u8; 300];
+        let mut encoder = MultiPartEncoder::new("test", &data, 60)
+            .unwrap()
+            .with_fountain_ratio(0.5);
+        let count = encoder.part_count();
+        for _ in 0..count {
+            encoder.next_part();
+        }
+
+        let fountain = (0..10).filter(|_| encoder.next_part().is_fountain).count();
+        assert_eq!(fountain, 5);
+
+        let mut cycling = MultiPartEncoder::new("test", &data, 60)
+            .unwrap()
+            .with_fountain_ratio(0.0);
+        let first_pass: Vec<String> = (0..count).map(|_| cycling.next_part().ur_string).collect();
+        assert_eq!(cycling.next_part().ur_string, first_pass[0]);
+    }
 }
@@ -2,17 +2,23 @@
 //!
 //! Implements cyclic encoding for animated QR codes and progressive decoding.
 
+mod animation;
 mod decoder;
 mod encoder;
 
+pub use animation::{AnimatedQr, QrFrame};
 pub use decoder::{DecodeProgress, MultiPartDecoder};
 pub use encoder::{EncodeResult, MultiPartEncoder};
+
 /// Default maximum fragment length (works well for most QR scanners)
 pub const DEFAULT_MAX_FRAGMENT_LEN: usize = 400;
 
 /// Recommended display time per frame (milliseconds)
 pub const RECOMMENDED_FRAME_DELAY_MS: u64 = 150;
 
+/// Default share of fresh fountain parts emitted after the first pass
+pub const DEFAULT_FOUNTAIN_RATIO: f32 = 1.0;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,369 @@
+//! Pluggable UR type registry
+//!
+//! [`KeystoneMessage::from_ur_type`] only knows the built-in registry types.
+//! Applications with their own UR types register a [`UrTypeHandler`] per type
+//! string: a decoder plus optional encoder and JSON / human renderers. The
+//! built-in types are pre-registered with just their decoder: their variants
+//! encode through `KeystonePayload::from` and `output` keeps its own
+//! rendering for them unless an application overrides the type.
+//!
+//! [`UrMessageRegistry::global`] is the process-wide registry consulted by
+//! [`KeystonePayload::message_type`] and `output::render_keystone_payload`.
+
+use crate::error::{Error, Result};
+use crate::keystone::types::{KeystoneMessage, KeystoneMetadata, KeystonePayload};
+use crate::keystone::ur::payload_encoding;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// UR types decoded by [`KeystoneMessage::from_ur_type`]
+pub const BUILTIN_UR_TYPES: &[&str] = &[
+    "crypto-account",
+    "crypto-hdkey",
+    "crypto-multi-accounts",
+    "crypto-output",
+    "crypto-psbt",
+    "psbt",
+    "eth-sign-request",
+    "eth-signature",
+    "aptos-sign-request",
+    "aptos-signature",
+    "bytes",
+    "cardano-sign-request",
+    "cardano-signature",
+    "cosmos-sign-request",
+    "cosmos-signature",
+    "hbar-sign-request",
+    "hbar-signature",
+    "keystone-sign-request",
+    "near-sign-request",
+    "near-signature",
+    "sol-sign-request",
+    "sol-signature",
+    "stellar-sign-request",
+    "stellar-signature",
+    "sui-sign-request",
+    "sui-signature",
+    "xrp-sign-request",
+    "xrp-signature",
+];
+
+static GLOBAL: OnceLock<RwLock<Arc<UrMessageRegistry>>> = OnceLock::new();
+
+/// Decoder from UR type and body to a message
+pub type DecodeFn = Arc<dyn Fn(&str, &[u8]) -> Result<KeystoneMessage> + Send + Sync>;
+/// Encoder from a message to a UR body
+pub type EncodeFn = Arc<dyn Fn(&KeystoneMessage) -> Result<Vec<u8>> + Send + Sync>;
+/// Renderer producing the `message` JSON value
+pub type JsonRenderFn = Arc<dyn Fn(&KeystoneMessage) -> Value + Send + Sync>;
+/// Renderer producing human-readable lines (starting with `  Variant: ...`)
+pub type HumanRenderFn = Arc<dyn Fn(&KeystoneMessage) -> Vec<String> + Send + Sync>;
+
+/// Application-defined message carried by [`KeystoneMessage::Custom`]
+#[derive(Clone)]
+pub struct CustomMessage {
+    ur_type: String,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl CustomMessage {
+    /// Wrap an application value decoded from `ur_type`
+    pub fn new<T: Any + Send + Sync>(ur_type: impl Into<String>, value: T) -> Self {
+        Self {
+            ur_type: ur_type.into(),
+            value: Arc::new(value),
+        }
+    }
+
+    /// UR type the value was decoded from
+    pub fn ur_type(&self) -> &str {
+        &self.ur_type
+    }
+
+    /// Borrow the value as its concrete type
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+}
+
+impl fmt::Debug for CustomMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomMessage")
+            .field("ur_type", &self.ur_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decoder, encoder and renderers for one UR type
+#[derive(Clone)]
+pub struct UrTypeHandler {
+    decode: DecodeFn,
+    encode: Option<EncodeFn>,
+    render_json: Option<JsonRenderFn>,
+    render_human: Option<HumanRenderFn>,
+}
+
+impl UrTypeHandler {
+    /// Create a handler from a decoder
+    pub fn new<F>(decode: F) -> Self
+    where
+        F: Fn(&str, &[u8]) -> Result<KeystoneMessage> + Send + Sync + 'static,
+    {
+        Self {
+            decode: Arc::new(decode),
+            encode: None,
+            render_json: None,
+            render_human: None,
+        }
+    }
+
+    /// Attach an encoder used when turning messages back into payloads
+    pub fn with_encoder<F>(mut self, encode: F) -> Self
+    where
+        F: Fn(&KeystoneMessage) -> Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.encode = Some(Arc::new(encode));
+        self
+    }
+
+    /// Attach a renderer for the structured JSON output
+    pub fn with_json_renderer<F>(mut self, render: F) -> Self
+    where
+        F: Fn(&KeystoneMessage) -> Value + Send + Sync + 'static,
+    {
+        self.render_json = Some(Arc::new(render));
+        self
+    }
+
+    /// Attach a renderer for the human-readable output
+    pub fn with_human_renderer<F>(mut self, render: F) -> Self
+    where
+        F: Fn(&KeystoneMessage) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.render_human = Some(Arc::new(render));
+        self
+    }
+
+    fn builtin() -> Self {
+        Self::new(KeystoneMessage::from_ur_type)
+    }
+}
+
+impl fmt::Debug for UrTypeHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UrTypeHandler")
+            .field("encode", &self.encode.is_some())
+            .field("render_json", &self.render_json.is_some())
+            .field("render_human", &self.render_human.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Map from UR type string to its handler
+#[derive(Debug, Clone)]
+pub struct UrMessageRegistry {
+    handlers: HashMap<String, UrTypeHandler>,
+}
+
+impl Default for UrMessageRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UrMessageRegistry {
+    /// Registry with the built-in UR types pre-registered
+    pub fn new() -> Self {
+        let handlers = BUILTIN_UR_TYPES
+            .iter()
+            .map(|ur_type| (ur_type.to_string(), UrTypeHandler::builtin()))
+            .collect();
+        Self { handlers }
+    }
+
+    /// Registry with no types registered
+    pub fn empty() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Register (or replace) the handler for `ur_type`
+    ///
+    /// Returns the previous handler, if any.
+    pub fn register(
+        &mut self,
+        ur_type: impl Into<String>,
+        handler: UrTypeHandler,
+    ) -> Option<UrTypeHandler> {
+        self.handlers.insert(ur_type.into(), handler)
+    }
+
+    /// Handler registered for `ur_type`
+    pub fn handler(&self, ur_type: &str) -> Option<&UrTypeHandler> {
+        self.handlers.get(ur_type)
+    }
+
+    /// Whether `ur_type` has a handler
+    pub fn contains(&self, ur_type: &str) -> bool {
+        self.handlers.contains_key(ur_type)
+    }
+
+    /// Decode a UR body, keeping unregistered types as [`KeystoneMessage::Unknown`]
+    pub fn decode(&self, ur_type: &str, data: &[u8]) -> Result<KeystoneMessage> {
+        match self.handler(ur_type) {
+            Some(handler) => (handler.decode)(ur_type, data),
+            None => Ok(KeystoneMessage::Unknown {
+                ur_type: ur_type.to_string(),
+                data: data.to_vec(),
+            }),
+        }
+    }
+
+    /// Encode a message into a payload
+    ///
+    /// Built-in variants always encode; custom messages need an encoder
+    /// registered for their UR type.
+    pub fn encode(&self, message: &KeystoneMessage) -> Result<KeystonePayload> {
+        let KeystoneMessage::Custom(custom) = message else {
+            return Ok(KeystonePayload::from(message.clone()));
+        };
+        let encode = self
+            .handler(custom.ur_type())
+            .and_then(|handler| handler.encode.as_ref())
+            .ok_or_else(|| {
+                Error::InvalidKeystonePayload(format!(
+                    "No encoder registered for UR type {}",
+                    custom.ur_type()
+                ))
+            })?;
+        let data = encode(message)?;
+        Ok(KeystonePayload {
+            ur_type: custom.ur_type().to_string(),
+            encoding: payload_encoding(custom.ur_type(), &data),
+            metadata: KeystoneMetadata::default(),
+            data,
+        })
+    }
+
+    /// JSON rendering from the handler registered for `ur_type`, if it has one
+    pub fn render_json(&self, ur_type: &str, message: &KeystoneMessage) -> Option<Value> {
+        let render = self.handler(ur_type)?.render_json.as_ref()?;
+        Some(render(message))
+    }
+
+    /// Human rendering from the handler registered for `ur_type`, if it has one
+    pub fn render_human(&self, ur_type: &str, message: &KeystoneMessage) -> Option<Vec<String>> {
+        let render = self.handler(ur_type)?.render_human.as_ref()?;
+        Some(render(message))
+    }
+
+    /// Snapshot of the process-wide registry
+    pub fn global() -> Arc<UrMessageRegistry> {
+        let lock = GLOBAL.get_or_init(|| RwLock::new(Arc::new(UrMessageRegistry::new())));
+        Arc::clone(&lock.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    /// Register a handler in the process-wide registry
+    ///
+    /// Snapshots already handed out by [`UrMessageRegistry::global`] are not
+    /// affected.
+    pub fn register_global(
+        ur_type: impl Into<String>,
+        handler: UrTypeHandler,
+    ) -> Option<UrTypeHandler> {
+        let lock = GLOBAL.get_or_init(|| RwLock::new(Arc::new(UrMessageRegistry::new())));
+        let mut global = lock
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::make_mut(&mut global).register(ur_type, handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct VaultApproval {
+        vault: String,
+    }
+
+    fn approval_handler() -> UrTypeHandler {
+        UrTypeHandler::new(|ur_type, data| {
+            let vault = String::from_utf8(data.to_vec())
+                .map_err(|e| Error::InvalidKeystonePayload(e.to_string()))?;
+            Ok(KeystoneMessage::Custom(CustomMessage::new(
+                ur_type,
+                VaultApproval { vault },
+            )))
+        })
+        .with_encoder(|message| match message {
+            KeystoneMessage::Custom(custom) => Ok(custom
+                .downcast_ref::<VaultApproval>()
+                .map(|approval| approval.vault.as_bytes().to_vec())
+                .unwrap_or_default()),
+            _ => Err(Error::InvalidKeystonePayload("not an approval".to_string())),
+        })
+    }
+
+    #[test]
+    fn test_builtin_types_are_preregistered() {
+        let registry = UrMessageRegistry::new();
+        assert!(
+            BUILTIN_UR_TYPES
+                .iter()
+                .all(|ur_type| registry.contains(ur_type))
+        );
+        assert!(
+            registry
+                .render_json(
+                    "eth-signature",
+                    &KeystoneMessage::Unknown {
+                        ur_type: String::new(),
+                        data: Vec::new(),
+                    }
+                )
+                .is_none()
+        );
+
+        let message = registry.decode("x-custody-approval", &[1, 2]).unwrap();
+        assert!(matches!(message, KeystoneMessage::Unknown { .. }));
+        assert!(
+            UrMessageRegistry::empty()
+                .decode("crypto-psbt", &[])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_custom_type_roundtrip() {
+        let mut registry = UrMessageRegistry::new();
+        assert!(
+            registry
+                .register("x-custody-approval", approval_handler())
+                .is_none()
+        );
+
+        let message = registry.decode("x-custody-approval", b"cold-1").unwrap();
+        let KeystoneMessage::Custom(ref custom) = message else {
+            panic!("expected custom message");
+        };
+        assert_eq!(custom.ur_type(), "x-custody-approval");
+        assert_eq!(
+            custom.downcast_ref::<VaultApproval>(),
+            Some(&VaultApproval {
+                vault: "cold-1".to_string()
+            })
+        );
+
+        let payload = registry.encode(&message).unwrap();
+        assert_eq!(payload.ur_type, "x-custody-approval");
+        assert_eq!(payload.data, b"cold-1");
+
+        assert!(UrMessageRegistry::new().encode(&message).is_err());
+    }
+}
@@ -1,12 +1,18 @@
 //! Keystone payload container and dynamic message decoding
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::keystone::cbor::{self, CborDecodeError, DecodeMode};
 use crate::keystone::messages::{
-    CryptoAccount, EthSignRequest, EthSignature, HederaSignRequest, HederaSignature,
-    SolanaSignRequest, SolanaSignature, StellarSignRequest, StellarSignature, XrpSignRequest,
+    AptosSignRequest, AptosSignature, Bytes, BytesFraming, CardanoSignRequest, CardanoSignature,
+    CosmosSignRequest, CosmosSignature, CryptoAccount, CryptoHdKey, CryptoMultiAccounts,
+    CryptoOutput, CryptoPsbt, EthSignRequest, EthSignature, HederaSignRequest, HederaSignature,
+    KeystoneSignRequest, NearSignRequest, NearSignature, SolanaSignRequest, SolanaSignature,
+    StellarSignRequest, StellarSignature, SuiSignRequest, SuiSignature, XrpSignRequest,
     XrpSignature,
 };
+use crate::keystone::registry::{BUILTIN_UR_TYPES, CustomMessage, UrMessageRegistry};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 /// Encoded data format carried by the UR payload
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -50,11 +56,29 @@ impl KeystonePayload {
     }
 
     /// Attempt to parse the payload into a strongly typed Keystone message
+    ///
+    /// Consults the global [`UrMessageRegistry`], so application-registered
+    /// UR types decode here too.
     pub fn message_type(&self) -> Result<KeystoneMessage> {
-        KeystoneMessage::from_ur_payload(self)
+        UrMessageRegistry::global().decode(&self.ur_type, &self.data)
+    }
+
+    /// Like [`message_type`](Self::message_type), checking built-in CBOR
+    /// types against `mode` first
+    pub fn message_type_with_mode(&self, mode: DecodeMode) -> Result<KeystoneMessage> {
+        if mode == DecodeMode::Strict && is_builtin_cbor(&self.ur_type) {
+            cbor::validate::validate(Some(&self.ur_type), &self.data, mode)
+                .map_err(Error::CborDecode)?;
+        }
+        self.message_type()
     }
 }
 
+/// Built-in UR types whose body is CBOR (everything but the XRP JSON types)
+fn is_builtin_cbor(ur_type: &str) -> bool {
+    BUILTIN_UR_TYPES.contains(&ur_type) && !ur_type.starts_with("xrp-")
+}
+
 /// Metadata describing how a UR payload was obtained
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct KeystoneMetadata {
@@ -71,14 +95,42 @@ pub struct KeystoneMetadata {
 pub enum KeystoneMessage {
     /// Wallet pairing account information
     CryptoAccount(CryptoAccount),
+    /// BIP32 extended public key (watch-only pairing)
+    CryptoHdKey(CryptoHdKey),
+    /// Multi-chain account export (Keystone software wallet pairing)
+    CryptoMultiAccounts(CryptoMultiAccounts),
+    /// Output descriptor
+    CryptoOutput(CryptoOutput),
+    /// Bitcoin partially signed transaction (`crypto-psbt` / `psbt`)
+    CryptoPsbt(CryptoPsbt),
     /// Ethereum sign request (transaction, typed data, personal message)
     EthSignRequest(EthSignRequest),
     /// Ethereum signature response
     EthSignature(EthSignature),
+    /// Aptos sign request
+    AptosSignRequest(AptosSignRequest),
+    /// Aptos signature response
+    AptosSignature(AptosSignature),
+    /// Cardano sign request (transaction body plus spent UTXOs)
+    CardanoSignRequest(CardanoSignRequest),
+    /// Cardano signature response (witness set)
+    CardanoSignature(CardanoSignature),
+    /// Cosmos SDK sign request (amino, direct, textual or message)
+    CosmosSignRequest(CosmosSignRequest),
+    /// Cosmos SDK signature response
+    CosmosSignature(CosmosSignature),
     /// Hedera sign request
     HederaSignRequest(HederaSignRequest),
     /// Hedera signature response
     HederaSignature(HederaSignature),
+    /// Generic `bytes` UR (text, JSON, nested UR or opaque data)
+    Bytes(Bytes),
+    /// gzip-compressed Keystone base protobuf envelope (Tron, LTC, DASH, BCH)
+    KeystoneSignRequest(KeystoneSignRequest),
+    /// NEAR sign request
+    NearSignRequest(NearSignRequest),
+    /// NEAR signature response
+    NearSignature(NearSignature),
     /// Solana sign request
     SolanaSignRequest(SolanaSignRequest),
     /// Solana signature response
@@ -91,6 +143,12 @@ pub enum KeystoneMessage {
     StellarSignRequest(StellarSignRequest),
     /// Stellar signature response
     StellarSignature(StellarSignature),
+    /// Sui sign request
+    SuiSignRequest(SuiSignRequest),
+    /// Sui signature response
+    SuiSignature(SuiSignature),
+    /// Application-defined message decoded through a registered handler
+    Custom(CustomMessage),
     /// Unrecognised UR payload
     Unknown {
         /// UR type string exactly as provided by the UR payload
@@ -107,13 +165,62 @@ impl KeystoneMessage {
     }
 
     /// Decode based on UR type and raw payload bytes
+    ///
+    /// Only knows the built-in types; see [`UrMessageRegistry`] for custom ones.
     pub fn from_ur_type(ur_type: &str, data: &[u8]) -> Result<Self> {
+        Self::from_ur_type_with_mode(ur_type, data, DecodeMode::Lenient)
+    }
+
+    /// Decode based on UR type in the given [`DecodeMode`]
+    ///
+    /// Strict mode validates CBOR bodies before decoding. Error paths are
+    /// rooted at `ur_type`, so untagged messages (Hedera, Stellar) get named
+    /// fields too.
+    pub fn from_ur_type_with_mode(ur_type: &str, data: &[u8], mode: DecodeMode) -> Result<Self> {
+        if mode == DecodeMode::Strict && is_builtin_cbor(ur_type) {
+            cbor::validate::validate(Some(ur_type), data, mode).map_err(Error::CborDecode)?;
+        }
+        Self::decode_builtin(ur_type, data).map_err(|e| match e {
+            Error::CborDecode(err) => Error::CborDecode(CborDecodeError {
+                path: cbor::validate::locate(Some(ur_type), data, err.offset),
+                ..err
+            }),
+            other => other,
+        })
+    }
+
+    fn decode_builtin(ur_type: &str, data: &[u8]) -> Result<Self> {
         match ur_type {
             "crypto-account" => Ok(Self::CryptoAccount(CryptoAccount::from_cbor(data)?)),
+            "crypto-hdkey" => Ok(Self::CryptoHdKey(CryptoHdKey::from_cbor(data)?)),
+            "crypto-multi-accounts" => Ok(Self::CryptoMultiAccounts(
+                CryptoMultiAccounts::from_cbor(data)?,
+            )),
+            "crypto-output" => Ok(Self::CryptoOutput(CryptoOutput::from_cbor(data)?)),
+            "crypto-psbt" | "psbt" => Ok(Self::CryptoPsbt(CryptoPsbt::from_cbor(data)?)),
             "eth-sign-request" => Ok(Self::EthSignRequest(EthSignRequest::from_cbor(data)?)),
             "eth-signature" => Ok(Self::EthSignature(EthSignature::from_cbor(data)?)),
+            "aptos-sign-request" => Ok(Self::AptosSignRequest(AptosSignRequest::from_cbor(data)?)),
+            "aptos-signature" => Ok(Self::AptosSignature(AptosSignature::from_cbor(data)?)),
+            "cardano-sign-request" => Ok(Self::CardanoSignRequest(CardanoSignRequest::from_cbor(
+                data,
+            )?)),
+            "cardano-signature" => Ok(Self::CardanoSignature(CardanoSignature::from_cbor(data)?)),
+            "cosmos-sign-request" => {
+                Ok(Self::CosmosSignRequest(CosmosSignRequest::from_cbor(data)?))
+            }
+            "cosmos-signature" => Ok(Self::CosmosSignature(CosmosSignature::from_cbor(data)?)),
             "hbar-sign-request" => Ok(Self::HederaSignRequest(HederaSignRequest::from_cbor(data)?)),
             "hbar-signature" => Ok(Self::HederaSignature(HederaSignature::from_cbor(data)?)),
+            "keystone-sign-request" => Ok(Self::KeystoneSignRequest(
+                KeystoneSignRequest::from_cbor(data)?,
+            )),
+            "bytes" => match KeystoneSignRequest::from_bytes_ur(data) {
+                Some(request) => Ok(Self::KeystoneSignRequest(request)),
+                None => Ok(Self::Bytes(Bytes::from_ur_data(data))),
+            },
+            "near-sign-request" => Ok(Self::NearSignRequest(NearSignRequest::from_cbor(data)?)),
+            "near-signature" => Ok(Self::NearSignature(NearSignature::from_cbor(data)?)),
             "sol-sign-request" => Ok(Self::SolanaSignRequest(SolanaSignRequest::from_cbor(data)?)),
             "sol-signature" => Ok(Self::SolanaSignature(SolanaSignature::from_cbor(data)?)),
             "xrp-sign-request" => Ok(Self::XrpSignRequest(XrpSignRequest::from_json_bytes(data)?)),
@@ -122,12 +229,93 @@ impl KeystoneMessage {
                 data,
             )?)),
             "stellar-signature" => Ok(Self::StellarSignature(StellarSignature::from_cbor(data)?)),
+            "sui-sign-request" => Ok(Self::SuiSignRequest(SuiSignRequest::from_cbor(data)?)),
+            "sui-signature" => Ok(Self::SuiSignature(SuiSignature::from_cbor(data)?)),
             _ => Ok(Self::Unknown {
                 ur_type: ur_type.to_string(),
                 data: data.to_vec(),
             }),
         }
     }
+
+    /// Request identifier carried by sign requests and signature responses
+    pub fn request_id(&self) -> Option<Uuid> {
+        match self {
+            Self::EthSignRequest(request) => request.request_id,
+            Self::EthSignature(signature) => signature.request_id,
+            Self::CosmosSignRequest(request) => request.request_id,
+            Self::CosmosSignature(signature) => signature.request_id,
+            Self::HederaSignRequest(request) => request.request_id,
+            Self::HederaSignature(signature) => signature.request_id,
+            Self::SolanaSignRequest(request) => request.request_id,
+            Self::SolanaSignature(signature) => signature.request_id,
+            Self::XrpSignRequest(request) => request.request_id,
+            Self::XrpSignature(signature) => signature.request_id,
+            Self::StellarSignRequest(request) => request.request_id,
+            Self::StellarSignature(signature) => signature.request_id,
+            Self::SuiSignRequest(request) => request.request_id,
+            Self::SuiSignature(signature) => signature.request_id,
+            Self::AptosSignRequest(request) => request.request_id,
+            Self::AptosSignature(signature) => signature.request_id,
+            Self::CardanoSignRequest(request) => request.request_id,
+            Self::CardanoSignature(signature) => signature.request_id,
+            Self::NearSignRequest(request) => request.request_id,
+            Self::NearSignature(signature) => signature.request_id,
+            _ => None,
+        }
+    }
+
+    /// Whether this message asks the wallet for a signature
+    pub fn is_sign_request(&self) -> bool {
+        matches!(
+            self,
+            Self::EthSignRequest(_)
+                | Self::CosmosSignRequest(_)
+                | Self::HederaSignRequest(_)
+                | Self::SolanaSignRequest(_)
+                | Self::XrpSignRequest(_)
+                | Self::StellarSignRequest(_)
+                | Self::AptosSignRequest(_)
+                | Self::SuiSignRequest(_)
+                | Self::CardanoSignRequest(_)
+                | Self::NearSignRequest(_)
+                | Self::KeystoneSignRequest(_)
+        )
+    }
+
+    /// Whether this message is a wallet's signature response
+    pub fn is_signature(&self) -> bool {
+        matches!(
+            self,
+            Self::EthSignature(_)
+                | Self::CosmosSignature(_)
+                | Self::HederaSignature(_)
+                | Self::SolanaSignature(_)
+                | Self::XrpSignature(_)
+                | Self::StellarSignature(_)
+                | Self::AptosSignature(_)
+                | Self::SuiSignature(_)
+                | Self::CardanoSignature(_)
+                | Self::NearSignature(_)
+        )
+    }
+
+    /// Whether `response` is the signature type that answers this request
+    pub fn answered_by(&self, response: &KeystoneMessage) -> bool {
+        matches!(
+            (self, response),
+            (Self::EthSignRequest(_), Self::EthSignature(_))
+                | (Self::CosmosSignRequest(_), Self::CosmosSignature(_))
+                | (Self::HederaSignRequest(_), Self::HederaSignature(_))
+                | (Self::SolanaSignRequest(_), Self::SolanaSignature(_))
+                | (Self::XrpSignRequest(_), Self::XrpSignature(_))
+                | (Self::StellarSignRequest(_), Self::StellarSignature(_))
+                | (Self::AptosSignRequest(_), Self::AptosSignature(_))
+                | (Self::SuiSignRequest(_), Self::SuiSignature(_))
+                | (Self::CardanoSignRequest(_), Self::CardanoSignature(_))
+                | (Self::NearSignRequest(_), Self::NearSignature(_))
+        )
+    }
 }
 
 impl From<&KeystoneMessage> for PayloadEncoding {
@@ -136,7 +324,10 @@ impl From<&KeystoneMessage> for PayloadEncoding {
             KeystoneMessage::XrpSignRequest(_) | KeystoneMessage::XrpSignature(_) => {
                 PayloadEncoding::Json
             }
-            KeystoneMessage::Unknown { .. } => PayloadEncoding::Binary,
+            KeystoneMessage::Bytes(bytes) if bytes.framing == BytesFraming::Raw => {
+                PayloadEncoding::Binary
+            }
+            KeystoneMessage::Custom(_) | KeystoneMessage::Unknown { .. } => PayloadEncoding::Binary,
             _ => PayloadEncoding::Cbor,
         }
     }
@@ -146,8 +337,18 @@ impl From<KeystoneMessage> for KeystonePayload {
     fn from(message: KeystoneMessage) -> Self {
         match message {
             KeystoneMessage::CryptoAccount(value) => serialize_cbor("crypto-account", &value),
+            KeystoneMessage::CryptoHdKey(value) => serialize_cbor("crypto-hdkey", &value),
+            KeystoneMessage::CryptoMultiAccounts(value) => {
+                serialize_cbor("crypto-multi-accounts", &value)
+            }
+            KeystoneMessage::CryptoOutput(value) => serialize_cbor("crypto-output", &value),
+            KeystoneMessage::CryptoPsbt(value) => serialize_cbor("crypto-psbt", &value),
             KeystoneMessage::EthSignRequest(value) => serialize_cbor("eth-sign-request", &value),
             KeystoneMessage::EthSignature(value) => serialize_cbor("eth-signature", &value),
+            KeystoneMessage::CosmosSignRequest(value) => {
+                serialize_cbor("cosmos-sign-request", &value)
+            }
+            KeystoneMessage::CosmosSignature(value) => serialize_cbor("cosmos-signature", &value),
             KeystoneMessage::HederaSignRequest(value) => {
                 serialize_cbor("hbar-sign-request", &value)
             }
@@ -158,8 +359,31 @@ impl From<KeystoneMessage> for KeystonePayload {
                 serialize_cbor("stellar-sign-request", &value)
             }
             KeystoneMessage::StellarSignature(value) => serialize_cbor("stellar-signature", &value),
+            KeystoneMessage::AptosSignRequest(value) => {
+                serialize_cbor("aptos-sign-request", &value)
+            }
+            KeystoneMessage::AptosSignature(value) => serialize_cbor("aptos-signature", &value),
+            KeystoneMessage::SuiSignRequest(value) => serialize_cbor("sui-sign-request", &value),
+            KeystoneMessage::SuiSignature(value) => serialize_cbor("sui-signature", &value),
+            KeystoneMessage::CardanoSignRequest(value) => {
+                serialize_cbor("cardano-sign-request", &value)
+            }
+            KeystoneMessage::CardanoSignature(value) => serialize_cbor("cardano-signature", &value),
+            KeystoneMessage::Bytes(value) => {
+                let mut payload = serialize_cbor("bytes", &value);
+                payload.encoding = crate::keystone::ur::payload_encoding("bytes", &payload.data);
+                payload
+            }
+            KeystoneMessage::KeystoneSignRequest(value) => {
+                serialize_cbor(value.wrapper.ur_type(), &value)
+            }
+            KeystoneMessage::NearSignRequest(value) => serialize_cbor("near-sign-request", &value),
+            KeystoneMessage::NearSignature(value) => serialize_cbor("near-signature", &value),
             KeystoneMessage::XrpSignRequest(value) => serialize_json("xrp-sign-request", &value),
             KeystoneMessage::XrpSignature(value) => serialize_json("xrp-signature", &value),
+            KeystoneMessage::Custom(_) => UrMessageRegistry::global()
+                .encode(&message)
+                .expect("no encoder registered for custom UR type"),
             KeystoneMessage::Unknown { ur_type, data } => KeystonePayload {
                 ur_type,
                 data,
@@ -200,7 +424,7 @@ where
 mod tests {
     use super::*;
     use crate::keystone::crypto_keypath::CryptoKeyPath;
-    use uuid::Uuid;
+    use crate::keystone::messages::{AptosSignType, CosmosAccount, CosmosDataType};
 
     #[test]
     fn detects_json_encoding() {
@@ -225,6 +449,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn strict_mode_and_error_paths() {
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_transaction(vec![1, 2, 3], path, Some(1));
+        let mut payload: KeystonePayload = KeystoneMessage::EthSignRequest(request).into();
+        assert!(payload.message_type_with_mode(DecodeMode::Strict).is_ok());
+
+        payload.data.push(0x00);
+        assert!(payload.message_type_with_mode(DecodeMode::Lenient).is_ok());
+        match payload.message_type_with_mode(DecodeMode::Strict) {
+            Err(Error::CborDecode(err)) => {
+                assert_eq!(err.path, "eth-sign-request");
+                assert_eq!(err.offset, payload.data.len() - 1);
+            }
+            other => panic!("expected strict failure, got {other:?}"),
+        }
+
+        // hbar-signature {2: "x"}: untagged, so the path comes from the UR type
+        let err =
+            KeystoneMessage::from_ur_type("hbar-signature", &[0xa1, 0x02, 0x61, b'x']).unwrap_err();
+        match err {
+            Error::CborDecode(err) => {
+                assert_eq!(err.path, "hbar-signature.signature");
+                assert_eq!(err.offset, 2);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
     #[test]
     fn unknown_payload_preserved() {
         let payload = KeystonePayload {
@@ -281,6 +534,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cbor_round_trip_crypto_psbt() {
+        let psbt = CryptoPsbt::new(b"psbt\xff\x00".to_vec());
+
+        let payload: KeystonePayload = KeystoneMessage::CryptoPsbt(psbt.clone()).into();
+        assert_eq!(payload.ur_type, "crypto-psbt");
+
+        let decoded = KeystoneMessage::from_ur_type("psbt", &payload.data).unwrap();
+        match decoded {
+            KeystoneMessage::CryptoPsbt(parsed) => assert_eq!(parsed, psbt),
+            _ => panic!("unexpected variant"),
+        }
+    }
+
     #[test]
     fn cbor_round_trip_hedera_request() {
         let path = CryptoKeyPath::from_str("m/44'/3030'/0'/0/0").unwrap();
@@ -335,4 +602,111 @@ mod tests {
             _ => panic!("unexpected variant"),
         }
     }
+
+    #[test]
+    fn cbor_round_trip_cosmos_request() {
+        let path = CryptoKeyPath::from_str("m/44'/118'/0'/0/0").unwrap();
+        let account = CosmosAccount::new(path).with_address("cosmos1abc".into());
+        let request = CosmosSignRequest::new(b"{}".to_vec(), CosmosDataType::Amino, vec![account]);
+
+        let payload: KeystonePayload = KeystoneMessage::CosmosSignRequest(request.clone()).into();
+        assert_eq!(payload.ur_type, "cosmos-sign-request");
+        let decoded = payload.message_type().unwrap();
+
+        match decoded {
+            KeystoneMessage::CosmosSignRequest(parsed) => {
+                assert_eq!(parsed.request_id, request.request_id);
+                assert_eq!(parsed.sign_data, request.sign_data);
+                assert_eq!(parsed.accounts[0].address.as_deref(), Some("cosmos1abc"));
+            }
+            _ => panic!("unexpected variant"),
+        }
+    }
+
+    #[test]
+    fn cbor_round_trip_aptos_and_sui_requests() {
+        let aptos_path = CryptoKeyPath::from_str("m/44'/637'/0'/0'/0'").unwrap();
+        let aptos =
+            AptosSignRequest::new(vec![1, 2], vec![aptos_path], AptosSignType::Single, None);
+        let payload: KeystonePayload = KeystoneMessage::AptosSignRequest(aptos.clone()).into();
+        assert_eq!(payload.ur_type, "aptos-sign-request");
+        match payload.message_type().unwrap() {
+            KeystoneMessage::AptosSignRequest(parsed) => {
+                assert_eq!(parsed.sign_data, aptos.sign_data)
+            }
+            _ => panic!("unexpected variant"),
+        }
+
+        let sui_path = CryptoKeyPath::from_str("m/44'/784'/0'/0'/0'").unwrap();
+        let sui = SuiSignRequest::new(vec![3, 0, 0, 0], vec![sui_path], None);
+        let payload: KeystonePayload = KeystoneMessage::SuiSignRequest(sui.clone()).into();
+        assert_eq!(payload.ur_type, "sui-sign-request");
+        match payload.message_type().unwrap() {
+            KeystoneMessage::SuiSignRequest(parsed) => {
+                assert_eq!(parsed.intent_message, sui.intent_message)
+            }
+            _ => panic!("unexpected variant"),
+        }
+    }
+
+    #[test]
+    fn cbor_round_trip_near_signature() {
+        let request_id = Some(Uuid::parse_str("9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d").unwrap());
+        let sig = NearSignature::new(vec![vec![0x33; 64]], request_id);
+
+        let payload: KeystonePayload = KeystoneMessage::NearSignature(sig.clone()).into();
+        assert_eq!(payload.ur_type, "near-signature");
+
+        match payload.message_type().unwrap() {
+            KeystoneMessage::NearSignature(parsed) => {
+                assert_eq!(parsed.signatures, sig.signatures);
+                assert_eq!(parsed.request_id, request_id);
+            }
+            _ => panic!("unexpected variant"),
+        }
+    }
+
+    #[test]
+    fn bytes_ur_keystone_envelope_decoded() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        // Base { version: 1, data: Payload { type: SIGN_TX, signTx: { coinCode: "TRON" } } }
+        let base = b"\x08\x01\x1a\x0a\x08\x02\x22\x06\x0a\x04TRON";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(base).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let data = minicbor::to_vec(minicbor::bytes::ByteVec::from(compressed.clone())).unwrap();
+
+        let message = KeystoneMessage::from_ur_type("bytes", &data).unwrap();
+        let KeystoneMessage::KeystoneSignRequest(ref request) = message else {
+            panic!("expected keystone sign request");
+        };
+        assert!(message.is_sign_request());
+        assert!(request.decode_base().is_ok());
+
+        let payload: KeystonePayload = message.into();
+        assert_eq!(payload.ur_type, "bytes");
+        assert_eq!(payload.data, data);
+
+        let plain = minicbor::to_vec(minicbor::bytes::ByteVec::from(b"hello".to_vec())).unwrap();
+        assert!(matches!(
+            KeystoneMessage::from_ur_type("bytes", &plain).unwrap(),
+            KeystoneMessage::Bytes(_)
+        ));
+    }
+
+    #[test]
+    fn bytes_ur_keeps_unframed_text() {
+        let message = KeystoneMessage::from_ur_type("bytes", b"signed message export").unwrap();
+        let KeystoneMessage::Bytes(ref bytes) = message else {
+            panic!("expected bytes variant");
+        };
+        assert_eq!(bytes.framing, BytesFraming::Raw);
+
+        let payload: KeystonePayload = message.into();
+        assert_eq!(payload.encoding, PayloadEncoding::Binary);
+        assert_eq!(payload.data, b"signed message export");
+    }
 }
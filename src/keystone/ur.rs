@@ -5,8 +5,8 @@
 use crate::error::{Error, Result};
 use crate::keystone::types::{KeystoneMetadata, KeystonePayload, PayloadEncoding};
 
-// Re-export Decoder for multi-part decoding
-pub use ur::Decoder;
+// Re-export Decoder/Encoder for multi-part decoding and fountain encoding
+pub use ur::{Decoder, Encoder};
 
 /// Decode a UR string (single or multi-part)
 pub fn decode_ur(ur_string: &str) -> Result<KeystonePayload> {
@@ -17,7 +17,7 @@ pub fn decode_ur(ur_string: &str) -> Result<KeystonePayload> {
     let ur_type = extract_ur_type(ur_string)?;
 
     Ok(KeystonePayload {
-        encoding: payload_encoding(&ur_type),
+        encoding: payload_encoding(&ur_type, &data),
         metadata: KeystoneMetadata::default(),
         ur_type,
         data,
@@ -40,10 +40,28 @@ pub(crate) fn extract_ur_type(ur_string: &str) -> Result<String> {
     Ok(parts[0].to_string())
 }
 
-pub(crate) fn payload_encoding(ur_type: &str) -> PayloadEncoding {
-    match ur_type {
-        t if t.starts_with("xrp-") => PayloadEncoding::Json,
-        _ => PayloadEncoding::Cbor,
+/// Detect the encoding of a UR body
+///
+/// XRP types are JSON by definition. Everything else is checked against the
+/// bytes: a single complete CBOR item is CBOR, a JSON object or array is
+/// JSON, and anything else (raw text included) is binary.
+pub(crate) fn payload_encoding(ur_type: &str, data: &[u8]) -> PayloadEncoding {
+    if ur_type.starts_with("xrp-") {
+        return PayloadEncoding::Json;
+    }
+    let mut decoder = minicbor::Decoder::new(data);
+    if !data.is_empty() && decoder.skip().is_ok() && decoder.position() == data.len() {
+        return PayloadEncoding::Cbor;
+    }
+    let is_json = data
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| matches!(byte, b'{' | b'['))
+        && serde_json::from_slice::<serde_json::Value>(data).is_ok();
+    if is_json {
+        PayloadEncoding::Json
+    } else {
+        PayloadEncoding::Binary
     }
 }
 
@@ -52,6 +70,16 @@ pub fn encode_ur(ur_type: &str, data: &[u8]) -> String {
     ur::encode(data, ur_type)
 }
 
+/// Create a fountain encoder that keeps producing parts past `fragment_count()`
+pub(crate) fn fountain_encoder(
+    ur_type: &str,
+    data: &[u8],
+    max_fragment_len: usize,
+) -> Result<Encoder> {
+    Encoder::new(data, max_fragment_len, ur_type)
+        .map_err(|e| Error::UrParse(format!("Failed to create encoder: {:?}", e)))
+}
+
 /// Encode data as UR with multi-part support
 ///
 /// Returns (parts, is_multipart)
@@ -60,18 +88,30 @@ pub fn encode_ur_with_fragments(
     data: &[u8],
     max_fragment_len: usize,
 ) -> Result<(Vec<String>, bool)> {
+    let (parts, fountain) = encode_ur_with_encoder(ur_type, data, max_fragment_len)?;
+    Ok((parts, fountain.is_some()))
+}
+
+/// Encode data as UR, keeping the fountain encoder for multi-part payloads
+///
+/// Returns the first pass of pure fragments and, for multi-part payloads, the
+/// fountain encoder positioned to continue with mixed parts.
+pub fn encode_ur_with_encoder(
+    ur_type: &str,
+    data: &[u8],
+    max_fragment_len: usize,
+) -> Result<(Vec<String>, Option<Encoder>)> {
     // Try single-part first
     let single = encode_ur(ur_type, data);
 
     if single.len() <= max_fragment_len {
-        return Ok((vec![single], false));
+        return Ok((vec![single], None));
     }
 
     // Need multi-part
-    let mut encoder = ur::Encoder::new(data, max_fragment_len, ur_type)
-        .map_err(|e| Error::UrParse(format!("Failed to create encoder: {:?}", e)))?;
+    let mut encoder = fountain_encoder(ur_type, data, max_fragment_len)?;
 
-    // Generate all parts
+    // Generate the first pass of pure fragments
     let mut ur_parts = Vec::new();
     let total_parts = encoder.fragment_count();
 
@@ -82,7 +122,7 @@ pub fn encode_ur_with_fragments(
         ur_parts.push(part);
     }
 
-    Ok((ur_parts, true))
+    Ok((ur_parts, Some(encoder)))
 }
 
 #[cfg(test)]
@@ -102,6 +142,30 @@ mod tests {
         assert_eq!(decoded.data, data);
     }
 
+    #[test]
+    fn test_payload_encoding_detection() {
+        assert_eq!(
+            payload_encoding("bytes", &[0x42, 0x01, 0x02]),
+            PayloadEncoding::Cbor
+        );
+        assert_eq!(
+            payload_encoding("bytes", b"hello world"),
+            PayloadEncoding::Binary
+        );
+        assert_eq!(
+            payload_encoding("bytes", br#"{"a":1}"#),
+            PayloadEncoding::Json
+        );
+        assert_eq!(
+            payload_encoding("xrp-sign-request", b"{}"),
+            PayloadEncoding::Json
+        );
+        assert_eq!(
+            payload_encoding("crypto-psbt", &[]),
+            PayloadEncoding::Binary
+        );
+    }
+
     #[test]
     fn test_invalid_ur() {
         let result = decode_ur("not-a-ur");
@@ -125,5 +189,9 @@ mod tests {
 
         assert!(is_multipart);
         assert!(parts.len() > 1);
+
+        let (fragments, fountain) = encode_ur_with_encoder("test-type", &data, 50).unwrap();
+        assert_eq!(fragments, parts);
+        assert!(fountain.is_some());
     }
 }
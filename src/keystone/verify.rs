@@ -0,0 +1,833 @@
+//! Signature verification binding a wallet response to its originating request
+//!
+//! Each verifier takes the request that was shown to the wallet and the
+//! signature it returned, reconstructs the digest the wallet should have
+//! signed, and checks the signature against the expected key:
+//!
+//! - Ethereum: secp256k1 public-key recovery, compared against the request
+//!   `address` or a key derived from a paired [`CryptoAccount`]
+//! - Solana, Stellar, Hedera: ed25519 against the known public key
+//!   (Hedera ECDSA keys are checked over the Keccak-256 digest)
+//! - XRP: against the transaction's `SigningPubKey`, over the canonical
+//!   binary signing data
+//!
+//! The expected key always comes from the request side (request address,
+//! paired account or caller). A key embedded in the response is only ever
+//! compared against it: a response carrying its own key proves nothing.
+
+use crate::keystone::crypto_keypath::{CryptoKeyPath, PathComponent};
+use crate::keystone::messages::ethereum::typed_data::keccak256;
+use crate::keystone::messages::stellar::strkey;
+use crate::keystone::messages::xrp::codec as xrp_codec;
+use crate::keystone::messages::{
+    CryptoAccount, EthDataType, EthSignRequest, EthSignature, HederaSignRequest, HederaSignature,
+    SolanaSignRequest, SolanaSignature, StellarSignRequest, StellarSignType, StellarSignature,
+    TypedData, XrpSignRequest, XrpSignature,
+};
+use crate::keystone::types::KeystoneMessage;
+use hmac::{Hmac, Mac};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use k256::elliptic_curve::PrimeField;
+use k256::{ProjectivePoint, Scalar};
+use sha2::{Digest, Sha256, Sha512};
+use uuid::Uuid;
+
+/// Outcome of a verification attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationStatus {
+    /// Signature was produced by the expected key over the requested data
+    Valid,
+    /// Signature does not match the request
+    Invalid,
+    /// Not enough information to decide (e.g. no known public key)
+    Unverifiable,
+}
+
+impl VerificationStatus {
+    /// Short label used in output rendering
+    pub fn label(&self) -> &'static str {
+        match self {
+            VerificationStatus::Valid => "valid",
+            VerificationStatus::Invalid => "invalid",
+            VerificationStatus::Unverifiable => "unverifiable",
+        }
+    }
+}
+
+/// Verification result with supporting detail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureVerification {
+    /// Overall outcome
+    pub status: VerificationStatus,
+    /// Human-readable explanation
+    pub detail: String,
+    /// Signer recovered from the signature (ETH address, hex with `0x`)
+    pub recovered_signer: Option<String>,
+}
+
+impl SignatureVerification {
+    fn valid(detail: impl Into<String>) -> Self {
+        Self {
+            status: VerificationStatus::Valid,
+            detail: detail.into(),
+            recovered_signer: None,
+        }
+    }
+
+    fn invalid(detail: impl Into<String>) -> Self {
+        Self {
+            status: VerificationStatus::Invalid,
+            detail: detail.into(),
+            recovered_signer: None,
+        }
+    }
+
+    fn unverifiable(detail: impl Into<String>) -> Self {
+        Self {
+            status: VerificationStatus::Unverifiable,
+            detail: detail.into(),
+            recovered_signer: None,
+        }
+    }
+
+    fn with_signer(mut self, signer: Option<String>) -> Self {
+        self.recovered_signer = signer;
+        self
+    }
+
+    /// Whether the signature was verified
+    pub fn is_valid(&self) -> bool {
+        self.status == VerificationStatus::Valid
+    }
+}
+
+/// Verify any supported request/signature pair
+///
+/// `account` is used to derive the expected ETH key when the request carries
+/// no address, and supplies the Solana / Hedera key when it was exported at
+/// the request's derivation path; `public_key` overrides both.
+pub fn verify_response(
+    request: &KeystoneMessage,
+    response: &KeystoneMessage,
+    account: Option<&CryptoAccount>,
+    public_key: Option<&[u8]>,
+) -> SignatureVerification {
+    match (request, response) {
+        (KeystoneMessage::EthSignRequest(request), KeystoneMessage::EthSignature(signature)) => {
+            verify_eth(request, signature, account)
+        }
+        (
+            KeystoneMessage::SolanaSignRequest(request),
+            KeystoneMessage::SolanaSignature(signature),
+        ) => verify_solana(
+            request,
+            signature,
+            public_key.or_else(|| paired_account_key(account, &request.derivation_path)),
+        ),
+        (
+            KeystoneMessage::StellarSignRequest(request),
+            KeystoneMessage::StellarSignature(signature),
+        ) => verify_stellar(request, signature, public_key),
+        (
+            KeystoneMessage::HederaSignRequest(request),
+            KeystoneMessage::HederaSignature(signature),
+        ) => verify_hedera(
+            request,
+            signature,
+            public_key.or_else(|| paired_account_key(account, &request.derivation_path)),
+        ),
+        (KeystoneMessage::XrpSignRequest(request), KeystoneMessage::XrpSignature(signature)) => {
+            verify_xrp(request, signature)
+        }
+        (request, response) if request.answered_by(response) => {
+            SignatureVerification::unverifiable(
+                "signature verification is not supported for this chain",
+            )
+        }
+        _ => SignatureVerification::invalid("response type does not answer the request type"),
+    }
+}
+
+/// Verify an Ethereum signature by recovering its signer
+pub fn verify_eth(
+    request: &EthSignRequest,
+    signature: &EthSignature,
+    account: Option<&CryptoAccount>,
+) -> SignatureVerification {
+    if let Some(mismatch) = request_id_mismatch(request.request_id, signature.request_id) {
+        return mismatch;
+    }
+
+    let digest = match eth_signing_digest(request) {
+        Ok(digest) => digest,
+        Err(detail) => return SignatureVerification::unverifiable(detail),
+    };
+    if signature.signature.len() < 65 {
+        return SignatureVerification::invalid(format!(
+            "signature has {} bytes (expected at least 65)",
+            signature.signature.len()
+        ));
+    }
+    let (rs, v) = signature.signature.split_at(64);
+    let parity = match recovery_parity(v, request.chain_id) {
+        Ok(parity) => parity,
+        Err(detail) => return SignatureVerification::unverifiable(detail),
+    };
+
+    let recovered = match recover_eth_address(&digest, rs, parity) {
+        Ok(address) => address,
+        Err(detail) => return SignatureVerification::invalid(detail),
+    };
+    let signer = Some(format!("0x{}", hex::encode(&recovered)));
+
+    let expected = match request.address.as_deref() {
+        Some(address) if address.len() == 20 => Ok(address.to_vec()),
+        Some(address) => Err(format!(
+            "request address has {} bytes (expected 20)",
+            address.len()
+        )),
+        None => match account {
+            Some(account) => derive_account_address(account, request),
+            None => Err("request has no address and no paired account was supplied".to_string()),
+        },
+    };
+
+    match expected {
+        Ok(expected) if expected == recovered => {
+            SignatureVerification::valid("recovered signer matches the expected address")
+                .with_signer(signer)
+        }
+        Ok(expected) => SignatureVerification::invalid(format!(
+            "recovered signer does not match expected address 0x{}",
+            hex::encode(expected)
+        ))
+        .with_signer(signer),
+        Err(detail) => SignatureVerification::unverifiable(detail).with_signer(signer),
+    }
+}
+
+/// Verify a Solana ed25519 signature over the message bytes
+///
+/// `public_key` is the key the request expects; the response's own key is
+/// never trusted on its own.
+pub fn verify_solana(
+    request: &SolanaSignRequest,
+    signature: &SolanaSignature,
+    public_key: Option<&[u8]>,
+) -> SignatureVerification {
+    if let Some(mismatch) = request_id_mismatch(request.request_id, signature.request_id) {
+        return mismatch;
+    }
+    let Some(public_key) = public_key else {
+        return SignatureVerification::unverifiable("no known Solana public key for the request");
+    };
+    if let Some(mismatch) = embedded_key_mismatch(signature.public_key.as_deref(), public_key) {
+        return mismatch;
+    }
+    verify_ed25519(&request.transaction, &signature.signature, public_key)
+}
+
+/// Verify a Stellar ed25519 signature
+///
+/// Full transactions are signed over `SHA-256(signature base)`; hash and
+/// message requests are signed over `sign_data` as-is.
+pub fn verify_stellar(
+    request: &StellarSignRequest,
+    signature: &StellarSignature,
+    public_key: Option<&[u8]>,
+) -> SignatureVerification {
+    if let Some(mismatch) = request_id_mismatch(request.request_id, signature.request_id) {
+        return mismatch;
+    }
+
+    let key = match public_key {
+        Some(key) => Ok(key.to_vec()),
+        None => match request.address.as_deref() {
+            Some(address) => stellar_public_key(address),
+            None => Err("no Stellar public key or address available".to_string()),
+        },
+    };
+    let key = match key {
+        Ok(key) => key,
+        Err(detail) => return SignatureVerification::unverifiable(detail),
+    };
+
+    let message = match request.sign_type {
+        StellarSignType::Transaction => Sha256::digest(&request.sign_data).to_vec(),
+        StellarSignType::TransactionHash | StellarSignType::Message => request.sign_data.clone(),
+    };
+    verify_ed25519(&message, signature.signature(), &key)
+}
+
+/// Verify a Hedera signature over the transaction body bytes
+///
+/// As with Solana, `public_key` must come from the request side.
+pub fn verify_hedera(
+    request: &HederaSignRequest,
+    signature: &HederaSignature,
+    public_key: Option<&[u8]>,
+) -> SignatureVerification {
+    if let Some(mismatch) = request_id_mismatch(request.request_id, signature.request_id) {
+        return mismatch;
+    }
+    let Some(public_key) = public_key else {
+        return SignatureVerification::unverifiable("no known Hedera public key for the request");
+    };
+    if let Some(mismatch) = embedded_key_mismatch(signature.public_key(), public_key) {
+        return mismatch;
+    }
+
+    // Wrapped transactions are signed over the inner body bytes
+    let body_bytes = request
+        .decode_transaction()
+        .map(|transaction| transaction.body_bytes)
+        .unwrap_or_else(|_| request.transaction_bytes.clone());
+
+    if public_key.len() == 33 {
+        // ECDSA(secp256k1) account keys sign the Keccak-256 digest
+        return verify_secp256k1_prehash(
+            &keccak256(&body_bytes),
+            signature.signature(),
+            public_key,
+        );
+    }
+    verify_ed25519(&body_bytes, signature.signature(), public_key)
+}
+
+/// Verify an XRP signature against the transaction's `SigningPubKey`
+///
+/// secp256k1 keys sign the SHA-512Half of the signing data (DER-encoded
+/// signature); ed25519 keys (`ED` prefix) sign the signing data itself.
+pub fn verify_xrp(request: &XrpSignRequest, signature: &XrpSignature) -> SignatureVerification {
+    if let Some(mismatch) = request_id_mismatch(request.request_id, signature.request_id) {
+        return mismatch;
+    }
+    let transaction: serde_json::Value = match serde_json::from_str(&request.transaction_json) {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            return SignatureVerification::unverifiable(format!(
+                "transaction JSON is invalid: {err}"
+            ));
+        }
+    };
+    let Some(public_key) = transaction
+        .get("SigningPubKey")
+        .and_then(|key| key.as_str())
+        .and_then(|key| hex::decode(key).ok())
+        .filter(|key| !key.is_empty())
+    else {
+        return SignatureVerification::unverifiable("transaction has no SigningPubKey");
+    };
+    let signing_data = match xrp_codec::signing_data(&transaction) {
+        Ok(data) => data,
+        Err(err) => return SignatureVerification::unverifiable(err.to_string()),
+    };
+    let Ok(signature_bytes) = hex::decode(signature.signature()) else {
+        return SignatureVerification::invalid("signature is not hex");
+    };
+
+    if public_key.len() == 33 && public_key[0] == 0xed {
+        return verify_ed25519(&signing_data, &signature_bytes, &public_key[1..]);
+    }
+    let compact = match Signature::from_der(&signature_bytes) {
+        Ok(signature) => signature.to_bytes().to_vec(),
+        Err(_) => signature_bytes,
+    };
+    verify_secp256k1_prehash(
+        &xrp_codec::sha512_half(&signing_data),
+        &compact,
+        &public_key,
+    )
+}
+
+/// Verify an ed25519 signature
+pub fn verify_ed25519(
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> SignatureVerification {
+    let Ok(key_bytes) = <[u8; 32]>::try_from(public_key) else {
+        return SignatureVerification::unverifiable(format!(
+            "ed25519 public key has {} bytes (expected 32)",
+            public_key.len()
+        ));
+    };
+    let Ok(key) = ed25519_dalek::VerifyingKey::from_bytes(&key_bytes) else {
+        return SignatureVerification::unverifiable("ed25519 public key is not a valid point");
+    };
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+        return SignatureVerification::invalid(format!(
+            "ed25519 signature has {} bytes (expected 64)",
+            signature.len()
+        ));
+    };
+
+    match key.verify_strict(message, &signature) {
+        Ok(()) => SignatureVerification::valid("ed25519 signature matches the public key"),
+        Err(_) => SignatureVerification::invalid("ed25519 signature does not match the public key"),
+    }
+}
+
+fn verify_secp256k1_prehash(
+    digest: &[u8; 32],
+    signature: &[u8],
+    public_key: &[u8],
+) -> SignatureVerification {
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+
+    let Ok(key) = VerifyingKey::from_sec1_bytes(public_key) else {
+        return SignatureVerification::unverifiable("secp256k1 public key is not a valid point");
+    };
+    let Some(signature) = signature
+        .get(..64)
+        .and_then(|bytes| Signature::from_slice(bytes).ok())
+    else {
+        return SignatureVerification::invalid("malformed secp256k1 signature");
+    };
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    match key.verify_prehash(digest, &signature) {
+        Ok(()) => SignatureVerification::valid("ECDSA signature matches the public key"),
+        Err(_) => SignatureVerification::invalid("ECDSA signature does not match the public key"),
+    }
+}
+
+/// Reject a response whose embedded key differs from the expected one
+fn embedded_key_mismatch(
+    embedded: Option<&[u8]>,
+    expected: &[u8],
+) -> Option<SignatureVerification> {
+    match embedded {
+        Some(embedded) if !embedded.is_empty() && embedded != expected => {
+            Some(SignatureVerification::invalid(format!(
+                "response public key {} is not the expected key {}",
+                hex::encode(embedded),
+                hex::encode(expected)
+            )))
+        }
+        _ => None,
+    }
+}
+
+/// Public key of a paired account exported at exactly `path`
+fn paired_account_key<'a>(
+    account: Option<&'a CryptoAccount>,
+    path: &CryptoKeyPath,
+) -> Option<&'a [u8]> {
+    let account = account?;
+    let exported = &account.key_path.components;
+    let same_path = exported.len() == path.components.len()
+        && exported
+            .iter()
+            .zip(&path.components)
+            .all(|(a, b)| a.index == b.index && a.hardened == b.hardened);
+    (same_path && !account.public_key.is_empty()).then_some(account.public_key.as_slice())
+}
+
+fn request_id_mismatch(
+    request: Option<Uuid>,
+    response: Option<Uuid>,
+) -> Option<SignatureVerification> {
+    match (request, response) {
+        (Some(request), Some(response)) if request != response => {
+            Some(SignatureVerification::invalid(format!(
+                "response request ID {response} does not match request {request}"
+            )))
+        }
+        _ => None,
+    }
+}
+
+/// Digest an Ethereum wallet signs for a request
+fn eth_signing_digest(request: &EthSignRequest) -> std::result::Result<[u8; 32], String> {
+    match request.data_type {
+        EthDataType::Transaction | EthDataType::TypedTransaction => {
+            Ok(keccak256(&request.sign_data))
+        }
+        EthDataType::PersonalMessage => {
+            let mut message =
+                format!("\x19Ethereum Signed Message:\n{}", request.sign_data.len()).into_bytes();
+            message.extend_from_slice(&request.sign_data);
+            Ok(keccak256(&message))
+        }
+        EthDataType::TypedData => TypedData::parse(&request.sign_data)
+            .and_then(|typed| typed.signing_hash())
+            .map_err(|err| err.to_string()),
+    }
+}
+
+/// Recovery parity from the big-endian `v` that follows `r || s`
+///
+/// Legacy EIP-155 signatures encode `v = chain_id * 2 + 35 + parity`, which
+/// takes more than one byte for chain IDs of 110 and above.
+fn recovery_parity(v: &[u8], chain_id: Option<i128>) -> std::result::Result<u8, String> {
+    if v.len() > 16 {
+        return Err(format!("recovery value v has {} bytes", v.len()));
+    }
+    let v = v
+        .iter()
+        .fold(0u128, |acc, byte| (acc << 8) | u128::from(*byte));
+    match v {
+        0 | 1 => Ok(v as u8),
+        27 | 28 => Ok((v - 27) as u8),
+        35.. => {
+            let offset = v - 35;
+            match chain_id {
+                Some(chain_id) => u128::try_from(chain_id)
+                    .ok()
+                    .and_then(|chain_id| chain_id.checked_mul(2))
+                    .and_then(|base| offset.checked_sub(base))
+                    .filter(|parity| *parity <= 1)
+                    .map(|parity| parity as u8)
+                    .ok_or_else(|| format!("v={v} does not encode chain ID {chain_id}")),
+                None => Ok((offset % 2) as u8),
+            }
+        }
+        _ => Err(format!("unsupported recovery value v={v}")),
+    }
+}
+
+fn recover_eth_address(
+    digest: &[u8; 32],
+    rs: &[u8],
+    parity: u8,
+) -> std::result::Result<Vec<u8>, String> {
+    let signature =
+        Signature::from_slice(rs).map_err(|_| "malformed ECDSA signature".to_string())?;
+    let (signature, parity) = match signature.normalize_s() {
+        Some(normalized) => (normalized, parity ^ 1),
+        None => (signature, parity),
+    };
+    let recovery_id = RecoveryId::from_byte(parity).ok_or("invalid recovery id")?;
+
+    let key = VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+        .map_err(|_| "signature does not recover to a public key".to_string())?;
+    Ok(eth_address(&key))
+}
+
+fn eth_address(key: &VerifyingKey) -> Vec<u8> {
+    let point = key.to_encoded_point(false);
+    keccak256(&point.as_bytes()[1..])[12..].to_vec()
+}
+
+/// Derive the address for the request path from a paired account xpub
+fn derive_account_address(
+    account: &CryptoAccount,
+    request: &EthSignRequest,
+) -> std::result::Result<Vec<u8>, String> {
+    let account_path = &account.key_path.components;
+    let request_path = &request.derivation_path.components;
+    let same =
+        |a: &PathComponent, b: &PathComponent| a.index == b.index && a.hardened == b.hardened;
+
+    if request_path.len() < account_path.len()
+        || !account_path
+            .iter()
+            .zip(request_path)
+            .all(|(a, b)| same(a, b))
+    {
+        return Err(format!(
+            "request path {} is not below paired account {}",
+            request.derivation_path.to_string(),
+            account.key_path.to_string()
+        ));
+    }
+
+    let mut key = VerifyingKey::from_sec1_bytes(&account.public_key)
+        .map_err(|_| "paired account public key is not a valid point".to_string())?;
+    let remaining = &request_path[account_path.len()..];
+    if !remaining.is_empty() {
+        let mut chain_code = account
+            .chain_code
+            .clone()
+            .ok_or("paired account has no chain code for derivation")?;
+        for component in remaining {
+            if component.hardened {
+                return Err("cannot derive hardened children from a public key".to_string());
+            }
+            (key, chain_code) = derive_public_child(&key, &chain_code, component.index)?;
+        }
+    }
+    Ok(eth_address(&key))
+}
+
+/// BIP-32 non-hardened public child derivation
+fn derive_public_child(
+    parent: &VerifyingKey,
+    chain_code: &[u8],
+    index: u32,
+) -> std::result::Result<(VerifyingKey, Vec<u8>), String> {
+    let mut mac =
+        Hmac::<Sha512>::new_from_slice(chain_code).map_err(|_| "invalid chain code".to_string())?;
+    mac.update(parent.to_encoded_point(true).as_bytes());
+    mac.update(&index.to_be_bytes());
+    let output = mac.finalize().into_bytes();
+
+    let mut tweak_bytes = [0u8; 32];
+    tweak_bytes.copy_from_slice(&output[..32]);
+    let tweak = Option::<Scalar>::from(Scalar::from_repr(tweak_bytes.into()))
+        .ok_or("derived tweak is out of range")?;
+
+    let child = ProjectivePoint::GENERATOR * tweak + parent.as_affine();
+    let child = VerifyingKey::from_affine(child.to_affine())
+        .map_err(|_| "derived key is the point at infinity".to_string())?;
+    Ok((child, output[32..].to_vec()))
+}
+
+/// Extract the raw ed25519 key from a Stellar address (raw or `G...` strkey)
+fn stellar_public_key(address: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    if address.len() == 32 {
+        return Ok(address.to_vec());
+    }
+    let text = std::str::from_utf8(address).map_err(|_| "unrecognised Stellar address")?;
+    match strkey::decode(text.trim()) {
+        Some((strkey::VERSION_ACCOUNT_ID, key)) if key.len() == 32 => Ok(key),
+        _ => Err(format!("{text} is not a Stellar account address")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use ed25519_dalek::Signer;
+    use k256::ecdsa::SigningKey;
+
+    fn eth_sign(key: &SigningKey, digest: &[u8; 32]) -> Vec<u8> {
+        let (signature, recovery_id) = key.sign_prehash_recoverable(digest).unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(27 + recovery_id.to_byte());
+        bytes
+    }
+
+    #[test]
+    fn test_eth_personal_message_against_address() {
+        let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let address = eth_address(key.verifying_key());
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_personal_message(b"hello".to_vec(), path)
+            .with_address(address.clone());
+        let digest = eth_signing_digest(&request).unwrap();
+
+        let mut signature = EthSignature::new(eth_sign(&key, &digest));
+        signature.request_id = request.request_id;
+        let verification = verify_eth(&request, &signature, None);
+        assert_eq!(verification.status, VerificationStatus::Valid);
+        assert_eq!(
+            verification.recovered_signer,
+            Some(format!("0x{}", hex::encode(&address)))
+        );
+
+        let other = SigningKey::from_slice(&[0x43; 32]).unwrap();
+        let forged = EthSignature::new(eth_sign(&other, &digest));
+        assert_eq!(
+            verify_eth(&request, &forged, None).status,
+            VerificationStatus::Invalid
+        );
+
+        let mut stale = signature.clone();
+        stale.request_id = Some(Uuid::new_v4());
+        assert_eq!(
+            verify_eth(&request, &stale, None).status,
+            VerificationStatus::Invalid
+        );
+    }
+
+    #[test]
+    fn test_eth_multi_byte_eip155_v() {
+        let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        // Polygon: v = 137 * 2 + 35 + parity = 309 or 310
+        let request = EthSignRequest::new_transaction(vec![0xc0], path, Some(137))
+            .with_address(eth_address(key.verifying_key()));
+        let (rs, recovery_id) = key.sign_prehash_recoverable(&keccak256(&[0xc0])).unwrap();
+        let v = 309 + u16::from(recovery_id.to_byte());
+        let mut bytes = rs.to_bytes().to_vec();
+        bytes.extend_from_slice(&v.to_be_bytes());
+
+        let signature = EthSignature::new(bytes.clone());
+        assert!(verify_eth(&request, &signature, None).is_valid());
+
+        // A v for another chain, or one too long to parse, is unverifiable
+        let mut other_chain = bytes.clone();
+        other_chain[64..].copy_from_slice(&(v + 2).to_be_bytes());
+        let verification = verify_eth(&request, &EthSignature::new(other_chain), None);
+        assert_eq!(verification.status, VerificationStatus::Unverifiable);
+        let mut oversized = bytes[..64].to_vec();
+        oversized.extend_from_slice(&[0x01; 17]);
+        let verification = verify_eth(&request, &EthSignature::new(oversized), None);
+        assert_eq!(verification.status, VerificationStatus::Unverifiable);
+    }
+
+    #[test]
+    fn test_eth_against_paired_account() {
+        // Account-level key at m/44'/60'/0' with a chain code
+        let account_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+        let chain_code = vec![0x22; 32];
+        let account = CryptoAccount::new(
+            [0, 0, 0, 0],
+            account_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            CryptoKeyPath::from_str("m/44'/60'/0'").unwrap(),
+        )
+        .with_chain_code(chain_code.clone());
+
+        // Private-side derivation of m/44'/60'/0'/0/0 to sign with
+        let mut private = account_key.clone();
+        let mut code = chain_code;
+        for index in [0u32, 0] {
+            let mut mac = Hmac::<Sha512>::new_from_slice(&code).unwrap();
+            mac.update(private.verifying_key().to_encoded_point(true).as_bytes());
+            mac.update(&index.to_be_bytes());
+            let output = mac.finalize().into_bytes();
+            let mut tweak_bytes = [0u8; 32];
+            tweak_bytes.copy_from_slice(&output[..32]);
+            let tweak = Scalar::from_repr(tweak_bytes.into()).unwrap();
+            let scalar = *private.as_nonzero_scalar().as_ref() + tweak;
+            private = SigningKey::from_bytes(&scalar.to_bytes()).unwrap();
+            code = output[32..].to_vec();
+        }
+
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_transaction(vec![0xc0], path, Some(1));
+        let signature = EthSignature::new(eth_sign(&private, &keccak256(&[0xc0])));
+
+        assert!(verify_eth(&request, &signature, Some(&account)).is_valid());
+        assert_eq!(
+            verify_eth(&request, &signature, None).status,
+            VerificationStatus::Unverifiable
+        );
+    }
+
+    #[test]
+    fn test_ed25519_chains() {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[0x07; 32]);
+        let public_key = key.verifying_key().to_bytes().to_vec();
+
+        let path = CryptoKeyPath::from_str("m/44'/501'/0'/0'").unwrap();
+        let request = SolanaSignRequest::new(vec![1, 2, 3], path, None);
+        let signature = SolanaSignature::new(key.sign(&[1, 2, 3]).to_bytes().to_vec(), None);
+        assert_eq!(
+            verify_solana(&request, &signature, None).status,
+            VerificationStatus::Unverifiable
+        );
+        assert!(verify_solana(&request, &signature, Some(&public_key)).is_valid());
+        let signature = signature.with_public_key(public_key.clone());
+        assert!(verify_solana(&request, &signature, Some(&public_key)).is_valid());
+
+        let path = CryptoKeyPath::from_str("m/44'/148'/0'").unwrap();
+        let base = vec![9u8; 40];
+        let request = StellarSignRequest::new_transaction(base.clone(), path, Some(Uuid::new_v4()))
+            .with_address(public_key.clone());
+        let signature = StellarSignature::new(
+            request.request_id,
+            key.sign(&Sha256::digest(&base)).to_bytes().to_vec(),
+        );
+        assert!(verify_stellar(&request, &signature, None).is_valid());
+
+        let tampered = StellarSignature::new(request.request_id, vec![0u8; 64]);
+        assert_eq!(
+            verify_stellar(&request, &tampered, None).status,
+            VerificationStatus::Invalid
+        );
+    }
+
+    #[test]
+    fn test_self_signed_response_is_not_valid() {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[0x07; 32]);
+        let expected = key.verifying_key().to_bytes().to_vec();
+        let attacker = ed25519_dalek::SigningKey::from_bytes(&[0x08; 32]);
+        let attacker_key = attacker.verifying_key().to_bytes().to_vec();
+
+        let path = CryptoKeyPath::from_str("m/44'/501'/0'/0'").unwrap();
+        let request = SolanaSignRequest::new(vec![1, 2, 3], path.clone(), Some(Uuid::new_v4()));
+        let forged = SolanaSignature::new(
+            attacker.sign(&[1, 2, 3]).to_bytes().to_vec(),
+            request.request_id,
+        )
+        .with_public_key(attacker_key.clone());
+
+        // Only the response's own key is available: nothing to check against
+        let request_message = KeystoneMessage::SolanaSignRequest(request.clone());
+        let response = KeystoneMessage::SolanaSignature(forged.clone());
+        assert_eq!(
+            verify_response(&request_message, &response, None, None).status,
+            VerificationStatus::Unverifiable
+        );
+        assert_eq!(
+            verify_solana(&request, &forged, Some(&expected)).status,
+            VerificationStatus::Invalid
+        );
+
+        // Same forgery without the embedded key still fails the signature check
+        let bare = SolanaSignature::new(
+            attacker.sign(&[1, 2, 3]).to_bytes().to_vec(),
+            request.request_id,
+        );
+        assert_eq!(
+            verify_solana(&request, &bare, Some(&expected)).status,
+            VerificationStatus::Invalid
+        );
+
+        // A paired account exported at the request path supplies the key
+        let account = CryptoAccount::new([0, 0, 0, 0], expected.clone(), path);
+        assert_eq!(
+            verify_response(&request_message, &response, Some(&account), None).status,
+            VerificationStatus::Invalid
+        );
+        let genuine = KeystoneMessage::SolanaSignature(SolanaSignature::new(
+            key.sign(&[1, 2, 3]).to_bytes().to_vec(),
+            request.request_id,
+        ));
+        assert!(verify_response(&request_message, &genuine, Some(&account), None).is_valid());
+    }
+
+    #[test]
+    fn test_stellar_strkey_address() {
+        // Account ID for the all-zero ed25519 key
+        let address = b"GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+        assert_eq!(stellar_public_key(address).unwrap(), vec![0u8; 32]);
+    }
+
+    #[test]
+    fn test_xrp_signature_over_signing_hash() {
+        use crate::keystone::messages::xrp::codec::{sha512_half, signing_data};
+
+        let key = SigningKey::from_slice(&[0x24; 32]).unwrap();
+        let public_key = hex::encode_upper(key.verifying_key().to_encoded_point(true).as_bytes());
+        let transaction = serde_json::json!({
+            "TransactionType": "Payment",
+            "Account": "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh",
+            "Destination": "rrrrrrrrrrrrrrrrrrrrBZbvji",
+            "Amount": "1000000",
+            "Fee": "12",
+            "Sequence": 7,
+            "SigningPubKey": public_key,
+        });
+        let request = XrpSignRequest::new(
+            transaction.to_string(),
+            "m/44'/144'/0'/0/0".to_string(),
+            Some(Uuid::new_v4()),
+        );
+        let digest = sha512_half(&signing_data(&transaction).unwrap());
+        let (signature, _) = key.sign_prehash_recoverable(&digest).unwrap();
+        let der = hex::encode_upper(signature.to_der().as_bytes());
+
+        let response = XrpSignature::new(request.request_id, der);
+        assert!(verify_xrp(&request, &response).is_valid());
+
+        let tampered = transaction.to_string().replace("1000000", "2000000");
+        let tampered = XrpSignRequest::new(
+            tampered,
+            request.derivation_path.clone(),
+            request.request_id,
+        );
+        assert_eq!(
+            verify_xrp(&tampered, &response).status,
+            VerificationStatus::Invalid
+        );
+    }
+}
@@ -31,21 +31,30 @@
 #![warn(missing_docs, rust_2024_compatibility)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod api;
 pub mod config;
 pub mod error;
+pub mod events;
 pub mod logging;
 pub mod metrics;
 pub mod output;
 pub mod qr;
+pub mod session;
+pub mod source;
 
 #[cfg(feature = "camera")]
 #[cfg_attr(docsrs, doc(cfg(feature = "camera")))]
 pub mod camera;
 
+#[cfg(feature = "simulator")]
+#[cfg_attr(docsrs, doc(cfg(feature = "simulator")))]
+pub mod simulator;
+
 pub mod keystone;
 
 // Re-exports for convenience
 pub use error::{Error, Result};
+pub use events::ScanEvent;
 
 #[cfg(feature = "camera")]
 pub use camera::{Camera, CameraConfig, CameraDevice};
@@ -53,53 +62,148 @@ pub use camera::{Camera, CameraConfig, CameraDevice};
 pub use config::{ApiOptions, CameraOptions, LogRotation, LoggingOptions, QlinkConfig};
 pub use keystone::{KeystoneMessage, KeystoneMetadata, KeystonePayload, PayloadEncoding};
 pub use qr::{QrDecoder, QrEncoder, QrPayload};
+pub use source::{ChannelSource, DirectorySource, FrameSource, StillImageSource};
 
-/// High-level scanner interface combining camera + QR + Keystone
+/// High-level scanner interface combining a frame source + QR + Keystone
+///
+/// Defaults to a V4L2 [`Camera`] but accepts any [`FrameSource`].
 #[cfg(feature = "camera")]
-pub struct QlinkScanner {
-    /// The camera device
-    pub camera: Camera,
+pub struct QlinkScanner<S: FrameSource = Camera> {
+    /// The frame source (camera by default)
+    pub source: S,
     decoder: QrDecoder,
+    events: events::EventSender,
+    sessions: session::SharedSessionStore,
+}
+
+/// High-level scanner interface combining a frame source + QR + Keystone
+#[cfg(not(feature = "camera"))]
+pub struct QlinkScanner<S: FrameSource> {
+    /// The frame source
+    pub source: S,
+    decoder: QrDecoder,
+    events: events::EventSender,
+    sessions: session::SharedSessionStore,
 }
 
 #[cfg(feature = "camera")]
-impl QlinkScanner {
-    /// Create a new scanner with the given configuration
+impl QlinkScanner<Camera> {
+    /// Create a new camera-backed scanner with the given configuration
     pub async fn new(config: ScanConfig) -> Result<Self> {
         let camera = Camera::open(config.camera_config).await?;
-        let decoder = QrDecoder::new();
+        Ok(Self::with_source(camera))
+    }
+}
+
+impl<S: FrameSource> QlinkScanner<S> {
+    /// Create a scanner that pulls frames from an arbitrary source
+    pub fn with_source(source: S) -> Self {
+        Self {
+            source,
+            decoder: QrDecoder::new(),
+            events: events::channel(),
+            sessions: session::SessionStore::default().shared(),
+        }
+    }
 
-        Ok(Self { camera, decoder })
+    /// Subscribe to progress events published by [`scan_keystone`](Self::scan_keystone)
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<ScanEvent> {
+        self.events.subscribe()
+    }
+
+    /// Sender for this scanner's events, for subscribing without holding the scanner
+    pub fn event_sender(&self) -> events::EventSender {
+        self.events.clone()
+    }
+
+    /// Store that scanned signatures are matched against
+    pub fn session_store(&self) -> session::SharedSessionStore {
+        self.sessions.clone()
+    }
+
+    /// Consume the scanner and return its frame source
+    pub fn into_source(self) -> S {
+        self.source
+    }
+
+    /// The frame source, formerly the public `camera` field
+    #[deprecated(note = "use the `source` field")]
+    pub fn camera(&self) -> &S {
+        &self.source
+    }
+
+    /// Mutable access to the frame source, formerly the public `camera` field
+    #[deprecated(note = "use the `source` field")]
+    pub fn camera_mut(&mut self) -> &mut S {
+        &mut self.source
     }
 
     /// Scan once and return the first QR code found
     pub async fn scan_once(&mut self) -> Result<QrPayload> {
-        let frame = self.camera.capture_frame().await?;
+        let frame = self.source.next_frame().await?;
         self.decoder.decode(&frame)
     }
 
     /// Scan continuously for Keystone-specific QR codes, including multi-part UR streams
+    ///
+    /// Publishes a [`ScanEvent`] for every step of the session; see [`subscribe`](Self::subscribe).
     pub async fn scan_keystone(&mut self) -> Result<KeystonePayload> {
         use crate::keystone::multipart::MultiPartDecoder;
 
+        let session_id = uuid::Uuid::new_v4();
+        self.emit(ScanEvent::SessionStarted { session_id });
+
         let mut multipart_decoder = MultiPartDecoder::new();
 
         loop {
             match self.scan_once().await {
                 Ok(qr) => {
                     match KeystonePayload::try_from(qr.clone()) {
-                        Ok(payload) => return Ok(payload),
+                        Ok(payload) => {
+                            if let Some(payload) = self.complete(session_id, payload) {
+                                return Ok(payload);
+                            }
+                        }
                         Err(Error::InvalidKeystonePayload(_)) | Err(Error::UrParse(_)) => {
                             if let Some(text) = qr.as_str() {
                                 if text.starts_with("ur:") {
+                                    let parts_before = multipart_decoder
+                                        .current_progress()
+                                        .map(|p| p.parts_received)
+                                        .unwrap_or(0);
                                     match multipart_decoder.receive(text) {
                                         Ok(progress) => {
+                                            self.emit(ScanEvent::FragmentReceived {
+                                                session_id,
+                                                ur_type: fragment_ur_type(text),
+                                                duplicate: progress.parts_received == parts_before,
+                                            });
+                                            if progress.parts_received != parts_before {
+                                                self.emit(ScanEvent::progress(
+                                                    session_id, &progress,
+                                                ));
+                                            }
+
                                             if progress.complete {
                                                 match multipart_decoder.result() {
-                                                    Ok(payload) => return Ok(payload),
-                                                    Err(err) => tracing::warn!(
-                                                        "Failed to finalize multi-part UR: {err}"
-                                                    ),
+                                                    Ok(payload) => {
+                                                        if let Some(payload) =
+                                                            self.complete(session_id, payload)
+                                                        {
+                                                            return Ok(payload);
+                                                        }
+                                                        multipart_decoder.reset();
+                                                    }
+                                                    Err(err) => {
+                                                        tracing::warn!(
+                                                            "Failed to finalize multi-part UR: {err}"
+                                                        );
+                                                        self.emit(ScanEvent::Error {
+                                                            session_id,
+                                                            message: err.to_string(),
+                                                            fatal: false,
+                                                        });
+                                                    }
                                                 }
                                             } else {
                                                 tracing::debug!(
@@ -112,6 +216,11 @@ impl QlinkScanner {
                                         }
                                         Err(err) => {
                                             tracing::warn!("Failed to process UR fragment: {err}");
+                                            self.emit(ScanEvent::Error {
+                                                session_id,
+                                                message: err.to_string(),
+                                                fatal: false,
+                                            });
                                         }
                                     }
                                 }
@@ -128,10 +237,81 @@ impl QlinkScanner {
                 Err(Error::NoQrCodeFound) => {
                     tokio::time::sleep(tokio::time::Duration::from_millis(90)).await;
                 }
-                Err(e) => return Err(e),
+                Err(e) => {
+                    self.emit(ScanEvent::Error {
+                        session_id,
+                        message: e.to_string(),
+                        fatal: true,
+                    });
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Finish a scan, or return `None` to keep scanning past a signature
+    /// that does not answer a pending request
+    ///
+    /// Signatures are only held back while requests are pending; with none
+    /// recorded (CLI scans, library users that never show a request) they are
+    /// reported and accepted.
+    fn complete(
+        &self,
+        session_id: uuid::Uuid,
+        payload: KeystonePayload,
+    ) -> Option<KeystonePayload> {
+        if let Ok(message) = payload.message_type() {
+            if message.is_signature() {
+                let (outcome, awaiting) = {
+                    let mut sessions = self
+                        .sessions
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    let awaiting = sessions.has_pending();
+                    (
+                        sessions.match_response(&payload.ur_type, &message),
+                        awaiting,
+                    )
+                };
+                self.emit(ScanEvent::response_checked(session_id, &outcome));
+                if !outcome.is_matched() {
+                    tracing::warn!(
+                        request_id = ?outcome.request_id(),
+                        outcome = outcome.label(),
+                        "Scanned signature does not answer a pending request"
+                    );
+                    if awaiting {
+                        self.emit(ScanEvent::Error {
+                            session_id,
+                            message: outcome.describe(),
+                            fatal: false,
+                        });
+                        return None;
+                    }
+                }
             }
         }
+
+        self.emit(ScanEvent::Completed {
+            session_id,
+            ur_type: payload.ur_type.clone(),
+            payload: crate::output::keystone_payload_value(&payload),
+        });
+        Some(payload)
     }
+
+    fn emit(&self, event: ScanEvent) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.events.send(event);
+    }
+}
+
+fn fragment_ur_type(fragment: &str) -> Option<String> {
+    fragment
+        .strip_prefix("ur:")?
+        .split('/')
+        .next()
+        .map(str::to_string)
 }
 
 /// Configuration for QR scanning operations
@@ -4,10 +4,11 @@
 compile_error!("qlinkd requires the `camera` feature");
 
 use clap::Parser;
+use qlink::api::ApiServer;
 use qlink::config::MetricsFormat;
 #[cfg(target_family = "unix")]
 use qlink::output::unix::UnixBroadcast;
-use qlink::output::{RenderedKeystone, render_keystone_payload};
+use qlink::output::{OutputSink, RenderedKeystone, qr_payload_value, render_keystone_payload};
 use qlink::{
     Error, KeystonePayload, QlinkConfig, QlinkScanner, Result, ScanConfig, camera, logging, metrics,
 };
@@ -69,6 +70,10 @@ struct Cli {
     #[arg(long, value_name = "PATH")]
     unix_socket: Option<PathBuf>,
 
+    /// Serve the local HTTP API (see `[api]` in the config file) until interrupted
+    #[arg(long)]
+    api: bool,
+
     /// List detected cameras and exit
     #[arg(long)]
     list_cameras: bool,
@@ -98,18 +103,6 @@ impl OutputSinks {
         self.json
     }
 
-    fn emit_keystone(&self, rendered: &RenderedKeystone) -> Result<()> {
-        if self.json {
-            println!("{}", serde_json::to_string_pretty(&rendered.json)?);
-        } else {
-            for line in &rendered.human {
-                println!("{line}");
-            }
-        }
-        self.send_unix_value(&rendered.json)?;
-        Ok(())
-    }
-
     fn send_unix_value(&self, value: &Value) -> Result<()> {
         #[cfg(target_family = "unix")]
         if let Some(unix) = &self.unix {
@@ -121,6 +114,20 @@ impl OutputSinks {
 
         Ok(())
     }
+}
+
+impl OutputSink for OutputSinks {
+    fn emit_keystone(&self, rendered: &RenderedKeystone) -> Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&rendered.json)?);
+        } else {
+            for line in &rendered.human {
+                println!("{line}");
+            }
+        }
+        self.send_unix_value(&rendered.json)?;
+        Ok(())
+    }
 
     fn emit_error(&self, message: &str) -> Result<()> {
         if self.json {
@@ -168,6 +175,10 @@ async fn main() -> Result<()> {
         config.logging.metrics = true;
     }
 
+    if cli.api {
+        config.api.enabled = true;
+    }
+
     if let Some(ref format) = cli.metrics_format {
         config.logging.metrics_format = format.parse::<MetricsFormat>().map_err(Error::Config)?;
     }
@@ -198,6 +209,9 @@ async fn main() -> Result<()> {
         None
     };
 
+    #[cfg(target_family = "unix")]
+    let event_forwarder = unix_broadcast.clone();
+
     #[cfg(target_family = "unix")]
     let sinks = OutputSinks::new(cli.json, unix_broadcast);
 
@@ -216,6 +230,15 @@ async fn main() -> Result<()> {
     let scan_config = ScanConfig { camera_config };
     let mut scanner = QlinkScanner::new(scan_config).await?;
 
+    #[cfg(target_family = "unix")]
+    if let Some(unix) = &event_forwarder {
+        unix.forward_events(scanner.subscribe());
+    }
+
+    if config.api.enabled {
+        return serve_api(&config, scanner).await;
+    }
+
     if cli.scan_once {
         handle_scan_once(&mut scanner, &sinks).await
     } else {
@@ -223,6 +246,16 @@ async fn main() -> Result<()> {
     }
 }
 
+async fn serve_api(config: &QlinkConfig, scanner: QlinkScanner) -> Result<()> {
+    let scanner = Arc::new(tokio::sync::Mutex::new(scanner));
+    let server = ApiServer::bind(&config.api, scanner).await?;
+    info!(addr = %server.local_addr(), "Local API listening");
+
+    tokio::signal::ctrl_c().await?;
+    info!("Shutting down local API");
+    Ok(())
+}
+
 fn list_cameras() -> Result<()> {
     match camera::list_devices() {
         Ok(devices) => {
@@ -245,13 +278,7 @@ async fn handle_scan_once(scanner: &mut QlinkScanner, sinks: &OutputSinks) -> Re
     let qr = scanner.scan_once().await?;
 
     if sinks.json() {
-        let mut root = json!({
-            "qr": {
-                "text": qr.as_str(),
-                "bytes_hex": hex::encode(qr.as_bytes()),
-                "byte_length": qr.as_bytes().len(),
-            }
-        });
+        let mut root = json!({ "qr": qr_payload_value(&qr) });
 
         match KeystonePayload::try_from(qr.clone()) {
             Ok(payload) => {
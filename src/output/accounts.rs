@@ -0,0 +1,233 @@
+//! Renderers for account exports (`crypto-account`, `crypto-hdkey`,
+//! `crypto-multi-accounts`, `crypto-output`)
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::keystone::messages::{CryptoAccount, CryptoHdKey, CryptoMultiAccounts, CryptoOutput};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn account_value(account: &CryptoAccount) -> Value {
+    json!({
+        "master_fingerprint": format!("{:08x}", account.fingerprint_u32()),
+        "derivation_path": account.key_path.to_string(),
+        "public_key_hex": hex_encode(&account.public_key),
+        "public_key_bytes": account.public_key.len(),
+        "chain_code_hex": account.chain_code.as_ref().map(hex_encode),
+        "outputs": account.outputs.iter().map(output_value).collect::<Vec<_>>(),
+    })
+}
+
+pub(super) fn multi_accounts_value(accounts: &CryptoMultiAccounts) -> Value {
+    json!({
+        "master_fingerprint": format!("{:08x}", accounts.fingerprint_u32()),
+        "device_type": accounts.device_type.clone(),
+        "device_id": accounts.device_id.clone(),
+        "firmware_version": accounts.firmware_version.clone(),
+        "keys": accounts
+            .keys
+            .iter()
+            .map(|key| {
+                let mut value = hdkey_value(key);
+                value["chain"] = json!(key.coin_type().and_then(coin_type_label));
+                value
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+pub(super) fn hdkey_value(key: &CryptoHdKey) -> Value {
+    json!({
+        "is_master": key.is_master,
+        "is_private": key.is_private,
+        "key_hex": if key.is_private { None } else { Some(hex_encode(&key.key_data)) },
+        "chain_code_hex": key.chain_code.as_ref().map(hex_encode),
+        "xpub": key.xpub().ok(),
+        "descriptor_key": key.descriptor_key().ok(),
+        "derivation_path": key.origin.as_ref().map(|path| path.to_string()),
+        "children_path": key.children.as_ref().map(|path| path.to_string()),
+        "source_fingerprint": key.source_fingerprint().map(hex_encode),
+        "parent_fingerprint": key.parent_fingerprint.map(hex_encode),
+        "coin_type": key.use_info.map(|info| info.coin_type),
+        "network": key.use_info.map(|info| info.network),
+        "name": key.name.clone(),
+        "note": key.note.clone(),
+    })
+}
+
+pub(super) fn output_value(output: &CryptoOutput) -> Value {
+    let (descriptor, descriptor_error) = match output.descriptor() {
+        Ok(descriptor) => (Some(descriptor), None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    json!({
+        "script_expressions": output
+            .script_expressions
+            .iter()
+            .map(|expression| expression.name())
+            .collect::<Vec<_>>(),
+        "descriptor": descriptor,
+        "descriptor_error": descriptor_error,
+        "hd_key": output.hd_key().map(hdkey_value),
+    })
+}
+
+pub(super) fn account_lines(account: &CryptoAccount) -> Vec<String> {
+    let mut lines = vec!["  Variant: crypto_account".to_string()];
+    lines.push(format!(
+        "    Fingerprint: {:08x}",
+        account.fingerprint_u32()
+    ));
+    lines.push(format!(
+        "    Derivation path: {}",
+        account.key_path.to_string()
+    ));
+    lines.push(format!(
+        "    Public key: {}",
+        format_hex_snippet(&account.public_key)
+    ));
+    if let Some(chain_code) = &account.chain_code {
+        lines.push(format!(
+            "    Chain code: {}",
+            format_hex_snippet(chain_code)
+        ));
+    }
+    for output in &account.outputs {
+        lines.push(descriptor_line(output));
+    }
+    lines
+}
+
+pub(super) fn hdkey_lines(key: &CryptoHdKey) -> Vec<String> {
+    let mut lines = vec!["  Variant: crypto_hdkey".to_string()];
+    if let Some(name) = &key.name {
+        lines.push(format!("    Name: {}", name));
+    }
+    if let Some(origin) = &key.origin {
+        lines.push(format!("    Derivation path: {}", origin.to_string()));
+    }
+    if let Some(fingerprint) = key.source_fingerprint() {
+        lines.push(format!("    Fingerprint: {}", hex_encode(fingerprint)));
+    }
+    match key.xpub() {
+        Ok(xpub) => lines.push(format!("    Xpub: {}", xpub)),
+        Err(_) => lines.push(format!("    Key: {}", format_hex_snippet(&key.key_data))),
+    }
+    if let Some(note) = &key.note {
+        lines.push(format!("    Note: {}", format_text_snippet(note)));
+    }
+    lines
+}
+
+pub(super) fn multi_accounts_lines(accounts: &CryptoMultiAccounts) -> Vec<String> {
+    let mut lines = vec!["  Variant: crypto_multi_accounts".to_string()];
+    lines.push(format!(
+        "    Fingerprint: {:08x}",
+        accounts.fingerprint_u32()
+    ));
+    if let Some(device_type) = &accounts.device_type {
+        lines.push(format!("    Device: {}", device_type));
+    }
+    if let Some(device_id) = &accounts.device_id {
+        lines.push(format!("    Device ID: {}", device_id));
+    }
+    if let Some(version) = &accounts.firmware_version {
+        lines.push(format!("    Firmware: {}", version));
+    }
+    lines.push(format!("    Keys: {}", accounts.keys.len()));
+    for key in &accounts.keys {
+        let chain = key
+            .coin_type()
+            .map(|coin| coin_type_label(coin).map_or(format!("coin {coin}"), str::to_string))
+            .unwrap_or_else(|| "unknown chain".to_string());
+        let path = key
+            .origin
+            .as_ref()
+            .map(|origin| origin.to_string())
+            .unwrap_or_else(|| "m".to_string());
+        let key_text = key
+            .xpub()
+            .unwrap_or_else(|_| format_hex_snippet(&key.key_data));
+        lines.push(format!("      {chain} {path}: {key_text}"));
+    }
+    lines
+}
+
+pub(super) fn output_lines(output: &CryptoOutput) -> Vec<String> {
+    vec![
+        "  Variant: crypto_output".to_string(),
+        descriptor_line(output),
+    ]
+}
+
+/// Chain name for common SLIP-44 coin types
+fn coin_type_label(coin_type: u32) -> Option<&'static str> {
+    match coin_type {
+        0 => Some("bitcoin"),
+        2 => Some("litecoin"),
+        3 => Some("dogecoin"),
+        60 => Some("ethereum"),
+        118 => Some("cosmos"),
+        144 => Some("xrp"),
+        145 => Some("bitcoin_cash"),
+        148 => Some("stellar"),
+        195 => Some("tron"),
+        397 => Some("near"),
+        501 => Some("solana"),
+        637 => Some("aptos"),
+        784 => Some("sui"),
+        1815 => Some("cardano"),
+        3030 => Some("hedera"),
+        _ => None,
+    }
+}
+
+fn descriptor_line(output: &CryptoOutput) -> String {
+    match output.descriptor() {
+        Ok(descriptor) => format!("    Descriptor: {}", descriptor),
+        Err(err) => format!("    Descriptor error: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{mentions, render};
+
+    #[test]
+    fn renders_crypto_output_descriptor() {
+        use crate::keystone::messages::{CryptoEcKey, OutputKey, ScriptExpression};
+
+        let output = CryptoOutput::new(
+            vec![ScriptExpression::PublicKeyHash],
+            OutputKey::EcKey(CryptoEcKey::new(vec![0x02; 33])),
+        );
+        let rendered = render(KeystoneMessage::CryptoOutput(output));
+
+        assert_eq!(rendered.json["ur_type"], "crypto-output");
+        assert_eq!(rendered.json["message_variant"], "crypto_output");
+        let descriptor = rendered.json["message"]["descriptor"].as_str().unwrap();
+        assert!(descriptor.starts_with("pkh(0202"));
+        assert!(mentions(&rendered, "Descriptor: pkh("));
+    }
+
+    #[test]
+    fn renders_multi_accounts_keys() {
+        let key = CryptoHdKey::new(vec![0x02; 33], vec![0xaa; 32])
+            .with_origin(CryptoKeyPath::from_str("m/44'/60'/0'").unwrap());
+        let accounts = CryptoMultiAccounts::new([0x12, 0x34, 0x56, 0x78], vec![key])
+            .with_device_type("Keystone 3 Pro".to_string())
+            .with_device_id("device-1".to_string())
+            .with_firmware_version("1.2.0".to_string());
+        let rendered = render(KeystoneMessage::CryptoMultiAccounts(accounts));
+
+        let message = &rendered.json["message"];
+        assert_eq!(rendered.json["ur_type"], "crypto-multi-accounts");
+        assert_eq!(rendered.json["message_variant"], "crypto_multi_accounts");
+        assert_eq!(message["device_id"], "device-1");
+        assert_eq!(message["keys"][0]["chain"], "ethereum");
+        assert!(message["keys"][0]["xpub"].is_string());
+        assert!(mentions(&rendered, "ethereum m/44'/60'/0': xpub"));
+    }
+}
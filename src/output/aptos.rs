@@ -0,0 +1,248 @@
+//! Renderers for Aptos sign requests and signatures
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::keystone::messages::aptos::transaction::{format_address, format_octas_as_apt};
+use crate::keystone::messages::{
+    AptosEntryFunction, AptosPayload, AptosSignRequest, AptosSignType, AptosSignature,
+    AptosTransaction,
+};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &AptosSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_paths": request
+            .derivation_paths
+            .iter()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>(),
+        "accounts": request.accounts.iter().map(hex_encode).collect::<Vec<_>>(),
+        "origin": request.origin.clone(),
+        "sign_type": request.sign_type.label(),
+        "sign_data_hex": hex_encode(&request.sign_data),
+        "sign_data_bytes": request.sign_data.len(),
+    });
+    match request.sign_type {
+        AptosSignType::Single | AptosSignType::Multi => match request.decode_transaction() {
+            Ok(transaction) => value["transaction"] = transaction_value(&transaction),
+            Err(err) => value["transaction_error"] = json!(err.to_string()),
+        },
+        AptosSignType::Message => {
+            value["message"] = json!(String::from_utf8_lossy(&request.sign_data))
+        }
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &AptosSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+        "authentication_key_hex": hex_encode(&signature.authentication_key),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &AptosSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: aptos_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    for path in &request.derivation_paths {
+        lines.push(format!("    Derivation path: {}", path.to_string()));
+    }
+    for account in &request.accounts {
+        lines.push(format!("    Account: 0x{}", hex_encode(account)));
+    }
+    lines.push(format!("    Sign type: {}", request.sign_type.label()));
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Sign data: {}",
+        format_hex_snippet(&request.sign_data)
+    ));
+    match request.sign_type {
+        AptosSignType::Single | AptosSignType::Multi => match request.decode_transaction() {
+            Ok(transaction) => lines.extend(transaction_lines(&transaction)),
+            Err(err) => lines.push(format!("    Transaction error: {}", err)),
+        },
+        AptosSignType::Message => lines.push(format!(
+            "    Message: {}",
+            format_text_snippet(&String::from_utf8_lossy(&request.sign_data))
+        )),
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &AptosSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: aptos_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    lines.push(format!(
+        "    Authentication key: {}",
+        format_hex_snippet(&signature.authentication_key)
+    ));
+    lines
+}
+
+fn transaction_value(transaction: &AptosTransaction) -> Value {
+    let mut payload = match &transaction.payload {
+        AptosPayload::Script { code_len } => json!({ "code_bytes": code_len }),
+        AptosPayload::ModuleBundle { modules } => json!({ "modules": modules }),
+        AptosPayload::EntryFunction(function) => entry_function_value(function),
+        AptosPayload::Multisig {
+            multisig_address,
+            function,
+        } => json!({
+            "multisig_address": format_address(multisig_address),
+            "function": function.as_ref().map(entry_function_value),
+        }),
+    };
+    payload["type"] = json!(transaction.payload.label());
+    json!({
+        "sender": format_address(&transaction.sender),
+        "sequence_number": transaction.sequence_number,
+        "chain_id": transaction.chain_id,
+        "network": transaction.network(),
+        "max_gas_amount": transaction.max_gas_amount,
+        "gas_unit_price": transaction.gas_unit_price,
+        "max_fee": transaction.max_fee().map(format_octas_as_apt),
+        "expiration_timestamp_secs": transaction.expiration_timestamp_secs,
+        "payload": payload,
+    })
+}
+
+fn entry_function_value(function: &AptosEntryFunction) -> Value {
+    let mut value = json!({
+        "function": function.id(),
+        "type_args": function.type_args,
+        "args_hex": function.args.iter().map(hex_encode).collect::<Vec<_>>(),
+    });
+    if let Some((recipient, octas)) = function.apt_transfer() {
+        value["transfer"] = json!({
+            "recipient": format_address(&recipient),
+            "octas": octas.to_string(),
+            "apt": format_octas_as_apt(octas),
+        });
+    }
+    value
+}
+
+fn transaction_lines(transaction: &AptosTransaction) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Transaction: {} from {}, sequence {}",
+        transaction.payload.label(),
+        format_address(&transaction.sender),
+        transaction.sequence_number
+    )];
+    match transaction.network() {
+        Some(network) => lines.push(format!("      Network: {}", network)),
+        None => lines.push(format!("      Chain ID: {}", transaction.chain_id)),
+    }
+    lines.push(format!(
+        "      Max fee: {} ({} gas at {} octas)",
+        transaction
+            .max_fee()
+            .map(format_octas_as_apt)
+            .unwrap_or_else(|| "overflow".to_string()),
+        transaction.max_gas_amount,
+        transaction.gas_unit_price
+    ));
+    lines.push(format!(
+        "      Expires: {}",
+        transaction.expiration_timestamp_secs
+    ));
+    let function = match &transaction.payload {
+        AptosPayload::Script { code_len } => {
+            lines.push(format!(
+                "      Script: {} bytes of bytecode (not reviewed)",
+                code_len
+            ));
+            None
+        }
+        AptosPayload::ModuleBundle { modules } => {
+            lines.push(format!("      Publishes {} modules", modules));
+            None
+        }
+        AptosPayload::EntryFunction(function) => Some(function),
+        AptosPayload::Multisig {
+            multisig_address,
+            function,
+        } => {
+            lines.push(format!(
+                "      Multisig account: {}",
+                format_address(multisig_address)
+            ));
+            function.as_ref()
+        }
+    };
+    if let Some(function) = function {
+        match function.apt_transfer() {
+            Some((recipient, octas)) => lines.push(format!(
+                "      Transfer {} to {}",
+                format_octas_as_apt(octas),
+                format_address(&recipient)
+            )),
+            None if function.type_args.is_empty() => {
+                lines.push(format!("      Call: {}", function.id()))
+            }
+            None => lines.push(format!(
+                "      Call: {}<{}>",
+                function.id(),
+                function.type_args.join(", ")
+            )),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{has_line, render};
+
+    #[test]
+    fn renders_aptos_transfer() {
+        let sign_data = [
+            hex::decode("b5e97db07fa0bd0e5598aa3643a9bc6f6693bddc1a9fec9e674a461eaa00b193")
+                .unwrap(), // sha3_256("APTOS::RawTransaction")
+            vec![0xaa; 32],                  // sender
+            7u64.to_le_bytes().to_vec(),     // sequence_number
+            vec![2],                         // EntryFunction
+            [vec![0; 31], vec![1]].concat(), // 0x1
+            [&[13][..], b"aptos_account", &[8], b"transfer", &[0, 2, 32]].concat(),
+            vec![0xbb; 32], // recipient
+            [&[8][..], &150_000_000u64.to_le_bytes()].concat(),
+            2_000u64.to_le_bytes().to_vec(),         // max_gas_amount
+            100u64.to_le_bytes().to_vec(),           // gas_unit_price
+            1_700_000_000u64.to_le_bytes().to_vec(), // expiration
+            vec![1],                                 // chain_id
+        ]
+        .concat();
+        let path = CryptoKeyPath::from_str("m/44'/637'/0'/0'/0'").unwrap();
+        let request = AptosSignRequest::new(sign_data, vec![path], AptosSignType::Single, None);
+        let rendered = render(KeystoneMessage::AptosSignRequest(request));
+
+        let transaction = &rendered.json["message"]["transaction"];
+        assert_eq!(transaction["network"], "mainnet");
+        assert_eq!(transaction["max_fee"], "0.002 APT");
+        assert_eq!(
+            transaction["payload"]["function"],
+            "0x1::aptos_account::transfer"
+        );
+        assert_eq!(transaction["payload"]["transfer"]["apt"], "1.5 APT");
+        assert!(has_line(
+            &rendered,
+            &format!("      Transfer 1.5 APT to 0x{}", "bb".repeat(32))
+        ));
+    }
+}
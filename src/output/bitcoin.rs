@@ -0,0 +1,144 @@
+//! Renderers for Bitcoin PSBTs (`crypto-psbt`)
+
+use super::format_hex_snippet;
+use crate::keystone::messages::{CryptoPsbt, InputSignStatus};
+use hex::encode as hex_encode;
+use serde_json::{Map, Value, json};
+
+pub(super) fn psbt_value(psbt: &CryptoPsbt) -> Value {
+    let mut root = Map::new();
+    root.insert(
+        "psbt_hex".to_string(),
+        Value::String(hex_encode(&psbt.psbt)),
+    );
+    root.insert("psbt_bytes".to_string(), json!(psbt.psbt.len()));
+
+    match psbt.parse() {
+        Ok(parsed) => {
+            let inputs: Vec<Value> = parsed
+                .inputs
+                .iter()
+                .map(|input| {
+                    let signatures = match input.status {
+                        InputSignStatus::Signed { signatures } => signatures,
+                        _ => 0,
+                    };
+                    json!({
+                        "previous_txid": input.previous_txid.clone(),
+                        "previous_vout": input.previous_vout,
+                        "sequence": input.sequence,
+                        "amount_sats": input.amount,
+                        "script_pubkey_hex": input.script_pubkey.as_ref().map(hex_encode),
+                        "status": input.status.label(),
+                        "signatures": signatures,
+                    })
+                })
+                .collect();
+            let outputs: Vec<Value> = parsed
+                .outputs
+                .iter()
+                .map(|output| {
+                    json!({
+                        "amount_sats": output.amount,
+                        "script_pubkey_hex": hex_encode(&output.script_pubkey),
+                        "script_type": output.script_type.label(),
+                        "is_change": output.is_change,
+                    })
+                })
+                .collect();
+
+            root.insert("psbt_version".to_string(), json!(parsed.version));
+            root.insert("tx_version".to_string(), json!(parsed.tx_version));
+            root.insert("lock_time".to_string(), json!(parsed.lock_time));
+            root.insert("inputs".to_string(), Value::Array(inputs));
+            root.insert("outputs".to_string(), Value::Array(outputs));
+            root.insert("total_input_sats".to_string(), json!(parsed.total_input()));
+            root.insert(
+                "total_output_sats".to_string(),
+                json!(parsed.total_output()),
+            );
+            root.insert("fee_sats".to_string(), json!(parsed.fee()));
+            root.insert("signed_inputs".to_string(), json!(parsed.signed_inputs()));
+        }
+        Err(err) => {
+            root.insert("parse_error".to_string(), Value::String(err.to_string()));
+        }
+    }
+
+    Value::Object(root)
+}
+
+pub(super) fn psbt_lines(psbt: &CryptoPsbt) -> Vec<String> {
+    let mut lines = vec!["  Variant: crypto_psbt".to_string()];
+    lines.push(format!("    PSBT: {}", format_hex_snippet(&psbt.psbt)));
+
+    let parsed = match psbt.parse() {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            lines.push(format!("    Parse error: {err}"));
+            return lines;
+        }
+    };
+
+    lines.push(format!(
+        "    Inputs: {} ({} signed)",
+        parsed.inputs.len(),
+        parsed.signed_inputs()
+    ));
+    for (index, input) in parsed.inputs.iter().enumerate() {
+        let amount = input
+            .amount
+            .map(format_btc)
+            .unwrap_or_else(|| "unknown amount".to_string());
+        lines.push(format!(
+            "      #{index} {}:{} {amount} [{}]",
+            input.previous_txid,
+            input.previous_vout,
+            input.status.label()
+        ));
+    }
+
+    lines.push(format!("    Outputs: {}", parsed.outputs.len()));
+    for (index, output) in parsed.outputs.iter().enumerate() {
+        let change = if output.is_change { " (change)" } else { "" };
+        lines.push(format!(
+            "      #{index} {} {}{change}",
+            format_btc(output.amount),
+            output.script_type.label()
+        ));
+    }
+
+    match parsed.fee() {
+        Some(fee) => lines.push(format!("    Fee: {}", format_btc(fee))),
+        None => lines.push("    Fee: unknown (missing input amounts)".to_string()),
+    }
+    lines
+}
+
+fn format_btc(sats: u64) -> String {
+    format!("{}.{:08} BTC", sats / 100_000_000, sats % 100_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::output::tests::{mentions, render};
+
+    #[test]
+    fn renders_crypto_psbt_summary() {
+        // v0 PSBT with an empty unsigned transaction
+        let mut bytes = b"psbt\xff".to_vec();
+        bytes.extend_from_slice(&[0x01, 0x00, 0x0a]);
+        bytes.extend_from_slice(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        bytes.push(0x00);
+        let rendered = render(KeystoneMessage::CryptoPsbt(CryptoPsbt::new(bytes)));
+
+        assert_eq!(rendered.json["message_variant"], "crypto_psbt");
+        assert_eq!(rendered.json["message"]["fee_sats"], 0);
+        assert!(mentions(&rendered, "Inputs: 0 (0 signed)"));
+
+        let rendered = render(KeystoneMessage::CryptoPsbt(CryptoPsbt::new(vec![1, 2, 3])));
+        assert!(rendered.json["message"]["parse_error"].is_string());
+    }
+}
@@ -0,0 +1,100 @@
+//! Renderers for generic `bytes` payloads
+
+use super::{
+    format_hex_snippet, format_text_snippet, keystone_message_value, message_variant_label,
+};
+use crate::KeystonePayload;
+use crate::keystone::messages::{Bytes, BytesContent};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn value(bytes: &Bytes) -> Value {
+    let content = bytes.content();
+    let mut value = json!({
+        "framing": bytes.framing.label(),
+        "data_hex": hex_encode(&bytes.data),
+        "data_bytes": bytes.data.len(),
+        "content_type": content.label(),
+    });
+    match content {
+        BytesContent::Ur(ur) => {
+            match KeystonePayload::from_ur(&ur).and_then(|payload| payload.message_type()) {
+                Ok(message) => {
+                    value["nested_variant"] = json!(message_variant_label(&message));
+                    value["nested_message"] = keystone_message_value(&message);
+                }
+                Err(err) => value["nested_error"] = json!(err.to_string()),
+            }
+            value["ur"] = json!(ur);
+        }
+        BytesContent::Json(json) => value["json"] = json,
+        BytesContent::Text(text) => value["text"] = json!(text),
+        BytesContent::Binary => {}
+    }
+    value
+}
+
+pub(super) fn lines(bytes: &Bytes) -> Vec<String> {
+    let mut lines = vec!["  Variant: bytes".to_string()];
+    lines.push(format!(
+        "    Framing: {} ({} bytes)",
+        bytes.framing.label(),
+        bytes.data.len()
+    ));
+    match bytes.content() {
+        BytesContent::Ur(ur) => {
+            lines.push(format!("    Nested UR: {}", format_text_snippet(&ur)));
+            match KeystonePayload::from_ur(&ur).and_then(|payload| payload.message_type()) {
+                Ok(message) => lines.push(format!(
+                    "      Decodes as: {}",
+                    message_variant_label(&message)
+                )),
+                Err(err) => lines.push(format!("      Decode error: {}", err)),
+            }
+        }
+        BytesContent::Json(json) => lines.push(format!(
+            "    JSON: {}",
+            format_text_snippet(&json.to_string())
+        )),
+        BytesContent::Text(text) => lines.push(format!("    Text: {}", format_text_snippet(&text))),
+        BytesContent::Binary => {
+            lines.push(format!("    Data: {}", format_hex_snippet(&bytes.data)))
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{has_line, render};
+
+    #[test]
+    fn renders_bytes_text_json_and_nested_ur() {
+        use crate::keystone::messages::SolanaSignRequest;
+
+        let render = |data: &[u8]| render(KeystoneMessage::Bytes(Bytes::new(data.to_vec())));
+
+        let rendered = render(b"hello keystone");
+        assert_eq!(rendered.json["message_variant"], "bytes");
+        assert_eq!(rendered.json["message"]["text"], "hello keystone");
+        assert!(has_line(&rendered, "    Text: hello keystone"));
+
+        let rendered = render(br#"{"signature":"0x01"}"#);
+        assert_eq!(rendered.json["message"]["json"]["signature"], "0x01");
+
+        let path = CryptoKeyPath::from_str("m/44'/501'/0'").unwrap();
+        let inner: KeystonePayload =
+            KeystoneMessage::SolanaSignRequest(SolanaSignRequest::new(vec![0x01], path, None))
+                .into();
+        let rendered = render(inner.to_ur().as_bytes());
+        assert_eq!(rendered.json["message"]["content_type"], "ur");
+        assert_eq!(
+            rendered.json["message"]["nested_variant"],
+            "solana_sign_request"
+        );
+        assert!(has_line(&rendered, "      Decodes as: solana_sign_request"));
+    }
+}
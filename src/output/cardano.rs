@@ -0,0 +1,135 @@
+//! Renderers for Cardano sign requests and signatures
+
+use super::format_hex_snippet;
+use crate::keystone::messages::cardano::format_lovelace_as_ada;
+use crate::keystone::messages::{CardanoSignRequest, CardanoSignature};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &CardanoSignRequest) -> Value {
+    json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "origin": request.origin.clone(),
+        "sign_data_hex": hex_encode(&request.sign_data),
+        "sign_data_bytes": request.sign_data.len(),
+        "utxos": request
+            .utxos
+            .iter()
+            .map(|utxo| json!({
+                "transaction_hash": hex_encode(&utxo.transaction_hash),
+                "index": utxo.index,
+                "amount_lovelace": utxo.amount,
+                "ada": utxo.lovelace().map(format_lovelace_as_ada),
+                "address": utxo.address,
+                "key_path": utxo.key_path.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+        "total_input": request.total_input_lovelace().map(format_lovelace_as_ada),
+        "cert_keys": request
+            .cert_keys
+            .iter()
+            .map(|key| json!({
+                "key_hash": hex_encode(&key.key_hash),
+                "key_path": key.key_path.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+pub(super) fn signature_value(signature: &CardanoSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "witness_set_hex": hex_encode(&signature.witness_set),
+        "witness_set_bytes": signature.witness_set.len(),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &CardanoSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: cardano_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Sign data: {}",
+        format_hex_snippet(&request.sign_data)
+    ));
+    lines.extend(input_lines(request));
+    for key in &request.cert_keys {
+        lines.push(format!(
+            "    Certificate key: {} ({})",
+            format_hex_snippet(&key.key_hash),
+            key.key_path.to_string()
+        ));
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &CardanoSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: cardano_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Witness set: {}",
+        format_hex_snippet(&signature.witness_set)
+    ));
+    lines
+}
+
+fn input_lines(request: &CardanoSignRequest) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Inputs: {} UTXOs totalling {}",
+        request.utxos.len(),
+        request
+            .total_input_lovelace()
+            .map(format_lovelace_as_ada)
+            .unwrap_or_else(|| "an unknown amount".to_string())
+    )];
+    for utxo in &request.utxos {
+        lines.push(format!(
+            "      {}#{}: {} from {} ({})",
+            hex_encode(&utxo.transaction_hash),
+            utxo.index,
+            utxo.lovelace()
+                .map(format_lovelace_as_ada)
+                .unwrap_or_else(|| format!("{} lovelace", utxo.amount)),
+            utxo.address,
+            utxo.key_path.to_string()
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{has_line, render};
+
+    #[test]
+    fn renders_cardano_inputs_with_total() {
+        use crate::keystone::messages::CardanoUtxo;
+
+        let utxo = |index, amount: &str| CardanoUtxo {
+            transaction_hash: vec![0xab; 32],
+            index,
+            amount: amount.to_string(),
+            key_path: CryptoKeyPath::from_str("m/1852'/1815'/0'/0/0").unwrap(),
+            address: "addr1qexample".to_string(),
+        };
+        let request = CardanoSignRequest::new(
+            vec![0xa4],
+            vec![utxo(0, "1500000"), utxo(1, "2000000")],
+            None,
+        );
+        let rendered = render(KeystoneMessage::CardanoSignRequest(request));
+
+        assert_eq!(rendered.json["message"]["total_input"], "3.5 ADA");
+        assert_eq!(rendered.json["message"]["utxos"][1]["ada"], "2 ADA");
+        assert!(has_line(&rendered, "    Inputs: 2 UTXOs totalling 3.5 ADA"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Renderers for Cosmos sign requests and signatures
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::keystone::messages::{AminoSignDoc, CosmosDataType, CosmosSignRequest, CosmosSignature};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &CosmosSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "data_type": request.data_type.label(),
+        "chain_id": request.chain_id(),
+        "accounts": request
+            .accounts
+            .iter()
+            .map(|account| json!({
+                "derivation_path": account.derivation_path.to_string(),
+                "address": account.address.clone(),
+            }))
+            .collect::<Vec<_>>(),
+        "origin": request.origin.clone(),
+        "sign_data_hex": hex_encode(&request.sign_data),
+        "sign_data_bytes": request.sign_data.len(),
+    });
+    if request.data_type == CosmosDataType::Amino {
+        match request.decode_amino() {
+            Ok(doc) => value["sign_doc"] = amino_sign_doc_value(&doc),
+            Err(err) => value["sign_doc_error"] = json!(err.to_string()),
+        }
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &CosmosSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+        "public_key_hex": signature.public_key.as_ref().map(hex_encode),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &CosmosSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: cosmos_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!("    Data type: {}", request.data_type.label()));
+    if let Some(chain_id) = request.chain_id() {
+        lines.push(format!("    Chain ID: {}", chain_id));
+    }
+    for account in &request.accounts {
+        match &account.address {
+            Some(address) => lines.push(format!(
+                "    Account: {} ({})",
+                address,
+                account.derivation_path.to_string()
+            )),
+            None => lines.push(format!(
+                "    Account: {}",
+                account.derivation_path.to_string()
+            )),
+        }
+    }
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Sign data: {}",
+        format_hex_snippet(&request.sign_data)
+    ));
+    if request.data_type == CosmosDataType::Amino {
+        match request.decode_amino() {
+            Ok(doc) => lines.extend(amino_sign_doc_lines(&doc)),
+            Err(err) => lines.push(format!("    Sign doc error: {}", err)),
+        }
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &CosmosSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: cosmos_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    if let Some(public_key) = &signature.public_key {
+        lines.push(format!(
+            "    Public key: {}",
+            format_hex_snippet(public_key)
+        ));
+    }
+    lines
+}
+
+fn amino_sign_doc_value(doc: &AminoSignDoc) -> Value {
+    json!({
+        "chain_id": doc.chain_id,
+        "account_number": doc.account_number,
+        "sequence": doc.sequence,
+        "fee": doc.fee.iter().map(|coin| coin.display()).collect::<Vec<_>>(),
+        "gas": doc.gas,
+        "memo": doc.memo,
+        "msgs": doc
+            .msgs
+            .iter()
+            .map(|msg| json!({ "type": msg.msg_type, "value": msg.value }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn amino_sign_doc_lines(doc: &AminoSignDoc) -> Vec<String> {
+    let fee = if doc.fee.is_empty() {
+        "none".to_string()
+    } else {
+        doc.fee
+            .iter()
+            .map(|coin| coin.display())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut lines = vec![format!(
+        "    Sign doc: {} messages on {}, account {}, sequence {}",
+        doc.msgs.len(),
+        doc.chain_id,
+        doc.account_number,
+        doc.sequence
+    )];
+    lines.push(format!("      Fee: {} (gas {})", fee, doc.gas));
+    if !doc.memo.is_empty() {
+        lines.push(format!("      Memo: {}", format_text_snippet(&doc.memo)));
+    }
+    for msg in &doc.msgs {
+        let details = msg.details();
+        if details.is_empty() {
+            lines.push(format!("      {}", msg.msg_type));
+        } else {
+            let fields = details
+                .iter()
+                .map(|(name, value)| format!("{} {}", name, value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!("      {}: {}", msg.label(), fields));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{has_line, render};
+
+    #[test]
+    fn renders_cosmos_amino_delegation() {
+        use crate::keystone::messages::CosmosAccount;
+
+        let sign_doc = r#"{"account_number":"12","chain_id":"cosmoshub-4","fee":{"amount":[{"amount":"2500","denom":"uatom"}],"gas":"250000"},"memo":"","msgs":[{"type":"cosmos-sdk/MsgDelegate","value":{"amount":{"amount":"1000000","denom":"uatom"},"delegator_address":"cosmos1delegator","validator_address":"cosmosvaloper1validator"}}],"sequence":"3"}"#;
+        let account = CosmosAccount::new(CryptoKeyPath::from_str("m/44'/118'/0'/0/0").unwrap())
+            .with_address("cosmos1delegator".to_string());
+        let request = CosmosSignRequest::new(
+            sign_doc.as_bytes().to_vec(),
+            CosmosDataType::Amino,
+            vec![account],
+        );
+        let rendered = render(KeystoneMessage::CosmosSignRequest(request));
+
+        let message = &rendered.json["message"];
+        assert_eq!(rendered.json["message_variant"], "cosmos_sign_request");
+        assert_eq!(message["chain_id"], "cosmoshub-4");
+        assert_eq!(message["accounts"][0]["address"], "cosmos1delegator");
+        assert_eq!(message["sign_doc"]["fee"][0], "2500 uatom");
+        assert_eq!(
+            message["sign_doc"]["msgs"][0]["type"],
+            "cosmos-sdk/MsgDelegate"
+        );
+        assert!(has_line(
+            &rendered,
+            "      MsgDelegate: delegator cosmos1delegator, validator cosmosvaloper1validator, amount 1000000 uatom"
+        ));
+    }
+}
@@ -0,0 +1,469 @@
+//! Renderers for Ethereum sign requests and signatures
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::error::Result;
+use crate::keystone::messages::ethereum::transaction::{format_wei_as_eth, format_wei_as_gwei};
+use crate::keystone::messages::{
+    ChainIdCheck, EthDataType, EthSignRequest, EthSignature, EthTransaction, OriginCheck,
+    PersonalMessage, SiweMessage, TypedData,
+};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &EthSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_path": request.derivation_path.to_string(),
+        "chain_id": request.chain_id,
+        "data_type": data_type_label(request.data_type),
+        "origin": request.origin.clone(),
+        "address_hex": request.address.as_ref().map(hex_encode),
+        "sign_data_hex": hex_encode(&request.sign_data),
+        "sign_data_bytes": request.sign_data.len(),
+    });
+    if matches!(
+        request.data_type,
+        EthDataType::Transaction | EthDataType::TypedTransaction
+    ) {
+        match request.decode_transaction() {
+            Ok(transaction) => {
+                value["transaction"] = transaction_value(&transaction);
+                value["chain_id_check"] = json!(request.check_chain_id(&transaction).label());
+            }
+            Err(err) => value["transaction_error"] = json!(err.to_string()),
+        }
+    }
+    if request.data_type == EthDataType::TypedData {
+        match request.decode_typed_data() {
+            Ok(typed) => value["typed_data"] = typed_data_value(&typed),
+            Err(err) => value["typed_data_error"] = json!(err.to_string()),
+        }
+    }
+    if let Ok(message) = request.decode_personal_message() {
+        value["personal_message"] = personal_message_value(&message);
+        if let Some(siwe) = message.siwe() {
+            value["siwe"] = siwe_value(&siwe);
+            value["origin_check"] = json!(request.check_siwe_origin(&siwe).label());
+        }
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &EthSignature) -> Value {
+    let components = signature.rsv().ok();
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "origin": signature.origin.clone(),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+        "r": components.as_ref().map(|(r, _, _)| hex_encode(r)),
+        "s": components.as_ref().map(|(_, s, _)| hex_encode(s)),
+        "v": components.as_ref().map(|(_, _, v)| format!("{:02x}", v)),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &EthSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: eth_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Data type: {}",
+        data_type_label(request.data_type)
+    ));
+    if let Some(chain_id) = request.chain_id {
+        lines.push(format!("    Chain ID: {}", chain_id));
+    }
+    lines.push(format!(
+        "    Derivation path: {}",
+        request.derivation_path.to_string()
+    ));
+    if let Some(address) = &request.address {
+        lines.push(format!("    Address: {}", format_hex_snippet(address)));
+    }
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Sign data: {}",
+        format_hex_snippet(&request.sign_data)
+    ));
+    if matches!(
+        request.data_type,
+        EthDataType::Transaction | EthDataType::TypedTransaction
+    ) {
+        match request.decode_transaction() {
+            Ok(transaction) => {
+                lines.extend(transaction_lines(&transaction));
+                if let ChainIdCheck::Mismatch {
+                    request,
+                    transaction,
+                } = request.check_chain_id(&transaction)
+                {
+                    lines.push(format!(
+                        "    WARNING: transaction chain ID {} does not match request chain ID {}",
+                        transaction, request
+                    ));
+                }
+            }
+            Err(err) => lines.push(format!("    Transaction error: {}", err)),
+        }
+    }
+    if request.data_type == EthDataType::TypedData {
+        match request.decode_typed_data() {
+            Ok(typed) => lines.extend(typed_data_lines(&typed)),
+            Err(err) => lines.push(format!("    Typed data error: {}", err)),
+        }
+    }
+    if let Ok(message) = request.decode_personal_message() {
+        lines.push(personal_message_line(&message));
+        if let Some(siwe) = message.siwe() {
+            lines.extend(siwe_lines(&siwe));
+            if let OriginCheck::Mismatch { domain, origin } = request.check_siwe_origin(&siwe) {
+                lines.push(format!(
+                    "    WARNING: sign-in domain {} does not match request origin {}",
+                    domain, origin
+                ));
+            }
+        }
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &EthSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: eth_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    if let Some(origin) = &signature.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    if let Ok((r, s, v)) = signature.rsv() {
+        lines.push(format!("    r: {}", hex_encode(r)));
+        lines.push(format!("    s: {}", hex_encode(s)));
+        lines.push(format!("    v: {:02x}", v));
+    }
+    lines
+}
+
+fn transaction_value(transaction: &EthTransaction) -> Value {
+    json!({
+        "type": transaction.tx_type.label(),
+        "chain_id": transaction.chain_id,
+        "nonce": transaction.nonce,
+        "to": transaction.to.map(|to| format!("0x{}", hex_encode(to))),
+        "contract_creation": transaction.is_contract_creation(),
+        "value_wei": transaction.value.to_string(),
+        "value_eth": format_wei_as_eth(transaction.value),
+        "gas_limit": transaction.gas_limit,
+        "gas_price_wei": transaction.gas_price.map(|fee| fee.to_string()),
+        "max_fee_per_gas_wei": transaction.max_fee_per_gas.map(|fee| fee.to_string()),
+        "max_priority_fee_per_gas_wei": transaction
+            .max_priority_fee_per_gas
+            .map(|fee| fee.to_string()),
+        "max_fee_per_blob_gas_wei": transaction.max_fee_per_blob_gas.map(|fee| fee.to_string()),
+        "blob_versioned_hashes": transaction
+            .blob_versioned_hashes
+            .iter()
+            .map(hex_encode)
+            .collect::<Vec<_>>(),
+        "max_fee_eth": transaction.max_fee_wei().map(format_wei_as_eth),
+        "access_list_entries": transaction.access_list_len,
+        "data_hex": hex_encode(&transaction.data),
+        "data_bytes": transaction.data.len(),
+        "function_selector": transaction.function_selector().map(hex_encode),
+        "signed": transaction.signed,
+    })
+}
+
+fn transaction_lines(transaction: &EthTransaction) -> Vec<String> {
+    let mut lines = vec![format!("    Transaction: {}", transaction.tx_type.label())];
+    if let Some(chain_id) = transaction.chain_id {
+        lines.push(format!("      Chain ID: {}", chain_id));
+    }
+    lines.push(format!("      Nonce: {}", transaction.nonce));
+    match transaction.to {
+        Some(to) => lines.push(format!("      To: 0x{}", hex_encode(to))),
+        None => lines.push("      To: (contract creation)".to_string()),
+    }
+    lines.push(format!(
+        "      Value: {}",
+        format_wei_as_eth(transaction.value)
+    ));
+    lines.push(format!("      Gas limit: {}", transaction.gas_limit));
+    if let Some(gas_price) = transaction.gas_price {
+        lines.push(format!(
+            "      Gas price: {}",
+            format_wei_as_gwei(gas_price)
+        ));
+    }
+    if let Some(max_fee) = transaction.max_fee_per_gas {
+        lines.push(format!("      Max fee: {}", format_wei_as_gwei(max_fee)));
+    }
+    if let Some(priority_fee) = transaction.max_priority_fee_per_gas {
+        lines.push(format!(
+            "      Priority fee: {}",
+            format_wei_as_gwei(priority_fee)
+        ));
+    }
+    if let Some(blob_fee) = transaction.max_fee_per_blob_gas {
+        lines.push(format!(
+            "      Blob fee: {} ({} blobs)",
+            format_wei_as_gwei(blob_fee),
+            transaction.blob_versioned_hashes.len()
+        ));
+    }
+    if let Some(max_fee) = transaction.max_fee_wei() {
+        lines.push(format!(
+            "      Max network fee: {}",
+            format_wei_as_eth(max_fee)
+        ));
+    }
+    if transaction.data.is_empty() {
+        lines.push("      Calldata: none".to_string());
+    } else {
+        lines.push(format!(
+            "      Calldata: {}",
+            format_hex_snippet(&transaction.data)
+        ));
+    }
+    lines
+}
+
+fn typed_data_value(typed: &TypedData) -> Value {
+    let hash_hex = |hash: Result<[u8; 32]>| hash.ok().map(hex_encode);
+    json!({
+        "primary_type": typed.primary_type,
+        "domain": typed.domain,
+        "message": typed.message,
+        "domain_separator": hash_hex(typed.domain_separator()),
+        "struct_hash": typed.struct_hash().ok().flatten().map(hex_encode),
+        "signing_hash": hash_hex(typed.signing_hash()),
+        "hash_error": typed.signing_hash().err().map(|err| err.to_string()),
+        "risk_flags": typed
+            .risk_flags()
+            .iter()
+            .map(|risk| risk.label())
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn typed_data_lines(typed: &TypedData) -> Vec<String> {
+    let mut lines = vec![format!("    Typed data: {}", typed.primary_type)];
+    if let Some(name) = typed.domain_name() {
+        lines.push(format!("      Domain: {}", name));
+    }
+    if let Some(chain_id) = typed.domain_chain_id() {
+        lines.push(format!("      Domain chain ID: {}", chain_id));
+    }
+    if let Some(contract) = typed.verifying_contract() {
+        lines.push(format!("      Verifying contract: {}", contract));
+    }
+    lines.push(format!(
+        "      Message: {}",
+        format_text_snippet(&typed.message.to_string())
+    ));
+    match (typed.domain_separator(), typed.signing_hash()) {
+        (Ok(domain_separator), Ok(signing_hash)) => {
+            lines.push(format!(
+                "      Domain separator: 0x{}",
+                hex_encode(domain_separator)
+            ));
+            if let Ok(Some(struct_hash)) = typed.struct_hash() {
+                lines.push(format!("      Struct hash: 0x{}", hex_encode(struct_hash)));
+            }
+            lines.push(format!(
+                "      Signing hash: 0x{}",
+                hex_encode(signing_hash)
+            ));
+        }
+        (Err(err), _) | (_, Err(err)) => lines.push(format!("      Hash error: {}", err)),
+    }
+    for risk in typed.risk_flags() {
+        lines.push(format!("    WARNING: {}", risk.description()));
+    }
+    lines
+}
+
+fn personal_message_value(message: &PersonalMessage) -> Value {
+    match message {
+        PersonalMessage::Text(text) => json!({ "encoding": "utf8", "text": text }),
+        PersonalMessage::Binary(bytes) => json!({ "encoding": "hex", "hex": hex_encode(bytes) }),
+    }
+}
+
+fn personal_message_line(message: &PersonalMessage) -> String {
+    match message {
+        PersonalMessage::Text(text) => format!(
+            "    Message: {}",
+            format_text_snippet(&text.replace('\n', "\\n"))
+        ),
+        PersonalMessage::Binary(bytes) => {
+            format!("    Message (hex): {}", format_hex_snippet(bytes))
+        }
+    }
+}
+
+fn siwe_value(siwe: &SiweMessage) -> Value {
+    json!({
+        "domain": siwe.domain,
+        "address": siwe.address,
+        "statement": siwe.statement,
+        "uri": siwe.uri,
+        "version": siwe.version,
+        "chain_id": siwe.chain_id,
+        "nonce": siwe.nonce,
+        "issued_at": siwe.issued_at,
+        "expiration_time": siwe.expiration_time,
+        "not_before": siwe.not_before,
+        "request_id": siwe.request_id,
+        "resources": siwe.resources,
+    })
+}
+
+fn siwe_lines(siwe: &SiweMessage) -> Vec<String> {
+    let mut lines = vec![
+        format!("    Sign-In-With-Ethereum: {}", siwe.domain),
+        format!("      Address: {}", siwe.address),
+    ];
+    if let Some(statement) = &siwe.statement {
+        lines.push(format!(
+            "      Statement: {}",
+            format_text_snippet(statement)
+        ));
+    }
+    if let Some(chain_id) = siwe.chain_id {
+        lines.push(format!("      Chain ID: {}", chain_id));
+    }
+    if let Some(nonce) = &siwe.nonce {
+        lines.push(format!("      Nonce: {}", nonce));
+    }
+    if let Some(issued_at) = &siwe.issued_at {
+        lines.push(format!("      Issued at: {}", issued_at));
+    }
+    if let Some(expiration_time) = &siwe.expiration_time {
+        lines.push(format!("      Expires: {}", expiration_time));
+    }
+    lines
+}
+
+fn data_type_label(data_type: EthDataType) -> &'static str {
+    match data_type {
+        EthDataType::Transaction => "transaction",
+        EthDataType::TypedData => "typed_data",
+        EthDataType::PersonalMessage => "personal_message",
+        EthDataType::TypedTransaction => "typed_transaction",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{mentions, render};
+
+    #[test]
+    fn renders_eth_request_consistently() {
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_transaction(vec![1, 2, 3], path, Some(1));
+        let rendered = render(KeystoneMessage::EthSignRequest(request.clone()));
+
+        assert_eq!(rendered.json["message_variant"], "eth_sign_request");
+        assert!(mentions(&rendered, "Variant: eth_sign_request"));
+        assert!(mentions(&rendered, "Chain ID: 1"));
+        assert!(mentions(&rendered, "Sign data:"));
+    }
+
+    #[test]
+    fn renders_eth_transaction_and_flags_chain_mismatch() {
+        // EIP-155 signing payload for chain 1
+        let tx = hex::decode(
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080",
+        )
+        .unwrap();
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_transaction(tx, path, Some(137));
+        let rendered = render(KeystoneMessage::EthSignRequest(request));
+
+        let message = &rendered.json["message"];
+        assert_eq!(message["transaction"]["value_eth"], "1 ETH");
+        assert_eq!(message["transaction"]["nonce"], 9);
+        assert_eq!(message["chain_id_check"], "mismatch");
+        assert!(mentions(&rendered, "Gas price: 20 gwei"));
+        assert!(mentions(&rendered, "WARNING: transaction chain ID 1"));
+    }
+
+    #[test]
+    fn renders_typed_data_and_flags_permit2() {
+        let json = r#"{
+            "types": {
+                "PermitDetails": [
+                    {"name": "token", "type": "address"},
+                    {"name": "amount", "type": "uint160"},
+                    {"name": "expiration", "type": "uint48"},
+                    {"name": "nonce", "type": "uint48"}
+                ],
+                "PermitSingle": [
+                    {"name": "details", "type": "PermitDetails"},
+                    {"name": "spender", "type": "address"},
+                    {"name": "sigDeadline", "type": "uint256"}
+                ]
+            },
+            "primaryType": "PermitSingle",
+            "domain": {"name": "Permit2", "chainId": 1,
+                       "verifyingContract": "0x000000000022d473030f116ddee9f6b43ac78ba3"},
+            "message": {
+                "details": {
+                    "token": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                    "amount": "1461501637330902918203684832716283019655932542975",
+                    "expiration": "1700000000",
+                    "nonce": "0"
+                },
+                "spender": "0x3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad",
+                "sigDeadline": "1700000000"
+            }
+        }"#;
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_typed_data(json.as_bytes().to_vec(), path, Some(1));
+        let rendered = render(KeystoneMessage::EthSignRequest(request));
+
+        let typed = &rendered.json["message"]["typed_data"];
+        assert_eq!(typed["primary_type"], "PermitSingle");
+        assert_eq!(
+            typed["risk_flags"],
+            json!(["permit2", "unlimited_approval"])
+        );
+        assert!(typed["signing_hash"].is_string());
+        assert!(mentions(&rendered, "WARNING: approval amount is unlimited"));
+    }
+
+    #[test]
+    fn renders_siwe_message_and_flags_origin_mismatch() {
+        let text = "app.example.org wants you to sign in with your Ethereum account:\n\
+            0x1111111111111111111111111111111111111111\n\n\
+            URI: https://app.example.org\n\
+            Version: 1\n\
+            Chain ID: 1\n\
+            Nonce: abc123\n\
+            Issued At: 2024-01-01T00:00:00Z";
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_personal_message(text.as_bytes().to_vec(), path)
+            .with_origin("https://phish.example.net".to_string());
+        let rendered = render(KeystoneMessage::EthSignRequest(request));
+
+        let message = &rendered.json["message"];
+        assert_eq!(message["personal_message"]["encoding"], "utf8");
+        assert_eq!(message["siwe"]["domain"], "app.example.org");
+        assert_eq!(message["siwe"]["nonce"], "abc123");
+        assert_eq!(message["origin_check"], "mismatch");
+        assert!(mentions(
+            &rendered,
+            "WARNING: sign-in domain app.example.org does not match request origin"
+        ));
+    }
+}
@@ -0,0 +1,286 @@
+//! Renderers for Hedera sign requests and signatures
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::keystone::messages::hedera::transaction::format_tinybars_as_hbar;
+use crate::keystone::messages::{
+    HederaSignRequest, HederaSignature, HederaTransaction, HederaTransactionData, PayerCheck,
+};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &HederaSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_path": request.derivation_path.to_string(),
+        "account_id": request.account_id.clone(),
+        "origin": request.origin.clone(),
+        "transaction_hex": hex_encode(&request.transaction_bytes),
+        "transaction_bytes": request.transaction_bytes.len(),
+    });
+    match request.decode_transaction() {
+        Ok(transaction) => {
+            value["transaction"] = transaction_value(&transaction);
+            value["payer_check"] = json!(request.check_payer(&transaction).label());
+        }
+        Err(err) => value["transaction_error"] = json!(err.to_string()),
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &HederaSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+        "public_key_hex": signature.public_key.as_ref().map(hex_encode),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &HederaSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: hedera_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Derivation path: {}",
+        request.derivation_path.to_string()
+    ));
+    if let Some(account_id) = &request.account_id {
+        lines.push(format!("    Account ID: {}", account_id));
+    }
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Transaction bytes: {}",
+        format_hex_snippet(&request.transaction_bytes)
+    ));
+    match request.decode_transaction() {
+        Ok(transaction) => {
+            lines.extend(transaction_lines(&transaction));
+            if let PayerCheck::Mismatch { account_id, payer } = request.check_payer(&transaction) {
+                lines.push(format!(
+                    "    WARNING: transaction is paid by {} but the request account is {}",
+                    payer, account_id
+                ));
+            }
+        }
+        Err(err) => lines.push(format!("    Transaction error: {}", err)),
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &HederaSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: hedera_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    if let Some(public_key) = &signature.public_key {
+        lines.push(format!(
+            "    Public key: {}",
+            format_hex_snippet(public_key)
+        ));
+    }
+    lines
+}
+
+fn transaction_value(transaction: &HederaTransaction) -> Value {
+    let mut data = match &transaction.data {
+        HederaTransactionData::CryptoTransfer {
+            hbar_transfers,
+            token_transfers,
+        } => json!({
+            "hbar_transfers": hbar_transfers
+                .iter()
+                .map(|transfer| json!({
+                    "account": transfer.account.display(),
+                    "tinybars": transfer.amount,
+                    "hbar": format_tinybars_as_hbar(transfer.amount),
+                    "is_approval": transfer.is_approval,
+                }))
+                .collect::<Vec<_>>(),
+            "token_transfers": token_transfers
+                .iter()
+                .map(|list| json!({
+                    "token": list.token.display(),
+                    "expected_decimals": list.expected_decimals,
+                    "transfers": list
+                        .transfers
+                        .iter()
+                        .map(|transfer| json!({
+                            "account": transfer.account.display(),
+                            "amount": transfer.amount,
+                            "is_approval": transfer.is_approval,
+                        }))
+                        .collect::<Vec<_>>(),
+                    "nft_transfers": list
+                        .nft_transfers
+                        .iter()
+                        .map(|nft| json!({
+                            "sender": nft.sender.display(),
+                            "receiver": nft.receiver.display(),
+                            "serial_number": nft.serial_number,
+                        }))
+                        .collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        HederaTransactionData::TokenAssociate { account, tokens } => json!({
+            "account": account.as_ref().map(|account| account.display()),
+            "tokens": tokens.iter().map(|token| token.display()).collect::<Vec<_>>(),
+        }),
+        HederaTransactionData::ContractCall {
+            contract,
+            gas,
+            amount,
+            function_parameters,
+        } => json!({
+            "contract": contract.as_ref().map(|contract| contract.display()),
+            "gas": gas,
+            "tinybars": amount,
+            "function_parameters_hex": hex_encode(function_parameters),
+        }),
+        HederaTransactionData::Other(_) => json!({}),
+    };
+    data["type"] = json!(transaction.data.label());
+    json!({
+        "payer": transaction.payer.as_ref().map(|payer| payer.display()),
+        "valid_start": transaction
+            .valid_start
+            .map(|(seconds, nanos)| format!("{}.{:09}", seconds, nanos)),
+        "scheduled": transaction.scheduled,
+        "node_account": transaction.node_account.as_ref().map(|node| node.display()),
+        "transaction_fee_tinybars": transaction.transaction_fee,
+        "valid_duration_secs": transaction.valid_duration,
+        "memo": transaction.memo,
+        "data": data,
+        "payer_hbar_debit": format_tinybars_as_hbar(transaction.payer_hbar_debit() as i64),
+    })
+}
+
+fn transaction_lines(transaction: &HederaTransaction) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Transaction: {}, max fee {}",
+        transaction.data.label(),
+        format_tinybars_as_hbar(transaction.transaction_fee as i64)
+    )];
+    if let Some(payer) = &transaction.payer {
+        lines.push(format!("      Payer: {}", payer.display()));
+    }
+    if let Some(node) = &transaction.node_account {
+        lines.push(format!("      Node: {}", node.display()));
+    }
+    if !transaction.memo.is_empty() {
+        lines.push(format!(
+            "      Memo: {}",
+            format_text_snippet(&transaction.memo)
+        ));
+    }
+    match &transaction.data {
+        HederaTransactionData::CryptoTransfer {
+            hbar_transfers,
+            token_transfers,
+        } => {
+            for transfer in hbar_transfers {
+                lines.push(format!(
+                    "      {} {}",
+                    transfer.account.display(),
+                    format_tinybars_as_hbar(transfer.amount)
+                ));
+            }
+            for list in token_transfers {
+                for transfer in &list.transfers {
+                    lines.push(format!(
+                        "      {} {} of token {}",
+                        transfer.account.display(),
+                        transfer.amount,
+                        list.token.display()
+                    ));
+                }
+                for nft in &list.nft_transfers {
+                    lines.push(format!(
+                        "      NFT {} #{} from {} to {}",
+                        list.token.display(),
+                        nft.serial_number,
+                        nft.sender.display(),
+                        nft.receiver.display()
+                    ));
+                }
+            }
+        }
+        HederaTransactionData::TokenAssociate { account, tokens } => {
+            let tokens: Vec<String> = tokens.iter().map(|token| token.display()).collect();
+            lines.push(format!(
+                "      Associate {} with {}",
+                account
+                    .as_ref()
+                    .map(|account| account.display())
+                    .unwrap_or_else(|| "payer".to_string()),
+                tokens.join(", ")
+            ));
+        }
+        HederaTransactionData::ContractCall {
+            contract,
+            gas,
+            amount,
+            function_parameters,
+        } => {
+            lines.push(format!(
+                "      Call {} with {} (gas {})",
+                contract
+                    .as_ref()
+                    .map(|contract| contract.display())
+                    .unwrap_or_else(|| "unknown contract".to_string()),
+                format_tinybars_as_hbar(*amount),
+                gas
+            ));
+            lines.push(format!(
+                "      Call data: {}",
+                format_hex_snippet(function_parameters)
+            ));
+        }
+        HederaTransactionData::Other(_) => {}
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{has_line, render};
+
+    #[test]
+    fn renders_hedera_transfer_and_flags_payer_mismatch() {
+        // TransactionBody: payer 0.0.1001, node 0.0.3, 1 HBAR to 0.0.2002
+        let body = vec![
+            0x0a, 0x05, 0x12, 0x03, 0x18, 0xe9, 0x07, // transactionID.accountID
+            0x12, 0x02, 0x18, 0x03, // nodeAccountID
+            0x18, 0x80, 0xc2, 0xd7, 0x2f, // transactionFee
+            0x72, 0x1a, 0x0a, 0x18, // cryptoTransfer.transfers
+            0x0a, 0x0a, 0x0a, 0x03, 0x18, 0xe9, 0x07, 0x10, 0xff, 0x83, 0xaf, 0x5f, // -1 HBAR
+            0x0a, 0x0a, 0x0a, 0x03, 0x18, 0xd2, 0x0f, 0x10, 0x80, 0x84, 0xaf, 0x5f, // +1 HBAR
+        ];
+        let path = CryptoKeyPath::from_str("m/44'/3030'/0'/0/0").unwrap();
+        let request = HederaSignRequest::new(body, path, None).with_account_id("0.0.1234".into());
+        let rendered = render(KeystoneMessage::HederaSignRequest(request));
+
+        let message = &rendered.json["message"];
+        assert_eq!(message["transaction"]["payer"], "0.0.1001");
+        assert_eq!(message["transaction"]["data"]["type"], "crypto_transfer");
+        assert_eq!(
+            message["transaction"]["data"]["hbar_transfers"][1]["hbar"],
+            "1 HBAR"
+        );
+        assert_eq!(message["payer_check"], "mismatch");
+        assert!(has_line(
+            &rendered,
+            "    WARNING: transaction is paid by 0.0.1001 but the request account is 0.0.1234"
+        ));
+    }
+}
@@ -0,0 +1,229 @@
+//! Renderers for Keystone protobuf envelopes (`keystone-sign-request`)
+
+use super::format_text_snippet;
+use crate::keystone::messages::keystone_base::format_units;
+use crate::keystone::messages::{
+    Base, BaseContent, BaseTransaction, KeystoneSignRequest, SignTransaction,
+};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &KeystoneSignRequest) -> Value {
+    let mut value = json!({
+        "wrapper": request.wrapper.ur_type(),
+        "sign_data_hex": hex_encode(&request.sign_data),
+        "sign_data_bytes": request.sign_data.len(),
+    });
+    match request.decode_base() {
+        Ok(base) => value["base"] = base_value(&base),
+        Err(err) => value["base_error"] = json!(err.to_string()),
+    }
+    value
+}
+
+pub(super) fn sign_request_lines(request: &KeystoneSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: keystone_sign_request".to_string()];
+    lines.push(format!("    Wrapper: {}", request.wrapper.ur_type()));
+    match request.decode_base() {
+        Ok(base) => lines.extend(base_lines(&base)),
+        Err(err) => lines.push(format!("    Envelope error: {}", err)),
+    }
+    lines
+}
+
+fn base_value(base: &Base) -> Value {
+    let mut value = json!({
+        "version": base.version,
+        "description": base.description.clone(),
+        "device_type": base.device_type.clone(),
+    });
+    let Some(payload) = &base.payload else {
+        return value;
+    };
+    value["payload_type"] = json!(payload.kind.label());
+    value["xfp"] = json!(payload.xfp.clone());
+    match &payload.content {
+        BaseContent::None => {}
+        BaseContent::SignTransaction(sign_tx) => {
+            value["sign_tx"] = sign_transaction_value(sign_tx);
+        }
+        BaseContent::SignTransactionResult(result) => {
+            value["sign_tx_result"] = json!({
+                "sign_id": result.sign_id.clone(),
+                "tx_id": result.tx_id.clone(),
+                "raw_tx": result.raw_tx.clone(),
+            });
+        }
+        BaseContent::Other { field } => value["content_field"] = json!(field),
+    }
+    value
+}
+
+fn sign_transaction_value(sign_tx: &SignTransaction) -> Value {
+    let mut value = json!({
+        "coin_code": sign_tx.coin_code.clone(),
+        "sign_id": sign_tx.sign_id.clone(),
+        "hd_path": sign_tx.hd_path.clone(),
+        "timestamp": sign_tx.timestamp,
+        "decimal": sign_tx.decimal,
+    });
+    match &sign_tx.transaction {
+        BaseTransaction::None => {}
+        BaseTransaction::Tron(tx) => {
+            value["tron_tx"] = json!({
+                "token": tx.token.clone(),
+                "contract_address": tx.contract_address.clone(),
+                "from": tx.from.clone(),
+                "to": tx.to.clone(),
+                "memo": tx.memo.clone(),
+                "value": tx.value.clone(),
+                "fee": tx.fee,
+            });
+        }
+        BaseTransaction::Utxo(tx) => {
+            value["utxo_tx"] = json!({
+                "fee": tx.fee,
+                "dust_threshold": tx.dust_threshold,
+                "memo": tx.memo.clone(),
+                "inputs": tx.inputs.iter().map(|input| json!({
+                    "hash": input.hash.clone(),
+                    "index": input.index,
+                    "value": input.value,
+                    "owner_key_path": input.owner_key_path.clone(),
+                })).collect::<Vec<_>>(),
+                "outputs": tx.outputs.iter().map(|output| json!({
+                    "address": output.address.clone(),
+                    "value": output.value,
+                    "is_change": output.is_change,
+                    "change_address_path": output.change_address_path.clone(),
+                })).collect::<Vec<_>>(),
+            });
+        }
+        BaseTransaction::Other { field } => value["transaction_field"] = json!(field),
+    }
+    value
+}
+
+fn base_lines(base: &Base) -> Vec<String> {
+    let Some(payload) = &base.payload else {
+        return vec!["    Payload: none".to_string()];
+    };
+    let mut lines = vec![format!(
+        "    Payload: {} (xfp {})",
+        payload.kind.label(),
+        payload.xfp
+    )];
+    match &payload.content {
+        BaseContent::None => {}
+        BaseContent::SignTransaction(sign_tx) => lines.extend(sign_transaction_lines(sign_tx)),
+        BaseContent::SignTransactionResult(result) => {
+            lines.push(format!("    Sign ID: {}", result.sign_id));
+            lines.push(format!("    Transaction ID: {}", result.tx_id));
+        }
+        BaseContent::Other { field } => {
+            lines.push(format!("    Content: field {} (not decoded)", field))
+        }
+    }
+    lines
+}
+
+fn sign_transaction_lines(sign_tx: &SignTransaction) -> Vec<String> {
+    let decimals = u32::try_from(sign_tx.decimal).unwrap_or(0);
+    let mut lines = vec![format!("    Coin: {}", sign_tx.coin_code)];
+    if !sign_tx.sign_id.is_empty() {
+        lines.push(format!("    Sign ID: {}", sign_tx.sign_id));
+    }
+    lines.push(format!("    HD path: {}", sign_tx.hd_path));
+    match &sign_tx.transaction {
+        BaseTransaction::None => lines.push("    Transaction: none".to_string()),
+        BaseTransaction::Tron(tx) => {
+            let unit = if tx.is_trc20() {
+                tx.token.as_str()
+            } else {
+                "TRX"
+            };
+            let amount = tx
+                .value
+                .parse::<u64>()
+                .map(|value| format_units(value, decimals, unit))
+                .unwrap_or_else(|_| format!("{} {}", tx.value, unit));
+            lines.push(format!(
+                "    Transfer {} from {} to {}",
+                amount, tx.from, tx.to
+            ));
+            if tx.is_trc20() {
+                lines.push(format!("    Token contract: {}", tx.contract_address));
+            }
+            lines.push(format!(
+                "    Fee limit: {}",
+                format_units(tx.fee.max(0) as u64, 6, "TRX")
+            ));
+            if !tx.memo.is_empty() {
+                lines.push(format!("    Memo: {}", format_text_snippet(&tx.memo)));
+            }
+        }
+        BaseTransaction::Utxo(tx) => {
+            let unit = sign_tx.coin_code.as_str();
+            let amount = |value: i64| format_units(value.max(0) as u64, decimals, unit);
+            lines.push(format!(
+                "    Inputs: {}, sending {} with fee {}",
+                tx.inputs.len(),
+                amount(tx.external_total()),
+                amount(tx.fee)
+            ));
+            for output in &tx.outputs {
+                let change = if output.is_change { " (change)" } else { "" };
+                lines.push(format!(
+                    "      {} -> {}{}",
+                    amount(output.value),
+                    output.address,
+                    change
+                ));
+            }
+            if !tx.memo.is_empty() {
+                lines.push(format!("    Memo: {}", format_text_snippet(&tx.memo)));
+            }
+        }
+        BaseTransaction::Other { field } => {
+            lines.push(format!("    Transaction: field {} (not decoded)", field))
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::output::tests::{has_line, render};
+
+    #[test]
+    fn renders_keystone_base_tron_transfer() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let tron = b"\x0a\x03TRX\x1a\x04Tali\x22\x04Tbob\x32\x071500000\x48\xa0\x8d\x06";
+        let sign_tx = [
+            &b"\x0a\x04TRON\x1a\x11m/44'/195'/0'/0/0\x28\x06\x42\x1e"[..],
+            &tron[..],
+        ]
+        .concat();
+        let base = [&b"\x08\x01\x1a\x3f\x08\x02\x22\x3b"[..], &sign_tx[..]].concat();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&base).unwrap();
+        let request = KeystoneSignRequest::new(encoder.finish().unwrap());
+
+        let rendered = render(KeystoneMessage::KeystoneSignRequest(request));
+
+        assert_eq!(rendered.json["message_variant"], "keystone_sign_request");
+        assert_eq!(
+            rendered.json["message"]["base"]["sign_tx"]["tron_tx"]["value"],
+            "1500000"
+        );
+        assert!(has_line(
+            &rendered,
+            "    Transfer 1.5 TRX from Tali to Tbob"
+        ));
+    }
+}
@@ -1,10 +1,25 @@
 //! Helpers for rendering and distributing structured scan output
 
+mod accounts;
+mod aptos;
+mod bitcoin;
+mod bytes;
+mod cardano;
+mod cosmos;
+mod ethereum;
+mod hedera;
+mod keystone_base;
+mod near;
+mod solana;
+mod stellar;
+mod sui;
 #[cfg(target_family = "unix")]
 pub mod unix;
+mod xrp;
 
-use crate::keystone::messages::{EthDataType, StellarSignType};
-use crate::{KeystoneMessage, KeystonePayload, PayloadEncoding};
+use crate::error::Result;
+use crate::keystone::UrMessageRegistry;
+use crate::{KeystoneMessage, KeystonePayload, PayloadEncoding, QrPayload};
 use hex::encode as hex_encode;
 use serde_json::{Map, Value, json};
 
@@ -17,9 +32,28 @@ pub struct RenderedKeystone {
     pub human: Vec<String>,
 }
 
+/// Destination for rendered scan results (stdout, sockets, test harnesses)
+pub trait OutputSink {
+    /// Deliver a successfully decoded Keystone payload
+    fn emit_keystone(&self, rendered: &RenderedKeystone) -> Result<()>;
+
+    /// Report a scan or decode failure
+    fn emit_error(&self, message: &str) -> Result<()>;
+}
+
 /// Render a Keystone payload into both JSON and human-readable forms.
+///
+/// Uses the global [`UrMessageRegistry`] for decoding and any registered renderers.
 pub fn render_keystone_payload(payload: &KeystonePayload) -> RenderedKeystone {
-    let json = keystone_payload_value(payload);
+    render_keystone_payload_with(payload, &UrMessageRegistry::global())
+}
+
+/// Render a Keystone payload using an explicit registry.
+pub fn render_keystone_payload_with(
+    payload: &KeystonePayload,
+    registry: &UrMessageRegistry,
+) -> RenderedKeystone {
+    let json = keystone_payload_value_with(payload, registry);
     let mut human = Vec::new();
 
     human.push("Keystone payload detected".to_string());
@@ -37,16 +71,37 @@ pub fn render_keystone_payload(payload: &KeystonePayload) -> RenderedKeystone {
 
     human.push(format!("  Raw bytes: {}", payload.data.len()));
 
-    match payload.message_type() {
-        Ok(message) => human.extend(human_lines_for_message(&message)),
+    match registry.decode(&payload.ur_type, &payload.data) {
+        Ok(message) => human.extend(
+            registry
+                .render_human(&payload.ur_type, &message)
+                .unwrap_or_else(|| human_lines_for_message(&message)),
+        ),
         Err(err) => human.push(format!("  Failed to decode message: {err}")),
     }
 
     RenderedKeystone { json, human }
 }
 
+/// Produce a structured JSON representation of a raw QR payload.
+pub fn qr_payload_value(qr: &QrPayload) -> Value {
+    json!({
+        "text": qr.as_str(),
+        "bytes_hex": hex_encode(qr.as_bytes()),
+        "byte_length": qr.as_bytes().len(),
+    })
+}
+
 /// Produce a structured JSON representation of the Keystone payload.
 pub fn keystone_payload_value(payload: &KeystonePayload) -> Value {
+    keystone_payload_value_with(payload, &UrMessageRegistry::global())
+}
+
+/// Produce a structured JSON representation using an explicit registry.
+pub fn keystone_payload_value_with(
+    payload: &KeystonePayload,
+    registry: &UrMessageRegistry,
+) -> Value {
     let mut root = Map::new();
     root.insert(
         "ur_type".to_string(),
@@ -70,13 +125,16 @@ pub fn keystone_payload_value(payload: &KeystonePayload) -> Value {
         }),
     );
 
-    match payload.message_type() {
+    match registry.decode(&payload.ur_type, &payload.data) {
         Ok(message) => {
             root.insert(
                 "message_variant".to_string(),
                 Value::String(message_variant_label(&message).to_string()),
             );
-            root.insert("message".to_string(), keystone_message_value(&message));
+            let value = registry
+                .render_json(&payload.ur_type, &message)
+                .unwrap_or_else(|| keystone_message_value(&message));
+            root.insert("message".to_string(), value);
         }
         Err(err) => {
             root.insert("message_error".to_string(), Value::String(err.to_string()));
@@ -88,89 +146,35 @@ pub fn keystone_payload_value(payload: &KeystonePayload) -> Value {
 
 fn keystone_message_value(message: &KeystoneMessage) -> Value {
     match message {
-        KeystoneMessage::CryptoAccount(account) => json!({
-            "master_fingerprint": format!("{:08x}", account.fingerprint_u32()),
-            "derivation_path": account.key_path.to_string(),
-            "public_key_hex": hex_encode(&account.public_key),
-            "public_key_bytes": account.public_key.len(),
-            "chain_code_hex": account.chain_code.as_ref().map(hex_encode),
-        }),
-        KeystoneMessage::EthSignRequest(request) => json!({
-            "request_id": request.request_id.map(|id| id.to_string()),
-            "derivation_path": request.derivation_path.to_string(),
-            "chain_id": request.chain_id,
-            "data_type": eth_data_type_label(request.data_type),
-            "origin": request.origin.clone(),
-            "address_hex": request.address.as_ref().map(hex_encode),
-            "sign_data_hex": hex_encode(&request.sign_data),
-            "sign_data_bytes": request.sign_data.len(),
-        }),
-        KeystoneMessage::EthSignature(signature) => {
-            let components = signature.rsv().ok();
-            json!({
-                "request_id": signature.request_id.map(|id| id.to_string()),
-                "origin": signature.origin.clone(),
-                "signature_hex": hex_encode(&signature.signature),
-                "signature_bytes": signature.signature.len(),
-                "r": components.as_ref().map(|(r, _, _)| hex_encode(r)),
-                "s": components.as_ref().map(|(_, s, _)| hex_encode(s)),
-                "v": components.as_ref().map(|(_, _, v)| format!("{:02x}", v)),
-            })
-        }
-        KeystoneMessage::HederaSignRequest(request) => json!({
-            "request_id": request.request_id.map(|id| id.to_string()),
-            "derivation_path": request.derivation_path.to_string(),
-            "account_id": request.account_id.clone(),
-            "origin": request.origin.clone(),
-            "transaction_hex": hex_encode(&request.transaction_bytes),
-            "transaction_bytes": request.transaction_bytes.len(),
-        }),
-        KeystoneMessage::HederaSignature(signature) => json!({
-            "request_id": signature.request_id.map(|id| id.to_string()),
-            "signature_hex": hex_encode(&signature.signature),
-            "signature_bytes": signature.signature.len(),
-            "public_key_hex": signature.public_key.as_ref().map(hex_encode),
-        }),
-        KeystoneMessage::SolanaSignRequest(request) => json!({
-            "request_id": request.request_id.map(|id| id.to_string()),
-            "derivation_path": request.derivation_path.to_string(),
-            "origin": request.origin.clone(),
-            "transaction_hex": hex_encode(&request.transaction),
-            "transaction_bytes": request.transaction.len(),
-        }),
-        KeystoneMessage::SolanaSignature(signature) => json!({
-            "request_id": signature.request_id.map(|id| id.to_string()),
-            "signature_hex": hex_encode(&signature.signature),
-            "signature_bytes": signature.signature.len(),
-            "public_key_hex": signature.public_key.as_ref().map(hex_encode),
-        }),
-        KeystoneMessage::StellarSignRequest(request) => json!({
-            "request_id": request.request_id.map(|id| id.to_string()),
-            "derivation_path": request.derivation_path.to_string(),
-            "sign_type": stellar_sign_type_label(&request.sign_type),
-            "origin": request.origin.clone(),
-            "address_hex": request.address.as_ref().map(hex_encode),
-            "sign_data_hex": hex_encode(&request.sign_data),
-            "sign_data_bytes": request.sign_data.len(),
-        }),
-        KeystoneMessage::StellarSignature(signature) => json!({
-            "request_id": signature.request_id.map(|id| id.to_string()),
-            "signature_hex": hex_encode(&signature.signature),
-            "signature_bytes": signature.signature.len(),
-        }),
-        KeystoneMessage::XrpSignRequest(request) => {
-            let tx_value = serde_json::from_str::<Value>(&request.transaction_json)
-                .unwrap_or_else(|_| Value::String(request.transaction_json.clone()));
-            json!({
-                "request_id": request.request_id.map(|id| id.to_string()),
-                "derivation_path": request.derivation_path.clone(),
-                "origin": request.origin.clone(),
-                "transaction": tx_value,
-            })
-        }
-        KeystoneMessage::XrpSignature(signature) => json!({
-            "request_id": signature.request_id.map(|id| id.to_string()),
-            "signature": signature.signature.clone(),
+        KeystoneMessage::CryptoAccount(account) => accounts::account_value(account),
+        KeystoneMessage::CryptoHdKey(key) => accounts::hdkey_value(key),
+        KeystoneMessage::CryptoMultiAccounts(accounts) => accounts::multi_accounts_value(accounts),
+        KeystoneMessage::CryptoOutput(output) => accounts::output_value(output),
+        KeystoneMessage::CryptoPsbt(psbt) => bitcoin::psbt_value(psbt),
+        KeystoneMessage::EthSignRequest(request) => ethereum::sign_request_value(request),
+        KeystoneMessage::EthSignature(signature) => ethereum::signature_value(signature),
+        KeystoneMessage::AptosSignRequest(request) => aptos::sign_request_value(request),
+        KeystoneMessage::AptosSignature(signature) => aptos::signature_value(signature),
+        KeystoneMessage::CardanoSignRequest(request) => cardano::sign_request_value(request),
+        KeystoneMessage::CardanoSignature(signature) => cardano::signature_value(signature),
+        KeystoneMessage::CosmosSignRequest(request) => cosmos::sign_request_value(request),
+        KeystoneMessage::CosmosSignature(signature) => cosmos::signature_value(signature),
+        KeystoneMessage::HederaSignRequest(request) => hedera::sign_request_value(request),
+        KeystoneMessage::HederaSignature(signature) => hedera::signature_value(signature),
+        KeystoneMessage::Bytes(bytes) => bytes::value(bytes),
+        KeystoneMessage::KeystoneSignRequest(request) => keystone_base::sign_request_value(request),
+        KeystoneMessage::NearSignRequest(request) => near::sign_request_value(request),
+        KeystoneMessage::NearSignature(signature) => near::signature_value(signature),
+        KeystoneMessage::SolanaSignRequest(request) => solana::sign_request_value(request),
+        KeystoneMessage::SolanaSignature(signature) => solana::signature_value(signature),
+        KeystoneMessage::StellarSignRequest(request) => stellar::sign_request_value(request),
+        KeystoneMessage::StellarSignature(signature) => stellar::signature_value(signature),
+        KeystoneMessage::SuiSignRequest(request) => sui::sign_request_value(request),
+        KeystoneMessage::SuiSignature(signature) => sui::signature_value(signature),
+        KeystoneMessage::XrpSignRequest(request) => xrp::sign_request_value(request),
+        KeystoneMessage::XrpSignature(signature) => xrp::signature_value(signature),
+        KeystoneMessage::Custom(custom) => json!({
+            "ur_type": custom.ur_type(),
         }),
         KeystoneMessage::Unknown { ur_type, data } => json!({
             "ur_type": ur_type,
@@ -182,206 +186,35 @@ fn keystone_message_value(message: &KeystoneMessage) -> Value {
 
 fn human_lines_for_message(message: &KeystoneMessage) -> Vec<String> {
     match message {
-        KeystoneMessage::CryptoAccount(account) => {
-            let mut lines = vec!["  Variant: crypto_account".to_string()];
-            lines.push(format!(
-                "    Fingerprint: {:08x}",
-                account.fingerprint_u32()
-            ));
-            lines.push(format!(
-                "    Derivation path: {}",
-                account.key_path.to_string()
-            ));
-            lines.push(format!(
-                "    Public key: {}",
-                format_hex_snippet(&account.public_key)
-            ));
-            if let Some(chain_code) = &account.chain_code {
-                lines.push(format!(
-                    "    Chain code: {}",
-                    format_hex_snippet(chain_code)
-                ));
-            }
-            lines
-        }
-        KeystoneMessage::EthSignRequest(request) => {
-            let mut lines = vec!["  Variant: eth_sign_request".to_string()];
-            if let Some(id) = request.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Data type: {}",
-                eth_data_type_label(request.data_type)
-            ));
-            if let Some(chain_id) = request.chain_id {
-                lines.push(format!("    Chain ID: {}", chain_id));
-            }
-            lines.push(format!(
-                "    Derivation path: {}",
-                request.derivation_path.to_string()
-            ));
-            if let Some(address) = &request.address {
-                lines.push(format!("    Address: {}", format_hex_snippet(address)));
-            }
-            if let Some(origin) = &request.origin {
-                lines.push(format!("    Origin: {}", origin));
-            }
-            lines.push(format!(
-                "    Sign data: {}",
-                format_hex_snippet(&request.sign_data)
-            ));
-            lines
-        }
-        KeystoneMessage::EthSignature(signature) => {
-            let mut lines = vec!["  Variant: eth_signature".to_string()];
-            if let Some(id) = signature.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            if let Some(origin) = &signature.origin {
-                lines.push(format!("    Origin: {}", origin));
-            }
-            lines.push(format!(
-                "    Signature: {}",
-                format_hex_snippet(&signature.signature)
-            ));
-            if let Ok((r, s, v)) = signature.rsv() {
-                lines.push(format!("    r: {}", hex_encode(r)));
-                lines.push(format!("    s: {}", hex_encode(s)));
-                lines.push(format!("    v: {:02x}", v));
-            }
-            lines
-        }
-        KeystoneMessage::HederaSignRequest(request) => {
-            let mut lines = vec!["  Variant: hedera_sign_request".to_string()];
-            if let Some(id) = request.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Derivation path: {}",
-                request.derivation_path.to_string()
-            ));
-            if let Some(account_id) = &request.account_id {
-                lines.push(format!("    Account ID: {}", account_id));
-            }
-            if let Some(origin) = &request.origin {
-                lines.push(format!("    Origin: {}", origin));
-            }
-            lines.push(format!(
-                "    Transaction bytes: {}",
-                format_hex_snippet(&request.transaction_bytes)
-            ));
-            lines
-        }
-        KeystoneMessage::HederaSignature(signature) => {
-            let mut lines = vec!["  Variant: hedera_signature".to_string()];
-            if let Some(id) = signature.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Signature: {}",
-                format_hex_snippet(&signature.signature)
-            ));
-            if let Some(public_key) = &signature.public_key {
-                lines.push(format!(
-                    "    Public key: {}",
-                    format_hex_snippet(public_key)
-                ));
-            }
-            lines
-        }
-        KeystoneMessage::SolanaSignRequest(request) => {
-            let mut lines = vec!["  Variant: solana_sign_request".to_string()];
-            if let Some(id) = request.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Derivation path: {}",
-                request.derivation_path.to_string()
-            ));
-            if let Some(origin) = &request.origin {
-                lines.push(format!("    Origin: {}", origin));
-            }
-            lines.push(format!(
-                "    Transaction bytes: {}",
-                format_hex_snippet(&request.transaction)
-            ));
-            lines
-        }
-        KeystoneMessage::SolanaSignature(signature) => {
-            let mut lines = vec!["  Variant: solana_signature".to_string()];
-            if let Some(id) = signature.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Signature: {}",
-                format_hex_snippet(&signature.signature)
-            ));
-            if let Some(public_key) = &signature.public_key {
-                lines.push(format!(
-                    "    Public key: {}",
-                    format_hex_snippet(public_key)
-                ));
-            }
-            lines
-        }
-        KeystoneMessage::StellarSignRequest(request) => {
-            let mut lines = vec!["  Variant: stellar_sign_request".to_string()];
-            if let Some(id) = request.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Derivation path: {}",
-                request.derivation_path.to_string()
-            ));
-            lines.push(format!(
-                "    Sign type: {}",
-                stellar_sign_type_label(&request.sign_type)
-            ));
-            if let Some(origin) = &request.origin {
-                lines.push(format!("    Origin: {}", origin));
-            }
-            if let Some(address) = &request.address {
-                lines.push(format!("    Address: {}", format_hex_snippet(address)));
-            }
-            lines.push(format!(
-                "    Sign data: {}",
-                format_hex_snippet(&request.sign_data)
-            ));
-            lines
-        }
-        KeystoneMessage::StellarSignature(signature) => {
-            let mut lines = vec!["  Variant: stellar_signature".to_string()];
-            if let Some(id) = signature.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!(
-                "    Signature: {}",
-                format_hex_snippet(&signature.signature)
-            ));
-            lines
-        }
-        KeystoneMessage::XrpSignRequest(request) => {
-            let mut lines = vec!["  Variant: xrp_sign_request".to_string()];
-            if let Some(id) = request.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!("    Derivation path: {}", request.derivation_path));
-            if let Some(origin) = &request.origin {
-                lines.push(format!("    Origin: {}", origin));
-            }
-            lines.push(format!(
-                "    Transaction JSON: {}",
-                format_text_snippet(&request.transaction_json)
-            ));
-            lines
-        }
-        KeystoneMessage::XrpSignature(signature) => {
-            let mut lines = vec!["  Variant: xrp_signature".to_string()];
-            if let Some(id) = signature.request_id {
-                lines.push(format!("    Request ID: {}", id));
-            }
-            lines.push(format!("    Signature: {}", signature.signature));
-            lines
+        KeystoneMessage::CryptoAccount(account) => accounts::account_lines(account),
+        KeystoneMessage::CryptoHdKey(key) => accounts::hdkey_lines(key),
+        KeystoneMessage::CryptoMultiAccounts(accounts) => accounts::multi_accounts_lines(accounts),
+        KeystoneMessage::CryptoOutput(output) => accounts::output_lines(output),
+        KeystoneMessage::CryptoPsbt(psbt) => bitcoin::psbt_lines(psbt),
+        KeystoneMessage::EthSignRequest(request) => ethereum::sign_request_lines(request),
+        KeystoneMessage::EthSignature(signature) => ethereum::signature_lines(signature),
+        KeystoneMessage::AptosSignRequest(request) => aptos::sign_request_lines(request),
+        KeystoneMessage::AptosSignature(signature) => aptos::signature_lines(signature),
+        KeystoneMessage::CardanoSignRequest(request) => cardano::sign_request_lines(request),
+        KeystoneMessage::CardanoSignature(signature) => cardano::signature_lines(signature),
+        KeystoneMessage::CosmosSignRequest(request) => cosmos::sign_request_lines(request),
+        KeystoneMessage::CosmosSignature(signature) => cosmos::signature_lines(signature),
+        KeystoneMessage::HederaSignRequest(request) => hedera::sign_request_lines(request),
+        KeystoneMessage::HederaSignature(signature) => hedera::signature_lines(signature),
+        KeystoneMessage::Bytes(bytes) => bytes::lines(bytes),
+        KeystoneMessage::KeystoneSignRequest(request) => keystone_base::sign_request_lines(request),
+        KeystoneMessage::NearSignRequest(request) => near::sign_request_lines(request),
+        KeystoneMessage::NearSignature(signature) => near::signature_lines(signature),
+        KeystoneMessage::SolanaSignRequest(request) => solana::sign_request_lines(request),
+        KeystoneMessage::SolanaSignature(signature) => solana::signature_lines(signature),
+        KeystoneMessage::StellarSignRequest(request) => stellar::sign_request_lines(request),
+        KeystoneMessage::StellarSignature(signature) => stellar::signature_lines(signature),
+        KeystoneMessage::SuiSignRequest(request) => sui::sign_request_lines(request),
+        KeystoneMessage::SuiSignature(signature) => sui::signature_lines(signature),
+        KeystoneMessage::XrpSignRequest(request) => xrp::sign_request_lines(request),
+        KeystoneMessage::XrpSignature(signature) => xrp::signature_lines(signature),
+        KeystoneMessage::Custom(custom) => {
+            vec![format!("  Variant: custom ({})", custom.ur_type())]
         }
         KeystoneMessage::Unknown { ur_type, data } => {
             vec![
@@ -392,19 +225,36 @@ fn human_lines_for_message(message: &KeystoneMessage) -> Vec<String> {
     }
 }
 
-fn message_variant_label(message: &KeystoneMessage) -> &'static str {
+pub(crate) fn message_variant_label(message: &KeystoneMessage) -> &'static str {
     match message {
         KeystoneMessage::CryptoAccount(_) => "crypto_account",
+        KeystoneMessage::CryptoHdKey(_) => "crypto_hdkey",
+        KeystoneMessage::CryptoMultiAccounts(_) => "crypto_multi_accounts",
+        KeystoneMessage::CryptoOutput(_) => "crypto_output",
+        KeystoneMessage::CryptoPsbt(_) => "crypto_psbt",
         KeystoneMessage::EthSignRequest(_) => "eth_sign_request",
         KeystoneMessage::EthSignature(_) => "eth_signature",
+        KeystoneMessage::AptosSignRequest(_) => "aptos_sign_request",
+        KeystoneMessage::AptosSignature(_) => "aptos_signature",
+        KeystoneMessage::CardanoSignRequest(_) => "cardano_sign_request",
+        KeystoneMessage::CardanoSignature(_) => "cardano_signature",
+        KeystoneMessage::CosmosSignRequest(_) => "cosmos_sign_request",
+        KeystoneMessage::CosmosSignature(_) => "cosmos_signature",
         KeystoneMessage::HederaSignRequest(_) => "hedera_sign_request",
         KeystoneMessage::HederaSignature(_) => "hedera_signature",
+        KeystoneMessage::Bytes(_) => "bytes",
+        KeystoneMessage::KeystoneSignRequest(_) => "keystone_sign_request",
+        KeystoneMessage::NearSignRequest(_) => "near_sign_request",
+        KeystoneMessage::NearSignature(_) => "near_signature",
         KeystoneMessage::SolanaSignRequest(_) => "solana_sign_request",
         KeystoneMessage::SolanaSignature(_) => "solana_signature",
         KeystoneMessage::StellarSignRequest(_) => "stellar_sign_request",
         KeystoneMessage::StellarSignature(_) => "stellar_signature",
+        KeystoneMessage::SuiSignRequest(_) => "sui_sign_request",
+        KeystoneMessage::SuiSignature(_) => "sui_signature",
         KeystoneMessage::XrpSignRequest(_) => "xrp_sign_request",
         KeystoneMessage::XrpSignature(_) => "xrp_signature",
+        KeystoneMessage::Custom(_) => "custom",
         KeystoneMessage::Unknown { .. } => "unknown",
     }
 }
@@ -417,23 +267,6 @@ fn encoding_label(encoding: PayloadEncoding) -> &'static str {
     }
 }
 
-fn eth_data_type_label(data_type: EthDataType) -> &'static str {
-    match data_type {
-        EthDataType::Transaction => "transaction",
-        EthDataType::TypedData => "typed_data",
-        EthDataType::PersonalMessage => "personal_message",
-        EthDataType::TypedTransaction => "typed_transaction",
-    }
-}
-
-fn stellar_sign_type_label(sign_type: &StellarSignType) -> &'static str {
-    match sign_type {
-        StellarSignType::Transaction => "transaction",
-        StellarSignType::TransactionHash => "transaction_hash",
-        StellarSignType::Message => "message",
-    }
-}
-
 fn format_hex_snippet(bytes: &[u8]) -> String {
     if bytes.is_empty() {
         return "empty".to_string();
@@ -462,34 +295,57 @@ fn format_text_snippet(text: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::keystone::crypto_keypath::CryptoKeyPath;
-    use crate::keystone::messages::ethereum::EthSignRequest;
+
+    /// Render a message through the global registry, as the CLI does
+    pub(super) fn render(message: KeystoneMessage) -> RenderedKeystone {
+        render_keystone_payload(&message.into())
+    }
+
+    /// Whether any human-readable line contains `needle`
+    pub(super) fn mentions(rendered: &RenderedKeystone, needle: &str) -> bool {
+        rendered.human.iter().any(|line| line.contains(needle))
+    }
+
+    /// Whether `line` is one of the human-readable lines, verbatim
+    pub(super) fn has_line(rendered: &RenderedKeystone, line: &str) -> bool {
+        rendered.human.iter().any(|human| human == line)
+    }
 
     #[test]
-    fn renders_eth_request_consistently() {
-        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
-        let request = EthSignRequest::new_transaction(vec![1, 2, 3], path, Some(1));
-        let payload: KeystonePayload = KeystoneMessage::EthSignRequest(request.clone()).into();
-        let rendered = render_keystone_payload(&payload);
+    fn renders_registered_custom_type() {
+        use crate::KeystoneMetadata;
+        use crate::keystone::{CustomMessage, UrTypeHandler};
 
-        assert_eq!(rendered.json["message_variant"], "eth_sign_request");
-        assert!(
-            rendered
-                .human
-                .iter()
-                .any(|line| line.contains("Variant: eth_sign_request"))
-        );
-        assert!(
-            rendered
-                .human
-                .iter()
-                .any(|line| line.contains("Chain ID: 1"))
-        );
-        assert!(
-            rendered
-                .human
-                .iter()
-                .any(|line| line.contains("Sign data:"))
+        let mut registry = UrMessageRegistry::new();
+        registry.register(
+            "x-custody-approval",
+            UrTypeHandler::new(|ur_type, data| {
+                Ok(KeystoneMessage::Custom(CustomMessage::new(
+                    ur_type,
+                    String::from_utf8_lossy(data).into_owned(),
+                )))
+            })
+            .with_json_renderer(|message| match message {
+                KeystoneMessage::Custom(custom) => {
+                    json!({ "vault": custom.downcast_ref::<String>() })
+                }
+                _ => Value::Null,
+            })
+            .with_human_renderer(|_| vec!["  Variant: custody_approval".to_string()]),
         );
+        let payload = KeystonePayload {
+            ur_type: "x-custody-approval".to_string(),
+            data: b"cold-1".to_vec(),
+            metadata: KeystoneMetadata::default(),
+            encoding: PayloadEncoding::Binary,
+        };
+
+        let rendered = render_keystone_payload_with(&payload, &registry);
+        assert_eq!(rendered.json["message_variant"], "custom");
+        assert_eq!(rendered.json["message"]["vault"], "cold-1");
+        assert!(has_line(&rendered, "  Variant: custody_approval"));
+
+        let rendered = render_keystone_payload(&payload);
+        assert_eq!(rendered.json["message_variant"], "unknown");
     }
 }
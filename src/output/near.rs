@@ -0,0 +1,70 @@
+//! Renderers for NEAR sign requests and signatures
+
+use super::format_hex_snippet;
+use crate::keystone::messages::{NearSignRequest, NearSignature};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &NearSignRequest) -> Value {
+    json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_path": request.derivation_path.to_string(),
+        "account": request.account_id(),
+        "account_hex": request.account.as_ref().map(hex_encode),
+        "origin": request.origin.clone(),
+        "transactions_hex": request.sign_data.iter().map(hex_encode).collect::<Vec<_>>(),
+        "transaction_count": request.sign_data.len(),
+    })
+}
+
+pub(super) fn signature_value(signature: &NearSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signatures_hex": signature.signatures.iter().map(hex_encode).collect::<Vec<_>>(),
+        "signature_count": signature.signatures.len(),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &NearSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: near_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Derivation path: {}",
+        request.derivation_path.to_string()
+    ));
+    match (request.account_id(), &request.account) {
+        (Some(account), _) => lines.push(format!("    Account: {}", account)),
+        (None, Some(account)) => {
+            lines.push(format!("    Account: {}", format_hex_snippet(account)))
+        }
+        (None, None) => {}
+    }
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    for (index, transaction) in request.sign_data.iter().enumerate() {
+        lines.push(format!(
+            "    Transaction {}: {}",
+            index + 1,
+            format_hex_snippet(transaction)
+        ));
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &NearSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: near_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    for (index, sig) in signature.signatures.iter().enumerate() {
+        lines.push(format!(
+            "    Signature {}: {}",
+            index + 1,
+            format_hex_snippet(sig)
+        ));
+    }
+    lines
+}
@@ -0,0 +1,297 @@
+//! Renderers for Solana sign requests and signatures
+
+use super::format_hex_snippet;
+use crate::keystone::messages::solana::message::format_lamports_as_sol;
+use crate::keystone::messages::{
+    SolanaInstruction, SolanaMessage, SolanaSignRequest, SolanaSignature,
+};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &SolanaSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_path": request.derivation_path.to_string(),
+        "origin": request.origin.clone(),
+        "transaction_hex": hex_encode(&request.transaction),
+        "transaction_bytes": request.transaction.len(),
+    });
+    match request.decode_message() {
+        Ok(message) => value["transaction"] = message_value(&message),
+        Err(err) => value["transaction_error"] = json!(err.to_string()),
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &SolanaSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+        "public_key_hex": signature.public_key.as_ref().map(hex_encode),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &SolanaSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: solana_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Derivation path: {}",
+        request.derivation_path.to_string()
+    ));
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Transaction bytes: {}",
+        format_hex_snippet(&request.transaction)
+    ));
+    match request.decode_message() {
+        Ok(message) => lines.extend(message_lines(&message)),
+        Err(err) => lines.push(format!("    Message error: {}", err)),
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &SolanaSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: solana_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    if let Some(public_key) = &signature.public_key {
+        lines.push(format!(
+            "    Public key: {}",
+            format_hex_snippet(public_key)
+        ));
+    }
+    lines
+}
+
+fn message_value(message: &SolanaMessage) -> Value {
+    json!({
+        "version": message.version.label(),
+        "fee_payer": message.fee_payer(),
+        "required_signatures": message.header.num_required_signatures,
+        "recent_blockhash": bs58::encode(message.recent_blockhash).into_string(),
+        "accounts": (0..message.total_accounts())
+            .map(|index| json!({
+                "address": message.account_address(index),
+                "signer": message.is_signer(index),
+                "writable": message.is_writable(index),
+            }))
+            .collect::<Vec<_>>(),
+        "address_table_lookups": message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| json!({
+                "table": bs58::encode(lookup.account_key).into_string(),
+                "writable_indexes": lookup.writable_indexes,
+                "readonly_indexes": lookup.readonly_indexes,
+            }))
+            .collect::<Vec<_>>(),
+        "instructions": message
+            .decoded_instructions()
+            .iter()
+            .map(instruction_value)
+            .collect::<Vec<_>>(),
+        "total_sol_transferred": format_lamports_as_sol(message.total_sol_transferred()),
+    })
+}
+
+fn instruction_value(instruction: &SolanaInstruction) -> Value {
+    let mut value = match instruction {
+        SolanaInstruction::SystemTransfer { from, to, lamports } => json!({
+            "from": from,
+            "to": to,
+            "lamports": lamports,
+            "sol": format_lamports_as_sol(*lamports),
+        }),
+        SolanaInstruction::TokenTransfer {
+            source,
+            destination,
+            authority,
+            mint,
+            amount,
+            decimals,
+        } => json!({
+            "source": source,
+            "destination": destination,
+            "authority": authority,
+            "mint": mint,
+            "amount": amount,
+            "decimals": decimals,
+        }),
+        SolanaInstruction::TokenApprove {
+            source,
+            delegate,
+            owner,
+            mint,
+            amount,
+            decimals,
+        } => json!({
+            "source": source,
+            "delegate": delegate,
+            "owner": owner,
+            "mint": mint,
+            "amount": amount,
+            "decimals": decimals,
+        }),
+        SolanaInstruction::SetComputeUnitLimit { units } => json!({ "units": units }),
+        SolanaInstruction::SetComputeUnitPrice { micro_lamports } => {
+            json!({ "micro_lamports": micro_lamports })
+        }
+        SolanaInstruction::RequestHeapFrame { bytes } => json!({ "bytes": bytes }),
+        SolanaInstruction::CreateAssociatedTokenAccount {
+            payer,
+            account,
+            wallet,
+            mint,
+            idempotent,
+        } => json!({
+            "payer": payer,
+            "account": account,
+            "wallet": wallet,
+            "mint": mint,
+            "idempotent": idempotent,
+        }),
+        SolanaInstruction::Unknown {
+            program,
+            accounts,
+            data,
+        } => json!({
+            "program": program,
+            "accounts": accounts,
+            "data_hex": hex_encode(data),
+        }),
+    };
+    value["type"] = json!(instruction.label());
+    value
+}
+
+fn message_lines(message: &SolanaMessage) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Message: {} ({} accounts, {} instructions)",
+        message.version.label(),
+        message.total_accounts(),
+        message.instructions.len()
+    )];
+    if let Some(fee_payer) = message.fee_payer() {
+        lines.push(format!("      Fee payer: {}", fee_payer));
+    }
+    if !message.address_table_lookups.is_empty() {
+        lines.push(format!(
+            "      Address lookup tables: {}",
+            message.address_table_lookups.len()
+        ));
+    }
+    for instruction in message.decoded_instructions() {
+        let line = match &instruction {
+            SolanaInstruction::SystemTransfer { from, to, lamports } => format!(
+                "Transfer {} from {} to {}",
+                format_lamports_as_sol(*lamports),
+                from,
+                to
+            ),
+            SolanaInstruction::TokenTransfer {
+                source,
+                destination,
+                amount,
+                decimals,
+                ..
+            } => format!(
+                "Token transfer {} from {} to {}",
+                format_token_amount(*amount, *decimals),
+                source,
+                destination
+            ),
+            SolanaInstruction::TokenApprove {
+                source,
+                delegate,
+                amount,
+                decimals,
+                ..
+            } => format!(
+                "Token approve {} of {} to delegate {}",
+                format_token_amount(*amount, *decimals),
+                source,
+                delegate
+            ),
+            SolanaInstruction::SetComputeUnitLimit { units } => {
+                format!("Compute unit limit: {}", units)
+            }
+            SolanaInstruction::SetComputeUnitPrice { micro_lamports } => {
+                format!("Compute unit price: {} micro-lamports", micro_lamports)
+            }
+            SolanaInstruction::RequestHeapFrame { bytes } => {
+                format!("Heap frame: {} bytes", bytes)
+            }
+            SolanaInstruction::CreateAssociatedTokenAccount {
+                account, wallet, ..
+            } => format!("Create token account {} for {}", account, wallet),
+            SolanaInstruction::Unknown { program, data, .. } => {
+                format!("Program {}: {}", program, format_hex_snippet(data))
+            }
+        };
+        lines.push(format!("      {}", line));
+    }
+    lines
+}
+
+fn format_token_amount(amount: u64, decimals: Option<u8>) -> String {
+    let Some(decimals) = decimals.filter(|decimals| *decimals > 0) else {
+        return format!("{} base units", amount);
+    };
+    let text = format!("{:0>width$}", amount, width = usize::from(decimals) + 1);
+    let (whole, fraction) = text.split_at(text.len() - usize::from(decimals));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{mentions, render};
+
+    #[test]
+    fn renders_solana_transfer_instructions() {
+        use crate::keystone::messages::solana::message::{
+            COMPUTE_BUDGET_PROGRAM_ID, SYSTEM_PROGRAM_ID,
+        };
+
+        let program = |id: &str| bs58::decode(id).into_vec().unwrap();
+        let mut transaction = vec![1, 0, 2, 4];
+        transaction.extend_from_slice(&[0x11; 32]);
+        transaction.extend_from_slice(&[0x22; 32]);
+        transaction.extend_from_slice(&program(SYSTEM_PROGRAM_ID));
+        transaction.extend_from_slice(&program(COMPUTE_BUDGET_PROGRAM_ID));
+        transaction.extend_from_slice(&[0x33; 32]);
+        transaction.extend_from_slice(&[2, 3, 0, 5, 2]);
+        transaction.extend_from_slice(&200_000u32.to_le_bytes());
+        transaction.extend_from_slice(&[2, 2, 0, 1, 12]);
+        transaction.extend_from_slice(&2u32.to_le_bytes());
+        transaction.extend_from_slice(&1_250_000_000u64.to_le_bytes());
+
+        let path = CryptoKeyPath::from_str("m/44'/501'/0'/0'").unwrap();
+        let request = SolanaSignRequest::new(transaction, path, None);
+        let rendered = render(KeystoneMessage::SolanaSignRequest(request));
+
+        let message = &rendered.json["message"]["transaction"];
+        assert_eq!(message["version"], "legacy");
+        assert_eq!(message["instructions"][0]["type"], "set_compute_unit_limit");
+        assert_eq!(message["instructions"][1]["sol"], "1.25 SOL");
+        assert_eq!(message["accounts"][1]["writable"], true);
+        assert!(mentions(&rendered, "Transfer 1.25 SOL from"));
+    }
+}
@@ -0,0 +1,444 @@
+//! Renderers for Stellar sign requests and signatures
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::keystone::messages::stellar::xdr::{StellarMemo, format_stroops};
+use crate::keystone::messages::{
+    StellarAsset, StellarOperationBody, StellarSignRequest, StellarSignType, StellarSignature,
+    StellarTransaction,
+};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &StellarSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_path": request.derivation_path.to_string(),
+        "sign_type": sign_type_label(&request.sign_type),
+        "origin": request.origin.clone(),
+        "address_hex": request.address.as_ref().map(hex_encode),
+        "sign_data_hex": hex_encode(&request.sign_data),
+        "sign_data_bytes": request.sign_data.len(),
+    });
+    match request.sign_type {
+        StellarSignType::Transaction => match request.decode_transaction() {
+            Ok(transaction) => value["transaction"] = transaction_value(&transaction),
+            Err(err) => value["transaction_error"] = json!(err.to_string()),
+        },
+        StellarSignType::TransactionHash => value["blind_signing"] = json!(true),
+        StellarSignType::Message => {}
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &StellarSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &StellarSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: stellar_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Derivation path: {}",
+        request.derivation_path.to_string()
+    ));
+    lines.push(format!(
+        "    Sign type: {}",
+        sign_type_label(&request.sign_type)
+    ));
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    if let Some(address) = &request.address {
+        lines.push(format!("    Address: {}", format_hex_snippet(address)));
+    }
+    lines.push(format!(
+        "    Sign data: {}",
+        format_hex_snippet(&request.sign_data)
+    ));
+    match request.sign_type {
+        StellarSignType::Transaction => match request.decode_transaction() {
+            Ok(transaction) => lines.extend(transaction_lines(&transaction)),
+            Err(err) => lines.push(format!("    Transaction error: {}", err)),
+        },
+        StellarSignType::TransactionHash => lines.push(
+            "    WARNING: blind signing — the wallet signs a bare transaction hash whose contents cannot be reviewed"
+                .to_string(),
+        ),
+        StellarSignType::Message => {}
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &StellarSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: stellar_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    lines
+}
+
+fn transaction_value(transaction: &StellarTransaction) -> Value {
+    json!({
+        "network": transaction.network.as_ref().map(|network| network.label()),
+        "source_account": transaction.source_account,
+        "fee_stroops": transaction.fee,
+        "sequence": transaction.sequence.to_string(),
+        "time_bounds": transaction.time_bounds.map(|bounds| json!({
+            "min_time": bounds.min_time,
+            "max_time": bounds.max_time,
+        })),
+        "memo": memo_value(&transaction.memo),
+        "operations": transaction
+            .operations
+            .iter()
+            .map(|operation| {
+                let mut value = operation_value(&operation.body);
+                value["type"] = json!(operation.body.label());
+                value["source_account"] = json!(operation.source_account);
+                value
+            })
+            .collect::<Vec<_>>(),
+        "fee_bump": transaction.fee_bump.as_ref().map(|fee_bump| json!({
+            "fee_source": fee_bump.fee_source,
+            "fee_stroops": fee_bump.fee,
+        })),
+        "soroban": transaction.soroban,
+        "total_xlm_sent": format_stroops(transaction.total_native_sent()),
+    })
+}
+
+fn memo_value(memo: &StellarMemo) -> Value {
+    match memo {
+        StellarMemo::None => Value::Null,
+        StellarMemo::Text(text) => json!({ "type": "text", "value": text }),
+        StellarMemo::Id(id) => json!({ "type": "id", "value": id.to_string() }),
+        StellarMemo::Hash(hash) => json!({ "type": "hash", "value": hex_encode(hash) }),
+        StellarMemo::Return(hash) => json!({ "type": "return", "value": hex_encode(hash) }),
+    }
+}
+
+fn operation_value(body: &StellarOperationBody) -> Value {
+    let assets = |path: &[StellarAsset]| path.iter().map(StellarAsset::display).collect::<Vec<_>>();
+    match body {
+        StellarOperationBody::CreateAccount {
+            destination,
+            starting_balance,
+        } => json!({
+            "destination": destination,
+            "starting_balance": format_stroops(*starting_balance),
+        }),
+        StellarOperationBody::Payment {
+            destination,
+            asset,
+            amount,
+        } => json!({
+            "destination": destination,
+            "asset": asset.display(),
+            "amount": format_stroops(*amount),
+        }),
+        StellarOperationBody::PathPaymentStrictReceive {
+            send_asset,
+            send_max,
+            destination,
+            dest_asset,
+            dest_amount,
+            path,
+        } => json!({
+            "send_asset": send_asset.display(),
+            "send_max": format_stroops(*send_max),
+            "destination": destination,
+            "dest_asset": dest_asset.display(),
+            "dest_amount": format_stroops(*dest_amount),
+            "path": assets(path),
+        }),
+        StellarOperationBody::PathPaymentStrictSend {
+            send_asset,
+            send_amount,
+            destination,
+            dest_asset,
+            dest_min,
+            path,
+        } => json!({
+            "send_asset": send_asset.display(),
+            "send_amount": format_stroops(*send_amount),
+            "destination": destination,
+            "dest_asset": dest_asset.display(),
+            "dest_min": format_stroops(*dest_min),
+            "path": assets(path),
+        }),
+        StellarOperationBody::ManageOffer {
+            selling,
+            buying,
+            amount,
+            price,
+            offer_id,
+            ..
+        } => json!({
+            "selling": selling.display(),
+            "buying": buying.display(),
+            "amount": format_stroops(*amount),
+            "price": format!("{}/{}", price.n, price.d),
+            "offer_id": offer_id.map(|id| id.to_string()),
+        }),
+        StellarOperationBody::SetOptions {
+            inflation_dest,
+            clear_flags,
+            set_flags,
+            master_weight,
+            low_threshold,
+            med_threshold,
+            high_threshold,
+            home_domain,
+            signer,
+        } => json!({
+            "inflation_dest": inflation_dest,
+            "clear_flags": clear_flags,
+            "set_flags": set_flags,
+            "master_weight": master_weight,
+            "low_threshold": low_threshold,
+            "med_threshold": med_threshold,
+            "high_threshold": high_threshold,
+            "home_domain": home_domain,
+            "signer": signer.as_ref().map(|(key, weight)| json!({
+                "key": key,
+                "weight": weight,
+            })),
+        }),
+        StellarOperationBody::ChangeTrust { line, limit } => json!({
+            "asset": line.display(),
+            "limit": format_stroops(*limit),
+        }),
+        StellarOperationBody::AllowTrust {
+            trustor,
+            asset_code,
+            authorize,
+        } => json!({
+            "trustor": trustor,
+            "asset_code": asset_code,
+            "authorize": authorize,
+        }),
+        StellarOperationBody::AccountMerge { destination } => {
+            json!({ "destination": destination })
+        }
+        StellarOperationBody::Inflation => json!({}),
+        StellarOperationBody::ManageData { name, value } => json!({
+            "name": name,
+            "value_hex": value.as_ref().map(hex_encode),
+        }),
+        StellarOperationBody::BumpSequence { bump_to } => {
+            json!({ "bump_to": bump_to.to_string() })
+        }
+    }
+}
+
+fn transaction_lines(transaction: &StellarTransaction) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Transaction: {} operations, fee {} stroops, sequence {}",
+        transaction.operations.len(),
+        transaction.fee,
+        transaction.sequence
+    )];
+    if let Some(network) = &transaction.network {
+        lines.push(format!("      Network: {}", network.label()));
+    }
+    lines.push(format!("      Source: {}", transaction.source_account));
+    if let Some(fee_bump) = &transaction.fee_bump {
+        lines.push(format!(
+            "      Fee bump: {} stroops paid by {}",
+            fee_bump.fee, fee_bump.fee_source
+        ));
+    }
+    match &transaction.memo {
+        StellarMemo::None => {}
+        StellarMemo::Text(text) => lines.push(format!("      Memo: {}", format_text_snippet(text))),
+        StellarMemo::Id(id) => lines.push(format!("      Memo ID: {}", id)),
+        StellarMemo::Hash(hash) | StellarMemo::Return(hash) => {
+            lines.push(format!("      Memo hash: {}", format_hex_snippet(hash)))
+        }
+    }
+    if let Some(bounds) = transaction.time_bounds {
+        lines.push(format!(
+            "      Valid: {} to {}",
+            bounds.min_time,
+            if bounds.max_time == 0 {
+                "unbounded".to_string()
+            } else {
+                bounds.max_time.to_string()
+            }
+        ));
+    }
+    for operation in &transaction.operations {
+        let line = match &operation.body {
+            StellarOperationBody::CreateAccount {
+                destination,
+                starting_balance,
+            } => format!(
+                "Create account {} with {} XLM",
+                destination,
+                format_stroops(*starting_balance)
+            ),
+            StellarOperationBody::Payment {
+                destination,
+                asset,
+                amount,
+            } => format!(
+                "Pay {} {} to {}",
+                format_stroops(*amount),
+                asset.display(),
+                destination
+            ),
+            StellarOperationBody::PathPaymentStrictReceive {
+                send_asset,
+                send_max,
+                destination,
+                dest_asset,
+                dest_amount,
+                ..
+            } => format!(
+                "Path payment {} {} to {} for at most {} {}",
+                format_stroops(*dest_amount),
+                dest_asset.display(),
+                destination,
+                format_stroops(*send_max),
+                send_asset.display()
+            ),
+            StellarOperationBody::PathPaymentStrictSend {
+                send_asset,
+                send_amount,
+                destination,
+                dest_asset,
+                dest_min,
+                ..
+            } => format!(
+                "Path payment {} {} to {} for at least {} {}",
+                format_stroops(*send_amount),
+                send_asset.display(),
+                destination,
+                format_stroops(*dest_min),
+                dest_asset.display()
+            ),
+            StellarOperationBody::ManageOffer {
+                selling,
+                buying,
+                amount,
+                price,
+                ..
+            } => format!(
+                "{}: {} selling {} for {} at {}/{}",
+                operation.body.label(),
+                format_stroops(*amount),
+                selling.display(),
+                buying.display(),
+                price.n,
+                price.d
+            ),
+            StellarOperationBody::SetOptions { signer, .. } => match signer {
+                Some((key, weight)) => format!("Set options (signer {} weight {})", key, weight),
+                None => "Set options".to_string(),
+            },
+            StellarOperationBody::ChangeTrust { line, limit } => {
+                if *limit == 0 {
+                    format!("Remove trustline {}", line.display())
+                } else {
+                    format!("Trust {} up to {}", line.display(), format_stroops(*limit))
+                }
+            }
+            StellarOperationBody::AllowTrust {
+                trustor,
+                asset_code,
+                authorize,
+            } => format!(
+                "Allow trust {} for {} (flags {})",
+                asset_code, trustor, authorize
+            ),
+            StellarOperationBody::AccountMerge { destination } => {
+                format!("Merge account into {}", destination)
+            }
+            StellarOperationBody::Inflation => "Inflation".to_string(),
+            StellarOperationBody::ManageData { name, value } => match value {
+                Some(value) => format!("Set data {} = {}", name, format_hex_snippet(value)),
+                None => format!("Delete data {}", name),
+            },
+            StellarOperationBody::BumpSequence { bump_to } => {
+                format!("Bump sequence to {}", bump_to)
+            }
+        };
+        lines.push(format!("      {}", line));
+        if let Some(source) = &operation.source_account {
+            lines.push(format!("        Operation source: {}", source));
+        }
+    }
+    if transaction.soroban {
+        lines.push("    WARNING: Soroban resource data is present but was not decoded".to_string());
+    }
+    lines
+}
+
+fn sign_type_label(sign_type: &StellarSignType) -> &'static str {
+    match sign_type {
+        StellarSignType::Transaction => "transaction",
+        StellarSignType::TransactionHash => "transaction_hash",
+        StellarSignType::Message => "message",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{mentions, render};
+
+    #[test]
+    fn renders_stellar_payment_and_flags_blind_signing() {
+        let mut envelope = Vec::new();
+        envelope.extend_from_slice(&2u32.to_be_bytes()); // ENVELOPE_TYPE_TX
+        envelope.extend_from_slice(&0u32.to_be_bytes());
+        envelope.extend_from_slice(&[0x11; 32]);
+        envelope.extend_from_slice(&100u32.to_be_bytes());
+        envelope.extend_from_slice(&7i64.to_be_bytes());
+        envelope.extend_from_slice(&0u32.to_be_bytes()); // no preconditions
+        envelope.extend_from_slice(&2u32.to_be_bytes()); // MEMO_ID
+        envelope.extend_from_slice(&99u64.to_be_bytes());
+        envelope.extend_from_slice(&1u32.to_be_bytes());
+        envelope.extend_from_slice(&0u32.to_be_bytes());
+        envelope.extend_from_slice(&1u32.to_be_bytes()); // PAYMENT
+        envelope.extend_from_slice(&0u32.to_be_bytes());
+        envelope.extend_from_slice(&[0x22; 32]);
+        envelope.extend_from_slice(&0u32.to_be_bytes()); // native
+        envelope.extend_from_slice(&30_000_000i64.to_be_bytes());
+        envelope.extend_from_slice(&0u32.to_be_bytes()); // ext
+        envelope.extend_from_slice(&0u32.to_be_bytes()); // no signatures
+
+        let path = CryptoKeyPath::from_str("m/44'/148'/0'").unwrap();
+        let request = StellarSignRequest::new_transaction(envelope, path.clone(), None);
+        let rendered = render(KeystoneMessage::StellarSignRequest(request));
+
+        let transaction = &rendered.json["message"]["transaction"];
+        assert_eq!(transaction["fee_stroops"], 100);
+        assert_eq!(transaction["memo"]["value"], "99");
+        assert_eq!(transaction["operations"][0]["type"], "payment");
+        assert_eq!(transaction["operations"][0]["amount"], "3");
+        assert!(mentions(&rendered, "Pay 3 XLM to G"));
+
+        let request = StellarSignRequest::new_transaction_hash(vec![0xab; 32], path, None);
+        let rendered = render(KeystoneMessage::StellarSignRequest(request));
+        assert_eq!(rendered.json["message"]["blind_signing"], true);
+        assert!(
+            rendered
+                .human
+                .iter()
+                .any(|line| line.starts_with("    WARNING: blind signing"))
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Renderers for Sui sign requests and signatures
+
+use super::{format_hex_snippet, format_text_snippet};
+use crate::keystone::messages::{SuiIntentMessage, SuiSignRequest, SuiSignature};
+use hex::encode as hex_encode;
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &SuiSignRequest) -> Value {
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_paths": request
+            .derivation_paths
+            .iter()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>(),
+        "addresses": request.addresses.iter().map(hex_encode).collect::<Vec<_>>(),
+        "origin": request.origin.clone(),
+        "intent_message_hex": hex_encode(&request.intent_message),
+        "intent_message_bytes": request.intent_message.len(),
+    });
+    match request.decode_intent() {
+        Ok(intent) => value["intent"] = intent_value(&intent),
+        Err(err) => value["intent_error"] = json!(err.to_string()),
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &SuiSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature_hex": hex_encode(&signature.signature),
+        "signature_bytes": signature.signature.len(),
+        "public_key_hex": signature.public_key.as_ref().map(hex_encode),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &SuiSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: sui_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    for path in &request.derivation_paths {
+        lines.push(format!("    Derivation path: {}", path.to_string()));
+    }
+    for address in &request.addresses {
+        lines.push(format!("    Address: 0x{}", hex_encode(address)));
+    }
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Intent message: {}",
+        format_hex_snippet(&request.intent_message)
+    ));
+    match request.decode_intent() {
+        Ok(intent) => lines.extend(intent_lines(&intent)),
+        Err(err) => lines.push(format!("    Intent error: {}", err)),
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &SuiSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: sui_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!(
+        "    Signature: {}",
+        format_hex_snippet(&signature.signature)
+    ));
+    if let Some(public_key) = &signature.public_key {
+        lines.push(format!(
+            "    Public key: {}",
+            format_hex_snippet(public_key)
+        ));
+    }
+    lines
+}
+
+fn intent_value(intent: &SuiIntentMessage) -> Value {
+    let mut value = json!({
+        "scope": intent.scope.label(),
+        "version": intent.version,
+        "app_id": intent.app_id,
+        "app": intent.app_label(),
+        "value_bytes": intent.value.len(),
+    });
+    if let Ok(message) = intent.personal_message() {
+        value["personal_message"] = json!(String::from_utf8_lossy(&message));
+    }
+    value
+}
+
+fn intent_lines(intent: &SuiIntentMessage) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Intent: {} (app {}, version {})",
+        intent.scope.label(),
+        intent
+            .app_label()
+            .map(str::to_string)
+            .unwrap_or_else(|| intent.app_id.to_string()),
+        intent.version
+    )];
+    if let Ok(message) = intent.personal_message() {
+        lines.push(format!(
+            "      Message: {}",
+            format_text_snippet(&String::from_utf8_lossy(&message))
+        ));
+    }
+    if !intent.scope.is_user_facing() {
+        lines.push(format!(
+            "    WARNING: intent scope {} is not something a user wallet should sign",
+            intent.scope.label()
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::keystone::crypto_keypath::CryptoKeyPath;
+    use crate::output::tests::{has_line, mentions, render};
+
+    #[test]
+    fn renders_sui_personal_message_and_flags_validator_scopes() {
+        let path = CryptoKeyPath::from_str("m/44'/784'/0'/0'/0'").unwrap();
+        let message = [&[3u8, 0, 0, 5][..], b"hello"].concat();
+        let request = SuiSignRequest::new(message, vec![path.clone()], None);
+        let rendered = render(KeystoneMessage::SuiSignRequest(request));
+
+        assert_eq!(
+            rendered.json["message"]["intent"]["scope"],
+            "personal_message"
+        );
+        assert_eq!(
+            rendered.json["message"]["intent"]["personal_message"],
+            "hello"
+        );
+        assert!(!mentions(&rendered, "WARNING"));
+
+        let request = SuiSignRequest::new(vec![2, 0, 0, 0xff], vec![path], None);
+        let rendered = render(KeystoneMessage::SuiSignRequest(request));
+        assert!(has_line(
+            &rendered,
+            "    WARNING: intent scope checkpoint_summary is not something a user wallet should sign"
+        ));
+    }
+}
@@ -2,6 +2,7 @@
 
 #![cfg(target_family = "unix")]
 
+use crate::ScanEvent;
 use crate::error::{Error, Result};
 use serde_json::Value;
 use std::path::{Path, PathBuf};
@@ -91,6 +92,30 @@ impl UnixBroadcast {
             })
     }
 
+    /// Forward scan progress events to listeners until the event channel closes.
+    pub fn forward_events(
+        self: &Arc<Self>,
+        mut events: broadcast::Receiver<ScanEvent>,
+    ) -> JoinHandle<()> {
+        let this = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let Err(err) = this.send_value(&event.to_value()) {
+                            // No connected clients; events are not buffered for later
+                            tracing::trace!("Dropping scan event: {err}");
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Unix socket event forwarder skipped {skipped} events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
     /// Broadcast an error message payload to listeners.
     pub fn send_error(&self, message: &str) -> Result<()> {
         let payload = json!({
@@ -0,0 +1,248 @@
+//! Renderers for XRP sign requests and signatures
+
+use super::format_text_snippet;
+use crate::keystone::messages::xrp::transaction::format_drops_as_xrp;
+use crate::keystone::messages::{
+    XrpAmount, XrpSignRequest, XrpSignature, XrpTransaction, XrpTransactionKind,
+};
+use serde_json::{Value, json};
+
+pub(super) fn sign_request_value(request: &XrpSignRequest) -> Value {
+    let tx_value = serde_json::from_str::<Value>(&request.transaction_json)
+        .unwrap_or_else(|_| Value::String(request.transaction_json.clone()));
+    let mut value = json!({
+        "request_id": request.request_id.map(|id| id.to_string()),
+        "derivation_path": request.derivation_path.clone(),
+        "origin": request.origin.clone(),
+        "transaction": tx_value,
+    });
+    match request.decode_transaction() {
+        Ok(transaction) => value["decoded"] = transaction_value(&transaction),
+        Err(err) => value["transaction_error"] = json!(err.to_string()),
+    }
+    match request.signing_hash() {
+        Ok(hash) => value["signing_hash"] = json!(hex::encode_upper(hash)),
+        Err(err) => value["signing_hash_error"] = json!(err.to_string()),
+    }
+    value
+}
+
+pub(super) fn signature_value(signature: &XrpSignature) -> Value {
+    json!({
+        "request_id": signature.request_id.map(|id| id.to_string()),
+        "signature": signature.signature.clone(),
+    })
+}
+
+pub(super) fn sign_request_lines(request: &XrpSignRequest) -> Vec<String> {
+    let mut lines = vec!["  Variant: xrp_sign_request".to_string()];
+    if let Some(id) = request.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!("    Derivation path: {}", request.derivation_path));
+    if let Some(origin) = &request.origin {
+        lines.push(format!("    Origin: {}", origin));
+    }
+    lines.push(format!(
+        "    Transaction JSON: {}",
+        format_text_snippet(&request.transaction_json)
+    ));
+    match request.decode_transaction() {
+        Ok(transaction) => lines.extend(transaction_lines(&transaction)),
+        Err(err) => lines.push(format!("    Transaction error: {}", err)),
+    }
+    if let Ok(hash) = request.signing_hash() {
+        lines.push(format!("    Signing hash: {}", hex::encode_upper(hash)));
+    }
+    lines
+}
+
+pub(super) fn signature_lines(signature: &XrpSignature) -> Vec<String> {
+    let mut lines = vec!["  Variant: xrp_signature".to_string()];
+    if let Some(id) = signature.request_id {
+        lines.push(format!("    Request ID: {}", id));
+    }
+    lines.push(format!("    Signature: {}", signature.signature));
+    lines
+}
+
+fn transaction_value(transaction: &XrpTransaction) -> Value {
+    let amount = |amount: &XrpAmount| match amount {
+        XrpAmount::Drops(drops) => json!({
+            "drops": drops.to_string(),
+            "xrp": format_drops_as_xrp(*drops),
+        }),
+        XrpAmount::Issued {
+            value,
+            currency,
+            issuer,
+        } => json!({
+            "value": value,
+            "currency": currency,
+            "issuer": issuer,
+        }),
+    };
+    let details = match &transaction.kind {
+        XrpTransactionKind::Payment {
+            destination,
+            amount: delivered,
+            destination_tag,
+            send_max,
+            deliver_min,
+        } => json!({
+            "destination": destination,
+            "amount": amount(delivered),
+            "destination_tag": destination_tag,
+            "send_max": send_max.as_ref().map(amount),
+            "deliver_min": deliver_min.as_ref().map(amount),
+        }),
+        XrpTransactionKind::TrustSet { limit_amount } => json!({
+            "limit_amount": amount(limit_amount),
+        }),
+        XrpTransactionKind::OfferCreate {
+            taker_pays,
+            taker_gets,
+            expiration,
+            offer_sequence,
+        } => json!({
+            "taker_pays": amount(taker_pays),
+            "taker_gets": amount(taker_gets),
+            "expiration": expiration,
+            "offer_sequence": offer_sequence,
+        }),
+        XrpTransactionKind::NFTokenCreateOffer {
+            nftoken_id,
+            amount: price,
+            owner,
+            destination,
+            expiration,
+            sell,
+        } => json!({
+            "nftoken_id": nftoken_id,
+            "amount": amount(price),
+            "owner": owner,
+            "destination": destination,
+            "expiration": expiration,
+            "sell": sell,
+        }),
+        XrpTransactionKind::Other => Value::Null,
+    };
+    json!({
+        "transaction_type": transaction.transaction_type,
+        "account": transaction.account,
+        "fee_drops": transaction.fee.map(|fee| fee.to_string()),
+        "fee_xrp": transaction.fee.map(format_drops_as_xrp),
+        "sequence": transaction.sequence,
+        "last_ledger_sequence": transaction.last_ledger_sequence,
+        "flags": transaction.flags,
+        "signing_pub_key": transaction.signing_pub_key,
+        "memo_count": transaction.memo_count,
+        "details": details,
+        "warnings": transaction
+            .warnings()
+            .iter()
+            .map(|warning| json!({
+                "type": warning.label(),
+                "description": warning.description(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn transaction_lines(transaction: &XrpTransaction) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    Transaction: {} from {}",
+        transaction.transaction_type, transaction.account
+    )];
+    if let Some(fee) = transaction.fee {
+        lines.push(format!("      Fee: {}", format_drops_as_xrp(fee)));
+    }
+    match &transaction.kind {
+        XrpTransactionKind::Payment {
+            destination,
+            amount,
+            destination_tag,
+            send_max,
+            ..
+        } => {
+            let tag = destination_tag
+                .map(|tag| format!(" (tag {})", tag))
+                .unwrap_or_default();
+            lines.push(format!(
+                "      Pay {} to {}{}",
+                amount.display(),
+                destination,
+                tag
+            ));
+            if let Some(send_max) = send_max {
+                lines.push(format!("      Spend at most {}", send_max.display()));
+            }
+        }
+        XrpTransactionKind::TrustSet { limit_amount } => {
+            lines.push(format!("      Trust up to {}", limit_amount.display()));
+        }
+        XrpTransactionKind::OfferCreate {
+            taker_pays,
+            taker_gets,
+            ..
+        } => {
+            lines.push(format!(
+                "      Offer {} for {}",
+                taker_gets.display(),
+                taker_pays.display()
+            ));
+        }
+        XrpTransactionKind::NFTokenCreateOffer {
+            nftoken_id,
+            amount,
+            sell,
+            ..
+        } => {
+            lines.push(format!(
+                "      {} NFT {} for {}",
+                if *sell { "Sell" } else { "Buy" },
+                nftoken_id,
+                amount.display()
+            ));
+        }
+        XrpTransactionKind::Other => {}
+    }
+    for warning in transaction.warnings() {
+        lines.push(format!("    WARNING: {}", warning.description()));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoneMessage;
+    use crate::output::tests::{mentions, render};
+
+    #[test]
+    fn renders_xrp_payment_and_flags_missing_destination_tag() {
+        let transaction = r#"{"TransactionType":"Payment","Account":"rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh","Destination":"rrrrrrrrrrrrrrrrrrrrBZbvji","Amount":"2500000","Fee":"12","Sequence":3}"#;
+        let request = XrpSignRequest::new(
+            transaction.to_string(),
+            "m/44'/144'/0'/0/0".to_string(),
+            None,
+        );
+        let rendered = render(KeystoneMessage::XrpSignRequest(request));
+
+        let message = &rendered.json["message"];
+        assert_eq!(message["transaction"]["Sequence"], 3);
+        assert_eq!(message["decoded"]["details"]["amount"]["xrp"], "2.5 XRP");
+        assert_eq!(
+            message["decoded"]["warnings"][0]["type"],
+            "missing_destination_tag"
+        );
+        assert_eq!(message["signing_hash"].as_str().unwrap().len(), 64);
+        assert!(mentions(&rendered, "Pay 2.5 XRP to r"));
+        assert!(
+            rendered
+                .human
+                .iter()
+                .any(|line| line.starts_with("    WARNING: payment has no destination tag"))
+        );
+    }
+}
@@ -38,6 +38,17 @@ impl QrEncoder {
         Ok(DynamicImage::ImageLuma8(image))
     }
 
+    /// Encode data into an SVG document (for UIs that render markup directly)
+    pub fn encode_svg(&self, payload: &QrPayload) -> Result<String> {
+        let code = QrCode::with_error_correction_level(&payload.data, self.ecc_level)
+            .map_err(|e| Error::QrEncode(format!("Failed to create QR code: {}", e)))?;
+
+        Ok(code
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(400, 400)
+            .build())
+    }
+
     /// Encode a string into a QR code image
     pub fn encode_string(&self, data: &str) -> Result<DynamicImage> {
         let payload = QrPayload::from_string(data.to_string());
@@ -80,6 +91,15 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_encode_svg() {
+        let encoder = QrEncoder::new();
+        let svg = encoder
+            .encode_svg(&QrPayload::from_string("ur:bytes/test".to_string()))
+            .unwrap();
+        assert!(svg.contains("<svg"));
+    }
+
     #[test]
     fn test_round_trip() {
         use crate::qr::QrDecoder;
@@ -0,0 +1,443 @@
+//! Request/response session tracking keyed by `request_id`
+//!
+//! Outbound sign requests are recorded when they are shown as QR codes; a
+//! scanned signature is only accepted when its `request_id` matches a
+//! pending, unexpired request of the right type and its signature does not
+//! fail verification. Everything else is reported as orphaned so a stale or
+//! forged QR code cannot be mistaken for the answer we are waiting on.
+
+use crate::error::{Error, Result};
+use crate::keystone::verify::{SignatureVerification, VerificationStatus, verify_response};
+use crate::keystone::{CryptoAccount, KeystoneMessage};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long a recorded request waits for its signature
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Number of orphaned responses remembered for reporting
+pub const MAX_ORPHANED_RESPONSES: usize = 32;
+
+/// Session store shared between the scanner, API and output layers
+pub type SharedSessionStore = Arc<Mutex<SessionStore>>;
+
+/// Outbound sign request awaiting a response
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// Request identifier
+    pub request_id: Uuid,
+    /// The request that was shown to the wallet
+    pub request: KeystoneMessage,
+    /// When the request was recorded
+    pub created: Instant,
+}
+
+/// Scanned response that did not match any pending request
+#[derive(Debug, Clone)]
+pub struct OrphanedResponse {
+    /// Request identifier carried by the response, if any
+    pub request_id: Option<Uuid>,
+    /// UR type of the response
+    pub ur_type: String,
+    /// Why the response was not accepted
+    pub reason: String,
+    /// When the response was scanned
+    pub received: Instant,
+}
+
+/// Outcome of matching a scanned response against pending requests
+#[derive(Debug, Clone)]
+pub enum ResponseMatch {
+    /// Response answers a pending request; the session is closed
+    Matched {
+        /// Request identifier
+        request_id: Uuid,
+        /// The request it answers
+        request: Box<KeystoneMessage>,
+        /// Signature check against the request
+        verification: SignatureVerification,
+    },
+    /// Response carries a pending request's ID but its signature is invalid;
+    /// the session stays open for the genuine response
+    Rejected {
+        /// Request identifier
+        request_id: Uuid,
+        /// Failed signature check
+        verification: SignatureVerification,
+    },
+    /// Response answers a request that timed out
+    Expired {
+        /// Request identifier
+        request_id: Uuid,
+    },
+    /// Response type does not answer the pending request with this ID
+    Mismatched {
+        /// Request identifier
+        request_id: Uuid,
+    },
+    /// No pending request carries this response's ID
+    Orphaned {
+        /// Request identifier carried by the response, if any
+        request_id: Option<Uuid>,
+    },
+}
+
+impl ResponseMatch {
+    /// Short label used in events and API output
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResponseMatch::Matched { .. } => "matched",
+            ResponseMatch::Rejected { .. } => "rejected",
+            ResponseMatch::Expired { .. } => "expired",
+            ResponseMatch::Mismatched { .. } => "mismatched",
+            ResponseMatch::Orphaned { .. } => "orphaned",
+        }
+    }
+
+    /// Request identifier involved in the match, if any
+    pub fn request_id(&self) -> Option<Uuid> {
+        match self {
+            ResponseMatch::Matched { request_id, .. }
+            | ResponseMatch::Rejected { request_id, .. }
+            | ResponseMatch::Expired { request_id }
+            | ResponseMatch::Mismatched { request_id } => Some(*request_id),
+            ResponseMatch::Orphaned { request_id } => *request_id,
+        }
+    }
+
+    /// Whether the response was accepted
+    pub fn is_matched(&self) -> bool {
+        matches!(self, ResponseMatch::Matched { .. })
+    }
+
+    /// Signature check, for matched and rejected responses
+    pub fn verification(&self) -> Option<&SignatureVerification> {
+        match self {
+            ResponseMatch::Matched { verification, .. }
+            | ResponseMatch::Rejected { verification, .. } => Some(verification),
+            _ => None,
+        }
+    }
+
+    /// Why the response was (or was not) accepted
+    pub fn reason(&self) -> String {
+        match self {
+            ResponseMatch::Matched { verification, .. }
+            | ResponseMatch::Rejected { verification, .. } => verification.detail.clone(),
+            ResponseMatch::Expired { .. } => "request expired before the response arrived".into(),
+            ResponseMatch::Mismatched { .. } => {
+                "response type does not answer the pending request".into()
+            }
+            ResponseMatch::Orphaned { request_id: None } => "response carries no request ID".into(),
+            ResponseMatch::Orphaned { .. } => "no pending request with this ID".into(),
+        }
+    }
+
+    /// One-line summary for logs and error events
+    pub fn describe(&self) -> String {
+        match self.request_id() {
+            Some(request_id) => format!(
+                "{} response for request {request_id}: {}",
+                self.label(),
+                self.reason()
+            ),
+            None => format!("{} response: {}", self.label(), self.reason()),
+        }
+    }
+}
+
+/// Tracks outbound sign requests and matches scanned signatures to them
+#[derive(Debug)]
+pub struct SessionStore {
+    sessions: HashMap<Uuid, Session>,
+    orphans: Vec<OrphanedResponse>,
+    ttl: Duration,
+    account: Option<CryptoAccount>,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_TTL)
+    }
+}
+
+impl SessionStore {
+    /// Create a store whose sessions expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            orphans: Vec::new(),
+            ttl,
+            account: None,
+        }
+    }
+
+    /// Wrap the store for sharing
+    pub fn shared(self) -> SharedSessionStore {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Paired account used to verify ETH signatures for requests without an address
+    pub fn set_account(&mut self, account: Option<CryptoAccount>) {
+        self.account = account;
+    }
+
+    /// Record an outbound sign request; returns its request ID
+    pub fn record_request(&mut self, request: KeystoneMessage) -> Result<Uuid> {
+        if !request.is_sign_request() {
+            return Err(Error::InvalidKeystonePayload(
+                "Only sign requests can open a session".to_string(),
+            ));
+        }
+        let request_id = request.request_id().ok_or_else(|| {
+            Error::InvalidKeystonePayload("Sign request has no request ID".to_string())
+        })?;
+
+        // Expired sessions linger for one more TTL so late responses report as expired
+        let retention = self.ttl * 2;
+        self.sessions
+            .retain(|_, session| session.created.elapsed() <= retention);
+
+        self.sessions.insert(
+            request_id,
+            Session {
+                request_id,
+                request,
+                created: Instant::now(),
+            },
+        );
+        Ok(request_id)
+    }
+
+    /// Match a scanned response, closing its session when it is accepted
+    ///
+    /// `ur_type` is only used to label orphaned responses.
+    pub fn match_response(&mut self, ur_type: &str, response: &KeystoneMessage) -> ResponseMatch {
+        let outcome = self.resolve(response);
+        if !outcome.is_matched() {
+            self.orphans.push(OrphanedResponse {
+                request_id: outcome.request_id(),
+                ur_type: ur_type.to_string(),
+                reason: outcome.reason(),
+                received: Instant::now(),
+            });
+            if self.orphans.len() > MAX_ORPHANED_RESPONSES {
+                self.orphans.remove(0);
+            }
+        }
+        outcome
+    }
+
+    fn resolve(&mut self, response: &KeystoneMessage) -> ResponseMatch {
+        let Some(request_id) = response.request_id() else {
+            return ResponseMatch::Orphaned { request_id: None };
+        };
+        let Some(session) = self.sessions.get(&request_id) else {
+            return ResponseMatch::Orphaned {
+                request_id: Some(request_id),
+            };
+        };
+
+        if session.created.elapsed() > self.ttl {
+            self.sessions.remove(&request_id);
+            return ResponseMatch::Expired { request_id };
+        }
+        if !session.request.answered_by(response) {
+            return ResponseMatch::Mismatched { request_id };
+        }
+
+        let verification = verify_response(&session.request, response, self.account.as_ref(), None);
+        if verification.status == VerificationStatus::Invalid {
+            // A forged or corrupted response must not close the real session
+            return ResponseMatch::Rejected {
+                request_id,
+                verification,
+            };
+        }
+        let session = self
+            .sessions
+            .remove(&request_id)
+            .expect("session looked up above");
+        ResponseMatch::Matched {
+            request_id,
+            request: Box::new(session.request),
+            verification,
+        }
+    }
+
+    /// Drop expired sessions, returning their request IDs
+    pub fn prune_expired(&mut self) -> Vec<Uuid> {
+        let ttl = self.ttl;
+        let expired: Vec<Uuid> = self
+            .sessions
+            .values()
+            .filter(|session| session.created.elapsed() > ttl)
+            .map(|session| session.request_id)
+            .collect();
+        for request_id in &expired {
+            self.sessions.remove(request_id);
+        }
+        expired
+    }
+
+    /// Whether any recorded request is still waiting for (or recently timed
+    /// out waiting for) its response
+    pub fn has_pending(&self) -> bool {
+        !self.sessions.is_empty()
+    }
+
+    /// Requests still awaiting a response, oldest first
+    pub fn pending(&self) -> Vec<&Session> {
+        let mut pending: Vec<&Session> = self.sessions.values().collect();
+        pending.sort_by_key(|session| session.created);
+        pending
+    }
+
+    /// Recently rejected responses, oldest first
+    pub fn orphans(&self) -> &[OrphanedResponse] {
+        &self.orphans
+    }
+
+    /// JSON snapshot for API clients
+    pub fn to_value(&self) -> Value {
+        json!({
+            "ttl_secs": self.ttl.as_secs(),
+            "pending": self
+                .pending()
+                .iter()
+                .map(|session| json!({
+                    "request_id": session.request_id.to_string(),
+                    "variant": crate::output::message_variant_label(&session.request),
+                    "age_secs": session.created.elapsed().as_secs(),
+                    "expired": session.created.elapsed() > self.ttl,
+                }))
+                .collect::<Vec<_>>(),
+            "orphaned": self
+                .orphans
+                .iter()
+                .map(|orphan| json!({
+                    "request_id": orphan.request_id.map(|id| id.to_string()),
+                    "ur_type": orphan.ur_type,
+                    "reason": orphan.reason,
+                    "age_secs": orphan.received.elapsed().as_secs(),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystone::messages::ethereum::typed_data::keccak256;
+    use crate::keystone::{CryptoKeyPath, EthSignRequest, EthSignature, SolanaSignature};
+    use k256::ecdsa::SigningKey;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    fn eth_request() -> KeystoneMessage {
+        let point = signing_key().verifying_key().to_encoded_point(false);
+        let address = keccak256(&point.as_bytes()[1..])[12..].to_vec();
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        KeystoneMessage::EthSignRequest(
+            EthSignRequest::new_personal_message(b"hello".to_vec(), path).with_address(address),
+        )
+    }
+
+    /// Personal-message signature over "hello" by [`signing_key`]
+    fn eth_signature(request_id: Option<Uuid>) -> KeystoneMessage {
+        let digest = keccak256(b"\x19Ethereum Signed Message:\n5hello");
+        let (rs, recovery_id) = signing_key().sign_prehash_recoverable(&digest).unwrap();
+        let mut bytes = rs.to_bytes().to_vec();
+        bytes.push(27 + recovery_id.to_byte());
+        let mut signature = EthSignature::new(bytes);
+        signature.request_id = request_id;
+        KeystoneMessage::EthSignature(signature)
+    }
+
+    #[test]
+    fn test_matches_response_and_closes_session() {
+        let mut store = SessionStore::default();
+        let request_id = store.record_request(eth_request()).unwrap();
+        assert_eq!(store.pending().len(), 1);
+
+        let outcome = store.match_response("eth-signature", &eth_signature(Some(request_id)));
+        assert!(outcome.is_matched());
+        assert_eq!(
+            outcome.verification().map(|v| v.status),
+            Some(VerificationStatus::Valid)
+        );
+        assert!(store.pending().is_empty());
+
+        // Replaying the same QR no longer matches anything
+        let replay = store.match_response("eth-signature", &eth_signature(Some(request_id)));
+        assert_eq!(replay.label(), "orphaned");
+        assert_eq!(store.orphans().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_signature_keeps_session_open() {
+        let mut store = SessionStore::default();
+        let request_id = store.record_request(eth_request()).unwrap();
+
+        // Well-formed signature over "hello" by a key other than the request's
+        let digest = keccak256(b"\x19Ethereum Signed Message:\n5hello");
+        let other = SigningKey::from_slice(&[0x43; 32]).unwrap();
+        let (rs, recovery_id) = other.sign_prehash_recoverable(&digest).unwrap();
+        let mut bytes = rs.to_bytes().to_vec();
+        bytes.push(27 + recovery_id.to_byte());
+        let mut forged = EthSignature::new(bytes);
+        forged.request_id = Some(request_id);
+        let outcome = store.match_response("eth-signature", &KeystoneMessage::EthSignature(forged));
+        assert_eq!(outcome.label(), "rejected");
+        assert_eq!(store.pending().len(), 1);
+        assert_eq!(store.orphans().len(), 1);
+
+        // The genuine signature still closes the session
+        let outcome = store.match_response("eth-signature", &eth_signature(Some(request_id)));
+        assert!(outcome.is_matched());
+        assert!(store.pending().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unknown_mismatched_and_expired_responses() {
+        let mut store = SessionStore::new(Duration::from_millis(50));
+        let request_id = store.record_request(eth_request()).unwrap();
+
+        let stray = store.match_response("eth-signature", &eth_signature(Some(Uuid::new_v4())));
+        assert_eq!(stray.label(), "orphaned");
+        let anonymous = store.match_response("eth-signature", &eth_signature(None));
+        assert_eq!(anonymous.request_id(), None);
+
+        let wrong_chain =
+            KeystoneMessage::SolanaSignature(SolanaSignature::new(vec![0u8; 64], Some(request_id)));
+        let mismatched = store.match_response("sol-signature", &wrong_chain);
+        assert_eq!(mismatched.label(), "mismatched");
+
+        std::thread::sleep(Duration::from_millis(60));
+        let expired = store.match_response("eth-signature", &eth_signature(Some(request_id)));
+        assert_eq!(expired.label(), "expired");
+        assert!(store.pending().is_empty());
+        assert_eq!(store.orphans().len(), 4);
+    }
+
+    #[test]
+    fn test_record_requires_sign_request_with_id() {
+        let mut store = SessionStore::default();
+        assert!(store.record_request(eth_signature(None)).is_err());
+
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let mut request = EthSignRequest::new_personal_message(b"hi".to_vec(), path);
+        request.request_id = None;
+        assert!(
+            store
+                .record_request(KeystoneMessage::EthSignRequest(request))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,549 @@
+//! Simulator mode for replaying prerecorded UR fragment streams
+//!
+//! A recording is a plain text file with one UR fragment per line. Replaying it
+//! drives the same multi-part decoding, rendering and output sinks as the camera
+//! path, so the daemon pipeline can be exercised without a webcam (e.g. in CI).
+//!
+//! ```text
+//! # Lines starting with '#' are comments
+//! delay=150        # default delay between fragments in milliseconds
+//! drop=0.1         # probability of dropping each fragment (0.0 - 1.0)
+//! shuffle=true     # shuffle fragment order within each sequence
+//! seed=42          # seed for drop/shuffle decisions (defaults to the clock)
+//!
+//! ur:eth-sign-request/1-3/lpadaxcs...
+//! ur:eth-sign-request/2-3/lpaoaxcs... 400   # per-line delay override (ms)
+//! ur:eth-sign-request/3-3/lpaxaxcs...
+//! ---              # sequence separator
+//! ur:crypto-account/oeadcyemrewytyao...
+//! ```
+//!
+//! Directives apply to the whole recording. Each sequence yields at most one
+//! payload; fragments that arrive after a sequence completes are ignored.
+
+use crate::error::{Error, Result};
+use crate::keystone::multipart::MultiPartDecoder;
+use crate::metrics;
+use crate::output::{OutputSink, render_keystone_payload};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Separator line between independent UR sequences in a recording
+pub const SEQUENCE_SEPARATOR: &str = "---";
+
+/// Pause between passes in watch mode, so a recording without `delay=`
+/// does not replay in a tight loop
+pub const WATCH_PASS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Replay behaviour shared by every fragment in a recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaySettings {
+    /// Default delay before each fragment is delivered
+    pub delay: Duration,
+    /// Probability (0.0 - 1.0) that a fragment is dropped, simulating missed frames
+    pub drop_rate: f64,
+    /// Shuffle fragment order within each sequence
+    pub shuffle: bool,
+    /// Seed for drop/shuffle decisions; `None` seeds from the system clock
+    pub seed: Option<u64>,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        Self {
+            delay: Duration::ZERO,
+            drop_rate: 0.0,
+            shuffle: false,
+            seed: None,
+        }
+    }
+}
+
+/// A single UR fragment captured in a recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFragment {
+    /// UR string exactly as it would be scanned from a QR frame
+    pub ur: String,
+    /// Optional delay override for this fragment
+    pub delay: Option<Duration>,
+    /// 1-indexed line number in the source file (for diagnostics)
+    pub line: usize,
+}
+
+/// Parsed simulator recording
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    /// Replay settings declared via `key=value` directives
+    pub settings: ReplaySettings,
+    /// Fragment sequences, each expected to decode into one payload
+    pub sequences: Vec<Vec<RecordedFragment>>,
+}
+
+impl Recording {
+    /// Load and parse a recording from disk
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read simulator recording {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&contents)
+    }
+
+    /// Parse a recording from its text representation
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut settings = ReplaySettings::default();
+        let mut sequences = Vec::new();
+        let mut current = Vec::new();
+
+        for (index, raw) in contents.lines().enumerate() {
+            let line_no = index + 1;
+            let line = strip_comment(raw).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line == SEQUENCE_SEPARATOR {
+                if !current.is_empty() {
+                    sequences.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+
+            if line
+                .get(..3)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("ur:"))
+            {
+                current.push(parse_fragment(line, line_no)?);
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                apply_directive(&mut settings, key.trim(), value.trim(), line_no)?;
+                continue;
+            }
+
+            return Err(Error::Config(format!(
+                "Simulator recording line {line_no}: expected a UR fragment, directive or '{SEQUENCE_SEPARATOR}'"
+            )));
+        }
+
+        if !current.is_empty() {
+            sequences.push(current);
+        }
+
+        Ok(Self {
+            settings,
+            sequences,
+        })
+    }
+
+    /// Total number of fragments across all sequences
+    pub fn fragment_count(&self) -> usize {
+        self.sequences.iter().map(Vec::len).sum()
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(pos) => &line[..pos],
+        None => line,
+    }
+}
+
+fn parse_fragment(line: &str, line_no: usize) -> Result<RecordedFragment> {
+    let mut tokens = line.split_whitespace();
+    let ur = tokens.next().unwrap_or_default().to_ascii_lowercase();
+    let delay = tokens
+        .next()
+        .map(|token| parse_delay(token, line_no))
+        .transpose()?;
+
+    if tokens.next().is_some() {
+        return Err(Error::Config(format!(
+            "Simulator recording line {line_no}: unexpected trailing data after delay"
+        )));
+    }
+
+    Ok(RecordedFragment {
+        ur,
+        delay,
+        line: line_no,
+    })
+}
+
+fn parse_delay(value: &str, line_no: usize) -> Result<Duration> {
+    let digits = value.strip_suffix("ms").unwrap_or(value);
+    digits
+        .parse::<u64>()
+        .map(Duration::from_millis)
+        .map_err(|e| {
+            Error::Config(format!(
+                "Simulator recording line {line_no}: invalid delay '{value}': {e}"
+            ))
+        })
+}
+
+fn apply_directive(
+    settings: &mut ReplaySettings,
+    key: &str,
+    value: &str,
+    line_no: usize,
+) -> Result<()> {
+    match key.to_ascii_lowercase().as_str() {
+        "delay" => settings.delay = parse_delay(value, line_no)?,
+        "drop" => {
+            let rate = value.parse::<f64>().map_err(|e| {
+                Error::Config(format!(
+                    "Simulator recording line {line_no}: invalid drop rate '{value}': {e}"
+                ))
+            })?;
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(Error::Config(format!(
+                    "Simulator recording line {line_no}: drop rate must be between 0.0 and 1.0"
+                )));
+            }
+            settings.drop_rate = rate;
+        }
+        "shuffle" => {
+            settings.shuffle = match value.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" => true,
+                "0" | "false" | "off" | "no" => false,
+                other => {
+                    return Err(Error::Config(format!(
+                        "Simulator recording line {line_no}: invalid shuffle flag '{other}'"
+                    )));
+                }
+            }
+        }
+        "seed" => {
+            settings.seed = Some(value.parse::<u64>().map_err(|e| {
+                Error::Config(format!(
+                    "Simulator recording line {line_no}: invalid seed '{value}': {e}"
+                ))
+            })?);
+        }
+        other => {
+            return Err(Error::Config(format!(
+                "Simulator recording line {line_no}: unknown directive '{other}'"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Counters describing a single pass over a recording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    /// Fragments delivered to the decoder
+    pub fragments_sent: usize,
+    /// Fragments skipped because of the configured drop rate
+    pub fragments_dropped: usize,
+    /// Payloads successfully decoded and emitted
+    pub payloads: usize,
+    /// Fragment or sequence failures reported to the sink
+    pub errors: usize,
+}
+
+/// Replays a recording through `MultiPartDecoder` into an output sink
+pub struct Simulator {
+    recording: Recording,
+    rng: ReplayRng,
+}
+
+impl Simulator {
+    /// Create a simulator for the given recording
+    pub fn new(recording: Recording) -> Self {
+        let seed = recording.settings.seed.unwrap_or_else(clock_seed);
+        Self {
+            recording,
+            rng: ReplayRng::new(seed),
+        }
+    }
+
+    /// Access the recording being replayed
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Replay every sequence once, emitting decoded payloads and failures to `sink`
+    pub async fn replay<S: OutputSink + ?Sized>(&mut self, sink: &S) -> Result<ReplayStats> {
+        let mut stats = ReplayStats::default();
+
+        for index in 0..self.recording.sequences.len() {
+            self.replay_sequence(index, sink, &mut stats).await?;
+        }
+
+        Ok(stats)
+    }
+
+    async fn replay_sequence<S: OutputSink + ?Sized>(
+        &mut self,
+        index: usize,
+        sink: &S,
+        stats: &mut ReplayStats,
+    ) -> Result<()> {
+        let settings = self.recording.settings.clone();
+        let mut order: Vec<usize> = (0..self.recording.sequences[index].len()).collect();
+        if settings.shuffle {
+            self.rng.shuffle(&mut order);
+        }
+
+        let mut decoder = MultiPartDecoder::new();
+        let mut last_percentage = 0u8;
+        let started = Instant::now();
+
+        for position in order {
+            let fragment = &self.recording.sequences[index][position];
+
+            let delay = fragment.delay.unwrap_or(settings.delay);
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+
+            if settings.drop_rate > 0.0 && self.rng.next_f64() < settings.drop_rate {
+                stats.fragments_dropped += 1;
+                tracing::debug!(line = fragment.line, "Simulator dropped fragment");
+                continue;
+            }
+
+            stats.fragments_sent += 1;
+
+            match decoder.receive(&fragment.ur) {
+                Ok(progress) if progress.is_complete() => match decoder.result() {
+                    Ok(payload) => {
+                        metrics::record(started.elapsed(), true, Some(&payload.ur_type));
+                        let rendered = render_keystone_payload(&payload);
+                        sink.emit_keystone(&rendered)?;
+                        stats.payloads += 1;
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        metrics::record(started.elapsed(), false, None);
+                        sink.emit_error(&err.to_string())?;
+                        stats.errors += 1;
+                        return Ok(());
+                    }
+                },
+                Ok(progress) => {
+                    last_percentage = progress.percentage;
+                    tracing::debug!(
+                        line = fragment.line,
+                        parts_received = progress.parts_received,
+                        percentage = progress.percentage,
+                        message = %progress.message(),
+                        "Simulator multi-part progress",
+                    );
+                }
+                Err(err) => {
+                    tracing::warn!(line = fragment.line, "Failed to process UR fragment: {err}");
+                    stats.errors += 1;
+                }
+            }
+        }
+
+        metrics::record(started.elapsed(), false, None);
+        sink.emit_error(&format!(
+            "Simulator sequence {} ended before the UR was complete ({}% received)",
+            index + 1,
+            last_percentage
+        ))?;
+        stats.errors += 1;
+
+        Ok(())
+    }
+}
+
+/// Replay the recording at `path` into `sink`, looping forever when `watch` is set.
+pub async fn run<S: OutputSink + ?Sized>(path: &Path, watch: bool, sink: &S) -> Result<()> {
+    let recording = Recording::load(path)?;
+    if recording.fragment_count() == 0 {
+        return Err(Error::Config(format!(
+            "Simulator recording {} contains no UR fragments",
+            path.display()
+        )));
+    }
+
+    tracing::info!(
+        path = %path.display(),
+        sequences = recording.sequences.len(),
+        fragments = recording.fragment_count(),
+        "Replaying simulator recording",
+    );
+
+    let mut simulator = Simulator::new(recording);
+
+    loop {
+        let stats = simulator.replay(sink).await?;
+        tracing::info!(
+            fragments_sent = stats.fragments_sent,
+            fragments_dropped = stats.fragments_dropped,
+            payloads = stats.payloads,
+            errors = stats.errors,
+            "Simulator pass complete",
+        );
+
+        if !watch {
+            break;
+        }
+        tokio::time::sleep(WATCH_PASS_INTERVAL).await;
+    }
+
+    Ok(())
+}
+
+fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x5eed)
+}
+
+/// Small xorshift generator so replays are reproducible without extra dependencies
+struct ReplayRng(u64);
+
+impl ReplayRng {
+    fn new(seed: u64) -> Self {
+        // xorshift must never be seeded with zero
+        Self((seed ^ 0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystone::multipart::MultiPartEncoder;
+    use crate::output::RenderedKeystone;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CollectingSink {
+        payloads: Mutex<Vec<RenderedKeystone>>,
+        errors: Mutex<Vec<String>>,
+    }
+
+    impl OutputSink for CollectingSink {
+        fn emit_keystone(&self, rendered: &RenderedKeystone) -> Result<()> {
+            self.payloads.lock().unwrap().push(rendered.clone());
+            Ok(())
+        }
+
+        fn emit_error(&self, message: &str) -> Result<()> {
+            self.errors.lock().unwrap().push(message.to_string());
+            Ok(())
+        }
+    }
+
+    fn multipart_lines(ur_type: &str, len: usize) -> Vec<String> {
+        let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        MultiPartEncoder::new(ur_type, &data, 40)
+            .unwrap()
+            .all_parts()
+    }
+
+    #[test]
+    fn parses_directives_and_sequences() {
+        let text = "\
+# recording
+delay=25
+drop=0.5
+shuffle=true
+seed=7
+
+ur:bytes/aaaa 100ms
+---
+ur:bytes/bbbb # trailing comment
+";
+        let recording = Recording::parse(text).unwrap();
+        assert_eq!(recording.settings.delay, Duration::from_millis(25));
+        assert_eq!(recording.settings.drop_rate, 0.5);
+        assert!(recording.settings.shuffle);
+        assert_eq!(recording.settings.seed, Some(7));
+        assert_eq!(recording.sequences.len(), 2);
+        assert_eq!(
+            recording.sequences[0][0].delay,
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(recording.sequences[1][0].line, 9);
+    }
+
+    #[test]
+    fn loads_readme_fixture() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/eth-sign.txt");
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.settings.delay, Duration::from_millis(150));
+        assert_eq!(recording.sequences.len(), 1);
+        assert_eq!(recording.fragment_count(), 4);
+        for (index, fragment) in recording.sequences[0].iter().enumerate() {
+            let prefix = format!("ur:eth-sign-request/{}-4/", index + 1);
+            assert!(fragment.ur.starts_with(&prefix));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_directive_and_bad_drop_rate() {
+        assert!(Recording::parse("speed=2").is_err());
+        assert!(Recording::parse("drop=1.5").is_err());
+        assert!(Recording::parse("not a fragment").is_err());
+    }
+
+    #[tokio::test]
+    async fn replays_shuffled_multipart_sequence() {
+        let mut text = String::from("seed=11\nshuffle=true\n");
+        for part in multipart_lines("bytes", 200) {
+            text.push_str(&part);
+            text.push('\n');
+        }
+
+        let sink = CollectingSink::default();
+        let mut simulator = Simulator::new(Recording::parse(&text).unwrap());
+        let stats = simulator.replay(&sink).await.unwrap();
+
+        assert_eq!(stats.payloads, 1);
+        assert_eq!(stats.errors, 0);
+        let payloads = sink.payloads.lock().unwrap();
+        assert_eq!(payloads[0].json["ur_type"], "bytes");
+        assert_eq!(payloads[0].json["byte_length"], 200);
+    }
+
+    #[tokio::test]
+    async fn reports_incomplete_sequence_when_everything_is_dropped() {
+        let mut text = String::from("drop=1.0\n");
+        for part in multipart_lines("bytes", 120) {
+            text.push_str(&part);
+            text.push('\n');
+        }
+
+        let sink = CollectingSink::default();
+        let mut simulator = Simulator::new(Recording::parse(&text).unwrap());
+        let stats = simulator.replay(&sink).await.unwrap();
+
+        assert_eq!(stats.payloads, 0);
+        assert_eq!(stats.fragments_sent, 0);
+        assert!(stats.fragments_dropped > 0);
+        assert_eq!(sink.errors.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,56 @@
+//! In-memory frame channel for embedding applications
+
+use crate::error::{Error, Result};
+use crate::source::FrameSource;
+use async_trait::async_trait;
+use image::DynamicImage;
+use tokio::sync::mpsc;
+
+/// Sending half used by applications to push frames into a [`ChannelSource`]
+pub type FrameSender = mpsc::Sender<DynamicImage>;
+
+/// Frame source backed by a bounded tokio channel
+///
+/// The stream ends once every [`FrameSender`] has been dropped and buffered frames are drained.
+pub struct ChannelSource {
+    receiver: mpsc::Receiver<DynamicImage>,
+}
+
+impl ChannelSource {
+    /// Create a source and its sender with room for `capacity` buffered frames
+    pub fn new(capacity: usize) -> (FrameSender, Self) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (sender, Self { receiver })
+    }
+
+    /// Wrap an existing receiver
+    pub fn from_receiver(receiver: mpsc::Receiver<DynamicImage>) -> Self {
+        Self { receiver }
+    }
+}
+
+#[async_trait]
+impl FrameSource for ChannelSource {
+    async fn next_frame(&mut self) -> Result<DynamicImage> {
+        self.receiver.recv().await.ok_or(Error::EndOfStream)
+    }
+
+    fn description(&self) -> String {
+        "frame channel".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ends_after_senders_drop() {
+        let (sender, mut source) = ChannelSource::new(2);
+        sender.send(DynamicImage::new_luma8(1, 1)).await.unwrap();
+        drop(sender);
+
+        assert!(source.next_frame().await.is_ok());
+        assert!(matches!(source.next_frame().await, Err(Error::EndOfStream)));
+    }
+}
@@ -0,0 +1,119 @@
+//! Directory-of-frames source for replaying recorded captures
+
+use crate::error::{Error, Result};
+use crate::source::FrameSource;
+use async_trait::async_trait;
+use image::DynamicImage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Frame source that yields PNG/JPEG files from a directory in filename order
+pub struct DirectorySource {
+    frames: Vec<PathBuf>,
+    position: usize,
+    looping: bool,
+}
+
+impl DirectorySource {
+    /// Collect every PNG/JPEG file in `dir` (non-recursive), sorted by filename
+    pub fn open(dir: &Path) -> Result<Self> {
+        let entries = fs::read_dir(dir).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to read frame directory {}: {e}", dir.display()),
+            ))
+        })?;
+
+        let mut frames = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_file() && is_frame_file(&path) {
+                frames.push(path);
+            }
+        }
+        frames.sort();
+
+        if frames.is_empty() {
+            return Err(Error::Config(format!(
+                "No PNG/JPEG frames found in {}",
+                dir.display()
+            )));
+        }
+
+        Ok(Self {
+            frames,
+            position: 0,
+            looping: false,
+        })
+    }
+
+    /// Restart from the first frame after the last one instead of ending the stream
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// Paths of the frames in playback order
+    pub fn frames(&self) -> &[PathBuf] {
+        &self.frames
+    }
+}
+
+fn is_frame_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_ascii_lowercase().as_str(), "png" | "jpg" | "jpeg"))
+        .unwrap_or(false)
+}
+
+#[async_trait]
+impl FrameSource for DirectorySource {
+    async fn next_frame(&mut self) -> Result<DynamicImage> {
+        if self.position >= self.frames.len() {
+            if !self.looping {
+                return Err(Error::EndOfStream);
+            }
+            self.position = 0;
+        }
+
+        let path = self.frames[self.position].clone();
+        self.position += 1;
+
+        tokio::task::spawn_blocking(move || {
+            image::open(&path)
+                .map_err(|e| Error::Image(format!("Failed to open {}: {e}", path.display())))
+        })
+        .await
+        .map_err(|e| Error::Other(format!("Frame loader task failed: {e}")))?
+    }
+
+    fn description(&self) -> String {
+        format!("directory ({} frames)", self.frames.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn yields_frames_in_filename_order() {
+        let dir = std::env::temp_dir().join(format!("qlink-frames-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        DynamicImage::new_luma8(2, 2)
+            .save(dir.join("b.png"))
+            .unwrap();
+        DynamicImage::new_luma8(3, 3)
+            .save(dir.join("a.png"))
+            .unwrap();
+        fs::write(dir.join("notes.txt"), b"ignored").unwrap();
+
+        let mut source = DirectorySource::open(&dir).unwrap();
+        assert_eq!(source.frames().len(), 2);
+        assert_eq!(source.next_frame().await.unwrap().width(), 3);
+        assert_eq!(source.next_frame().await.unwrap().width(), 2);
+        assert!(matches!(source.next_frame().await, Err(Error::EndOfStream)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
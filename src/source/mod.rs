@@ -0,0 +1,136 @@
+//! Frame sources that feed images into the QR scanner
+//!
+//! `QlinkScanner` pulls frames through the [`FrameSource`] trait so the same
+//! scanning logic can run against a V4L2 camera, still images, a directory of
+//! recorded frames, or frames pushed in by an embedding application (e.g. a
+//! Tauri webview or a screenshot tool).
+
+mod channel;
+mod directory;
+mod still;
+
+pub use channel::{ChannelSource, FrameSender};
+pub use directory::DirectorySource;
+pub use still::StillImageSource;
+
+use crate::error::Result;
+use async_trait::async_trait;
+use image::DynamicImage;
+
+/// Asynchronous producer of video frames
+///
+/// Implementations return [`Error::EndOfStream`](crate::Error::EndOfStream) once no
+/// further frames will be produced.
+#[async_trait]
+pub trait FrameSource: Send {
+    /// Wait for and return the next frame
+    async fn next_frame(&mut self) -> Result<DynamicImage>;
+
+    /// Short human-readable description used in status reports
+    fn description(&self) -> String {
+        "custom frame source".to_string()
+    }
+}
+
+#[cfg(feature = "camera")]
+#[async_trait]
+impl FrameSource for crate::camera::Camera {
+    async fn next_frame(&mut self) -> Result<DynamicImage> {
+        self.capture_frame().await
+    }
+
+    fn description(&self) -> String {
+        format!("camera: {} ({})", self.info().name, self.info().path)
+    }
+}
+
+#[async_trait]
+impl<S: FrameSource + ?Sized> FrameSource for Box<S> {
+    async fn next_frame(&mut self) -> Result<DynamicImage> {
+        (**self).next_frame().await
+    }
+
+    fn description(&self) -> String {
+        (**self).description()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::{KeystonePayload, QlinkScanner, QrEncoder};
+
+    #[tokio::test]
+    async fn scanner_reports_end_of_stream_for_blank_frames() {
+        let source = StillImageSource::new(DynamicImage::new_luma8(64, 64));
+        let mut scanner = QlinkScanner::with_source(source);
+
+        assert!(matches!(
+            scanner.scan_keystone().await,
+            Err(Error::EndOfStream)
+        ));
+    }
+
+    #[test]
+    fn scanner_skips_unmatched_signature_only_while_requests_are_pending() {
+        use crate::keystone::{CryptoKeyPath, EthSignRequest, KeystoneMessage, SolanaSignature};
+
+        let source = StillImageSource::new(DynamicImage::new_luma8(8, 8));
+        let scanner = QlinkScanner::with_source(source);
+        let mut events = scanner.subscribe();
+        let stray = || {
+            KeystonePayload::from(KeystoneMessage::SolanaSignature(SolanaSignature::new(
+                vec![0u8; 64],
+                Some(uuid::Uuid::new_v4()),
+            )))
+        };
+
+        // Nothing recorded (CLI scans): reported as orphaned but accepted
+        assert!(scanner.complete(uuid::Uuid::new_v4(), stray()).is_some());
+
+        let path = CryptoKeyPath::from_str("m/44'/60'/0'/0/0").unwrap();
+        let request = EthSignRequest::new_personal_message(b"hello".to_vec(), path);
+        let store = scanner.session_store();
+        store
+            .lock()
+            .unwrap()
+            .record_request(KeystoneMessage::EthSignRequest(request))
+            .unwrap();
+
+        // A request is pending: skipped with a non-fatal error, scan goes on
+        assert!(scanner.complete(uuid::Uuid::new_v4(), stray()).is_none());
+        assert_eq!(store.lock().unwrap().orphans().len(), 2);
+        let mut skipped = None;
+        while let Ok(event) = events.try_recv() {
+            if let crate::ScanEvent::Error { message, fatal, .. } = event {
+                skipped = Some((message, fatal));
+            }
+        }
+        let (message, fatal) = skipped.unwrap();
+        assert!(message.starts_with("orphaned response for request"));
+        assert!(!fatal);
+    }
+
+    #[tokio::test]
+    async fn scanner_decodes_keystone_payload_from_channel() {
+        let ur = KeystonePayload {
+            ur_type: "bytes".to_string(),
+            data: vec![0x43, 0x01, 0x02, 0x03],
+            metadata: Default::default(),
+            encoding: crate::PayloadEncoding::Cbor,
+        }
+        .to_ur();
+        let frame = QrEncoder::new().encode_string(&ur).unwrap();
+
+        let (sender, source) = ChannelSource::new(4);
+        sender.send(DynamicImage::new_luma8(64, 64)).await.unwrap();
+        sender.send(frame).await.unwrap();
+        drop(sender);
+
+        let mut scanner = QlinkScanner::with_source(source);
+        let payload = scanner.scan_keystone().await.unwrap();
+        assert_eq!(payload.ur_type, "bytes");
+        assert_eq!(payload.data, vec![0x43, 0x01, 0x02, 0x03]);
+    }
+}
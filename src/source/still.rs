@@ -0,0 +1,72 @@
+//! Single still-image frame source
+
+use crate::error::{Error, Result};
+use crate::source::FrameSource;
+use async_trait::async_trait;
+use image::DynamicImage;
+use std::path::Path;
+
+/// Frame source that yields one still image (e.g. a screenshot)
+pub struct StillImageSource {
+    image: DynamicImage,
+    repeat: bool,
+    consumed: bool,
+}
+
+impl StillImageSource {
+    /// Create a source that yields `image` once and then reports end of stream
+    pub fn new(image: DynamicImage) -> Self {
+        Self {
+            image,
+            repeat: false,
+            consumed: false,
+        }
+    }
+
+    /// Load a still image from a PNG/JPEG file
+    pub fn open(path: &Path) -> Result<Self> {
+        let image = image::open(path)
+            .map_err(|e| Error::Image(format!("Failed to open {}: {e}", path.display())))?;
+        Ok(Self::new(image))
+    }
+
+    /// Keep yielding the same image forever, like a camera pointed at a static screen
+    pub fn repeating(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+}
+
+#[async_trait]
+impl FrameSource for StillImageSource {
+    async fn next_frame(&mut self) -> Result<DynamicImage> {
+        if self.consumed && !self.repeat {
+            return Err(Error::EndOfStream);
+        }
+        self.consumed = true;
+        Ok(self.image.clone())
+    }
+
+    fn description(&self) -> String {
+        "still image".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn yields_once_unless_repeating() {
+        let image = DynamicImage::new_luma8(4, 4);
+
+        let mut once = StillImageSource::new(image.clone());
+        assert!(once.next_frame().await.is_ok());
+        assert!(matches!(once.next_frame().await, Err(Error::EndOfStream)));
+
+        let mut repeating = StillImageSource::new(image).repeating();
+        for _ in 0..3 {
+            assert!(repeating.next_frame().await.is_ok());
+        }
+    }
+}
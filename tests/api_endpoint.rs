@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use image::{DynamicImage, GrayImage, Luma};
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use qlink::api::ApiServer;
+use qlink::{ApiOptions, QlinkScanner, StillImageSource};
+
+async fn spawn_server(token: Option<&str>, origins: &[&str]) -> ApiServer {
+    let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 64, Luma([255u8])));
+    let scanner = QlinkScanner::with_source(StillImageSource::new(blank).repeating());
+    let options = ApiOptions {
+        port: 0,
+        token: token.map(str::to_string),
+        allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+        ..ApiOptions::default()
+    };
+
+    ApiServer::bind(&options, Arc::new(Mutex::new(scanner)))
+        .await
+        .expect("bind api server")
+}
+
+async fn request(server: &ApiServer, raw: &str) -> (u16, String, Value) {
+    let mut stream = TcpStream::connect(server.local_addr())
+        .await
+        .expect("connect api");
+    stream
+        .write_all(raw.as_bytes())
+        .await
+        .expect("write request");
+
+    let mut buf = Vec::new();
+    stream.read_to_end(&mut buf).await.expect("read response");
+    let response = String::from_utf8_lossy(&buf).to_string();
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .expect("invalid HTTP response format");
+    let status = head[9..12].parse().expect("status code");
+    let body = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(body).expect("parse json body")
+    };
+    (status, head.to_string(), body)
+}
+
+fn post(path: &str, headers: &str, body: &str) -> String {
+    format!(
+        "POST {path} HTTP/1.1\r\nHost: localhost\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn health_is_public_and_reports_source() {
+    let server = spawn_server(Some("secret"), &[]).await;
+    let (status, _, body) = request(&server, "GET /health HTTP/1.1\r\n\r\n").await;
+
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["scanner"]["source"], "still image");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn token_is_enforced() {
+    let server = spawn_server(Some("secret"), &[]).await;
+
+    let (status, _, body) = request(&server, &post("/show-qr", "", r#"{"text":"hi"}"#)).await;
+    assert_eq!(status, 401);
+    assert!(body["error"].is_string());
+
+    let (status, _, body) = request(
+        &server,
+        &post(
+            "/show-qr",
+            "Authorization: Bearer secret\r\n",
+            r#"{"text":"hi"}"#,
+        ),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["part_count"], 1);
+    assert!(body["parts"][0]["svg"].as_str().unwrap().contains("<svg"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cors_allowlist_is_enforced() {
+    let server = spawn_server(None, &["http://localhost:5173"]).await;
+
+    let (status, _, _) = request(
+        &server,
+        "GET /health HTTP/1.1\r\nOrigin: https://evil.example\r\n\r\n",
+    )
+    .await;
+    assert_eq!(status, 403);
+
+    let (status, head, _) = request(
+        &server,
+        "OPTIONS /scan-keystone HTTP/1.1\r\nOrigin: http://localhost:5173\r\n\r\n",
+    )
+    .await;
+    assert_eq!(status, 204);
+    assert!(head.contains("Access-Control-Allow-Origin: http://localhost:5173"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn show_qr_splits_multipart_payloads() {
+    let server = spawn_server(None, &[]).await;
+    let data_hex = "ab".repeat(300);
+    let body = format!(r#"{{"ur_type":"bytes","data_hex":"{data_hex}","max_fragment_len":100}}"#);
+
+    let (status, _, body) = request(&server, &post("/show-qr", "", &body)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["multipart"], true);
+    assert!(body["part_count"].as_u64().unwrap() > 1);
+    assert!(body["frame_delay_ms"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn scan_times_out_without_qr() {
+    let server = spawn_server(None, &[]).await;
+
+    let (status, _, body) =
+        request(&server, &post("/scan-once", "", r#"{"timeout_ms":200}"#)).await;
+    assert_eq!(status, 408);
+    assert!(body["error"].as_str().unwrap().contains("timed out"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn non_loopback_bind_is_refused() {
+    let scanner = QlinkScanner::with_source(StillImageSource::new(DynamicImage::new_luma8(8, 8)));
+    let options = ApiOptions {
+        bind_address: "0.0.0.0".to_string(),
+        port: 0,
+        ..ApiOptions::default()
+    };
+
+    assert!(
+        ApiServer::bind(&options, Arc::new(Mutex::new(scanner)))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn events_websocket_streams_scan_session() {
+    use futures_util::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 64, Luma([255u8])));
+    let scanner = QlinkScanner::with_source(StillImageSource::new(blank));
+    let options = ApiOptions {
+        port: 0,
+        token: Some("secret".to_string()),
+        ..ApiOptions::default()
+    };
+    let server = ApiServer::bind(&options, Arc::new(Mutex::new(scanner)))
+        .await
+        .expect("bind api server");
+
+    let url = format!("ws://{}/events?token=secret", server.local_addr());
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .expect("websocket handshake");
+
+    let (status, _, _) = request(
+        &server,
+        &post("/scan-keystone", "Authorization: Bearer secret\r\n", ""),
+    )
+    .await;
+    assert_eq!(status, 503, "still image source should be exhausted");
+
+    let mut kinds = Vec::new();
+    while kinds.len() < 2 {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(2), socket.next())
+            .await
+            .expect("event before timeout")
+            .expect("stream open")
+            .expect("valid frame");
+        if let Message::Text(text) = message {
+            let event: Value = serde_json::from_str(&text).expect("event json");
+            kinds.push(event["event"].as_str().unwrap_or_default().to_string());
+        }
+    }
+    assert_eq!(kinds, ["session_started", "error"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn events_websocket_requires_token() {
+    let server = spawn_server(Some("secret"), &[]).await;
+    let url = format!("ws://{}/events", server.local_addr());
+    assert!(tokio_tungstenite::connect_async(url).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn stalled_request_times_out() {
+    let server = spawn_server(None, &[]).await;
+    let (status, _, body) = request(&server, "GET /health HTTP/1.1\r\nHost: loc").await;
+
+    assert_eq!(status, 408);
+    assert_eq!(body["error"], "Timed out reading request");
+}